@@ -0,0 +1,297 @@
+use crate::audio::filters::{Biquad, BiquadMode, FilterMode, SVF};
+use crate::audio::reverbs::{QualityReverb, ReverbQuality};
+use crate::audio::{AudioProcessor, StereoAudioProcessor, TWO_PI};
+
+/// Sample rate used when analysing nodes outside the audio thread
+pub const ANALYSIS_SAMPLE_RATE: f32 = 48000.0;
+
+/// Feed a unit impulse through a processor and capture `length` samples of output
+pub fn impulse_response<P: AudioProcessor>(processor: &mut P, length: usize) -> Vec<f32> {
+    (0..length)
+        .map(|i| processor.process(if i == 0 { 1.0 } else { 0.0 }))
+        .collect()
+}
+
+/// Impulse response of a stereo processor, with the impulse on both inputs
+pub fn stereo_impulse_response<P: StereoAudioProcessor>(
+    processor: &mut P,
+    length: usize,
+) -> Vec<(f32, f32)> {
+    (0..length)
+        .map(|i| {
+            let input = if i == 0 { 1.0 } else { 0.0 };
+            processor.process(input, input)
+        })
+        .collect()
+}
+
+/// Schroeder backward-integrated energy decay curve, in dB relative to the total energy
+pub fn energy_decay_curve(impulse_response: &[f32]) -> Vec<f32> {
+    let mut remaining = 0.0f64;
+    let mut curve: Vec<f64> = impulse_response
+        .iter()
+        .rev()
+        .map(|&sample| {
+            remaining += (sample as f64) * (sample as f64);
+            remaining
+        })
+        .collect();
+    curve.reverse();
+
+    let total = curve.first().copied().unwrap_or(0.0);
+    curve
+        .into_iter()
+        .map(|energy| {
+            if total > 0.0 && energy > 0.0 {
+                (10.0 * (energy / total).log10()) as f32
+            } else {
+                f32::NEG_INFINITY
+            }
+        })
+        .collect()
+}
+
+/// Estimate RT60 in seconds from the -5 to -25 dB slope of the decay curve (T20).
+/// Returns None if the response doesn't decay far enough to measure.
+pub fn rt60(impulse_response: &[f32], sample_rate: f32) -> Option<f32> {
+    let curve = energy_decay_curve(impulse_response);
+    let start = curve.iter().position(|&db| db <= -5.0)?;
+    let end = curve.iter().position(|&db| db <= -25.0)?;
+    if end <= start {
+        return None;
+    }
+
+    // Least-squares line through the curve between the two points
+    let points = &curve[start..=end];
+    let n = points.len() as f32;
+    let mean_x = (points.len() - 1) as f32 / 2.0;
+    let mean_y = points.iter().sum::<f32>() / n;
+    let mut covariance = 0.0;
+    let mut variance = 0.0;
+    for (i, &db) in points.iter().enumerate() {
+        let dx = i as f32 - mean_x;
+        covariance += dx * (db - mean_y);
+        variance += dx * dx;
+    }
+    let db_per_sample = covariance / variance;
+    if db_per_sample >= 0.0 {
+        return None;
+    }
+
+    Some(-60.0 / db_per_sample / sample_rate)
+}
+
+/// `count` frequencies spaced logarithmically from `low` to `high` Hz
+pub fn log_frequencies(low: f32, high: f32, count: usize) -> Vec<f32> {
+    if count < 2 {
+        return vec![low; count];
+    }
+    let ratio = (high / low).ln();
+    (0..count)
+        .map(|i| low * (ratio * i as f32 / (count - 1) as f32).exp())
+        .collect()
+}
+
+/// Magnitude of an impulse response at each frequency, in dB
+pub fn magnitude_response(
+    impulse_response: &[f32],
+    frequencies: &[f32],
+    sample_rate: f32,
+) -> Vec<f32> {
+    frequencies
+        .iter()
+        .map(|&frequency| {
+            let omega = TWO_PI * frequency / sample_rate;
+            let (mut re, mut im) = (0.0f64, 0.0f64);
+            for (n, &sample) in impulse_response.iter().enumerate() {
+                let phase = (omega * n as f32) as f64;
+                re += sample as f64 * phase.cos();
+                im -= sample as f64 * phase.sin();
+            }
+            let magnitude = (re * re + im * im).sqrt().max(1e-10);
+            (20.0 * magnitude.log10()) as f32
+        })
+        .collect()
+}
+
+/// Reduce a response to at most `points` values by taking the peak of each block,
+/// so long tails can be plotted without sending every sample to the UI
+pub fn peak_envelope(samples: &[f32], points: usize) -> Vec<f32> {
+    if points == 0 || samples.is_empty() {
+        return Vec::new();
+    }
+    let block = samples.len().div_ceil(points);
+    samples
+        .chunks(block)
+        .map(|chunk| chunk.iter().fold(0.0f32, |peak, s| peak.max(s.abs())))
+        .collect()
+}
+
+/// Length of the impulse response used for filter plots
+const FILTER_IMPULSE_LENGTH: usize = 8192;
+
+/// Longest reverb tail rendered for analysis
+pub const MAX_REVERB_ANALYSIS_SECONDS: f32 = 10.0;
+
+/// Most envelope points returned for a reverb tail
+pub const MAX_REVERB_ANALYSIS_POINTS: usize = 2048;
+
+/// Decay time and plottable tail of a reverb setting
+pub struct ReverbAnalysis {
+    pub rt60: Option<f32>,
+    pub envelope: Vec<f32>,
+}
+
+/// Render a reverb's impulse response offline with a fixed seed, so the same
+/// settings always measure the same. `seconds` and `points` are capped at
+/// MAX_REVERB_ANALYSIS_SECONDS and MAX_REVERB_ANALYSIS_POINTS.
+pub fn analyze_reverb(
+    quality: u32,
+    size: f32,
+    feedback: f32,
+    modulation_depth: f32,
+    seconds: f32,
+    points: usize,
+) -> Result<ReverbAnalysis, String> {
+    let quality = ReverbQuality::from_index(quality)
+        .ok_or_else(|| format!("Invalid reverb quality: {}", quality))?;
+    let mut reverb = QualityReverb::new(quality, ANALYSIS_SAMPLE_RATE);
+    reverb.set_seed(0);
+    reverb.set_size(size);
+    reverb.set_feedback(feedback);
+    reverb.set_modulation_depth(modulation_depth);
+
+    let seconds = seconds.clamp(0.0, MAX_REVERB_ANALYSIS_SECONDS);
+    let points = points.min(MAX_REVERB_ANALYSIS_POINTS);
+    let length = (seconds * ANALYSIS_SAMPLE_RATE) as usize;
+    let response: Vec<f32> = stereo_impulse_response(&mut reverb, length)
+        .into_iter()
+        .map(|(left, right)| (left + right) * 0.5)
+        .collect();
+
+    Ok(ReverbAnalysis {
+        rt60: rt60(&response, ANALYSIS_SAMPLE_RATE),
+        envelope: peak_envelope(&response, points),
+    })
+}
+
+/// Magnitude response in dB of a filter at `points` frequencies from 20 Hz to 20 kHz.
+/// `mode` is lowpass, highpass or bandpass for the SVF, or low_shelf, high_shelf
+/// or peak for the EQ biquad (the only mode that uses `gain_db`).
+pub fn filter_response(
+    mode: &str,
+    frequency: f32,
+    q: f32,
+    gain_db: f32,
+    points: usize,
+) -> Result<(Vec<f32>, Vec<f32>), String> {
+    let sample_rate = ANALYSIS_SAMPLE_RATE;
+    let svf = |mode| SVF::new(frequency, q, mode, sample_rate);
+    let biquad = |mode| Biquad::new(frequency, q, gain_db, mode, sample_rate);
+    let response = match mode {
+        "lowpass" => impulse_response(&mut svf(FilterMode::Lowpass), FILTER_IMPULSE_LENGTH),
+        "highpass" => impulse_response(&mut svf(FilterMode::Highpass), FILTER_IMPULSE_LENGTH),
+        "bandpass" => impulse_response(&mut svf(FilterMode::Bandpass), FILTER_IMPULSE_LENGTH),
+        "low_shelf" => impulse_response(&mut biquad(BiquadMode::LowShelf), FILTER_IMPULSE_LENGTH),
+        "high_shelf" => impulse_response(&mut biquad(BiquadMode::HighShelf), FILTER_IMPULSE_LENGTH),
+        "peak" => impulse_response(&mut biquad(BiquadMode::Peak), FILTER_IMPULSE_LENGTH),
+        _ => return Err(format!("Unknown filter mode: {}", mode)),
+    };
+
+    let frequencies = log_frequencies(20.0, 20000.0, points);
+    let magnitudes = magnitude_response(&response, &frequencies, sample_rate);
+    Ok((frequencies, magnitudes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Multiplies its input by a constant every sample, a pure exponential decay
+    struct Decay {
+        level: f32,
+        coefficient: f32,
+    }
+
+    impl AudioProcessor for Decay {
+        fn process(&mut self, input: f32) -> f32 {
+            self.level = self.level * self.coefficient + input;
+            self.level
+        }
+
+        fn set_sample_rate(&mut self, _sample_rate: f32) {}
+    }
+
+    #[test]
+    fn test_rt60_of_exponential_decay() {
+        let sample_rate = 1000.0;
+        // Amplitude falls 60 dB in 0.5 seconds
+        let coefficient = 10.0f32.powf(-3.0 / (0.5 * sample_rate));
+        let mut decay = Decay {
+            level: 0.0,
+            coefficient,
+        };
+        let ir = impulse_response(&mut decay, 2000);
+
+        let rt60 = rt60(&ir, sample_rate).unwrap();
+        assert!((rt60 - 0.5).abs() < 0.02, "rt60 was {}", rt60);
+        assert!(super::rt60(&[1.0, 1.0, 1.0], sample_rate).is_none());
+    }
+
+    #[test]
+    fn test_lowpass_magnitude_response() {
+        let sample_rate = ANALYSIS_SAMPLE_RATE;
+        let mut filter = SVF::new(1000.0, 0.707, FilterMode::Lowpass, sample_rate);
+        let ir = impulse_response(&mut filter, 4096);
+        let response = magnitude_response(&ir, &[100.0, 1000.0, 10000.0], sample_rate);
+
+        assert!(response[0].abs() < 1.0, "passband was {} dB", response[0]);
+        assert!(
+            (response[1] + 3.0).abs() < 1.5,
+            "cutoff was {} dB",
+            response[1]
+        );
+        assert!(response[2] < -30.0, "stopband was {} dB", response[2]);
+    }
+
+    #[test]
+    fn test_peak_filter_response() {
+        let (frequencies, magnitudes) = filter_response("peak", 1000.0, 1.0, 6.0, 61).unwrap();
+        let peak = magnitudes.iter().cloned().fold(f32::MIN, f32::max);
+        assert_eq!(frequencies.len(), 61);
+        assert!((peak - 6.0).abs() < 0.5, "peak was {} dB", peak);
+        assert!(filter_response("comb", 1000.0, 1.0, 0.0, 8).is_err());
+    }
+
+    #[test]
+    fn test_reverb_feedback_lengthens_decay() {
+        let short = analyze_reverb(0, 0.5, 0.3, 0.0, 2.0, 100).unwrap();
+        let long = analyze_reverb(0, 0.5, 0.8, 0.0, 2.0, 100).unwrap();
+        assert_eq!(short.envelope.len(), 100);
+        assert!(long.rt60.unwrap() > short.rt60.unwrap());
+    }
+
+    #[test]
+    fn test_reverb_analysis_is_capped() {
+        let analysis = analyze_reverb(0, 0.5, 0.3, 0.0, 1e9, usize::MAX).unwrap();
+        assert!(analysis.envelope.len() <= MAX_REVERB_ANALYSIS_POINTS);
+        assert!(analysis.envelope.len() > MAX_REVERB_ANALYSIS_POINTS / 2);
+        assert!(analyze_reverb(0, 0.5, 0.3, 0.0, f32::NAN, 100)
+            .unwrap()
+            .envelope
+            .is_empty());
+    }
+
+    #[test]
+    fn test_log_frequencies_and_envelope() {
+        let frequencies = log_frequencies(20.0, 20000.0, 4);
+        assert_eq!(frequencies.len(), 4);
+        assert!((frequencies[1] - 200.0).abs() < 0.1);
+        assert!((frequencies[3] - 20000.0).abs() < 1.0);
+
+        assert_eq!(
+            peak_envelope(&[0.5, -1.0, 0.25, 0.1, 0.0], 2),
+            vec![1.0, 0.1]
+        );
+    }
+}
@@ -1,3 +1,11 @@
+/// How reads between samples are reconstructed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    None,   // Truncate to the nearest earlier sample
+    Linear, // Cheap, but rolls off highs at fractional delays
+    Cubic,  // 4-point Hermite, flat to much higher frequencies
+}
+
 pub struct DelayBuffer {
     buffer: Vec<f32>,
     delay_samples: usize,
@@ -27,6 +35,16 @@ impl DelayBuffer {
         self.delay_samples = delay_samples;
     }
 
+    /// Longest delay the buffer can hold, in samples
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Silence everything written so far
+    pub fn clear(&mut self) {
+        self.buffer.fill(0.0);
+    }
+
     fn get_read_pos(&self, delay_samples: usize) -> usize {
         if delay_samples <= self.write_pos {
             self.write_pos - delay_samples
@@ -50,6 +68,34 @@ impl DelayBuffer {
         self.read_at(self.delay_samples)
     }
 
+    /// Read at a fractional delay. Linear and cubic reads clamp the delay to
+    /// the range where all their neighbouring samples exist.
+    pub fn read_at_interpolated(&self, delay_samples: f32, interpolation: Interpolation) -> f32 {
+        if interpolation == Interpolation::None {
+            return self.read_at(delay_samples as usize);
+        }
+
+        let delay = delay_samples.clamp(1.0, (self.buffer.len() - 2) as f32);
+        let index = delay as usize;
+        let frac = delay - index as f32;
+
+        let x0 = self.read_at(index);
+        let x1 = self.read_at(index + 1);
+
+        match interpolation {
+            Interpolation::Cubic => {
+                let xm1 = self.read_at((index - 1).max(1));
+                let x2 = self.read_at(index + 2);
+
+                let c1 = 0.5 * (x1 - xm1);
+                let c2 = xm1 - 2.5 * x0 + 2.0 * x1 - 0.5 * x2;
+                let c3 = 0.5 * (x2 - xm1) + 1.5 * (x0 - x1);
+                ((c3 * frac + c2) * frac + c1) * frac + x0
+            }
+            _ => x0 + (x1 - x0) * frac,
+        }
+    }
+
     pub fn advance(&mut self) {
         self.write_pos = (self.write_pos + 1) & self.mask;
     }
@@ -66,6 +112,7 @@ impl DelayBuffer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::audio::TWO_PI;
 
     #[test]
     fn test_delay_buffer_basic_operation() {
@@ -129,6 +176,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_delay_buffer_interpolated_reads() {
+        let mut buffer = DelayBuffer::new(64);
+
+        // A high sine (a quarter of the sample rate) exposes interpolation error
+        let frequency = 0.2;
+        for i in 0..64 {
+            buffer.write((i as f32 * frequency * TWO_PI).sin());
+        }
+        let expected = |delay: f32| ((64.0 - delay) * frequency * TWO_PI).sin();
+
+        // Whole-sample delays read back exactly
+        for interpolation in [Interpolation::Linear, Interpolation::Cubic] {
+            let value = buffer.read_at_interpolated(10.0, interpolation);
+            assert!((value - expected(10.0)).abs() < 1e-5);
+        }
+
+        // Halfway between samples, cubic is much closer than linear
+        let mut linear_error = 0.0f32;
+        let mut cubic_error = 0.0f32;
+        for delay in [10.5, 11.25, 20.75, 30.5] {
+            let linear = buffer.read_at_interpolated(delay, Interpolation::Linear);
+            let cubic = buffer.read_at_interpolated(delay, Interpolation::Cubic);
+            linear_error = linear_error.max((linear - expected(delay)).abs());
+            cubic_error = cubic_error.max((cubic - expected(delay)).abs());
+        }
+        assert!(
+            cubic_error < linear_error * 0.5,
+            "Cubic error {} should beat linear error {}",
+            cubic_error,
+            linear_error
+        );
+
+        // No interpolation matches the integer read
+        assert_eq!(
+            buffer.read_at_interpolated(12.9, Interpolation::None),
+            buffer.read_at(12)
+        );
+    }
+
     #[test]
     fn test_delay_buffer_feedback_loop() {
         let mut buffer = DelayBuffer::new(100);
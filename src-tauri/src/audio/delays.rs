@@ -1,12 +1,26 @@
-use crate::audio::buffers::DelayBuffer;
-use crate::audio::filters::{OnePoleFilter, OnePoleMode};
+use crate::audio::buffers::{DelayBuffer, Interpolation};
+use crate::audio::filters::{Biquad, BiquadMode, FilterCascade, FilterSlope, OnePoleMode};
 use crate::audio::AudioProcessor;
 
+// Highest feedback accepted; above 1.0 only makes sense with a ceiling set
+const MAX_FEEDBACK: f32 = 1.5;
+
+// Crossfade into the loop start when a freeze loop wraps
+const FREEZE_LOOP_CROSSFADE_TIME: f32 = 0.005;
+
 // Simple delay line without filtering
 pub struct DelayLine {
     buffer: DelayBuffer,
     frozen: bool,
+    // Freeze loops the last `loop_seconds` instead of the whole buffer
+    freeze_loop_seconds: Option<f32>,
+    loop_samples: usize,
+    loop_crossfade_samples: usize,
+    loop_position: usize,
     feedback: f32,
+    // Soft limit on what gets written back, lets feedback swell instead of running away
+    feedback_ceiling: Option<f32>,
+    interpolation: Interpolation, // Used by read_at
     sample_rate: f32,
     gain: f32,
 }
@@ -16,14 +30,78 @@ impl DelayLine {
         Self {
             buffer: DelayBuffer::new((max_delay_seconds * sample_rate) as usize),
             frozen: false,
+            freeze_loop_seconds: None,
+            loop_samples: 0,
+            loop_crossfade_samples: 0,
+            loop_position: 0,
             feedback: 0.0,
+            feedback_ceiling: None,
+            interpolation: Interpolation::None,
             sample_rate,
             gain: 1.0,
         }
     }
 
     pub fn set_freeze(&mut self, freeze: bool) {
+        if freeze && !self.frozen {
+            self.loop_position = 0;
+        }
         self.frozen = freeze;
+        self.update_freeze_loop();
+    }
+
+    /// Loop only the most recent slice of this length while frozen, or the
+    /// whole buffer with None
+    pub fn set_freeze_loop_seconds(&mut self, loop_seconds: Option<f32>) {
+        self.freeze_loop_seconds = loop_seconds;
+        self.update_freeze_loop();
+    }
+
+    /// Loop a clock division while frozen, e.g. 0.25 beats for a 16th note repeat
+    pub fn set_freeze_loop_beats(&mut self, beats: f32, bpm: f32) {
+        self.set_freeze_loop_seconds(Some(beats * 60.0 / bpm.max(1.0)));
+    }
+
+    pub fn is_looping(&self) -> bool {
+        self.frozen && self.loop_samples > 0
+    }
+
+    fn update_freeze_loop(&mut self) {
+        self.loop_crossfade_samples = (FREEZE_LOOP_CROSSFADE_TIME * self.sample_rate) as usize;
+        self.loop_samples = match self.freeze_loop_seconds {
+            // Leave room behind the slice for the loop crossfade
+            Some(seconds) => ((seconds * self.sample_rate) as usize).min(
+                self.buffer
+                    .capacity()
+                    .saturating_sub(self.loop_crossfade_samples + 1),
+            ),
+            None => 0,
+        };
+        if self.loop_samples > 0 {
+            self.loop_crossfade_samples = self.loop_crossfade_samples.min(self.loop_samples / 2);
+            self.loop_position %= self.loop_samples;
+        }
+    }
+
+    /// Next sample of the frozen slice. The last few milliseconds blend into
+    /// the audio that led up to the slice, so the wrap back to its start is seamless.
+    pub fn next_loop_sample(&mut self) -> f32 {
+        let length = self.loop_samples;
+        let position = self.loop_position;
+        self.loop_position = (position + 1) % length;
+
+        // Delay from the frozen write position back to this point in the slice
+        let delay = length - position;
+        let sample = self.buffer.read_at(delay);
+
+        let crossfade_start = length - self.loop_crossfade_samples;
+        if position < crossfade_start {
+            return sample;
+        }
+
+        let fade = (position - crossfade_start) as f32 / self.loop_crossfade_samples as f32;
+        let lead_in = self.buffer.read_at(delay + length);
+        sample + (lead_in - sample) * fade
     }
 
     pub fn set_delay_seconds(&mut self, delay_seconds: f32) {
@@ -31,8 +109,19 @@ impl DelayLine {
         self.buffer.set_delay_samples(delay_samples);
     }
 
+    /// Feedback above 1.0 is only applied when a ceiling is set
     pub fn set_feedback(&mut self, feedback: f32) {
-        self.feedback = feedback.clamp(-1.0, 1.0);
+        self.feedback = feedback.clamp(-MAX_FEEDBACK, MAX_FEEDBACK);
+    }
+
+    /// Saturate the written signal towards this level, or write it untouched with None
+    pub fn set_feedback_ceiling(&mut self, ceiling: Option<f32>) {
+        self.feedback_ceiling = ceiling.map(|c| c.max(0.01));
+    }
+
+    /// Interpolation for modulated reads through read_at
+    pub fn set_interpolation(&mut self, interpolation: Interpolation) {
+        self.interpolation = interpolation;
     }
 
     pub fn read(&mut self) -> f32 {
@@ -40,18 +129,30 @@ impl DelayLine {
     }
 
     pub fn read_at(&self, delay_seconds: f32) -> f32 {
-        let delay_samples = (delay_seconds * self.sample_rate) as usize;
-        self.buffer.read_at(delay_samples)
+        self.buffer
+            .read_at_interpolated(delay_seconds * self.sample_rate, self.interpolation)
     }
 
     pub fn write(&mut self, input: f32, feedback: f32) {
-        self.buffer.write(input + feedback * self.feedback);
+        let value = match self.feedback_ceiling {
+            Some(ceiling) => {
+                let value = input + feedback * self.feedback;
+                ceiling * (value / ceiling).tanh()
+            }
+            None => input + feedback * self.feedback.clamp(-1.0, 1.0),
+        };
+        self.buffer.write(value);
     }
 
     pub fn advance(&mut self) {
         self.buffer.advance();
     }
 
+    /// Silence everything written so far
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+    }
+
     pub fn set_gain(&mut self, gain: f32) {
         self.gain = gain;
     }
@@ -59,10 +160,16 @@ impl DelayLine {
 
 impl AudioProcessor for DelayLine {
     fn process(&mut self, input: f32) -> f32 {
+        if self.is_looping() {
+            return self.next_loop_sample();
+        }
+
         let delayed = self.read();
 
         if !self.frozen {
             self.write(input, delayed);
+        } else {
+            self.advance();
         }
 
         delayed
@@ -70,15 +177,17 @@ impl AudioProcessor for DelayLine {
 
     fn set_sample_rate(&mut self, sample_rate: f32) {
         self.sample_rate = sample_rate;
+        self.update_freeze_loop();
     }
 }
 
-
 // Delay line with filtering
 pub struct FilteredDelayLine {
     delay_line: DelayLine,
-    highpass: OnePoleFilter,
-    lowpass: OnePoleFilter,
+    highpass: FilterCascade,
+    lowpass: FilterCascade,
+    low_shelf: Biquad,
+    high_shelf: Biquad,
     gain: f32,
 }
 
@@ -86,8 +195,21 @@ impl FilteredDelayLine {
     pub fn new(max_delay_seconds: f32, sample_rate: f32) -> Self {
         Self {
             delay_line: DelayLine::new(max_delay_seconds, sample_rate),
-            highpass: OnePoleFilter::new(300.0, OnePoleMode::Highpass, sample_rate),
-            lowpass: OnePoleFilter::new(8000.0, OnePoleMode::Lowpass, sample_rate),
+            highpass: FilterCascade::new(
+                300.0,
+                OnePoleMode::Highpass,
+                FilterSlope::Db6,
+                sample_rate,
+            ),
+            lowpass: FilterCascade::new(
+                8000.0,
+                OnePoleMode::Lowpass,
+                FilterSlope::Db6,
+                sample_rate,
+            ),
+            // Shelves start flat (0 dB) so the feedback tone is set by the HP/LP pair
+            low_shelf: Biquad::new(250.0, 0.707, 0.0, BiquadMode::LowShelf, sample_rate),
+            high_shelf: Biquad::new(4000.0, 0.707, 0.0, BiquadMode::HighShelf, sample_rate),
             gain: 1.0,
         }
     }
@@ -96,6 +218,14 @@ impl FilteredDelayLine {
         self.delay_line.set_freeze(freeze);
     }
 
+    pub fn set_freeze_loop_seconds(&mut self, loop_seconds: Option<f32>) {
+        self.delay_line.set_freeze_loop_seconds(loop_seconds);
+    }
+
+    pub fn set_freeze_loop_beats(&mut self, beats: f32, bpm: f32) {
+        self.delay_line.set_freeze_loop_beats(beats, bpm);
+    }
+
     pub fn set_delay_seconds(&mut self, delay_seconds: f32) {
         self.delay_line.set_delay_seconds(delay_seconds);
     }
@@ -104,6 +234,14 @@ impl FilteredDelayLine {
         self.delay_line.set_feedback(feedback);
     }
 
+    pub fn set_feedback_ceiling(&mut self, ceiling: Option<f32>) {
+        self.delay_line.set_feedback_ceiling(ceiling);
+    }
+
+    pub fn set_interpolation(&mut self, interpolation: Interpolation) {
+        self.delay_line.set_interpolation(interpolation);
+    }
+
     pub fn set_highpass_freq(&mut self, freq: f32) {
         self.highpass.set_cutoff_frequency(freq);
     }
@@ -112,6 +250,24 @@ impl FilteredDelayLine {
         self.lowpass.set_cutoff_frequency(freq);
     }
 
+    pub fn set_highpass_slope(&mut self, slope: FilterSlope) {
+        self.highpass.set_slope(slope);
+    }
+
+    pub fn set_lowpass_slope(&mut self, slope: FilterSlope) {
+        self.lowpass.set_slope(slope);
+    }
+
+    pub fn set_low_shelf(&mut self, freq: f32, gain_db: f32) {
+        self.low_shelf.set_frequency(freq);
+        self.low_shelf.set_gain_db(gain_db);
+    }
+
+    pub fn set_high_shelf(&mut self, freq: f32, gain_db: f32) {
+        self.high_shelf.set_frequency(freq);
+        self.high_shelf.set_gain_db(gain_db);
+    }
+
     pub fn read(&mut self) -> f32 {
         self.delay_line.read()
     }
@@ -131,12 +287,18 @@ impl FilteredDelayLine {
 
 impl AudioProcessor for FilteredDelayLine {
     fn process(&mut self, input: f32) -> f32 {
-        let delayed = self.delay_line.read();
+        let looping = self.delay_line.is_looping();
+        let delayed = if looping {
+            self.delay_line.next_loop_sample()
+        } else {
+            self.delay_line.read()
+        };
         let filtered = self.lowpass.process(self.highpass.process(delayed));
+        let filtered = self.high_shelf.process(self.low_shelf.process(filtered));
 
         if !self.delay_line.frozen {
             self.delay_line.write(input, filtered);
-        } else {
+        } else if !looping {
             self.delay_line.advance();
         }
 
@@ -147,6 +309,8 @@ impl AudioProcessor for FilteredDelayLine {
         AudioProcessor::set_sample_rate(&mut self.delay_line, sample_rate);
         self.highpass.set_sample_rate(sample_rate);
         self.lowpass.set_sample_rate(sample_rate);
+        self.low_shelf.set_sample_rate(sample_rate);
+        self.high_shelf.set_sample_rate(sample_rate);
     }
 }
 
@@ -192,6 +356,70 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_delay_line_feedback_ceiling() {
+        let sample_rate = 44100.0;
+        let run = |delay: &mut DelayLine| {
+            delay.set_delay_seconds(100.0 / sample_rate);
+            AudioProcessor::process(delay, 0.5);
+            (0..5000)
+                .map(|_| AudioProcessor::process(delay, 0.0).abs())
+                .fold(0.0f32, f32::max)
+        };
+
+        // Without a ceiling, feedback above 1.0 is held at unity
+        let mut unlimited = DelayLine::new(1.0, sample_rate);
+        unlimited.set_feedback(1.2);
+        assert!((run(&mut unlimited) - 0.5).abs() < 1e-6);
+
+        // With one, the echoes swell up to the ceiling and stay there
+        let mut limited = DelayLine::new(1.0, sample_rate);
+        limited.set_feedback(1.2);
+        limited.set_feedback_ceiling(Some(0.8));
+        let peak = run(&mut limited);
+        assert!(peak > 0.5, "Feedback should swell, peak {}", peak);
+        assert!(
+            peak <= 0.8,
+            "Feedback should stay under the ceiling, peak {}",
+            peak
+        );
+    }
+
+    #[test]
+    fn test_delay_line_freeze_loop() {
+        let sample_rate = 44100.0;
+        let mut delay = DelayLine::new(1.0, sample_rate);
+        delay.set_delay_seconds(0.1);
+
+        // A 16th note at 120 BPM
+        delay.set_freeze_loop_beats(0.25, 120.0);
+        let loop_samples = (0.125 * sample_rate) as usize;
+
+        // Fill with a sine whose period doesn't divide the loop length
+        for i in 0..sample_rate as usize {
+            AudioProcessor::process(&mut delay, (i as f32 * 0.0173).sin());
+        }
+
+        delay.set_freeze(true);
+        assert!(delay.is_looping());
+
+        let outputs: Vec<f32> = (0..loop_samples * 3)
+            .map(|_| AudioProcessor::process(&mut delay, 1.0)) // Input is ignored while frozen
+            .collect();
+
+        // The slice repeats exactly
+        for i in 0..loop_samples * 2 {
+            assert_eq!(outputs[i], outputs[i + loop_samples]);
+        }
+
+        // And the wrap back to the start doesn't click
+        let max_step = outputs
+            .windows(2)
+            .map(|w| (w[1] - w[0]).abs())
+            .fold(0.0f32, f32::max);
+        assert!(max_step < 0.05, "Loop point jumped by {}", max_step);
+    }
+
     #[test]
     fn test_delay_line_feedback_one() {
         let sample_rate = 44100.0;
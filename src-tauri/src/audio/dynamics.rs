@@ -0,0 +1,130 @@
+use crate::audio::AudioProcessor;
+
+/// Peak envelope follower with separate attack and release times
+pub struct EnvelopeFollower {
+    attack_time: f32,
+    release_time: f32,
+    attack_coeff: f32,
+    release_coeff: f32,
+    envelope: f32,
+    sample_rate: f32,
+}
+
+impl EnvelopeFollower {
+    pub fn new(attack_time: f32, release_time: f32, sample_rate: f32) -> Self {
+        let mut follower = Self {
+            attack_time,
+            release_time,
+            attack_coeff: 0.0,
+            release_coeff: 0.0,
+            envelope: 0.0,
+            sample_rate,
+        };
+        follower.update_coefficients();
+        follower
+    }
+
+    pub fn set_attack_time(&mut self, attack_time: f32) {
+        self.attack_time = attack_time.max(0.0001);
+        self.update_coefficients();
+    }
+
+    pub fn set_release_time(&mut self, release_time: f32) {
+        self.release_time = release_time.max(0.001);
+        self.update_coefficients();
+    }
+
+    pub fn envelope(&self) -> f32 {
+        self.envelope
+    }
+
+    fn update_coefficients(&mut self) {
+        // One-pole smoothing: reaches ~63% of a step in the given time
+        self.attack_coeff = (-1.0 / (self.attack_time * self.sample_rate)).exp();
+        self.release_coeff = (-1.0 / (self.release_time * self.sample_rate)).exp();
+    }
+}
+
+impl AudioProcessor for EnvelopeFollower {
+    fn process(&mut self, input: f32) -> f32 {
+        let level = input.abs();
+        let coeff = if level > self.envelope {
+            self.attack_coeff
+        } else {
+            self.release_coeff
+        };
+        self.envelope = level + coeff * (self.envelope - level);
+        self.envelope
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.update_coefficients();
+    }
+}
+
+/// Turns a sidechain signal into a gain that dips while the sidechain is loud
+/// and recovers over the release time
+pub struct Ducker {
+    follower: EnvelopeFollower,
+    amount: f32,
+}
+
+impl Ducker {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            follower: EnvelopeFollower::new(0.005, 0.25, sample_rate), // 5ms attack, 250ms release
+            amount: 0.0,
+        }
+    }
+
+    /// 0.0 disables ducking, 1.0 fully mutes while the sidechain is at full level
+    pub fn set_amount(&mut self, amount: f32) {
+        self.amount = amount.clamp(0.0, 1.0);
+    }
+
+    /// How long the ducked signal takes to bloom back after a hit
+    pub fn set_release_time(&mut self, release_time: f32) {
+        self.follower.set_release_time(release_time);
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.follower.set_sample_rate(sample_rate);
+    }
+
+    /// Gain to apply to the ducked signal for the current sidechain sample
+    pub fn process(&mut self, sidechain: f32) -> f32 {
+        let envelope = self.follower.process(sidechain).min(1.0);
+        1.0 - self.amount * envelope
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ducker_dips_and_recovers() {
+        let sample_rate = 44100.0;
+        let mut ducker = Ducker::new(sample_rate);
+
+        // No ducking until an amount is set
+        assert_eq!(ducker.process(1.0), 1.0);
+
+        ducker.set_amount(0.8);
+        ducker.set_release_time(0.1);
+
+        // Dips while the sidechain is loud
+        let mut gain = 1.0;
+        for _ in 0..(0.05 * sample_rate) as usize {
+            gain = ducker.process(1.0);
+        }
+        assert!(gain < 0.25, "Expected ducked gain, got {}", gain);
+
+        // And blooms back once it goes quiet
+        for _ in 0..(0.5 * sample_rate) as usize {
+            gain = ducker.process(0.0);
+        }
+        assert!(gain > 0.99, "Expected recovered gain, got {}", gain);
+    }
+}
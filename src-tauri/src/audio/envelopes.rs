@@ -119,13 +119,18 @@ impl AudioGenerator for Segment {
     }
 }
 
+/// Fade time used when a voice is choked
+const CHOKE_TIME: f32 = 0.005;
+
 pub struct AREnvelope {
     attack_segment: Segment,
     release_segment: Segment,
     sample_rate: f32,
+    release_time: f32,
 
     state: AREnvelopeState,
     current_level: f32,
+    is_choked: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -141,8 +146,10 @@ impl AREnvelope {
             attack_segment: Segment::new(0.0, 1.0, 0.01, 0.3, sample_rate), // 10ms attack, logarithmic-like
             release_segment: Segment::new(1.0, 0.0, 0.1, 0.7, sample_rate), // 100ms release, exponential-like
             sample_rate,
+            release_time: 0.1,
             state: AREnvelopeState::Idle,
             current_level: 0.0,
+            is_choked: false,
         }
     }
 
@@ -153,7 +160,10 @@ impl AREnvelope {
 
     pub fn set_release_time(&mut self, time: f32) {
         let time = time.max(0.001); // Minimum 1ms
-        self.release_segment.set_duration_seconds(time);
+        self.release_time = time;
+        if !self.is_choked {
+            self.release_segment.set_duration_seconds(time);
+        }
     }
 
     pub fn set_attack_bias(&mut self, bias: f32) {
@@ -164,7 +174,27 @@ impl AREnvelope {
         self.release_segment.set_bias(bias);
     }
 
+    /// Fade out from the current level over a few milliseconds
+    pub fn choke(&mut self) {
+        if self.state == AREnvelopeState::Idle {
+            return;
+        }
+
+        self.is_choked = true;
+        self.state = AREnvelopeState::Release;
+        self.release_segment.set_start_value(self.current_level);
+        self.release_segment.set_duration_seconds(CHOKE_TIME);
+        self.release_segment.trigger();
+    }
+
     pub fn trigger(&mut self) {
+        if self.is_choked {
+            // Restore the normal release after a choke
+            self.is_choked = false;
+            self.release_segment.set_start_value(1.0);
+            self.release_segment.set_duration_seconds(self.release_time);
+        }
+
         self.state = AREnvelopeState::Attack;
         // Start attack from current level to avoid pops
         self.attack_segment.set_start_value(self.current_level);
@@ -365,6 +395,39 @@ mod tests {
         assert!(!env.is_active());
     }
 
+    #[test]
+    fn test_ar_envelope_choke() {
+        let sample_rate = 44100.0;
+        let mut env = AREnvelope::new(sample_rate);
+        env.set_attack_time(0.01);
+        env.set_release_time(1.0);
+
+        env.trigger();
+        for _ in 0..(0.05 * sample_rate) as usize {
+            env.next_sample();
+        }
+        assert!(env.is_active());
+
+        // Choked envelope is silent within a few milliseconds
+        env.choke();
+        let choke_samples = (0.006 * sample_rate) as usize;
+        let mut previous = 1.0f32;
+        for _ in 0..choke_samples {
+            let level = env.next_sample();
+            assert!(level <= previous + 1e-6, "Choke should only fall");
+            previous = level;
+        }
+        env.next_sample();
+        assert!(!env.is_active(), "Envelope should be idle after choking");
+
+        // The next trigger uses the full release again
+        env.trigger();
+        for _ in 0..(0.5 * sample_rate) as usize {
+            env.next_sample();
+        }
+        assert!(env.is_active(), "Release should be restored after a choke");
+    }
+
     #[test]
     fn test_ar_envelope_levels() {
         let sample_rate = 44100.0;
@@ -0,0 +1,178 @@
+use crate::sequencing::TransportClock;
+
+/// Longest wait between mutations
+pub const MAX_EVOLVE_BARS: u32 = 64;
+
+/// Random source and strength handed to a system when it's due to evolve.
+/// Every helper scales with the intensity, so at 0 nothing changes.
+pub struct Mutations {
+    rng: fastrand::Rng,
+    intensity: f32,
+}
+
+impl Mutations {
+    fn new(seed: u64) -> Self {
+        Self {
+            rng: fastrand::Rng::with_seed(seed),
+            intensity: 0.25,
+        }
+    }
+
+    /// 0 leaves the system alone, 1 makes the biggest changes
+    pub fn intensity(&self) -> f32 {
+        self.intensity
+    }
+
+    /// Whether to make a change that happens with the given probability at
+    /// full intensity
+    pub fn chance(&mut self, probability: f32) -> bool {
+        self.rng.f32() < probability * self.intensity
+    }
+
+    /// Move a value up or down by at most `range` at full intensity, kept
+    /// within min and max
+    pub fn nudge(&mut self, value: f32, range: f32, min: f32, max: f32) -> f32 {
+        (value + self.bipolar() * range * self.intensity).clamp(min, max)
+    }
+
+    /// Scale a frequency up or down by at most `octaves` at full intensity
+    pub fn drift_octaves(&mut self, frequency: f32, octaves: f32) -> f32 {
+        frequency * (self.bipolar() * octaves * self.intensity).exp2()
+    }
+
+    /// A random index below `len`, which must not be zero
+    pub fn index(&mut self, len: usize) -> usize {
+        self.rng.usize(..len)
+    }
+
+    fn bipolar(&mut self) -> f32 {
+        self.rng.f32() * 2.0 - 1.0
+    }
+}
+
+/// Asks a system to mutate itself every few bars while enabled, so it
+/// develops slowly over a long session. Bars are counted on the clock it's
+/// ticked with, so mutations land on bar lines.
+pub struct Evolver {
+    enabled: bool,
+    every_bars: u32,
+    // Bar the clock was last in
+    bar: Option<u64>,
+    bars_until_due: u32,
+    mutations: Mutations,
+}
+
+impl Default for Evolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Evolver {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            every_bars: 4,
+            bar: None,
+            bars_until_due: 4,
+            mutations: Mutations::new(0),
+        }
+    }
+
+    /// Turning on starts counting bars from now
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if enabled && !self.enabled {
+            self.restart();
+        }
+        self.enabled = enabled;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Bars between mutations, from 1 to 64; restarts the count
+    pub fn set_every_bars(&mut self, bars: u32) {
+        self.every_bars = bars.clamp(1, MAX_EVOLVE_BARS);
+        self.restart();
+    }
+
+    /// How big each mutation is, 0 to 1
+    pub fn set_intensity(&mut self, intensity: f32) {
+        self.mutations.intensity = intensity.clamp(0.0, 1.0);
+    }
+
+    /// Restart the random sequence, so the same seed evolves the same way
+    pub fn set_seed(&mut self, seed: u64) {
+        self.mutations.rng.seed(seed);
+    }
+
+    fn restart(&mut self) {
+        self.bars_until_due = self.every_bars;
+    }
+
+    /// Step one frame, returning the mutations to apply when one is due
+    pub fn tick(&mut self, clock: &TransportClock) -> Option<&mut Mutations> {
+        let bar = clock.bar();
+        let at_bar_line = self.bar.is_some_and(|last| last != bar);
+        self.bar = Some(bar);
+        if !self.enabled || !at_bar_line {
+            return None;
+        }
+        self.bars_until_due = self.bars_until_due.saturating_sub(1);
+        if self.bars_until_due > 0 {
+            return None;
+        }
+        self.restart();
+        Some(&mut self.mutations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evolver_is_due_every_n_bars_and_repeats_with_a_seed() {
+        // A bar at 60 BPM is 4 seconds, 40 frames at 10Hz
+        let mut clock = TransportClock {
+            bpm: 60.0,
+            beats_per_bar: 4,
+            beats: 0.0,
+        };
+        let mut evolver = Evolver::new();
+        evolver.set_every_bars(2);
+        for _ in 0..250 {
+            assert!(evolver.tick(&clock).is_none());
+            clock.advance(10.0);
+        }
+
+        evolver.set_enabled(true);
+        let due: Vec<usize> = (1..=250)
+            .filter(|_| {
+                let due = evolver.tick(&clock).is_some();
+                clock.advance(10.0);
+                due
+            })
+            .collect();
+        assert_eq!(due.len(), 3);
+
+        let draws = |seed: u64| {
+            let mut evolver = Evolver::new();
+            evolver.set_seed(seed);
+            evolver.set_intensity(1.0);
+            let mutations = &mut evolver.mutations;
+            (0..8)
+                .map(|_| mutations.nudge(0.5, 0.5, 0.0, 1.0))
+                .collect::<Vec<f32>>()
+        };
+        assert_eq!(draws(7), draws(7));
+        assert_ne!(draws(7), draws(8));
+
+        // Nothing moves at zero intensity
+        let mut mutations = Mutations::new(1);
+        mutations.intensity = 0.0;
+        assert_eq!(mutations.nudge(0.5, 0.5, 0.0, 1.0), 0.5);
+        assert!(!mutations.chance(1.0));
+    }
+}
@@ -1,5 +1,5 @@
 use crate::audio::buffers::DelayBuffer;
-use crate::audio::{AudioProcessor, PI};
+use crate::audio::{AudioProcessor, StereoAudioProcessor, PI};
 
 // Tan approximation function
 fn tan_a(x: f32) -> f32 {
@@ -87,6 +87,16 @@ impl SVF {
         }
     }
 
+    /// Set the cutoff and recompute only the frequency-dependent coefficients.
+    /// Cheap enough to call every sample for audio-rate cutoff modulation.
+    pub fn modulate_cutoff_frequency(&mut self, cf: f32) {
+        self.update_coefficients();
+        self.cf = cf.clamp(10.0, self.sample_rate * 0.49);
+        self.g = tan_a(self.cf * PI / self.sample_rate);
+        self.h = 1.0 / (1.0 + self.r * self.g + self.g * self.g);
+        self.rpg = self.r + self.g;
+    }
+
     pub fn set_mode(&mut self, mode: FilterMode) {
         self.mode = mode;
     }
@@ -197,6 +207,398 @@ impl AudioProcessor for OnePoleFilter {
     }
 }
 
+#[derive(Clone, Copy, PartialEq)]
+pub enum FilterSlope {
+    Db6,
+    Db12,
+    Db24,
+}
+
+impl FilterSlope {
+    fn stages(self) -> usize {
+        match self {
+            FilterSlope::Db6 => 1,
+            FilterSlope::Db12 => 2,
+            FilterSlope::Db24 => 4,
+        }
+    }
+}
+
+// Cascade of one-pole stages for selectable 6/12/24 dB per octave slopes
+pub struct FilterCascade {
+    stages: [OnePoleFilter; 4],
+    slope: FilterSlope,
+}
+
+impl FilterCascade {
+    pub fn new(cutoff: f32, mode: OnePoleMode, slope: FilterSlope, sample_rate: f32) -> Self {
+        Self {
+            stages: [
+                OnePoleFilter::new(cutoff, mode, sample_rate),
+                OnePoleFilter::new(cutoff, mode, sample_rate),
+                OnePoleFilter::new(cutoff, mode, sample_rate),
+                OnePoleFilter::new(cutoff, mode, sample_rate),
+            ],
+            slope,
+        }
+    }
+
+    pub fn set_cutoff_frequency(&mut self, cutoff: f32) {
+        for stage in &mut self.stages {
+            stage.set_cutoff_frequency(cutoff);
+        }
+    }
+
+    pub fn set_mode(&mut self, mode: OnePoleMode) {
+        for stage in &mut self.stages {
+            stage.set_mode(mode);
+        }
+    }
+
+    pub fn set_slope(&mut self, slope: FilterSlope) {
+        if slope != self.slope {
+            self.slope = slope;
+            // Newly engaged stages start from silence, so clear them all
+            self.reset();
+        }
+    }
+
+    pub fn reset(&mut self) {
+        for stage in &mut self.stages {
+            stage.reset();
+        }
+    }
+}
+
+impl AudioProcessor for FilterCascade {
+    fn process(&mut self, input: f32) -> f32 {
+        let mut output = input;
+        for stage in self.stages.iter_mut().take(self.slope.stages()) {
+            output = stage.process(output);
+        }
+        output
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        for stage in &mut self.stages {
+            stage.set_sample_rate(sample_rate);
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum BiquadMode {
+    LowShelf,
+    HighShelf,
+    Peak,
+}
+
+// Biquad shelf/peak filter using the RBJ Audio EQ Cookbook formulas
+// Processed in transposed direct form II
+pub struct Biquad {
+    // State variables
+    z1: f32,
+    z2: f32,
+
+    // Filter parameters
+    mode: BiquadMode,
+    frequency: f32,
+    q: f32,
+    gain_db: f32,
+    sample_rate: f32,
+
+    // Normalized coefficients (a0 = 1)
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+
+    coeffs_dirty: bool,
+}
+
+impl Biquad {
+    pub fn new(frequency: f32, q: f32, gain_db: f32, mode: BiquadMode, sample_rate: f32) -> Self {
+        let mut biquad = Self {
+            z1: 0.0,
+            z2: 0.0,
+            mode,
+            frequency,
+            q,
+            gain_db,
+            sample_rate,
+            b0: 1.0,
+            b1: 0.0,
+            b2: 0.0,
+            a1: 0.0,
+            a2: 0.0,
+            coeffs_dirty: true,
+        };
+        biquad.update_coefficients();
+        biquad
+    }
+
+    fn update_coefficients(&mut self) {
+        if !self.coeffs_dirty {
+            return;
+        }
+
+        let frequency = self.frequency.clamp(10.0, self.sample_rate * 0.49);
+        let a = 10.0f32.powf(self.gain_db / 40.0);
+        let w0 = 2.0 * PI * frequency / self.sample_rate;
+        let cos_w0 = w0.cos();
+        let alpha = w0.sin() / (2.0 * self.q.max(0.1));
+
+        let (b0, b1, b2, a0, a1, a2) = match self.mode {
+            BiquadMode::Peak => (
+                1.0 + alpha * a,
+                -2.0 * cos_w0,
+                1.0 - alpha * a,
+                1.0 + alpha / a,
+                -2.0 * cos_w0,
+                1.0 - alpha / a,
+            ),
+            BiquadMode::LowShelf => {
+                let two_sqrt_a_alpha = 2.0 * a.sqrt() * alpha;
+                (
+                    a * ((a + 1.0) - (a - 1.0) * cos_w0 + two_sqrt_a_alpha),
+                    2.0 * a * ((a - 1.0) - (a + 1.0) * cos_w0),
+                    a * ((a + 1.0) - (a - 1.0) * cos_w0 - two_sqrt_a_alpha),
+                    (a + 1.0) + (a - 1.0) * cos_w0 + two_sqrt_a_alpha,
+                    -2.0 * ((a - 1.0) + (a + 1.0) * cos_w0),
+                    (a + 1.0) + (a - 1.0) * cos_w0 - two_sqrt_a_alpha,
+                )
+            }
+            BiquadMode::HighShelf => {
+                let two_sqrt_a_alpha = 2.0 * a.sqrt() * alpha;
+                (
+                    a * ((a + 1.0) + (a - 1.0) * cos_w0 + two_sqrt_a_alpha),
+                    -2.0 * a * ((a - 1.0) + (a + 1.0) * cos_w0),
+                    a * ((a + 1.0) + (a - 1.0) * cos_w0 - two_sqrt_a_alpha),
+                    (a + 1.0) - (a - 1.0) * cos_w0 + two_sqrt_a_alpha,
+                    2.0 * ((a - 1.0) - (a + 1.0) * cos_w0),
+                    (a + 1.0) - (a - 1.0) * cos_w0 - two_sqrt_a_alpha,
+                )
+            }
+        };
+
+        self.b0 = b0 / a0;
+        self.b1 = b1 / a0;
+        self.b2 = b2 / a0;
+        self.a1 = a1 / a0;
+        self.a2 = a2 / a0;
+        self.coeffs_dirty = false;
+    }
+
+    pub fn set_frequency(&mut self, frequency: f32) {
+        if (self.frequency - frequency).abs() > f32::EPSILON {
+            self.frequency = frequency;
+            self.coeffs_dirty = true;
+        }
+    }
+
+    pub fn set_q(&mut self, q: f32) {
+        if (self.q - q).abs() > f32::EPSILON {
+            self.q = q;
+            self.coeffs_dirty = true;
+        }
+    }
+
+    pub fn set_gain_db(&mut self, gain_db: f32) {
+        if (self.gain_db - gain_db).abs() > f32::EPSILON {
+            self.gain_db = gain_db;
+            self.coeffs_dirty = true;
+        }
+    }
+
+    pub fn set_mode(&mut self, mode: BiquadMode) {
+        self.mode = mode;
+        self.coeffs_dirty = true;
+    }
+
+    pub fn reset(&mut self) {
+        self.z1 = 0.0;
+        self.z2 = 0.0;
+    }
+}
+
+impl AudioProcessor for Biquad {
+    fn process(&mut self, input: f32) -> f32 {
+        self.update_coefficients();
+
+        let output = self.b0 * input + self.z1;
+        self.z1 = self.b1 * input - self.a1 * output + self.z2;
+        self.z2 = self.b2 * input - self.a2 * output;
+        output
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.coeffs_dirty = true;
+        self.update_coefficients();
+    }
+}
+
+// Three-band parametric EQ: low shelf, peaking mid and high shelf
+pub struct ParametricEQ {
+    low_shelf: Biquad,
+    mid_peak: Biquad,
+    high_shelf: Biquad,
+}
+
+impl ParametricEQ {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            low_shelf: Biquad::new(120.0, 0.707, 0.0, BiquadMode::LowShelf, sample_rate),
+            mid_peak: Biquad::new(1000.0, 1.0, 0.0, BiquadMode::Peak, sample_rate),
+            high_shelf: Biquad::new(8000.0, 0.707, 0.0, BiquadMode::HighShelf, sample_rate),
+        }
+    }
+
+    pub fn set_low_frequency(&mut self, frequency: f32) {
+        self.low_shelf.set_frequency(frequency);
+    }
+
+    pub fn set_low_gain(&mut self, gain_db: f32) {
+        self.low_shelf.set_gain_db(gain_db.clamp(-24.0, 24.0));
+    }
+
+    pub fn set_mid_frequency(&mut self, frequency: f32) {
+        self.mid_peak.set_frequency(frequency);
+    }
+
+    pub fn set_mid_gain(&mut self, gain_db: f32) {
+        self.mid_peak.set_gain_db(gain_db.clamp(-24.0, 24.0));
+    }
+
+    pub fn set_mid_q(&mut self, q: f32) {
+        self.mid_peak.set_q(q.clamp(0.1, 10.0));
+    }
+
+    pub fn set_high_frequency(&mut self, frequency: f32) {
+        self.high_shelf.set_frequency(frequency);
+    }
+
+    pub fn set_high_gain(&mut self, gain_db: f32) {
+        self.high_shelf.set_gain_db(gain_db.clamp(-24.0, 24.0));
+    }
+
+    pub fn reset(&mut self) {
+        self.low_shelf.reset();
+        self.mid_peak.reset();
+        self.high_shelf.reset();
+    }
+}
+
+impl AudioProcessor for ParametricEQ {
+    fn process(&mut self, input: f32) -> f32 {
+        let low = self.low_shelf.process(input);
+        let mid = self.mid_peak.process(low);
+        self.high_shelf.process(mid)
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.low_shelf.set_sample_rate(sample_rate);
+        self.mid_peak.set_sample_rate(sample_rate);
+        self.high_shelf.set_sample_rate(sample_rate);
+    }
+}
+
+// DJ-style filter: one bipolar control sweeps lowpass (negative) through
+// neutral (center) to highpass (positive)
+pub struct DJFilter {
+    filter_left: SVF,
+    filter_right: SVF,
+    position: f32,
+    smoothed_position: f32,
+    resonance: f32,
+    smoothing_coeff: f32,
+}
+
+impl DJFilter {
+    // Knob range around center that passes the signal untouched
+    const DEAD_ZONE: f32 = 0.02;
+    const MIN_CUTOFF: f32 = 20.0;
+    const MAX_CUTOFF: f32 = 20000.0;
+
+    pub fn new(sample_rate: f32) -> Self {
+        let mut filter = Self {
+            filter_left: SVF::new(Self::MAX_CUTOFF, 0.707, FilterMode::Lowpass, sample_rate),
+            filter_right: SVF::new(Self::MAX_CUTOFF, 0.707, FilterMode::Lowpass, sample_rate),
+            position: 0.0,
+            smoothed_position: 0.0,
+            resonance: 0.707,
+            smoothing_coeff: 0.0,
+        };
+        filter.update_smoothing(sample_rate);
+        filter
+    }
+
+    fn update_smoothing(&mut self, sample_rate: f32) {
+        // ~20ms knob smoothing to avoid zipper noise
+        self.smoothing_coeff = 1.0 - (-1.0 / (0.02 * sample_rate)).exp();
+    }
+
+    /// Set the knob position: -1.0 = lowpass closed, 0.0 = neutral, 1.0 = highpass closed
+    pub fn set_position(&mut self, position: f32) {
+        self.position = position.clamp(-1.0, 1.0);
+    }
+
+    pub fn position(&self) -> f32 {
+        self.position
+    }
+
+    pub fn set_resonance(&mut self, resonance: f32) {
+        self.resonance = resonance.clamp(0.5, 8.0);
+    }
+
+    pub fn reset(&mut self) {
+        self.filter_left.reset();
+        self.filter_right.reset();
+    }
+}
+
+impl StereoAudioProcessor for DJFilter {
+    fn process(&mut self, left: f32, right: f32) -> (f32, f32) {
+        self.smoothed_position += (self.position - self.smoothed_position) * self.smoothing_coeff;
+        let amount = self.smoothed_position.abs();
+
+        // Exponential sweep: lowpass closes from the top, highpass opens from the bottom
+        let (mode, cutoff) = if self.smoothed_position < 0.0 {
+            let ratio = Self::MIN_CUTOFF / Self::MAX_CUTOFF;
+            (FilterMode::Lowpass, Self::MAX_CUTOFF * ratio.powf(amount))
+        } else {
+            let ratio = Self::MAX_CUTOFF / Self::MIN_CUTOFF;
+            (FilterMode::Highpass, Self::MIN_CUTOFF * ratio.powf(amount))
+        };
+
+        // Fade resonance in away from center so the neutral point stays flat
+        let resonance = 0.707 + (self.resonance - 0.707) * (amount * 4.0).min(1.0);
+
+        for filter in [&mut self.filter_left, &mut self.filter_right] {
+            filter.set_mode(mode);
+            filter.set_resonance(resonance);
+            filter.modulate_cutoff_frequency(cutoff);
+        }
+
+        // Keep the filters running in the dead zone so leaving it doesn't click
+        let filtered_left = self.filter_left.process(left);
+        let filtered_right = self.filter_right.process(right);
+
+        if amount < Self::DEAD_ZONE {
+            (left, right)
+        } else {
+            (filtered_left, filtered_right)
+        }
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.filter_left.set_sample_rate(sample_rate);
+        self.filter_right.set_sample_rate(sample_rate);
+        self.update_smoothing(sample_rate);
+    }
+}
+
 // Allpass filter
 pub struct Allpass {
     delay: DelayBuffer,
@@ -236,3 +638,146 @@ impl AudioProcessor for Allpass {
         self.sample_rate = sample_rate;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Measure the steady-state gain of a processor at a given frequency
+    fn measure_gain(filter: &mut impl AudioProcessor, frequency: f32, sample_rate: f32) -> f32 {
+        let mut peak = 0.0f32;
+        let total_samples = (sample_rate * 0.5) as usize;
+        for i in 0..total_samples {
+            let input = (2.0 * PI * frequency * i as f32 / sample_rate).sin();
+            let output = filter.process(input);
+            // Skip the transient at the start
+            if i > total_samples / 2 {
+                peak = peak.max(output.abs());
+            }
+        }
+        peak
+    }
+
+    #[test]
+    fn test_biquad_flat_at_zero_gain() {
+        let sample_rate = 44100.0;
+        for mode in [
+            BiquadMode::LowShelf,
+            BiquadMode::HighShelf,
+            BiquadMode::Peak,
+        ] {
+            let mut biquad = Biquad::new(1000.0, 0.707, 0.0, mode, sample_rate);
+            for frequency in [100.0, 1000.0, 10000.0] {
+                let gain = measure_gain(&mut biquad, frequency, sample_rate);
+                assert!(
+                    (gain - 1.0).abs() < 0.01,
+                    "0 dB biquad should be transparent at {} Hz, got gain {}",
+                    frequency,
+                    gain
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_biquad_shelf_and_peak_gains() {
+        let sample_rate = 44100.0;
+        let boost = 10.0f32.powf(12.0 / 20.0); // +12 dB
+
+        let mut low_shelf = Biquad::new(200.0, 0.707, 12.0, BiquadMode::LowShelf, sample_rate);
+        let low_gain = measure_gain(&mut low_shelf, 40.0, sample_rate);
+        assert!(
+            (low_gain - boost).abs() / boost < 0.1,
+            "Low shelf should boost low frequencies by 12 dB, got gain {}",
+            low_gain
+        );
+        low_shelf.reset();
+        let low_shelf_high = measure_gain(&mut low_shelf, 8000.0, sample_rate);
+        assert!(
+            (low_shelf_high - 1.0).abs() < 0.1,
+            "Low shelf should pass high frequencies, got gain {}",
+            low_shelf_high
+        );
+
+        let mut high_shelf = Biquad::new(2000.0, 0.707, 12.0, BiquadMode::HighShelf, sample_rate);
+        let high_gain = measure_gain(&mut high_shelf, 15000.0, sample_rate);
+        assert!(
+            (high_gain - boost).abs() / boost < 0.1,
+            "High shelf should boost high frequencies by 12 dB, got gain {}",
+            high_gain
+        );
+
+        let mut peak = Biquad::new(1000.0, 2.0, -12.0, BiquadMode::Peak, sample_rate);
+        let peak_gain = measure_gain(&mut peak, 1000.0, sample_rate);
+        assert!(
+            (peak_gain - 1.0 / boost).abs() < 0.05,
+            "Peak filter should cut 12 dB at its center, got gain {}",
+            peak_gain
+        );
+    }
+
+    #[test]
+    fn test_filter_cascade_slopes() {
+        let sample_rate = 44100.0;
+        let mut previous_gain = 1.0f32;
+
+        // Each steeper slope should attenuate more well above the cutoff
+        for slope in [FilterSlope::Db6, FilterSlope::Db12, FilterSlope::Db24] {
+            let mut cascade = FilterCascade::new(500.0, OnePoleMode::Lowpass, slope, sample_rate);
+            let gain = measure_gain(&mut cascade, 4000.0, sample_rate);
+            assert!(
+                gain < previous_gain,
+                "Steeper slope should attenuate more: gain {} vs previous {}",
+                gain,
+                previous_gain
+            );
+            previous_gain = gain;
+        }
+
+        // Passband should stay close to unity for every slope
+        let mut cascade =
+            FilterCascade::new(5000.0, OnePoleMode::Lowpass, FilterSlope::Db24, sample_rate);
+        let passband_gain = measure_gain(&mut cascade, 100.0, sample_rate);
+        assert!(
+            (passband_gain - 1.0).abs() < 0.05,
+            "Passband gain should be near unity, got {}",
+            passband_gain
+        );
+    }
+
+    #[test]
+    fn test_dj_filter_neutral_and_sweeps() {
+        let sample_rate = 44100.0;
+        let tone = |i: usize, frequency: f32| (2.0 * PI * frequency * i as f32 / sample_rate).sin();
+
+        // Neutral position passes the signal through untouched
+        let mut filter = DJFilter::new(sample_rate);
+        for i in 0..1000 {
+            let input = tone(i, 1000.0);
+            let (left, right) = filter.process(input, input);
+            assert_eq!(left, input);
+            assert_eq!(right, input);
+        }
+
+        // Fully left (lowpass) removes high frequencies, fully right (highpass) removes lows
+        for (position, frequency) in [(-1.0, 5000.0), (1.0, 100.0)] {
+            let mut filter = DJFilter::new(sample_rate);
+            filter.set_position(position);
+            let mut peak = 0.0f32;
+            for i in 0..(sample_rate as usize) {
+                let input = tone(i, frequency);
+                let (left, _) = filter.process(input, input);
+                if i > sample_rate as usize / 2 {
+                    peak = peak.max(left.abs());
+                }
+            }
+            assert!(
+                peak < 0.1,
+                "Position {} should attenuate {} Hz, got peak {}",
+                position,
+                frequency,
+                peak
+            );
+        }
+    }
+}
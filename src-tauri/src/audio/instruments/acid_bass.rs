@@ -0,0 +1,289 @@
+use crate::audio::envelopes::AREnvelope;
+use crate::audio::filters::{FilterMode, SVF};
+use crate::audio::oscillators::{PolyBlepPulse, PolyBlepSaw};
+use crate::audio::{AudioGenerator, AudioProcessor};
+
+/// The 303 slides over a fixed time, a little under a sixteenth at 130 BPM
+const DEFAULT_SLIDE_SECONDS: f32 = 0.06;
+const MAX_SLIDE_SECONDS: f32 = 0.5;
+
+/// Most octaves the filter envelope opens the cutoff by
+const MAX_ENV_MOD_OCTAVES: f32 = 5.0;
+
+/// TB-303 style bass: a saw or square into a 24 dB resonant lowpass made of
+/// two cascaded SVFs, with a decaying filter envelope, accents that boost the
+/// level and sharpen the envelope, and notes that slide into each other
+pub struct AcidBass {
+    // Saw and square run in step, started and tuned together
+    saw: PolyBlepSaw,
+    square: PolyBlepPulse,
+    filter_a: SVF,
+    filter_b: SVF,
+    amp_envelope: AREnvelope,
+    filter_envelope: AREnvelope,
+    // Frequency now and the one being slid to
+    frequency: f32,
+    target_frequency: f32,
+    // Octaves the frequency moves each sample while sliding
+    slide_step: f32,
+    slide_time: f32,
+    waveform: f32, // 0 = saw, 1 = square
+    cutoff: f32,
+    env_mod: f32, // 0 to 1 of the deepest filter sweep
+    accent: f32,  // How much an accent adds, 0 to 1
+    accented: bool,
+    velocity: f32, // Level of the last note, 0 to 1
+    decay: f32,
+    gain: f32,
+    sample_rate: f32,
+}
+
+impl AcidBass {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut bass = Self {
+            saw: PolyBlepSaw::new(55.0, sample_rate),
+            square: PolyBlepPulse::new(55.0, sample_rate),
+            filter_a: SVF::new(500.0, 4.0, FilterMode::Lowpass, sample_rate),
+            filter_b: SVF::new(500.0, 0.7, FilterMode::Lowpass, sample_rate),
+            amp_envelope: AREnvelope::new(sample_rate),
+            filter_envelope: AREnvelope::new(sample_rate),
+            frequency: 55.0,
+            target_frequency: 55.0,
+            slide_step: 0.0,
+            slide_time: DEFAULT_SLIDE_SECONDS,
+            waveform: 0.0,
+            cutoff: 500.0,
+            env_mod: 0.5,
+            accent: 0.5,
+            accented: false,
+            velocity: 1.0,
+            decay: 0.3,
+            gain: 1.0,
+            sample_rate,
+        };
+
+        bass.amp_envelope.set_attack_time(0.003);
+        bass.amp_envelope.set_release_time(0.6);
+        bass.amp_envelope.set_attack_bias(0.3); // Logarithmic-like
+        bass.amp_envelope.set_release_bias(0.6);
+
+        bass.filter_envelope.set_attack_time(0.001);
+        bass.filter_envelope.set_release_bias(0.7); // Exponential-like
+        bass.update_filter_decay();
+
+        bass
+    }
+
+    /// Play a note scaled by the velocity. An accent adds level on top, with
+    /// a shorter, deeper filter sweep; a slide glides from the ringing note
+    /// without restarting it.
+    pub fn trigger(&mut self, velocity: f32, accented: bool, slide: bool) {
+        if slide && self.is_active() {
+            let slide_samples = self.slide_time * self.sample_rate;
+            if slide_samples >= 1.0 {
+                self.slide_step = (self.target_frequency / self.frequency).log2() / slide_samples;
+            } else {
+                self.frequency = self.target_frequency;
+            }
+            return;
+        }
+
+        // A note started from silence has nothing to slide from
+        if !self.is_active() {
+            self.saw.reset();
+            self.square.reset();
+        }
+        self.frequency = self.target_frequency;
+        self.slide_step = 0.0;
+        self.velocity = velocity.clamp(0.0, 1.0);
+        self.accented = accented;
+        self.update_filter_decay();
+        self.amp_envelope.trigger();
+        self.filter_envelope.trigger();
+    }
+
+    /// Fast-release the voice (used by choke groups)
+    pub fn choke(&mut self) {
+        self.amp_envelope.choke();
+    }
+
+    /// Tune the next note; the pitch only changes on a trigger, sliding there
+    /// if the trigger asks to
+    pub fn set_base_frequency(&mut self, frequency: f32) {
+        self.target_frequency = frequency.clamp(20.0, 2000.0);
+    }
+
+    pub fn set_slide_time(&mut self, seconds: f32) {
+        self.slide_time = seconds.clamp(0.0, MAX_SLIDE_SECONDS);
+    }
+
+    /// Saw (0) or square (1); values between blend the two
+    pub fn set_waveform(&mut self, waveform: f32) {
+        self.waveform = waveform.clamp(0.0, 1.0);
+    }
+
+    pub fn set_cutoff(&mut self, cutoff: f32) {
+        self.cutoff = cutoff.clamp(20.0, 10000.0);
+    }
+
+    pub fn set_resonance(&mut self, resonance: f32) {
+        self.filter_a.set_resonance(resonance.clamp(0.5, 20.0));
+    }
+
+    /// Depth of the filter envelope, 0 to 1
+    pub fn set_env_mod(&mut self, amount: f32) {
+        self.env_mod = amount.clamp(0.0, 1.0);
+    }
+
+    /// Filter envelope decay; accented notes use a shorter one, as on the 303
+    pub fn set_decay(&mut self, time: f32) {
+        self.decay = time.clamp(0.01, 3.0);
+        self.update_filter_decay();
+    }
+
+    /// How much louder and brighter accented notes are
+    pub fn set_accent(&mut self, accent: f32) {
+        self.accent = accent.clamp(0.0, 1.0);
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.amp_envelope.is_active()
+    }
+
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain = gain;
+    }
+
+    fn update_filter_decay(&mut self) {
+        let decay = if self.accented {
+            self.decay.min(0.2)
+        } else {
+            self.decay
+        };
+        self.filter_envelope.set_release_time(decay);
+    }
+
+    fn advance_slide(&mut self) {
+        if self.slide_step == 0.0 {
+            return;
+        }
+        let next = self.frequency * self.slide_step.exp2();
+        let arrived = if self.slide_step > 0.0 {
+            next >= self.target_frequency
+        } else {
+            next <= self.target_frequency
+        };
+        if arrived {
+            self.frequency = self.target_frequency;
+            self.slide_step = 0.0;
+        } else {
+            self.frequency = next;
+        }
+    }
+}
+
+impl AudioGenerator for AcidBass {
+    fn next_sample(&mut self) -> f32 {
+        if !self.is_active() {
+            return 0.0;
+        }
+
+        self.advance_slide();
+        self.saw.set_frequency(self.frequency);
+        self.square.set_frequency(self.frequency);
+        let saw = self.saw.next_sample();
+        let square = self.square.next_sample();
+        let wave = saw + (square - saw) * self.waveform;
+
+        let accent = if self.accented { self.accent } else { 0.0 };
+        let sweep = self.filter_envelope.next_sample()
+            * (self.env_mod + accent * 0.5)
+            * MAX_ENV_MOD_OCTAVES;
+        let cutoff = self.cutoff * sweep.exp2();
+        self.filter_a.modulate_cutoff_frequency(cutoff);
+        self.filter_b.modulate_cutoff_frequency(cutoff);
+        let filtered = self.filter_b.process(self.filter_a.process(wave));
+
+        let level = self.amp_envelope.next_sample() * (self.velocity + accent);
+        filtered.tanh() * level * self.gain
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.saw.set_sample_rate(sample_rate);
+        self.square.set_sample_rate(sample_rate);
+        self.filter_a.set_sample_rate(sample_rate);
+        self.filter_b.set_sample_rate(sample_rate);
+        self.amp_envelope.set_sample_rate(sample_rate);
+        self.filter_envelope.set_sample_rate(sample_rate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 44100.0;
+
+    fn peak(bass: &mut AcidBass, samples: usize) -> f32 {
+        (0..samples).map(|_| bass.next_sample().abs()).fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn test_slide_glides_without_retriggering() {
+        let mut bass = AcidBass::new(SAMPLE_RATE);
+        bass.set_base_frequency(110.0);
+        bass.trigger(1.0, false, false);
+        peak(&mut bass, 1000);
+
+        bass.set_base_frequency(220.0);
+        bass.trigger(1.0, false, true);
+        assert_eq!(bass.frequency, 110.0, "Slide should start from the old note");
+
+        // The default slide is 60ms
+        peak(&mut bass, 1323);
+        assert!(
+            bass.frequency > 140.0 && bass.frequency < 170.0,
+            "Expected a frequency between the notes, got {}",
+            bass.frequency
+        );
+        peak(&mut bass, 1400);
+        assert_eq!(bass.frequency, 220.0, "Slide should land on the new note");
+    }
+
+    #[test]
+    fn test_notes_without_slide_jump_to_pitch() {
+        let mut bass = AcidBass::new(SAMPLE_RATE);
+        bass.set_base_frequency(110.0);
+        bass.trigger(1.0, false, false);
+        peak(&mut bass, 1000);
+
+        bass.set_base_frequency(220.0);
+        assert_eq!(bass.frequency, 110.0, "Pitch should wait for the trigger");
+        bass.trigger(1.0, false, false);
+        assert_eq!(bass.frequency, 220.0);
+    }
+
+    #[test]
+    fn test_accent_is_louder_with_a_shorter_sweep() {
+        let mut plain = AcidBass::new(SAMPLE_RATE);
+        plain.set_decay(1.0);
+        plain.trigger(1.0, false, false);
+        let plain_peak = peak(&mut plain, 11025);
+
+        let mut accented = AcidBass::new(SAMPLE_RATE);
+        accented.set_decay(1.0);
+        accented.trigger(1.0, true, false);
+        let accented_peak = peak(&mut accented, 11025);
+
+        assert!(
+            accented_peak > plain_peak * 1.2,
+            "Accent peak {} should be well above {}",
+            accented_peak,
+            plain_peak
+        );
+        // Accents cap the filter decay at 200ms, as on the 303
+        assert!(!accented.filter_envelope.is_active());
+        assert!(plain.filter_envelope.is_active());
+    }
+}
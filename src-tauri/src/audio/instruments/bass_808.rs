@@ -0,0 +1,265 @@
+use crate::audio::envelopes::AREnvelope;
+use crate::audio::oscillators::{PhaseDistortionOscillator, TriangleOscillator};
+use crate::audio::AudioGenerator;
+
+/// Longest slide between two notes
+const MAX_GLIDE_SECONDS: f32 = 1.0;
+
+/// 808-style bass: a long-decaying sine that can be bent towards a triangle
+/// or, by phase distortion, a saw, gliding between notes, with drive and
+/// accented notes
+pub struct Bass808 {
+    sine: PhaseDistortionOscillator,
+    // Runs in step with the sine, started and tuned together
+    triangle: TriangleOscillator,
+    amp_envelope: AREnvelope,
+    // Frequency now and the one being glided to
+    frequency: f32,
+    target_frequency: f32,
+    // Octaves the frequency moves each sample while gliding
+    glide_step: f32,
+    glide_time: f32,
+    shape: f32, // 0 = sine, 1 = triangle
+    drive: f32,
+    accent: f32, // How much an accent adds, 0 to 1
+    accented: bool,
+    velocity: f32, // Level of the last note, 0 to 1
+    gain: f32,
+    sample_rate: f32,
+}
+
+impl Bass808 {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut bass = Self {
+            sine: PhaseDistortionOscillator::new(55.0, sample_rate),
+            triangle: TriangleOscillator::new(55.0, sample_rate),
+            amp_envelope: AREnvelope::new(sample_rate),
+            frequency: 55.0,
+            target_frequency: 55.0,
+            glide_step: 0.0,
+            glide_time: 0.05,
+            shape: 0.0,
+            drive: 0.2,
+            accent: 0.5,
+            accented: false,
+            velocity: 1.0,
+            gain: 1.0,
+            sample_rate,
+        };
+
+        bass.amp_envelope.set_attack_time(0.002);
+        bass.amp_envelope.set_release_time(1.5);
+        bass.amp_envelope.set_attack_bias(0.3); // Logarithmic-like
+        bass.amp_envelope.set_release_bias(0.6);
+
+        bass
+    }
+
+    /// Play a note scaled by the velocity; an accent adds level and drive
+    /// on top
+    pub fn trigger(&mut self, velocity: f32, accented: bool) {
+        // A note started from silence has nothing to glide from
+        if !self.is_active() {
+            self.frequency = self.target_frequency;
+            self.glide_step = 0.0;
+            self.sine.reset();
+            self.triangle.reset();
+        }
+        self.velocity = velocity.clamp(0.0, 1.0);
+        self.accented = accented;
+        self.amp_envelope.trigger();
+    }
+
+    /// Fast-release the voice (used by choke groups)
+    pub fn choke(&mut self) {
+        self.amp_envelope.choke();
+    }
+
+    /// Tune to a note, sliding there over the glide time while a note rings
+    pub fn set_base_frequency(&mut self, frequency: f32) {
+        self.target_frequency = frequency.clamp(20.0, 2000.0);
+        let glide_samples = self.glide_time * self.sample_rate;
+        if self.is_active() && glide_samples >= 1.0 {
+            self.glide_step = (self.target_frequency / self.frequency).log2() / glide_samples;
+        } else {
+            self.frequency = self.target_frequency;
+            self.glide_step = 0.0;
+        }
+    }
+
+    pub fn set_glide(&mut self, seconds: f32) {
+        self.glide_time = seconds.clamp(0.0, MAX_GLIDE_SECONDS);
+    }
+
+    /// Blend from a sine (0) to a triangle (1)
+    pub fn set_shape(&mut self, shape: f32) {
+        self.shape = shape.clamp(0.0, 1.0);
+    }
+
+    /// Bend the sine towards a saw, 0 to 1, for a growlier bass
+    pub fn set_phase_distortion(&mut self, distortion: f32) {
+        self.sine.set_distortion(distortion);
+    }
+
+    pub fn set_decay(&mut self, time: f32) {
+        self.amp_envelope.set_release_time(time);
+    }
+
+    /// Saturation from clean (0) to heavily driven (1)
+    pub fn set_drive(&mut self, drive: f32) {
+        self.drive = drive.clamp(0.0, 1.0);
+    }
+
+    /// How much louder and more driven accented notes are
+    pub fn set_accent(&mut self, accent: f32) {
+        self.accent = accent.clamp(0.0, 1.0);
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.amp_envelope.is_active()
+    }
+
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain = gain;
+    }
+
+    fn advance_glide(&mut self) {
+        if self.glide_step == 0.0 {
+            return;
+        }
+        let next = self.frequency * self.glide_step.exp2();
+        let arrived = if self.glide_step > 0.0 {
+            next >= self.target_frequency
+        } else {
+            next <= self.target_frequency
+        };
+        if arrived {
+            self.frequency = self.target_frequency;
+            self.glide_step = 0.0;
+        } else {
+            self.frequency = next;
+        }
+    }
+}
+
+impl AudioGenerator for Bass808 {
+    fn next_sample(&mut self) -> f32 {
+        if !self.is_active() {
+            return 0.0;
+        }
+
+        self.advance_glide();
+        self.sine.set_frequency(self.frequency);
+        self.triangle.set_frequency(self.frequency);
+
+        // Both start at zero rising, so the blend starts each note at zero
+        let sine = self.sine.next_sample();
+        let triangle = self.triangle.next_sample();
+        let wave = sine + (triangle - sine) * self.shape;
+
+        let accent = if self.accented { self.accent } else { 0.0 };
+        let level = self.amp_envelope.next_sample() * (self.velocity + accent);
+
+        // Normalised so drive changes the tone more than the level
+        let drive = 1.0 + (self.drive + accent * 0.5) * 9.0;
+        let driven = (wave * drive).tanh() / drive.tanh();
+        driven * level * self.gain
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.sine.set_sample_rate(sample_rate);
+        self.triangle.set_sample_rate(sample_rate);
+        self.amp_envelope.set_sample_rate(sample_rate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 44100.0;
+
+    fn peak(bass: &mut Bass808, samples: usize) -> f32 {
+        (0..samples).map(|_| bass.next_sample().abs()).fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn test_note_rings_and_chokes_to_silence() {
+        let mut bass = Bass808::new(SAMPLE_RATE);
+        assert_eq!(peak(&mut bass, 100), 0.0, "Should be silent before a note");
+
+        bass.trigger(1.0, false);
+        let level = peak(&mut bass, 4410);
+        assert!(level > 0.5 && level <= 1.0, "Unaccented peak was {}", level);
+        assert!(bass.is_active(), "Note should still ring after 0.1s");
+
+        bass.choke();
+        peak(&mut bass, 441);
+        assert!(!bass.is_active(), "Choke should silence within 10ms");
+        assert_eq!(bass.next_sample(), 0.0);
+    }
+
+    #[test]
+    fn test_glide_slides_to_the_next_note() {
+        let mut bass = Bass808::new(SAMPLE_RATE);
+        bass.set_glide(0.1);
+
+        // Nothing is ringing, so the first note is tuned straight away
+        bass.set_base_frequency(110.0);
+        assert_eq!(bass.frequency, 110.0);
+
+        bass.trigger(1.0, false);
+        peak(&mut bass, 1000);
+        bass.set_base_frequency(220.0);
+
+        // Halfway through the glide the pitch is near the geometric middle
+        peak(&mut bass, 2205);
+        assert!(
+            bass.frequency > 140.0 && bass.frequency < 170.0,
+            "Expected a frequency between the notes, got {}",
+            bass.frequency
+        );
+
+        peak(&mut bass, 2300);
+        assert_eq!(bass.frequency, 220.0, "Glide should land on the new note");
+    }
+
+    #[test]
+    fn test_accented_notes_are_louder() {
+        let mut plain = Bass808::new(SAMPLE_RATE);
+        plain.trigger(1.0, false);
+        let plain_peak = peak(&mut plain, 4410);
+
+        let mut accented = Bass808::new(SAMPLE_RATE);
+        accented.trigger(1.0, true);
+        let accented_peak = peak(&mut accented, 4410);
+
+        assert!(
+            accented_peak > plain_peak * 1.2,
+            "Accent peak {} should be well above {}",
+            accented_peak,
+            plain_peak
+        );
+    }
+
+    #[test]
+    fn test_louder_velocities_play_louder() {
+        let peak_at = |velocity: f32, accented: bool| {
+            let mut bass = Bass808::new(SAMPLE_RATE);
+            bass.trigger(velocity, accented);
+            peak(&mut bass, 4410)
+        };
+
+        let soft = peak_at(0.3, false);
+        let loud = peak_at(0.8, false);
+        assert!(soft > 0.0, "Soft notes should still sound");
+        assert!(
+            soft < loud * 0.5,
+            "Velocity 0.3 peak {} should be well under velocity 0.8 peak {}",
+            soft,
+            loud
+        );
+        assert!(peak_at(0.8, true) > loud, "Accent should add on top");
+    }
+}
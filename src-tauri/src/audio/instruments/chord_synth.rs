@@ -1,9 +1,17 @@
 use super::fm_voice::FMVoice;
-use crate::audio::AudioGenerator;
+use crate::audio::StereoAudioGenerator;
+use crate::sequencing::ChordQuality;
 
+/// FM chord of five voices. The chord is a list of ratios to the root, from
+/// a tonal chord quality or set directly; inversion and spread revoice it,
+/// and voices beyond the chord's notes repeat them an octave up. The voices
+/// fan out across the stereo field, lowest on the left.
 pub struct ChordSynth {
     voices: Vec<FMVoice>,
-    chord_ratios: Vec<f32>, // Just intonation ratios
+    pan_gains: Vec<(f32, f32)>, // Left and right gain of each voice
+    chord_ratios: Vec<f32>,     // Notes of the chord as ratios to the root
+    inversion: usize,           // Lowest notes lifted an octave
+    spread: u32,                // Octaves every other note is lifted
     base_frequency: f32,
     gain: f32,
 }
@@ -28,29 +36,49 @@ impl ChordSynth {
 
         let mut chord = Self {
             voices,
+            pan_gains: Vec::new(),
             chord_ratios,
+            inversion: 0,
+            spread: 0,
             base_frequency: 220.0, // A3
             gain: 0.25,
         };
 
         // Update voice frequencies
         chord.update_frequencies();
+        chord.set_stereo_width(0.6);
 
         chord
     }
 
     fn update_frequencies(&mut self) {
+        let mut notes = self.chord_ratios.clone();
+        // Invert by lifting the lowest notes, as listed, an octave
+        for ratio in notes.iter_mut().take(self.inversion) {
+            *ratio *= 2.0;
+        }
+        // Open the voicing by lifting every other note
+        for ratio in notes.iter_mut().skip(1).step_by(2) {
+            *ratio *= 2.0_f32.powi(self.spread as i32);
+        }
+
         for (i, voice) in self.voices.iter_mut().enumerate() {
-            if i < self.chord_ratios.len() {
-                let freq = self.base_frequency * self.chord_ratios[i];
-                voice.set_base_frequency(freq);
-            }
+            let octave = (i / notes.len()) as f32;
+            let freq = self.base_frequency * notes[i % notes.len()] * octave.exp2();
+            voice.set_base_frequency(freq);
         }
     }
 
-    pub fn trigger(&mut self) {
+    pub fn trigger(&mut self, velocity: f32) {
         for voice in self.voices.iter_mut() {
-            voice.trigger();
+            voice.trigger(velocity);
+        }
+    }
+
+    /// Fast-release every voice (used by choke groups)
+    pub fn choke(&mut self) {
+        for voice in self.voices.iter_mut() {
+            voice.choke();
         }
     }
 
@@ -59,12 +87,97 @@ impl ChordSynth {
         self.update_frequencies();
     }
 
+    /// Equal-tempered chord of a tonal quality on the root
+    pub fn set_chord_quality(&mut self, quality: ChordQuality) {
+        self.chord_ratios = quality
+            .intervals()
+            .iter()
+            .map(|semitones| (semitones / 12.0).exp2())
+            .collect();
+        self.inversion = self.inversion.min(self.chord_ratios.len() - 1);
+        self.update_frequencies();
+    }
+
+    /// Any chord, as ratios of each note to the root; ratios past the number
+    /// of voices are dropped, and an empty list is ignored
+    pub fn set_chord_ratios(&mut self, ratios: &[f32]) {
+        if ratios.is_empty() {
+            return;
+        }
+        self.chord_ratios = ratios.iter().take(self.voices.len()).copied().collect();
+        self.inversion = self.inversion.min(self.chord_ratios.len() - 1);
+        self.update_frequencies();
+    }
+
+    /// Number of the chord's lowest notes lifted an octave
+    pub fn set_inversion(&mut self, inversion: usize) {
+        self.inversion = inversion.min(self.chord_ratios.len() - 1);
+        self.update_frequencies();
+    }
+
+    /// Octaves every other note is lifted by, from a close voicing at 0 to
+    /// a wide open one at 2
+    pub fn set_spread(&mut self, octaves: u32) {
+        self.spread = octaves.min(2);
+        self.update_frequencies();
+    }
+
+    /// Spread of the voices across the stereo field, 0 (mono) to 1
+    pub fn set_stereo_width(&mut self, width: f32) {
+        let width = width.clamp(0.0, 1.0);
+        let last = (self.voices.len() - 1).max(1) as f32;
+        self.pan_gains = (0..self.voices.len())
+            .map(|i| {
+                let pan = 0.5 + (i as f32 / last - 0.5) * width;
+                // Equal power panning
+                let pan_radians = pan * std::f32::consts::PI * 0.5;
+                (pan_radians.cos(), pan_radians.sin())
+            })
+            .collect();
+    }
+
     pub fn set_modulation_index(&mut self, index: f32) {
         for voice in self.voices.iter_mut() {
             voice.set_modulation_index(index);
         }
     }
 
+    /// Analog-style pitch drift of every voice, in cents
+    pub fn set_drift_amount(&mut self, cents: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.set_drift_amount(cents);
+        }
+    }
+
+    /// Restart every voice's drift from a seed, each on its own stream
+    pub fn set_seed(&mut self, seed: u64) {
+        let mut rng = fastrand::Rng::with_seed(seed);
+        for voice in self.voices.iter_mut() {
+            voice.set_seed(rng.u64(..));
+        }
+    }
+
+    /// Detuned copies stacked into every voice, 1 (off) to 8
+    pub fn set_unison_voices(&mut self, voices: usize) {
+        for voice in self.voices.iter_mut() {
+            voice.set_unison_voices(voices);
+        }
+    }
+
+    /// Cents the outermost unison copies sit from each note
+    pub fn set_unison_detune(&mut self, cents: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.set_unison_detune(cents);
+        }
+    }
+
+    /// How much operator `src` modulates operator `dst` in every voice
+    pub fn set_mod_amount(&mut self, src: usize, dst: usize, amount: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.set_mod_amount(src, dst, amount);
+        }
+    }
+
     pub fn set_feedback(&mut self, feedback: f32) {
         for voice in self.voices.iter_mut() {
             voice.set_feedback(feedback);
@@ -92,25 +205,27 @@ impl ChordSynth {
     }
 }
 
-impl AudioGenerator for ChordSynth {
-    fn next_sample(&mut self) -> f32 {
+impl StereoAudioGenerator for ChordSynth {
+    fn next_sample(&mut self) -> (f32, f32) {
         if !self.is_active() {
-            return 0.0;
+            return (0.0, 0.0);
         }
 
-        let mut output = 0.0;
-        for voice in self.voices.iter_mut() {
-            output += voice.next_sample();
+        let (mut left, mut right) = (0.0, 0.0);
+        for (voice, (left_gain, right_gain)) in self.voices.iter_mut().zip(&self.pan_gains) {
+            let (voice_left, voice_right) = voice.next_sample();
+            left += voice_left * left_gain;
+            right += voice_right * right_gain;
         }
 
         // Mix down the voices and apply gain
-        output * 0.2 * self.gain // Divide by 5 for equal mixing
+        let level = 0.2 * self.gain; // Divide by 5 for equal mixing
+        (left * level, right * level)
     }
 
     fn set_sample_rate(&mut self, sample_rate: f32) {
         for voice in self.voices.iter_mut() {
-            AudioGenerator::set_sample_rate(voice, sample_rate);
+            voice.set_sample_rate(sample_rate);
         }
     }
 }
-
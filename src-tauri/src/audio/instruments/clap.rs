@@ -5,6 +5,7 @@ use crate::audio::{AudioGenerator, AudioProcessor};
 
 pub struct ClapDrum {
     noise_generator: NoiseGenerator,
+    rng: fastrand::Rng, // Randomizes the first envelope segment
 
     // Three bandpass filters at different frequencies
     filter_1320: SVF,
@@ -18,16 +19,23 @@ pub struct ClapDrum {
     envelope_value: f32,
     is_envelope_active: bool,
 
+    // Fast fade applied on top of the envelope when choked
+    choke_segment: Segment,
+    is_choked: bool,
+
     sample_rate: f32,
+    velocity: f32, // Level of the last hit, 0 to 1
     gain: f32,
 }
 
 impl ClapDrum {
     pub fn new(sample_rate: f32) -> Self {
+        let mut rng = fastrand::Rng::new();
+
         // Create the multi-segment envelope with randomized timing
         // SuperCollider: [0, 1, 0, 1, 0, 1, 0] with durations [Rand(0.001, 0.01), 0.01, 0.001, 0.01, 0.001, 0.08]
         let envelope_segments = [
-            Segment::new(0.0, 1.0, fastrand::f32() * 0.009 + 0.001, 0.9, sample_rate), // 0->1: 0.001-0.01s, fast attack
+            Segment::new(0.0, 1.0, rng.f32() * 0.009 + 0.001, 0.9, sample_rate), // 0->1: 0.001-0.01s, fast attack
             Segment::new(1.0, 0.0, 0.01, 0.1, sample_rate), // 1->0: 0.01s, fast decay
             Segment::new(0.0, 1.0, 0.001, 0.9, sample_rate), // 0->1: 0.001s, fast attack
             Segment::new(1.0, 0.0, 0.01, 0.1, sample_rate), // 1->0: 0.01s, fast decay
@@ -37,6 +45,7 @@ impl ClapDrum {
 
         Self {
             noise_generator: NoiseGenerator::new(),
+            rng,
 
             filter_1320: SVF::new(1320.0, 10.0, FilterMode::Bandpass, sample_rate), // Q=10 for narrow band
             filter_1100: SVF::new(1100.0, 10.0, FilterMode::Bandpass, sample_rate),
@@ -47,22 +56,43 @@ impl ClapDrum {
             envelope_value: 0.0,
             is_envelope_active: false,
 
+            choke_segment: Segment::new(1.0, 0.0, 0.005, 0.5, sample_rate),
+            is_choked: false,
+
             sample_rate,
+            velocity: 1.0,
             gain: 1.0,
         }
     }
 
-    pub fn trigger(&mut self) {
+    pub fn trigger(&mut self, velocity: f32) {
+        self.velocity = velocity.clamp(0.0, 1.0);
+
         // Randomize the first segment timing (like SuperCollider Rand)
-        self.envelope_segments[0].set_duration_seconds(fastrand::f32() * 0.009 + 0.001);
+        self.envelope_segments[0].set_duration_seconds(self.rng.f32() * 0.009 + 0.001);
 
         // Start the envelope sequence
         self.current_segment = 0;
         self.envelope_value = 0.0;
         self.is_envelope_active = true;
+        self.is_choked = false;
         self.envelope_segments[0].trigger();
     }
 
+    /// Restart the noise and envelope randomization from a seed
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng.seed(seed);
+        self.noise_generator.set_seed(self.rng.u64(..));
+    }
+
+    /// Fast-release the clap (used by choke groups)
+    pub fn choke(&mut self) {
+        if self.is_envelope_active && !self.is_choked {
+            self.is_choked = true;
+            self.choke_segment.trigger();
+        }
+    }
+
     pub fn is_active(&self) -> bool {
         self.is_envelope_active
     }
@@ -109,6 +139,15 @@ impl AudioGenerator for ClapDrum {
         // Update the multi-segment envelope
         self.update_envelope();
 
+        let choke_gain = if self.is_choked {
+            if self.choke_segment.is_finished() {
+                self.is_envelope_active = false;
+            }
+            self.choke_segment.next_sample()
+        } else {
+            1.0
+        };
+
         // Generate noise and process through three bandpass filters
         let noise = self.noise_generator.next_sample();
 
@@ -121,7 +160,7 @@ impl AudioGenerator for ClapDrum {
         let filtered_sum = (filtered_1320 + filtered_1100 + filtered_1420) * 3.16;
 
         // Apply envelope and tanh saturation
-        (filtered_sum * self.envelope_value).tanh() * self.gain
+        (filtered_sum * self.envelope_value).tanh() * self.velocity * self.gain * choke_gain
     }
 
     fn set_sample_rate(&mut self, sample_rate: f32) {
@@ -135,6 +174,7 @@ impl AudioGenerator for ClapDrum {
         for segment in &mut self.envelope_segments {
             segment.set_sample_rate(sample_rate);
         }
+        self.choke_segment.set_sample_rate(sample_rate);
     }
 }
 
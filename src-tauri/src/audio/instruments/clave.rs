@@ -0,0 +1,170 @@
+use crate::audio::filters::{FilterMode, SVF};
+use crate::audio::{AudioGenerator, AudioProcessor};
+use std::f32::consts::PI;
+
+const DEFAULT_TUNING: f32 = 2500.0;
+const DEFAULT_DECAY: f32 = 0.12;
+
+/// A ring under -60 dB counts as finished
+const DECAY_DB_FACTOR: f32 = 6.91; // ln(1000)
+
+/// Q that damps the ring out within a few cycles when choked
+const CHOKE_Q: f32 = 0.7;
+
+/// Clave or woodblock: a single high-Q bandpass rung by an impulse on each
+/// hit. The decay sets the filter's Q so the ring lasts that long at any
+/// tuning.
+pub struct Clave {
+    resonator: SVF,
+    tuning: f32,
+    decay: f32,
+    // Impulse waiting to go into the resonator on the next sample
+    impulse: f32,
+    ring_samples: u32,
+    gain: f32,
+    sample_rate: f32,
+}
+
+impl Clave {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut clave = Self {
+            resonator: SVF::new(DEFAULT_TUNING, 10.0, FilterMode::Bandpass, sample_rate),
+            tuning: DEFAULT_TUNING,
+            decay: DEFAULT_DECAY,
+            impulse: 0.0,
+            ring_samples: 0,
+            gain: 1.0,
+            sample_rate,
+        };
+        clave.update_resonance();
+        clave
+    }
+
+    pub fn trigger(&mut self, velocity: f32) {
+        // Restore the ring if a choke damped it
+        self.update_resonance();
+        self.impulse = velocity.clamp(0.0, 1.0);
+        self.ring_samples = (self.decay * self.sample_rate) as u32;
+    }
+
+    /// Damp the resonator (used by choke groups)
+    pub fn choke(&mut self) {
+        self.resonator.set_resonance(CHOKE_Q);
+        self.ring_samples = self.ring_samples.min((0.01 * self.sample_rate) as u32);
+    }
+
+    /// Pitch of the ping: around 2.5kHz for a clave, lower for a woodblock
+    pub fn set_tuning(&mut self, frequency: f32) {
+        self.tuning = frequency.clamp(200.0, 8000.0);
+        self.resonator.set_cutoff_frequency(self.tuning);
+        self.update_resonance();
+    }
+
+    /// Time for the ring to fall by 60 dB
+    pub fn set_decay(&mut self, time: f32) {
+        self.decay = time.clamp(0.01, 2.0);
+        self.update_resonance();
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.ring_samples > 0
+    }
+
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain = gain;
+    }
+
+    fn update_resonance(&mut self) {
+        // A resonator's ring falls by 60 dB in 6.91 * Q / (pi * f) seconds
+        let q = self.decay * PI * self.tuning / DECAY_DB_FACTOR;
+        self.resonator.set_resonance(q.max(CHOKE_Q));
+    }
+}
+
+impl AudioGenerator for Clave {
+    fn next_sample(&mut self) -> f32 {
+        if !self.is_active() {
+            return 0.0;
+        }
+        self.ring_samples -= 1;
+
+        let excitation = self.impulse;
+        self.impulse = 0.0;
+
+        // An impulse rings the bandpass at about 2 * pi * f / sample_rate,
+        // so scale it back up to full level
+        let normalization = self.sample_rate / (2.0 * PI * self.tuning);
+        self.resonator.process(excitation) * normalization * self.gain
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.resonator.set_sample_rate(sample_rate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 44100.0;
+
+    fn render(clave: &mut Clave, samples: usize) -> Vec<f32> {
+        (0..samples).map(|_| clave.next_sample()).collect()
+    }
+
+    fn peak(samples: &[f32]) -> f32 {
+        samples.iter().fold(0.0, |a, s| a.max(s.abs()))
+    }
+
+    #[test]
+    fn test_hit_rings_at_the_tuning_and_dies_away() {
+        let mut clave = Clave::new(SAMPLE_RATE);
+        clave.trigger(1.0);
+        let output = render(&mut clave, 6615);
+
+        let crossings = output[..882]
+            .windows(2)
+            .filter(|w| w[0] <= 0.0 && w[1] > 0.0)
+            .count();
+        assert!(
+            (47..=53).contains(&crossings),
+            "Expected 50 cycles of 2.5kHz in 20ms, got {}",
+            crossings
+        );
+
+        let start = peak(&output[..441]);
+        assert!(start > 0.5 && start < 1.5, "Expected about full level, got {}", start);
+        // 60 dB down by the 120ms decay, and silent after
+        assert!(peak(&output[4851..5292]) < start * 0.01);
+        assert!(!clave.is_active());
+        assert!(output[5292..].iter().all(|s| *s == 0.0));
+    }
+
+    #[test]
+    fn test_velocity_scales_the_level() {
+        let mut full = Clave::new(SAMPLE_RATE);
+        let mut half = Clave::new(SAMPLE_RATE);
+        full.trigger(1.0);
+        half.trigger(0.5);
+        for (full, half) in render(&mut full, 2000).iter().zip(render(&mut half, 2000)) {
+            assert!((full * 0.5 - half).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_choke_damps_the_ring() {
+        let mut clave = Clave::new(SAMPLE_RATE);
+        clave.set_decay(1.0);
+        clave.trigger(1.0);
+        render(&mut clave, 441);
+        clave.choke();
+        render(&mut clave, 441);
+        assert!(!clave.is_active(), "Choke should end the ring within 10ms");
+
+        // The next hit rings for the full decay again
+        clave.trigger(1.0);
+        render(&mut clave, 22050);
+        assert!(clave.is_active());
+    }
+}
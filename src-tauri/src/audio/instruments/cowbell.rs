@@ -0,0 +1,159 @@
+use crate::audio::envelopes::AREnvelope;
+use crate::audio::filters::{FilterMode, SVF};
+use crate::audio::oscillators::PolyBlepPulse;
+use crate::audio::{AudioGenerator, AudioProcessor};
+
+/// The 808 tunes its two oscillators to 540Hz and 800Hz
+const DEFAULT_TUNING: f32 = 540.0;
+const SECOND_OSCILLATOR_RATIO: f32 = 800.0 / 540.0;
+
+/// Width of each pulse, a little off square for the 808's hollow tone
+const PULSE_WIDTH: f32 = 0.45;
+
+/// Classic 808 cowbell: two detuned pulses through a bandpass. The pulses
+/// are band-limited, which keeps the aliasing of these high oscillators down.
+pub struct Cowbell {
+    low_pulse: PolyBlepPulse,
+    high_pulse: PolyBlepPulse,
+    bandpass: SVF,
+    amp_envelope: AREnvelope,
+    tuning: f32,
+    velocity: f32, // Level of the last hit, 0 to 1
+    gain: f32,
+}
+
+impl Cowbell {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut cowbell = Self {
+            low_pulse: PolyBlepPulse::new(DEFAULT_TUNING, sample_rate),
+            high_pulse: PolyBlepPulse::new(DEFAULT_TUNING * SECOND_OSCILLATOR_RATIO, sample_rate),
+            bandpass: SVF::new(2640.0, 2.0, FilterMode::Bandpass, sample_rate),
+            amp_envelope: AREnvelope::new(sample_rate),
+            tuning: DEFAULT_TUNING,
+            velocity: 1.0,
+            gain: 1.0,
+        };
+
+        cowbell.low_pulse.set_pulse_width(PULSE_WIDTH);
+        cowbell.high_pulse.set_pulse_width(PULSE_WIDTH);
+
+        cowbell.amp_envelope.set_attack_time(0.001);
+        cowbell.amp_envelope.set_release_time(0.35);
+        cowbell.amp_envelope.set_attack_bias(0.5); // Linear
+        cowbell.amp_envelope.set_release_bias(0.8); // Fast drop, long tail
+
+        cowbell.set_tuning(DEFAULT_TUNING);
+        cowbell
+    }
+
+    pub fn trigger(&mut self, velocity: f32) {
+        self.velocity = velocity.clamp(0.0, 1.0);
+        self.amp_envelope.trigger();
+        self.low_pulse.reset();
+        self.high_pulse.reset();
+    }
+
+    /// Fast-release the voice (used by choke groups)
+    pub fn choke(&mut self) {
+        self.amp_envelope.choke();
+    }
+
+    /// Frequency of the lower oscillator; the higher keeps the 808's interval
+    /// above it and the bandpass follows both
+    pub fn set_tuning(&mut self, frequency: f32) {
+        self.tuning = frequency.clamp(200.0, 2000.0);
+        self.low_pulse.set_frequency(self.tuning);
+        self.high_pulse
+            .set_frequency(self.tuning * SECOND_OSCILLATOR_RATIO);
+        self.bandpass.set_cutoff_frequency(self.tuning * 4.9);
+    }
+
+    pub fn set_decay(&mut self, time: f32) {
+        self.amp_envelope.set_release_time(time);
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.amp_envelope.is_active()
+    }
+
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain = gain;
+    }
+}
+
+impl AudioGenerator for Cowbell {
+    fn next_sample(&mut self) -> f32 {
+        if !self.is_active() {
+            return 0.0;
+        }
+
+        let amp_env = self.amp_envelope.next_sample();
+        let low = self.low_pulse.next_sample();
+        let high = self.high_pulse.next_sample();
+
+        let filtered = self.bandpass.process(0.5 * (low + high));
+        filtered * amp_env * self.velocity * self.gain
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.low_pulse.set_sample_rate(sample_rate);
+        self.high_pulse.set_sample_rate(sample_rate);
+        self.bandpass.set_sample_rate(sample_rate);
+        self.amp_envelope.set_sample_rate(sample_rate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 44100.0;
+
+    fn render(cowbell: &mut Cowbell, samples: usize) -> Vec<f32> {
+        (0..samples).map(|_| cowbell.next_sample()).collect()
+    }
+
+    fn rising_crossings(samples: &[f32]) -> usize {
+        samples.windows(2).filter(|w| w[0] <= 0.0 && w[1] > 0.0).count()
+    }
+
+    #[test]
+    fn test_tuning_moves_the_whole_voice() {
+        let mut low = Cowbell::new(SAMPLE_RATE);
+        low.set_tuning(400.0);
+        low.trigger(1.0);
+        let low_crossings = rising_crossings(&render(&mut low, 4410));
+
+        let mut high = Cowbell::new(SAMPLE_RATE);
+        high.set_tuning(800.0);
+        high.trigger(1.0);
+        let high_crossings = rising_crossings(&render(&mut high, 4410));
+
+        let ratio = high_crossings as f32 / low_crossings as f32;
+        assert!(
+            (ratio - 2.0).abs() < 0.3,
+            "An octave up should double the pitch, got a ratio of {}",
+            ratio
+        );
+
+        low.set_tuning(50.0);
+        assert_eq!(low.tuning, 200.0, "Tuning should clamp to the 808's range");
+    }
+
+    #[test]
+    fn test_hits_decay_and_choke_to_silence() {
+        let mut cowbell = Cowbell::new(SAMPLE_RATE);
+        cowbell.trigger(1.0);
+        let output = render(&mut cowbell, 22050);
+        let peak = output.iter().fold(0.0f32, |a, s| a.max(s.abs()));
+        assert!(peak > 0.1 && peak < 2.0, "Unexpected peak {}", peak);
+        assert!(!cowbell.is_active(), "Should be silent after its decay");
+
+        cowbell.trigger(1.0);
+        render(&mut cowbell, 441);
+        cowbell.choke();
+        render(&mut cowbell, 441);
+        assert!(!cowbell.is_active(), "Choke should silence within 10ms");
+        assert_eq!(cowbell.next_sample(), 0.0);
+    }
+}
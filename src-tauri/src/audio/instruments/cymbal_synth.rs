@@ -0,0 +1,228 @@
+use crate::audio::envelopes::AREnvelope;
+use crate::audio::filters::{FilterMode, SVF};
+use crate::audio::oscillators::PMOscillator;
+use crate::audio::{AudioGenerator, AudioProcessor};
+
+/// Carrier and modulator ratios for each operator pair, taken from the six
+/// square oscillators of the 808 metal section so no two share a harmonic
+const OPERATOR_RATIOS: [(f32, f32); 3] = [(1.0, 1.483), (1.8, 2.546), (2.63, 3.897)];
+
+/// Ride or crash voicing of the cymbal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CymbalMode {
+    /// Short wash over a long, pingy tail
+    Ride,
+    /// Bright, dense wash that rings out
+    Crash,
+}
+
+impl CymbalMode {
+    pub fn from_index(index: u32) -> Option<Self> {
+        match index {
+            0 => Some(CymbalMode::Ride),
+            1 => Some(CymbalMode::Crash),
+            _ => None,
+        }
+    }
+}
+
+/// Metallic cymbal from three pairs of inharmonic FM operators. A sizzle
+/// envelope drives the modulation index so the start is noisier than the
+/// tail, and a high-pass keeps it clear of the drums below.
+pub struct CymbalSynth {
+    carriers: [PMOscillator; 3],
+    modulators: [PMOscillator; 3],
+    amp_envelope: AREnvelope,
+    sizzle_envelope: AREnvelope,
+    highpass: SVF,
+    tone: f32,
+    index: f32,
+    velocity: f32, // Level of the last hit, 0 to 1
+    gain: f32,
+}
+
+impl CymbalSynth {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut cymbal = Self {
+            carriers: std::array::from_fn(|_| PMOscillator::new(420.0, sample_rate)),
+            modulators: std::array::from_fn(|_| PMOscillator::new(420.0, sample_rate)),
+            amp_envelope: AREnvelope::new(sample_rate),
+            sizzle_envelope: AREnvelope::new(sample_rate),
+            highpass: SVF::new(3000.0, 0.7, FilterMode::Highpass, sample_rate),
+            tone: 420.0,
+            index: 0.6,
+            velocity: 1.0,
+            gain: 1.0,
+        };
+
+        cymbal.amp_envelope.set_attack_bias(0.9); // Very fast attack
+        cymbal.amp_envelope.set_release_bias(0.8); // Fast drop, long tail
+        cymbal.sizzle_envelope.set_attack_time(0.001);
+        cymbal.sizzle_envelope.set_release_bias(0.7); // Exponential-like
+
+        cymbal.set_tone(420.0);
+        cymbal.set_mode(CymbalMode::Ride);
+        cymbal
+    }
+
+    /// Softer hits are quieter and less noisy
+    pub fn trigger(&mut self, velocity: f32) {
+        self.velocity = velocity.clamp(0.0, 1.0);
+        self.amp_envelope.trigger();
+        self.sizzle_envelope.trigger();
+        for operator in self.carriers.iter_mut().chain(self.modulators.iter_mut()) {
+            operator.reset();
+        }
+    }
+
+    /// Fast-release the voice (used by choke groups)
+    pub fn choke(&mut self) {
+        self.amp_envelope.choke();
+    }
+
+    /// Switch between ride and crash, which resets the decay, sizzle and
+    /// high-pass to suit the mode
+    pub fn set_mode(&mut self, mode: CymbalMode) {
+        match mode {
+            CymbalMode::Ride => {
+                self.amp_envelope.set_attack_time(0.001);
+                self.amp_envelope.set_release_time(1.6);
+                self.sizzle_envelope.set_release_time(0.25);
+                self.highpass.set_cutoff_frequency(2500.0);
+                self.index = 0.6;
+            }
+            CymbalMode::Crash => {
+                self.amp_envelope.set_attack_time(0.005);
+                self.amp_envelope.set_release_time(2.5);
+                self.sizzle_envelope.set_release_time(1.2);
+                self.highpass.set_cutoff_frequency(4000.0);
+                self.index = 2.0;
+            }
+        }
+    }
+
+    /// Frequency of the lowest operator; the rest keep their ratios above it
+    pub fn set_tone(&mut self, frequency: f32) {
+        self.tone = frequency.clamp(100.0, 2000.0);
+        for ((carrier, modulator), (carrier_ratio, modulator_ratio)) in self
+            .carriers
+            .iter_mut()
+            .zip(self.modulators.iter_mut())
+            .zip(OPERATOR_RATIOS)
+        {
+            carrier.set_frequency(self.tone * carrier_ratio);
+            modulator.set_frequency(self.tone * modulator_ratio);
+        }
+    }
+
+    pub fn set_decay(&mut self, time: f32) {
+        self.amp_envelope.set_release_time(time);
+    }
+
+    /// How long the noisy wash at the start of each hit lasts
+    pub fn set_sizzle(&mut self, time: f32) {
+        self.sizzle_envelope.set_release_time(time);
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.amp_envelope.is_active()
+    }
+
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain = gain;
+    }
+}
+
+impl AudioGenerator for CymbalSynth {
+    fn next_sample(&mut self) -> f32 {
+        if !self.is_active() {
+            return 0.0;
+        }
+
+        let amp_env = self.amp_envelope.next_sample();
+        let sizzle_env = self.sizzle_envelope.next_sample();
+
+        // The tail keeps a little modulation so it stays metallic
+        let index = self.index * (0.3 + sizzle_env) * (0.5 + 0.5 * self.velocity);
+        let mut sum = 0.0;
+        for (carrier, modulator) in self.carriers.iter_mut().zip(self.modulators.iter_mut()) {
+            let modulation = modulator.next_sample() * index;
+            sum += carrier.next_sample_with_pm(modulation);
+        }
+
+        let filtered = self.highpass.process(sum / OPERATOR_RATIOS.len() as f32);
+        filtered * amp_env * self.velocity * self.gain
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        for operator in self.carriers.iter_mut().chain(self.modulators.iter_mut()) {
+            operator.set_sample_rate(sample_rate);
+        }
+        self.amp_envelope.set_sample_rate(sample_rate);
+        self.sizzle_envelope.set_sample_rate(sample_rate);
+        self.highpass.set_sample_rate(sample_rate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 44100.0;
+
+    fn render(cymbal: &mut CymbalSynth, samples: usize) -> Vec<f32> {
+        (0..samples).map(|_| cymbal.next_sample()).collect()
+    }
+
+    fn rising_crossings(samples: &[f32]) -> usize {
+        samples.windows(2).filter(|w| w[0] <= 0.0 && w[1] > 0.0).count()
+    }
+
+    #[test]
+    fn test_mode_from_index() {
+        assert_eq!(CymbalMode::from_index(0), Some(CymbalMode::Ride));
+        assert_eq!(CymbalMode::from_index(1), Some(CymbalMode::Crash));
+        assert_eq!(CymbalMode::from_index(2), None);
+    }
+
+    #[test]
+    fn test_crash_rings_brighter_and_longer_than_ride() {
+        let mut ride = CymbalSynth::new(SAMPLE_RATE);
+        ride.trigger(1.0);
+        let ride_wash = render(&mut ride, 4410);
+
+        let mut crash = CymbalSynth::new(SAMPLE_RATE);
+        crash.set_mode(CymbalMode::Crash);
+        crash.trigger(1.0);
+        let crash_wash = render(&mut crash, 4410);
+
+        assert!(
+            rising_crossings(&crash_wash) > rising_crossings(&ride_wash),
+            "Crash wash should be brighter than the ride's"
+        );
+        for sample in ride_wash.iter().chain(crash_wash.iter()) {
+            assert!(sample.abs() <= 1.0, "Cymbal output out of range: {}", sample);
+        }
+
+        // 2 seconds in, the ride has died away and the crash still rings
+        render(&mut ride, 84000);
+        render(&mut crash, 84000);
+        assert!(!ride.is_active(), "Ride should have decayed by 2s");
+        assert!(crash.is_active(), "Crash should still ring at 2s");
+
+        render(&mut crash, 44100);
+        assert!(!crash.is_active(), "Crash should have decayed by 3s");
+    }
+
+    #[test]
+    fn test_choke_silences_an_open_crash() {
+        let mut cymbal = CymbalSynth::new(SAMPLE_RATE);
+        cymbal.set_mode(CymbalMode::Crash);
+        cymbal.trigger(1.0);
+        render(&mut cymbal, 4410);
+        cymbal.choke();
+        render(&mut cymbal, 441);
+        assert!(!cymbal.is_active(), "Choke should silence within 10ms");
+        assert_eq!(cymbal.next_sample(), 0.0);
+    }
+}
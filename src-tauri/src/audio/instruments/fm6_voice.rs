@@ -0,0 +1,318 @@
+use crate::audio::envelopes::{AREEnvelope, AREnvelope};
+use crate::audio::oscillators::PMOscillator;
+use crate::audio::AudioGenerator;
+
+pub const FM6_OPERATORS: usize = 6;
+
+/// Phase modulation a modulator at full level applies, in cycles
+const MAX_MOD_DEPTH: f32 = 2.0;
+
+/// How the six operators feed each other. As on the DX7, an operator is only
+/// ever modulated by higher-numbered ones, so one pass from the top down
+/// renders any algorithm.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FM6Algorithm {
+    /// 6 -> 5 -> 4 -> 3 -> 2 -> 1, one deep stack into a single carrier
+    Serial,
+    /// 3 -> 2 -> 1 and 6 -> 5 -> 4, two three-operator stacks
+    TwoStacks,
+    /// 2 -> 1, 4 -> 3 and 6 -> 5, three pairs
+    ThreeStacks,
+    /// 2 -> 1, and 6 modulating carriers 3, 4 and 5 together
+    SharedModulator,
+    /// All six operators are carriers, like drawbars
+    Parallel,
+}
+
+impl FM6Algorithm {
+    pub fn from_index(index: u32) -> Option<Self> {
+        match index {
+            0 => Some(FM6Algorithm::Serial),
+            1 => Some(FM6Algorithm::TwoStacks),
+            2 => Some(FM6Algorithm::ThreeStacks),
+            3 => Some(FM6Algorithm::SharedModulator),
+            4 => Some(FM6Algorithm::Parallel),
+            _ => None,
+        }
+    }
+
+    /// For each operator, the bitmask of operators modulating it
+    fn modulators(self) -> [u8; FM6_OPERATORS] {
+        match self {
+            FM6Algorithm::Serial => [1 << 1, 1 << 2, 1 << 3, 1 << 4, 1 << 5, 0],
+            FM6Algorithm::TwoStacks => [1 << 1, 1 << 2, 0, 1 << 4, 1 << 5, 0],
+            FM6Algorithm::ThreeStacks => [1 << 1, 0, 1 << 3, 0, 1 << 5, 0],
+            FM6Algorithm::SharedModulator => [1 << 1, 0, 1 << 5, 1 << 5, 1 << 5, 0],
+            FM6Algorithm::Parallel => [0; FM6_OPERATORS],
+        }
+    }
+
+    /// Bitmask of the operators heard at the output
+    fn carriers(self) -> u8 {
+        match self {
+            FM6Algorithm::Serial => 0b000001,
+            FM6Algorithm::TwoStacks => 0b001001,
+            FM6Algorithm::ThreeStacks => 0b010101,
+            FM6Algorithm::SharedModulator => 0b011101,
+            FM6Algorithm::Parallel => 0b111111,
+        }
+    }
+}
+
+/// Six-operator FM voice with selectable algorithms. Every operator has its
+/// own frequency ratio, level and attack-decay-sustain envelope; a carrier's
+/// level sets its loudness and a modulator's sets its modulation depth.
+/// The top operator can feed back on itself.
+pub struct FM6Voice {
+    operators: [PMOscillator; FM6_OPERATORS],
+    op_envelopes: [AREEnvelope; FM6_OPERATORS],
+    amp_envelope: AREnvelope,
+
+    ratios: [f32; FM6_OPERATORS],
+    levels: [f32; FM6_OPERATORS],
+    algorithm: FM6Algorithm,
+
+    base_frequency: f32,
+    velocity: f32, // Level of the last note, 0 to 1
+    gain: f32,
+}
+
+impl FM6Voice {
+    pub fn new(sample_rate: f32) -> Self {
+        let ratios = [1.0, 2.0, 1.0, 1.0, 3.0, 7.0];
+        let mut voice = Self {
+            operators: std::array::from_fn(|i| PMOscillator::new(220.0 * ratios[i], sample_rate)),
+            op_envelopes: std::array::from_fn(|_| AREEnvelope::new(sample_rate)),
+            amp_envelope: AREnvelope::new(sample_rate),
+            ratios,
+            levels: [1.0, 0.5, 1.0, 0.3, 0.4, 0.2],
+            algorithm: FM6Algorithm::TwoStacks,
+            base_frequency: 220.0,
+            velocity: 1.0,
+            gain: 0.5,
+        };
+
+        // Operators strike and settle, the higher ones faster, so notes
+        // start bright and mellow as they hold
+        for (i, envelope) in voice.op_envelopes.iter_mut().enumerate() {
+            envelope.set_attack_time(0.001);
+            envelope.set_release_time(1.5 / (1.0 + i as f32));
+            envelope.set_end_level(0.5);
+        }
+
+        voice.amp_envelope.set_attack_time(0.005);
+        voice.amp_envelope.set_release_time(1.5);
+        voice.amp_envelope.set_attack_bias(0.3);
+        voice.amp_envelope.set_release_bias(0.7);
+
+        voice
+    }
+
+    /// Softer notes are quieter and less bright
+    pub fn trigger(&mut self, velocity: f32) {
+        self.velocity = velocity.clamp(0.0, 1.0);
+        self.amp_envelope.trigger();
+        for (operator, envelope) in self.operators.iter_mut().zip(self.op_envelopes.iter_mut()) {
+            envelope.trigger();
+            operator.reset();
+        }
+    }
+
+    /// Fast-release the voice (used by choke groups)
+    pub fn choke(&mut self) {
+        self.amp_envelope.choke();
+    }
+
+    pub fn set_base_frequency(&mut self, frequency: f32) {
+        self.base_frequency = frequency;
+        for (operator, ratio) in self.operators.iter_mut().zip(self.ratios) {
+            operator.set_frequency(frequency * ratio);
+        }
+    }
+
+    pub fn set_algorithm(&mut self, algorithm: FM6Algorithm) {
+        self.algorithm = algorithm;
+    }
+
+    /// Feedback of the top operator onto itself
+    pub fn set_feedback(&mut self, feedback: f32) {
+        self.operators[FM6_OPERATORS - 1].set_feedback(feedback);
+    }
+
+    /// Operator frequency as a multiple of the note's
+    pub fn set_op_ratio(&mut self, op: usize, ratio: f32) {
+        self.ratios[op] = ratio.clamp(0.125, 32.0);
+        self.operators[op].set_frequency(self.base_frequency * self.ratios[op]);
+    }
+
+    /// Loudness of a carrier or modulation depth of a modulator, 0 to 1
+    pub fn set_op_level(&mut self, op: usize, level: f32) {
+        self.levels[op] = level.clamp(0.0, 1.0);
+    }
+
+    pub fn set_op_attack(&mut self, op: usize, time: f32) {
+        self.op_envelopes[op].set_attack_time(time);
+    }
+
+    /// Time an operator takes to fall from its peak to its sustain level
+    pub fn set_op_decay(&mut self, op: usize, time: f32) {
+        self.op_envelopes[op].set_release_time(time);
+    }
+
+    /// Level an operator holds at after its decay, 0 to 1
+    pub fn set_op_sustain(&mut self, op: usize, level: f32) {
+        self.op_envelopes[op].set_end_level(level);
+    }
+
+    pub fn set_attack(&mut self, time: f32) {
+        self.amp_envelope.set_attack_time(time);
+    }
+
+    pub fn set_release(&mut self, time: f32) {
+        self.amp_envelope.set_release_time(time);
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.amp_envelope.is_active()
+    }
+
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain = gain;
+    }
+}
+
+impl AudioGenerator for FM6Voice {
+    fn next_sample(&mut self) -> f32 {
+        if !self.is_active() {
+            return 0.0;
+        }
+
+        let amp_env = self.amp_envelope.next_sample();
+        // Softer notes modulate less, so they're darker as well as quieter
+        let brightness = 0.5 + 0.5 * self.velocity;
+        let modulators = self.algorithm.modulators();
+        let carriers = self.algorithm.carriers();
+
+        let mut outputs = [0.0; FM6_OPERATORS];
+        let mut mix = 0.0;
+        for op in (0..FM6_OPERATORS).rev() {
+            let phase_mod: f32 = (op + 1..FM6_OPERATORS)
+                .filter(|source| modulators[op] & (1 << source) != 0)
+                .map(|source| outputs[source] * MAX_MOD_DEPTH * brightness)
+                .sum();
+            let envelope = self.op_envelopes[op].next_sample();
+            outputs[op] =
+                self.operators[op].next_sample_with_pm(phase_mod) * envelope * self.levels[op];
+            if carriers & (1 << op) != 0 {
+                mix += outputs[op];
+            }
+        }
+
+        // Keep algorithms with more carriers from getting louder
+        let mix = mix / carriers.count_ones() as f32;
+        mix * amp_env * self.velocity * self.gain
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        for (operator, envelope) in self.operators.iter_mut().zip(self.op_envelopes.iter_mut()) {
+            operator.set_sample_rate(sample_rate);
+            envelope.set_sample_rate(sample_rate);
+        }
+        self.amp_envelope.set_sample_rate(sample_rate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 44100.0;
+    const ALGORITHMS: [FM6Algorithm; 5] = [
+        FM6Algorithm::Serial,
+        FM6Algorithm::TwoStacks,
+        FM6Algorithm::ThreeStacks,
+        FM6Algorithm::SharedModulator,
+        FM6Algorithm::Parallel,
+    ];
+
+    fn render(voice: &mut FM6Voice, samples: usize) -> Vec<f32> {
+        (0..samples).map(|_| voice.next_sample()).collect()
+    }
+
+    fn rising_crossings(samples: &[f32]) -> usize {
+        samples.windows(2).filter(|w| w[0] <= 0.0 && w[1] > 0.0).count()
+    }
+
+    #[test]
+    fn test_algorithms_render_top_down() {
+        for (index, algorithm) in ALGORITHMS.iter().enumerate() {
+            assert_eq!(FM6Algorithm::from_index(index as u32), Some(*algorithm));
+
+            let modulators = algorithm.modulators();
+            let carriers = algorithm.carriers();
+            for op in 0..FM6_OPERATORS {
+                // Only higher operators modulate, so one pass renders them
+                assert_eq!(modulators[op] & ((1 << (op + 1)) - 1), 0);
+
+                // Every operator is either heard or modulates one that is
+                let modulates = modulators.iter().any(|mask| mask & (1 << op) != 0);
+                assert!(
+                    carriers & (1 << op) != 0 || modulates,
+                    "{:?} leaves operator {} unused",
+                    algorithm,
+                    op
+                );
+            }
+        }
+        assert_eq!(FM6Algorithm::from_index(5), None);
+    }
+
+    #[test]
+    fn test_modulators_brighten_the_carrier() {
+        let mut pure = FM6Voice::new(SAMPLE_RATE);
+        pure.set_algorithm(FM6Algorithm::Serial);
+        for op in 1..FM6_OPERATORS {
+            pure.set_op_level(op, 0.0);
+        }
+        pure.trigger(1.0);
+        let pure_crossings = rising_crossings(&render(&mut pure, 4410));
+        assert!(
+            (21..=23).contains(&pure_crossings),
+            "An unmodulated carrier should play 220Hz, got {} cycles in 0.1s",
+            pure_crossings
+        );
+
+        let mut bright = FM6Voice::new(SAMPLE_RATE);
+        bright.set_algorithm(FM6Algorithm::Serial);
+        bright.set_op_level(1, 1.0);
+        bright.set_op_ratio(1, 7.0);
+        bright.trigger(1.0);
+        let bright_crossings = rising_crossings(&render(&mut bright, 4410));
+        assert!(
+            bright_crossings > pure_crossings * 2,
+            "Modulation should add overtones: {} vs {} crossings",
+            bright_crossings,
+            pure_crossings
+        );
+    }
+
+    #[test]
+    fn test_softer_notes_are_quieter_and_choke_to_silence() {
+        let peak = |velocity: f32| {
+            let mut voice = FM6Voice::new(SAMPLE_RATE);
+            voice.trigger(velocity);
+            render(&mut voice, 4410)
+                .iter()
+                .fold(0.0f32, |a, s| a.max(s.abs()))
+        };
+        assert!(peak(0.3) < peak(1.0) * 0.5);
+
+        let mut voice = FM6Voice::new(SAMPLE_RATE);
+        voice.trigger(1.0);
+        render(&mut voice, 4410);
+        voice.choke();
+        render(&mut voice, 441);
+        assert!(!voice.is_active(), "Choke should silence within 10ms");
+        assert_eq!(voice.next_sample(), 0.0);
+    }
+}
@@ -1,37 +1,74 @@
 use crate::audio::envelopes::{AREEnvelope, AREnvelope};
 use crate::audio::oscillators::PMOscillator;
-use crate::audio::AudioGenerator;
+use crate::audio::{AudioGenerator, StereoAudioGenerator};
+
+pub const FM_OPERATORS: usize = 4;
+
+/// Most detuned copies of the operator stack a voice can play at once
+pub const MAX_UNISON_VOICES: usize = 8;
+
+/// One copy of the operators, detuned and panned for unison. The copies
+/// share the voice's envelopes and modulation matrix.
+struct FMStack {
+    operators: [PMOscillator; FM_OPERATORS],
+    last_outputs: [f32; FM_OPERATORS],
+    detune: f32, // Frequency multiplier
+    left_gain: f32,
+    right_gain: f32,
+}
+
+impl FMStack {
+    fn new(sample_rate: f32) -> Self {
+        Self {
+            operators: [
+                PMOscillator::new(220.0, sample_rate),
+                PMOscillator::new(440.0, sample_rate),
+                PMOscillator::new(660.0, sample_rate),
+                PMOscillator::new(2640.0, sample_rate),
+            ],
+            last_outputs: [0.0; FM_OPERATORS],
+            detune: 1.0,
+            left_gain: 1.0,
+            right_gain: 1.0,
+        }
+    }
+}
 
 pub struct FMVoice {
-    // 4 operators with their own envelopes
-    operators: [PMOscillator; 4],
-    op_envelopes: [AREEnvelope; 4],
+    // Unison copies of the 4 operators; the first unison_voices play
+    stacks: Vec<FMStack>,
+    unison_voices: usize,
+    unison_detune: f32, // Cents between the outermost copies and the centre
+
+    // Operator envelopes, shared by every copy
+    op_envelopes: [AREEnvelope; FM_OPERATORS],
 
     // Voice amplitude envelope
     amp_envelope: AREnvelope,
 
     // Operator frequencies (as multipliers of base frequency)
-    op_multipliers: [f32; 4],
+    op_multipliers: [f32; FM_OPERATORS],
 
-    // Modulation matrix parameters
-    op2_to_op1_amount: f32, // op2 modulates op1
-    op1_to_op0_amount: f32, // op1 modulates op0
-    op3_to_op0_amount: f32, // op3 modulates op0
+    // Modulation matrix: mod_matrix[src][dst] is how much op src modulates
+    // op dst. Operators render from op3 down, so routings from a lower
+    // operator (or one onto itself) hear its previous sample.
+    mod_matrix: [[f32; FM_OPERATORS]; FM_OPERATORS],
+    modulation_index: f32, // Scales the whole matrix
 
     // Global parameters
     base_frequency: f32,
+    velocity: f32, // Level of the last note, 0 to 1
     gain: f32,
 }
 
 impl FMVoice {
     pub fn new(sample_rate: f32) -> Self {
         let mut voice = Self {
-            operators: [
-                PMOscillator::new(220.0, sample_rate),
-                PMOscillator::new(440.0, sample_rate),
-                PMOscillator::new(660.0, sample_rate),
-                PMOscillator::new(2640.0, sample_rate),
-            ],
+            stacks: (0..MAX_UNISON_VOICES)
+                .map(|_| FMStack::new(sample_rate))
+                .collect(),
+            unison_voices: 1,
+            unison_detune: 10.0,
             op_envelopes: [
                 AREEnvelope::new(sample_rate),
                 AREEnvelope::new(sample_rate),
@@ -40,13 +77,18 @@ impl FMVoice {
             ],
             amp_envelope: AREnvelope::new(sample_rate),
             op_multipliers: [1.0, 2.0, 3.0, 12.0],
-            op2_to_op1_amount: 0.5,
-            op1_to_op0_amount: 0.5,
-            op3_to_op0_amount: 0.5,
+            mod_matrix: [[0.0; FM_OPERATORS]; FM_OPERATORS],
+            modulation_index: 1.0,
             base_frequency: 220.0,
+            velocity: 1.0,
             gain: 0.5,
         };
 
+        // Default routing: op2 modulates op1, and op1 and op3 modulate op0
+        voice.mod_matrix[2][1] = 0.5;
+        voice.mod_matrix[1][0] = 0.5;
+        voice.mod_matrix[3][0] = 0.5;
+
         // Set up operator envelopes based on inspiration.gen
         // op0: carrier (no decay, stays at 1.0)
         voice.op_envelopes[0].set_attack_time(0.001);
@@ -74,46 +116,94 @@ impl FMVoice {
         voice.amp_envelope.set_attack_bias(0.3);
         voice.amp_envelope.set_release_bias(0.7);
 
+        voice.update_unison();
         voice
     }
 
-    pub fn trigger(&mut self) {
+    /// Softer notes are quieter and less bright
+    pub fn trigger(&mut self, velocity: f32) {
+        self.velocity = velocity.clamp(0.0, 1.0);
         self.amp_envelope.trigger();
-        for i in 0..4 {
-            self.op_envelopes[i].trigger();
-            self.operators[i].reset();
+        for envelope in self.op_envelopes.iter_mut() {
+            envelope.trigger();
         }
+        for stack in self.stacks.iter_mut() {
+            for operator in stack.operators.iter_mut() {
+                operator.reset();
+            }
+            stack.last_outputs = [0.0; FM_OPERATORS];
+        }
+    }
+
+    /// Fast-release the voice (used by choke groups)
+    pub fn choke(&mut self) {
+        self.amp_envelope.choke();
     }
 
     pub fn set_base_frequency(&mut self, freq: f32) {
         self.base_frequency = freq;
-        for i in 0..4 {
-            self.operators[i].set_frequency(freq * self.op_multipliers[i]);
-        }
+        self.update_frequencies();
     }
 
     pub fn set_op_multiplier(&mut self, op_index: usize, multiplier: f32) {
-        if op_index < 4 {
+        if op_index < FM_OPERATORS {
             self.op_multipliers[op_index] = multiplier;
-            self.operators[op_index].set_frequency(self.base_frequency * multiplier);
+            self.update_frequencies();
         }
     }
 
     pub fn set_modulation_index(&mut self, index: f32) {
         // Scale the modulation amounts together
-        let scale = index.clamp(0.0, 2.0);
-        self.op2_to_op1_amount = 0.5 * scale;
-        self.op1_to_op0_amount = 0.5 * scale;
-        self.op3_to_op0_amount = 0.5 * scale;
+        self.modulation_index = index.clamp(0.0, 2.0);
+    }
+
+    /// How much operator `src` modulates operator `dst`; op0 is the carrier
+    pub fn set_mod_amount(&mut self, src: usize, dst: usize, amount: f32) {
+        if src < FM_OPERATORS && dst < FM_OPERATORS {
+            self.mod_matrix[src][dst] = amount.clamp(0.0, 1.0);
+        }
     }
 
     pub fn set_feedback(&mut self, feedback: f32) {
         // Apply feedback to all operators
-        for op in self.operators.iter_mut() {
-            op.set_feedback(feedback);
+        for stack in self.stacks.iter_mut() {
+            for op in stack.operators.iter_mut() {
+                op.set_feedback(feedback);
+            }
+        }
+    }
+
+    /// Analog-style pitch drift of every operator, in cents
+    pub fn set_drift_amount(&mut self, cents: f32) {
+        for stack in self.stacks.iter_mut() {
+            for op in stack.operators.iter_mut() {
+                op.set_drift_amount(cents);
+            }
+        }
+    }
+
+    /// Restart every operator's drift from a seed, each on its own stream
+    pub fn set_seed(&mut self, seed: u64) {
+        let mut rng = fastrand::Rng::with_seed(seed);
+        for stack in self.stacks.iter_mut() {
+            for op in stack.operators.iter_mut() {
+                op.set_seed(rng.u64(..));
+            }
         }
     }
 
+    /// Number of detuned copies stacked into the voice, 1 (off) to 8
+    pub fn set_unison_voices(&mut self, voices: usize) {
+        self.unison_voices = voices.clamp(1, MAX_UNISON_VOICES);
+        self.update_unison();
+    }
+
+    /// Cents the outermost unison copies sit from the note, 0 to 50
+    pub fn set_unison_detune(&mut self, cents: f32) {
+        self.unison_detune = cents.clamp(0.0, 50.0);
+        self.update_unison();
+    }
+
     pub fn set_attack(&mut self, time: f32) {
         self.amp_envelope.set_attack_time(time);
     }
@@ -129,48 +219,82 @@ impl FMVoice {
     pub fn set_gain(&mut self, gain: f32) {
         self.gain = gain;
     }
+
+    /// Spread the playing copies evenly in pitch and across the stereo
+    /// field, the flattest on the left
+    fn update_unison(&mut self) {
+        let count = self.unison_voices;
+        for (i, stack) in self.stacks.iter_mut().take(count).enumerate() {
+            // -1 to 1 across the copies, 0 for a single one
+            let position = if count > 1 {
+                2.0 * i as f32 / (count - 1) as f32 - 1.0
+            } else {
+                0.0
+            };
+            stack.detune = (position * self.unison_detune / 1200.0).exp2();
+            // Equal power panning
+            let pan_radians = (0.5 + 0.5 * position) * std::f32::consts::PI * 0.5;
+            stack.left_gain = pan_radians.cos() * std::f32::consts::SQRT_2;
+            stack.right_gain = pan_radians.sin() * std::f32::consts::SQRT_2;
+        }
+        self.update_frequencies();
+    }
+
+    fn update_frequencies(&mut self) {
+        for stack in self.stacks.iter_mut() {
+            for (operator, multiplier) in stack.operators.iter_mut().zip(self.op_multipliers) {
+                operator.set_frequency(self.base_frequency * multiplier * stack.detune);
+            }
+        }
+    }
 }
 
-impl AudioGenerator for FMVoice {
-    fn next_sample(&mut self) -> f32 {
+impl StereoAudioGenerator for FMVoice {
+    fn next_sample(&mut self) -> (f32, f32) {
         if !self.is_active() {
-            return 0.0;
+            return (0.0, 0.0);
         }
 
         // Get envelope values
         let amp_env = self.amp_envelope.next_sample();
-        let op_envs: [f32; 4] = [
-            self.op_envelopes[0].next_sample(),
-            self.op_envelopes[1].next_sample(),
-            self.op_envelopes[2].next_sample(),
-            self.op_envelopes[3].next_sample(),
-        ];
-
-        // Generate operators with modulation routing
-        // op3: pure (no modulation input)
-        let op3_out = self.operators[3].next_sample_with_pm(0.0) * op_envs[3];
+        let op_envs: [f32; FM_OPERATORS] =
+            std::array::from_fn(|i| self.op_envelopes[i].next_sample());
 
-        // op2: pure (no modulation input)
-        let op2_out = self.operators[2].next_sample_with_pm(0.0) * op_envs[2];
+        // Softer notes modulate less, so they're darker as well as quieter
+        let brightness = 0.5 + 0.5 * self.velocity;
+        let depth = self.modulation_index * brightness;
 
-        // op1: modulated by op2
-        let op1_pm = op2_out * self.op2_to_op1_amount;
-        let op1_out = self.operators[1].next_sample_with_pm(op1_pm) * op_envs[1];
+        let (mut left, mut right) = (0.0, 0.0);
+        for stack in self.stacks.iter_mut().take(self.unison_voices) {
+            // Generate operators from op3 down through the modulation matrix;
+            // last_outputs already holds this sample for the operators above dst
+            for dst in (0..FM_OPERATORS).rev() {
+                let phase_mod: f32 = (0..FM_OPERATORS)
+                    .map(|src| stack.last_outputs[src] * self.mod_matrix[src][dst])
+                    .sum();
+                stack.last_outputs[dst] =
+                    stack.operators[dst].next_sample_with_pm(phase_mod * depth) * op_envs[dst];
+            }
 
-        // op0: carrier modulated by op1 and op3
-        let op0_pm = op1_out * self.op1_to_op0_amount + op3_out * self.op3_to_op0_amount;
-        let op0_out = self.operators[0].next_sample_with_pm(op0_pm) * op_envs[0];
+            // Each copy is heard through its carrier, op0
+            left += stack.last_outputs[0] * stack.left_gain;
+            right += stack.last_outputs[0] * stack.right_gain;
+        }
 
-        // Output is op0 with amplitude envelope
-        op0_out * amp_env * self.gain
+        // Detuned copies add up like noise, so keep the level by their root
+        let level = amp_env * self.velocity * self.gain / (self.unison_voices as f32).sqrt();
+        (left * level, right * level)
     }
 
     fn set_sample_rate(&mut self, sample_rate: f32) {
-        for i in 0..4 {
-            self.operators[i].set_sample_rate(sample_rate);
-            self.op_envelopes[i].set_sample_rate(sample_rate);
+        for stack in self.stacks.iter_mut() {
+            for operator in stack.operators.iter_mut() {
+                operator.set_sample_rate(sample_rate);
+            }
+        }
+        for envelope in self.op_envelopes.iter_mut() {
+            envelope.set_sample_rate(sample_rate);
         }
         self.amp_envelope.set_sample_rate(sample_rate);
     }
 }
-
@@ -0,0 +1,259 @@
+use crate::audio::envelopes::AREnvelope;
+use crate::audio::filters::{FilterMode, SVF};
+use crate::audio::oscillators::{PolyBlepPulse, PolyBlepSaw, TriangleOscillator};
+use crate::audio::{AudioGenerator, AudioProcessor};
+
+/// First three formants of each vowel, A, E, I, O, U, for an adult voice
+const VOWEL_FORMANTS: [[f32; 3]; 5] = [
+    [800.0, 1150.0, 2900.0],
+    [400.0, 1600.0, 2700.0],
+    [300.0, 2300.0, 3000.0],
+    [450.0, 800.0, 2830.0],
+    [325.0, 700.0, 2530.0],
+];
+
+/// Upper formants sit lower than the first, as they do in speech
+const FORMANT_LEVELS: [f32; 3] = [1.0, 0.5, 0.25];
+
+/// Widest the pulse swings either side of square at full PWM depth
+const MAX_PWM_SWING: f32 = 0.4;
+
+/// Robotic vocal voice: a band-limited saw or pulse through three parallel
+/// bandpass filters at the formants of a vowel. The vowel control morphs
+/// smoothly through A, E, I, O and U. A slow LFO can sweep the pulse's
+/// width, for a moving, choir-like source.
+pub struct FormantVoice {
+    // Saw and pulse run in step, started and tuned together
+    saw: PolyBlepSaw,
+    pulse: PolyBlepPulse,
+    pwm_lfo: TriangleOscillator,
+    formants: [SVF; 3],
+    amp_envelope: AREnvelope,
+
+    vowel: f32,         // 0 = A through 4 = U
+    formant_shift: f32, // Scales every formant, for smaller or larger throats
+    waveform: f32,      // 0 = saw, 1 = pulse
+    pwm_depth: f32,     // 0 to 1 of the widest swing
+    resonance: f32,
+    velocity: f32, // Level of the last note, 0 to 1
+    gain: f32,
+    sample_rate: f32,
+}
+
+impl FormantVoice {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut amp_envelope = AREnvelope::new(sample_rate);
+        amp_envelope.set_attack_time(0.01);
+        amp_envelope.set_release_time(0.4);
+
+        let mut voice = Self {
+            saw: PolyBlepSaw::new(110.0, sample_rate),
+            pulse: PolyBlepPulse::new(110.0, sample_rate),
+            pwm_lfo: TriangleOscillator::new(0.5, sample_rate),
+            formants: std::array::from_fn(|i| {
+                SVF::new(VOWEL_FORMANTS[0][i], 8.0, FilterMode::Bandpass, sample_rate)
+            }),
+            amp_envelope,
+            vowel: 0.0,
+            formant_shift: 1.0,
+            waveform: 0.0,
+            pwm_depth: 0.0,
+            resonance: 8.0,
+            velocity: 1.0,
+            gain: 0.5,
+            sample_rate,
+        };
+        voice.update_formants();
+        voice
+    }
+
+    pub fn trigger(&mut self, velocity: f32) {
+        self.velocity = velocity.clamp(0.0, 1.0);
+        if !self.is_active() {
+            self.saw.reset();
+            self.pulse.reset();
+        }
+        self.amp_envelope.trigger();
+    }
+
+    /// Fast-release the voice (used by choke groups)
+    pub fn choke(&mut self) {
+        self.amp_envelope.choke();
+    }
+
+    pub fn set_base_frequency(&mut self, frequency: f32) {
+        let frequency = frequency.clamp(20.0, 2000.0);
+        self.saw.set_frequency(frequency);
+        self.pulse.set_frequency(frequency);
+    }
+
+    /// Vowel from 0 (A) through 1 (E), 2 (I) and 3 (O) to 4 (U); values
+    /// between glide the formants from one to the next
+    pub fn set_vowel(&mut self, vowel: f32) {
+        self.vowel = vowel.clamp(0.0, (VOWEL_FORMANTS.len() - 1) as f32);
+        self.update_formants();
+    }
+
+    /// Multiplies every formant; above 1 sounds smaller, below 1 larger
+    pub fn set_formant_shift(&mut self, shift: f32) {
+        self.formant_shift = shift.clamp(0.5, 2.0);
+        self.update_formants();
+    }
+
+    /// Saw (0) or pulse (1); values between blend the two
+    pub fn set_waveform(&mut self, waveform: f32) {
+        self.waveform = waveform.clamp(0.0, 1.0);
+    }
+
+    /// How far the LFO sweeps the pulse's width, 0 to 1
+    pub fn set_pwm_depth(&mut self, depth: f32) {
+        self.pwm_depth = depth.clamp(0.0, 1.0);
+    }
+
+    pub fn set_pwm_rate(&mut self, rate_hz: f32) {
+        self.pwm_lfo.set_frequency(rate_hz.clamp(0.01, 20.0));
+    }
+
+    /// Q of the formant filters; higher is more nasal and robotic
+    pub fn set_resonance(&mut self, resonance: f32) {
+        self.resonance = resonance.clamp(1.0, 30.0);
+        for formant in self.formants.iter_mut() {
+            formant.set_resonance(self.resonance);
+        }
+    }
+
+    pub fn set_attack(&mut self, time: f32) {
+        self.amp_envelope.set_attack_time(time);
+    }
+
+    pub fn set_release(&mut self, time: f32) {
+        self.amp_envelope.set_release_time(time);
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.amp_envelope.is_active()
+    }
+
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain = gain;
+    }
+
+    fn update_formants(&mut self) {
+        let from = self.vowel.floor() as usize;
+        let to = (from + 1).min(VOWEL_FORMANTS.len() - 1);
+        let blend = self.vowel - from as f32;
+        let nyquist = self.sample_rate * 0.45;
+        for (i, formant) in self.formants.iter_mut().enumerate() {
+            let (a, b) = (VOWEL_FORMANTS[from][i], VOWEL_FORMANTS[to][i]);
+            let frequency = (a + (b - a) * blend) * self.formant_shift;
+            formant.set_cutoff_frequency(frequency.min(nyquist));
+        }
+    }
+}
+
+impl AudioGenerator for FormantVoice {
+    fn next_sample(&mut self) -> f32 {
+        // Keep the LFO running between notes so it doesn't restart
+        let pwm = self.pwm_lfo.next_sample() * self.pwm_depth * MAX_PWM_SWING;
+        if !self.is_active() {
+            return 0.0;
+        }
+
+        let saw = self.saw.next_sample();
+        let pulse = self.pulse.next_sample_with_pwm(pwm);
+        let source = saw + (pulse - saw) * self.waveform;
+
+        // A bandpass passes a bright source with a level growing as the root
+        // of its Q, so scale that out to keep the resonance from changing
+        // the loudness
+        let mut voiced = 0.0;
+        for (formant, level) in self.formants.iter_mut().zip(FORMANT_LEVELS) {
+            voiced += formant.process(source) * level;
+        }
+        let voiced = voiced / self.resonance.sqrt();
+
+        voiced * self.amp_envelope.next_sample() * self.velocity * self.gain
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.saw.set_sample_rate(sample_rate);
+        self.pulse.set_sample_rate(sample_rate);
+        self.pwm_lfo.set_sample_rate(sample_rate);
+        for formant in self.formants.iter_mut() {
+            formant.set_sample_rate(sample_rate);
+        }
+        self.amp_envelope.set_sample_rate(sample_rate);
+        self.update_formants();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::TWO_PI;
+
+    const SAMPLE_RATE: f32 = 44100.0;
+
+    fn render(voice: &mut FormantVoice, samples: usize) -> Vec<f32> {
+        (0..samples).map(|_| voice.next_sample()).collect()
+    }
+
+    /// Magnitude of one frequency in a block, by correlating with a sinusoid
+    fn level_at(samples: &[f32], frequency: f32) -> f32 {
+        let omega = TWO_PI * frequency / SAMPLE_RATE;
+        let (re, im) = samples
+            .iter()
+            .enumerate()
+            .fold((0.0, 0.0), |(re, im), (n, s)| {
+                let angle = omega * n as f32;
+                (re + s * angle.cos(), im + s * angle.sin())
+            });
+        (re * re + im * im).sqrt() / samples.len() as f32
+    }
+
+    fn sung(vowel: f32) -> Vec<f32> {
+        let mut voice = FormantVoice::new(SAMPLE_RATE);
+        voice.set_base_frequency(100.0);
+        voice.set_vowel(vowel);
+        voice.trigger(1.0);
+        render(&mut voice, 8820)[2205..].to_vec()
+    }
+
+    #[test]
+    fn test_vowels_move_the_formants() {
+        // A's first formant sits at 800Hz, I's at 300Hz
+        let a = sung(0.0);
+        let i = sung(2.0);
+        assert!(
+            level_at(&a, 800.0) > level_at(&i, 800.0) * 2.0,
+            "A should be stronger at 800Hz"
+        );
+        assert!(
+            level_at(&i, 300.0) > level_at(&a, 300.0) * 2.0,
+            "I should be stronger at 300Hz"
+        );
+    }
+
+    #[test]
+    fn test_softer_notes_are_quieter_and_choke_to_silence() {
+        let peak = |velocity: f32| {
+            let mut voice = FormantVoice::new(SAMPLE_RATE);
+            voice.trigger(velocity);
+            render(&mut voice, 4410)
+                .iter()
+                .fold(0.0f32, |a, s| a.max(s.abs()))
+        };
+        let full = peak(1.0);
+        assert!(full > 0.01 && full <= 1.0, "Unexpected peak {}", full);
+        assert!((peak(0.5) - full * 0.5).abs() < 1e-4);
+
+        let mut voice = FormantVoice::new(SAMPLE_RATE);
+        voice.trigger(1.0);
+        render(&mut voice, 4410);
+        voice.choke();
+        render(&mut voice, 441);
+        assert!(!voice.is_active(), "Choke should silence within 10ms");
+        assert_eq!(voice.next_sample(), 0.0);
+    }
+}
@@ -0,0 +1,184 @@
+use crate::audio::envelopes::AREnvelope;
+use crate::audio::lofi::LoFi;
+use crate::audio::oscillators::HasherNoise;
+use crate::audio::{AudioGenerator, AudioProcessor};
+
+/// Sweep rates the texture control spans; the slowest holds each hash for
+/// around a hundred samples, the fastest hashes a new value every sample
+const MIN_SWEEP_RATE: f32 = 0.0005;
+const MAX_SWEEP_RATE: f32 = 1.0;
+
+/// Short bursts of hashed noise through a sample-rate and bit-depth crusher,
+/// for IDM-style glitch fills. Each hit rolls its own texture, crush and
+/// length around the set values, so repeated hits never quite match.
+pub struct GlitchPerc {
+    noise: HasherNoise,
+    crusher: LoFi,
+    amp_envelope: AREnvelope,
+    rng: fastrand::Rng,
+
+    texture: f32,    // 0 = stepped and buzzy, 1 = fine hash
+    crush: f32,      // Crusher amount before randomizing, 0 to 1
+    decay: f32,      // Burst length before randomizing
+    randomness: f32, // How far each hit strays from the set values, 0 to 1
+    velocity: f32,   // Level of the last hit, 0 to 1
+    gain: f32,
+}
+
+impl GlitchPerc {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut amp_envelope = AREnvelope::new(sample_rate);
+        amp_envelope.set_attack_time(0.0005);
+        amp_envelope.set_release_bias(0.8); // Exponential-like
+
+        Self {
+            noise: HasherNoise::new(sample_rate),
+            crusher: LoFi::new(),
+            amp_envelope,
+            rng: fastrand::Rng::new(),
+            texture: 0.6,
+            crush: 0.5,
+            decay: 0.06,
+            randomness: 0.5,
+            velocity: 1.0,
+            gain: 1.0,
+        }
+    }
+
+    pub fn trigger(&mut self, velocity: f32) {
+        self.velocity = velocity.clamp(0.0, 1.0);
+
+        let texture = self.vary(self.texture).clamp(0.0, 1.0);
+        let sweep_rate = MIN_SWEEP_RATE * (MAX_SWEEP_RATE / MIN_SWEEP_RATE).powf(texture);
+        self.noise.set_sweep_rate(sweep_rate);
+        self.noise.reset();
+
+        let crush = self.vary(self.crush);
+        self.crusher.set_amount(crush);
+        // Lengths vary by up to a factor of three either way
+        let length = self.decay * 3.0_f32.powf(self.vary(0.0));
+        self.amp_envelope.set_release_time(length.clamp(0.005, 1.0));
+        self.amp_envelope.trigger();
+    }
+
+    /// Fast-release the burst (used by choke groups)
+    pub fn choke(&mut self) {
+        self.amp_envelope.choke();
+    }
+
+    /// Grain of the noise, from stepped and buzzy (0) to a fine hash (1)
+    pub fn set_texture(&mut self, texture: f32) {
+        self.texture = texture.clamp(0.0, 1.0);
+    }
+
+    /// Sample-rate and bit-depth reduction, 0 to 1
+    pub fn set_crush(&mut self, crush: f32) {
+        self.crush = crush.clamp(0.0, 1.0);
+    }
+
+    pub fn set_decay(&mut self, time: f32) {
+        self.decay = time.clamp(0.005, 1.0);
+    }
+
+    /// How far each hit's texture, crush and length stray, 0 to 1
+    pub fn set_randomness(&mut self, randomness: f32) {
+        self.randomness = randomness.clamp(0.0, 1.0);
+    }
+
+    /// Restart the per-hit variations from a seed
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng.seed(seed);
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.amp_envelope.is_active()
+    }
+
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain = gain;
+    }
+
+    /// A value moved up or down by at most the randomness
+    fn vary(&mut self, value: f32) -> f32 {
+        value + (self.rng.f32() * 2.0 - 1.0) * self.randomness
+    }
+}
+
+impl AudioGenerator for GlitchPerc {
+    fn next_sample(&mut self) -> f32 {
+        if !self.is_active() {
+            return 0.0;
+        }
+
+        let burst = self.noise.next_sample() * self.amp_envelope.next_sample();
+        self.crusher.process(burst) * self.velocity * self.gain
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.noise.set_sample_rate(sample_rate);
+        self.crusher.set_sample_rate(sample_rate);
+        self.amp_envelope.set_sample_rate(sample_rate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 44100.0;
+
+    fn hit(glitch: &mut GlitchPerc) -> Vec<f32> {
+        glitch.trigger(1.0);
+        (0..22050).map(|_| glitch.next_sample()).collect()
+    }
+
+    #[test]
+    fn test_hits_vary_only_with_randomness() {
+        let mut a = GlitchPerc::new(SAMPLE_RATE);
+        let mut b = GlitchPerc::new(SAMPLE_RATE);
+        a.set_seed(1);
+        b.set_seed(2);
+
+        a.set_randomness(0.0);
+        b.set_randomness(0.0);
+        let first = hit(&mut a);
+        assert!(first.iter().any(|s| s.abs() > 0.05), "Burst should sound");
+        assert_eq!(first, hit(&mut b), "Without randomness the seed is unheard");
+
+        a.set_randomness(1.0);
+        b.set_randomness(1.0);
+        assert_ne!(hit(&mut a), hit(&mut b), "Random hits should differ");
+    }
+
+    #[test]
+    fn test_seeded_hits_repeat() {
+        let mut a = GlitchPerc::new(SAMPLE_RATE);
+        let mut b = GlitchPerc::new(SAMPLE_RATE);
+        a.set_seed(5);
+        b.set_seed(5);
+        for _ in 0..4 {
+            assert_eq!(hit(&mut a), hit(&mut b));
+        }
+    }
+
+    #[test]
+    fn test_bursts_are_short() {
+        let mut glitch = GlitchPerc::new(SAMPLE_RATE);
+        glitch.set_randomness(0.0);
+        glitch.set_decay(0.02);
+        glitch.trigger(1.0);
+        for _ in 0..2205 {
+            glitch.next_sample();
+        }
+        assert!(!glitch.is_active(), "A 20ms burst should be over by 50ms");
+
+        glitch.set_decay(1.0);
+        glitch.trigger(1.0);
+        glitch.next_sample();
+        glitch.choke();
+        for _ in 0..441 {
+            glitch.next_sample();
+        }
+        assert!(!glitch.is_active(), "Choke should silence within 10ms");
+    }
+}
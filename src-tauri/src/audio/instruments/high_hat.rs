@@ -3,6 +3,13 @@ use crate::audio::filters::{FilterMode, SVF};
 use crate::audio::oscillators::NoiseGenerator;
 use crate::audio::{AudioGenerator, AudioProcessor};
 
+/// Closed hats are short and tight; open hats ring on with a slower tail
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HatMode {
+    Closed,
+    Open,
+}
+
 pub struct HiHat {
     noise_generator: NoiseGenerator,
 
@@ -15,7 +22,9 @@ pub struct HiHat {
     amp_envelope: AREnvelope,
 
     // Parameters
+    mode: HatMode,
     length: f32,
+    velocity: f32, // Level of the last hit, 0 to 1
     gain: f32,
 }
 
@@ -32,7 +41,9 @@ impl HiHat {
 
             amp_envelope: AREnvelope::new(sample_rate),
 
+            mode: HatMode::Closed,
             length: 0.05, // 50ms default
+            velocity: 1.0,
             gain: 1.0,
         };
 
@@ -44,10 +55,31 @@ impl HiHat {
         hihat
     }
 
-    pub fn trigger(&mut self) {
+    pub fn trigger(&mut self, velocity: f32) {
+        self.velocity = velocity.clamp(0.0, 1.0);
         self.amp_envelope.trigger();
     }
 
+    /// Restart the noise sequence from a seed
+    pub fn set_seed(&mut self, seed: u64) {
+        self.noise_generator.set_seed(seed);
+    }
+
+    /// Fast-release the voice (used by choke groups)
+    pub fn choke(&mut self) {
+        self.amp_envelope.choke();
+    }
+
+    /// Switch between closed and open, which resets the length to suit
+    pub fn set_mode(&mut self, mode: HatMode) {
+        self.mode = mode;
+        self.length = match mode {
+            HatMode::Closed => 0.05,
+            HatMode::Open => 0.45,
+        };
+        self.update_release_time();
+    }
+
     pub fn set_length(&mut self, length: f32) {
         self.length = length.max(0.002); // Minimum 2ms
         self.update_release_time();
@@ -57,7 +89,11 @@ impl HiHat {
         // Release time is length - attack time (1ms)
         self.amp_envelope
             .set_release_time((self.length - 0.001).max(0.001));
-        self.amp_envelope.set_release_bias(0.7); // Exponential decay
+        let release_bias = match self.mode {
+            HatMode::Closed => 0.7, // Exponential decay
+            HatMode::Open => 0.55,  // Closer to linear so it rings on
+        };
+        self.amp_envelope.set_release_bias(release_bias);
     }
 
     pub fn is_active(&self) -> bool {
@@ -91,7 +127,7 @@ impl AudioGenerator for HiHat {
 
         // Apply envelope
         let amp_env = self.amp_envelope.next_sample();
-        saturated * amp_env * self.gain
+        saturated * amp_env * self.velocity * self.gain
     }
 
     fn set_sample_rate(&mut self, sample_rate: f32) {
@@ -102,4 +138,3 @@ impl AudioGenerator for HiHat {
         self.amp_envelope.set_sample_rate(sample_rate);
     }
 }
-
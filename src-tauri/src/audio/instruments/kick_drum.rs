@@ -1,13 +1,21 @@
 use crate::audio::envelopes::AREnvelope;
-use crate::audio::oscillators::SineOscillator;
-use crate::audio::AudioGenerator;
+use crate::audio::filters::{FilterMode, SVF};
+use crate::audio::oscillators::{NoiseGenerator, SineOscillator};
+use crate::audio::{AudioGenerator, AudioProcessor};
 
+/// Sine kick with a pitch sweep, plus a click of highpassed noise on top of
+/// the attack to help it cut through a dense mix
 pub struct KickDrum {
     oscillator: SineOscillator,
     amp_envelope: AREnvelope,
     freq_envelope: AREnvelope,
+    click_generator: NoiseGenerator,
+    click_filter: SVF,
+    click_envelope: AREnvelope,
     base_frequency: f32,
     frequency_ratio: f32,
+    click_level: f32, // 0 = body only
+    velocity: f32,    // Level of the last hit, 0 to 1
     gain: f32,
 }
 
@@ -17,8 +25,13 @@ impl KickDrum {
             oscillator: SineOscillator::new(60.0, sample_rate),
             amp_envelope: AREnvelope::new(sample_rate),
             freq_envelope: AREnvelope::new(sample_rate),
+            click_generator: NoiseGenerator::new(),
+            click_filter: SVF::new(3000.0, 0.7, FilterMode::Highpass, sample_rate),
+            click_envelope: AREnvelope::new(sample_rate),
             base_frequency: 60.0,
             frequency_ratio: 7.0,
+            click_level: 0.0,
+            velocity: 1.0,
             gain: 1.0,
         };
 
@@ -32,15 +45,28 @@ impl KickDrum {
         kick.freq_envelope.set_attack_bias(0.7); // Exponential-like
         kick.freq_envelope.set_release_bias(0.7); // Exponential-like
 
+        kick.click_envelope.set_attack_time(0.0005);
+        kick.click_envelope.set_release_time(0.01);
+        kick.click_envelope.set_release_bias(0.8); // Exponential-like
+
         kick
     }
 
-    pub fn trigger(&mut self) {
+    /// Softer hits are quieter and sweep down from lower
+    pub fn trigger(&mut self, velocity: f32) {
+        self.velocity = velocity.clamp(0.0, 1.0);
         self.amp_envelope.trigger();
         self.freq_envelope.trigger();
+        self.click_envelope.trigger();
         self.oscillator.reset();
     }
 
+    /// Fast-release the voice (used by choke groups)
+    pub fn choke(&mut self) {
+        self.amp_envelope.choke();
+        self.click_envelope.choke();
+    }
+
     pub fn set_base_frequency(&mut self, freq: f32) {
         self.base_frequency = freq;
     }
@@ -65,6 +91,20 @@ impl KickDrum {
         self.freq_envelope.set_release_time(time);
     }
 
+    /// Level of the click against the body, 0 to 1
+    pub fn set_click_level(&mut self, level: f32) {
+        self.click_level = level.clamp(0.0, 1.0);
+    }
+
+    pub fn set_click_decay(&mut self, time: f32) {
+        self.click_envelope.set_release_time(time.clamp(0.001, 0.1));
+    }
+
+    /// Restart the click's noise sequence from a seed
+    pub fn set_seed(&mut self, seed: u64) {
+        self.click_generator.set_seed(seed);
+    }
+
     pub fn is_active(&self) -> bool {
         self.amp_envelope.is_active()
     }
@@ -84,18 +124,28 @@ impl AudioGenerator for KickDrum {
         let freq_env = self.freq_envelope.next_sample();
 
         // Use frequency ratio for sharper sweep: starts at base_frequency * ratio, sweeps down to base_frequency
-        let start_freq = self.base_frequency * self.frequency_ratio;
+        let ratio = 1.0 + (self.frequency_ratio - 1.0) * (0.5 + 0.5 * self.velocity);
+        let start_freq = self.base_frequency * ratio;
         let current_freq = self.base_frequency + (freq_env * (start_freq - self.base_frequency));
         self.oscillator.set_frequency(current_freq);
 
-        let sample = self.oscillator.next_sample();
-        sample * amp_env * self.gain
+        let body = self.oscillator.next_sample() * amp_env;
+
+        let mut click = 0.0;
+        if self.click_envelope.is_active() {
+            let noise = self.click_generator.next_sample();
+            click = self.click_filter.process(noise) * self.click_envelope.next_sample();
+        }
+
+        (body + click * self.click_level) * self.velocity * self.gain
     }
 
     fn set_sample_rate(&mut self, sample_rate: f32) {
         self.oscillator.set_sample_rate(sample_rate);
         self.amp_envelope.set_sample_rate(sample_rate);
         self.freq_envelope.set_sample_rate(sample_rate);
+        self.click_generator.set_sample_rate(sample_rate);
+        self.click_filter.set_sample_rate(sample_rate);
+        self.click_envelope.set_sample_rate(sample_rate);
     }
 }
-
@@ -0,0 +1,126 @@
+use crate::audio::envelopes::AREnvelope;
+use crate::audio::oscillators::SineOscillator;
+use crate::audio::AudioGenerator;
+
+const CLICK_FREQUENCY: f32 = 1000.0;
+const ACCENT_FREQUENCY: f32 = 1600.0;
+const ACCENT_GAIN: f32 = 1.5;
+
+/// Short sine blip used for the metronome and count-in
+pub struct Metronome {
+    oscillator: SineOscillator,
+    amp_envelope: AREnvelope,
+
+    // Parameters
+    volume: f32,
+    accent_downbeat: bool,
+
+    // Gain of the click currently sounding
+    click_gain: f32,
+}
+
+impl Metronome {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut metronome = Self {
+            oscillator: SineOscillator::new(CLICK_FREQUENCY, sample_rate),
+            amp_envelope: AREnvelope::new(sample_rate),
+            volume: 0.5,
+            accent_downbeat: true,
+            click_gain: 1.0,
+        };
+
+        metronome.amp_envelope.set_attack_time(0.001);
+        metronome.amp_envelope.set_attack_bias(0.9);
+        metronome.amp_envelope.set_release_time(0.03);
+        metronome.amp_envelope.set_release_bias(0.7);
+
+        metronome
+    }
+
+    /// Click once; downbeats are pitched up and louder when accenting is on
+    pub fn trigger(&mut self, is_downbeat: bool) {
+        if is_downbeat && self.accent_downbeat {
+            self.oscillator.set_frequency(ACCENT_FREQUENCY);
+            self.click_gain = ACCENT_GAIN;
+        } else {
+            self.oscillator.set_frequency(CLICK_FREQUENCY);
+            self.click_gain = 1.0;
+        }
+        self.oscillator.reset();
+        self.amp_envelope.trigger();
+    }
+
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume.clamp(0.0, 1.0);
+    }
+
+    pub fn set_accent_downbeat(&mut self, accent: bool) {
+        self.accent_downbeat = accent;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.amp_envelope.is_active()
+    }
+}
+
+impl AudioGenerator for Metronome {
+    fn next_sample(&mut self) -> f32 {
+        if !self.is_active() {
+            return 0.0;
+        }
+
+        let amp_env = self.amp_envelope.next_sample();
+        self.oscillator.next_sample() * amp_env * self.click_gain * self.volume
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.oscillator.set_sample_rate(sample_rate);
+        self.amp_envelope.set_sample_rate(sample_rate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 44100.0;
+
+    /// Peak level and cycles of one click
+    fn click(metronome: &mut Metronome, is_downbeat: bool) -> (f32, usize) {
+        metronome.trigger(is_downbeat);
+        let output: Vec<f32> = (0..2205).map(|_| metronome.next_sample()).collect();
+        let peak = output.iter().fold(0.0f32, |a, s| a.max(s.abs()));
+        let crossings = output
+            .windows(2)
+            .filter(|w| w[0] <= 0.0 && w[1] > 0.0)
+            .count();
+        (peak, crossings)
+    }
+
+    #[test]
+    fn test_downbeats_are_higher_and_louder() {
+        let mut metronome = Metronome::new(SAMPLE_RATE);
+        let (beat_peak, beat_cycles) = click(&mut metronome, false);
+        assert!(!metronome.is_active(), "Clicks should be over within 50ms");
+
+        let (downbeat_peak, downbeat_cycles) = click(&mut metronome, true);
+        assert!(downbeat_peak > beat_peak * 1.3);
+        assert!(downbeat_cycles > beat_cycles * 3 / 2);
+
+        metronome.set_accent_downbeat(false);
+        assert_eq!(click(&mut metronome, true), (beat_peak, beat_cycles));
+    }
+
+    #[test]
+    fn test_volume_scales_the_click() {
+        let mut metronome = Metronome::new(SAMPLE_RATE);
+        metronome.set_volume(1.0);
+        let (loud, _) = click(&mut metronome, false);
+        metronome.set_volume(0.25);
+        let (quiet, _) = click(&mut metronome, false);
+        assert!((quiet - loud * 0.25).abs() < 1e-4);
+
+        metronome.set_volume(0.0);
+        assert_eq!(click(&mut metronome, false).0, 0.0);
+    }
+}
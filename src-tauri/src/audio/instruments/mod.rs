@@ -1,15 +1,43 @@
+mod acid_bass;
+mod bass_808;
 mod chord_synth;
 mod clap;
+mod clave;
+mod cowbell;
+mod cymbal_synth;
+mod fm6_voice;
 mod fm_voice;
+mod formant_voice;
+mod glitch_perc;
 mod high_hat;
 mod kick_drum;
+mod metronome;
+mod modal_drum;
+mod pad_synth;
+mod sample_player;
 mod snare_drum;
 mod supersaw_synth;
+mod tom_drum;
+mod wavetable_synth;
 
+pub use acid_bass::AcidBass;
+pub use bass_808::Bass808;
 pub use chord_synth::ChordSynth;
 pub use clap::ClapDrum;
-pub use fm_voice::FMVoice;
-pub use high_hat::HiHat;
+pub use clave::Clave;
+pub use cowbell::Cowbell;
+pub use cymbal_synth::{CymbalMode, CymbalSynth};
+pub use fm6_voice::{FM6Algorithm, FM6Voice, FM6_OPERATORS};
+pub use fm_voice::{FMVoice, FM_OPERATORS};
+pub use formant_voice::FormantVoice;
+pub use glitch_perc::GlitchPerc;
+pub use high_hat::{HatMode, HiHat};
 pub use kick_drum::KickDrum;
+pub use metronome::Metronome;
+pub use modal_drum::ModalDrum;
+pub use pad_synth::PadSynth;
+pub use sample_player::SamplePlayer;
 pub use snare_drum::SnareDrum;
 pub use supersaw_synth::SupersawSynth;
+pub use tom_drum::TomDrum;
+pub use wavetable_synth::WavetableSynth;
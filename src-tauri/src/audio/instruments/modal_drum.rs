@@ -0,0 +1,340 @@
+use crate::audio::envelopes::AREnvelope;
+use crate::audio::oscillators::NoiseGenerator;
+use crate::audio::AudioGenerator;
+use std::f32::consts::PI;
+
+/// Modes of an ideal circular membrane as (nodal diameters, frequency ratio
+/// to the fundamental), from the zeros of the Bessel functions
+const MEMBRANE_MODES: [(u32, f32); 10] = [
+    (0, 1.0),
+    (1, 1.594),
+    (2, 2.136),
+    (0, 2.296),
+    (3, 2.653),
+    (1, 2.918),
+    (4, 3.156),
+    (2, 3.501),
+    (0, 3.600),
+    (5, 3.652),
+];
+
+/// A ring under -60 dB counts as finished
+const DECAY_DB_FACTOR: f32 = 6.91; // ln(1000)
+
+/// Ring time of every mode once choked
+const CHOKE_DECAY_SECONDS: f32 = 0.01;
+
+/// One mode of the membrane: a two-pole resonator whose impulse response is
+/// an exponentially decaying sine
+struct ModalResonator {
+    ratio: f32,
+    diameters: u32,
+    // Feedback coefficients and input scale for a unit-amplitude ring
+    b1: f32,
+    b2: f32,
+    input_scale: f32,
+    y1: f32,
+    y2: f32,
+    amplitude: f32,
+}
+
+impl ModalResonator {
+    fn new(diameters: u32, ratio: f32) -> Self {
+        Self {
+            ratio,
+            diameters,
+            b1: 0.0,
+            b2: 0.0,
+            input_scale: 0.0,
+            y1: 0.0,
+            y2: 0.0,
+            amplitude: 0.0,
+        }
+    }
+
+    fn tune(&mut self, frequency: f32, decay: f32, sample_rate: f32) {
+        // Modes above Nyquist are left silent rather than aliasing
+        let omega = 2.0 * PI * (frequency / sample_rate).min(0.49);
+        let radius = (-DECAY_DB_FACTOR / (decay * sample_rate)).exp();
+        self.b1 = 2.0 * radius * omega.cos();
+        self.b2 = -radius * radius;
+        self.input_scale = if frequency < sample_rate * 0.49 {
+            omega.sin()
+        } else {
+            0.0
+        };
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        let output = self.b1 * self.y1 + self.b2 * self.y2 + input * self.input_scale;
+        self.y2 = self.y1;
+        self.y1 = output;
+        output
+    }
+
+    fn reset(&mut self) {
+        self.y1 = 0.0;
+        self.y2 = 0.0;
+    }
+}
+
+/// Membrane drum from modal synthesis: a bank of resonators tuned to the
+/// modes of a circular membrane, struck by an impulse and a short noise
+/// burst. Moving the strike from the centre towards the rim trades the round
+/// axisymmetric modes for the clangy ones with nodal diameters.
+pub struct ModalDrum {
+    modes: Vec<ModalResonator>,
+    noise_generator: NoiseGenerator,
+    burst_envelope: AREnvelope,
+
+    tune: f32,
+    decay: f32,
+    damping: f32,  // How much faster the upper modes die, 0 to 1
+    position: f32, // Strike point, 0 = centre, 1 = rim
+    noise: f32,    // Level of the noise burst against the impulse
+    // Impulse waiting to go into the resonators on the next sample
+    impulse: f32,
+    choked: bool,
+    ring_samples: u32,
+    velocity: f32, // Level of the last hit, 0 to 1
+    gain: f32,
+    sample_rate: f32,
+}
+
+impl ModalDrum {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut burst_envelope = AREnvelope::new(sample_rate);
+        burst_envelope.set_attack_time(0.0005);
+        burst_envelope.set_release_time(0.008);
+        burst_envelope.set_release_bias(0.7); // Exponential-like
+
+        let mut drum = Self {
+            modes: MEMBRANE_MODES
+                .iter()
+                .map(|&(diameters, ratio)| ModalResonator::new(diameters, ratio))
+                .collect(),
+            noise_generator: NoiseGenerator::new(),
+            burst_envelope,
+            tune: 120.0,
+            decay: 0.6,
+            damping: 0.5,
+            position: 0.3,
+            noise: 0.3,
+            impulse: 0.0,
+            choked: false,
+            ring_samples: 0,
+            velocity: 1.0,
+            gain: 1.0,
+            sample_rate,
+        };
+        drum.update_modes();
+        drum.update_amplitudes();
+        drum
+    }
+
+    /// Harder hits are louder and ring the upper modes more
+    pub fn trigger(&mut self, velocity: f32) {
+        self.velocity = velocity.clamp(0.0, 1.0);
+        if self.choked {
+            self.choked = false;
+            self.update_modes();
+        }
+        if !self.is_active() {
+            for mode in self.modes.iter_mut() {
+                mode.reset();
+            }
+        }
+        self.update_amplitudes();
+        self.impulse = 1.0;
+        self.burst_envelope.trigger();
+        self.ring_samples = (self.decay * self.sample_rate) as u32;
+    }
+
+    /// Damp every mode (used by choke groups)
+    pub fn choke(&mut self) {
+        self.choked = true;
+        self.update_modes();
+        self.burst_envelope.choke();
+        self.ring_samples = self
+            .ring_samples
+            .min((CHOKE_DECAY_SECONDS * self.sample_rate) as u32);
+    }
+
+    /// Frequency of the fundamental mode
+    pub fn set_tune(&mut self, frequency: f32) {
+        self.tune = frequency.clamp(30.0, 2000.0);
+        self.update_modes();
+    }
+
+    /// Time for the fundamental to fall by 60 dB
+    pub fn set_decay(&mut self, time: f32) {
+        self.decay = time.clamp(0.02, 4.0);
+        self.update_modes();
+    }
+
+    /// How much faster the upper modes die than the fundamental, 0 to 1
+    pub fn set_damping(&mut self, damping: f32) {
+        self.damping = damping.clamp(0.0, 1.0);
+        self.update_modes();
+    }
+
+    /// Where the membrane is struck, from the centre (0) to the rim (1)
+    pub fn set_strike_position(&mut self, position: f32) {
+        self.position = position.clamp(0.0, 1.0);
+        self.update_amplitudes();
+    }
+
+    /// Level of the noise burst that excites the modes alongside the impulse
+    pub fn set_noise(&mut self, noise: f32) {
+        self.noise = noise.clamp(0.0, 1.0);
+    }
+
+    /// Restart the noise sequence from a seed
+    pub fn set_seed(&mut self, seed: u64) {
+        self.noise_generator.set_seed(seed);
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.ring_samples > 0
+    }
+
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain = gain;
+    }
+
+    fn update_modes(&mut self) {
+        for mode in self.modes.iter_mut() {
+            let decay = if self.choked {
+                CHOKE_DECAY_SECONDS
+            } else {
+                // Upper modes lose energy faster, as air and the head damp them
+                self.decay / (1.0 + self.damping * 3.0 * (mode.ratio - 1.0))
+            };
+            mode.tune(self.tune * mode.ratio, decay, self.sample_rate);
+        }
+    }
+
+    fn update_amplitudes(&mut self) {
+        // Softer hits barely reach the upper modes
+        let brightness = 0.5 + 0.5 * self.velocity;
+        for mode in self.modes.iter_mut() {
+            // A centre strike sits on the nodal diameters, so only the
+            // axisymmetric modes sound; towards the rim the rest come in
+            let weight = if mode.diameters == 0 {
+                1.0 - 0.6 * self.position
+            } else {
+                self.position.powf(0.5 * mode.diameters as f32)
+            };
+            mode.amplitude = weight * brightness.powf(mode.ratio - 1.0) / mode.ratio;
+        }
+    }
+}
+
+impl AudioGenerator for ModalDrum {
+    fn next_sample(&mut self) -> f32 {
+        if !self.is_active() {
+            return 0.0;
+        }
+        self.ring_samples -= 1;
+
+        let burst = self.noise_generator.next_sample() * self.burst_envelope.next_sample();
+        let excitation = self.impulse * (1.0 - self.noise) + burst * self.noise * 0.2;
+        self.impulse = 0.0;
+
+        let mut output = 0.0;
+        for mode in self.modes.iter_mut() {
+            output += mode.process(excitation) * mode.amplitude;
+        }
+        output * self.velocity * self.gain
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.noise_generator.set_sample_rate(sample_rate);
+        self.burst_envelope.set_sample_rate(sample_rate);
+        self.update_modes();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 44100.0;
+
+    fn render(drum: &mut ModalDrum, samples: usize) -> Vec<f32> {
+        (0..samples).map(|_| drum.next_sample()).collect()
+    }
+
+    #[test]
+    fn test_strike_position_picks_the_modes() {
+        let mut drum = ModalDrum::new(SAMPLE_RATE);
+        drum.set_strike_position(0.0);
+        for mode in drum.modes.iter() {
+            if mode.diameters == 0 {
+                assert!(mode.amplitude > 0.0, "Centre strike should ring round modes");
+            } else {
+                assert_eq!(mode.amplitude, 0.0, "Centre strike sits on nodal diameters");
+            }
+        }
+
+        drum.set_strike_position(1.0);
+        assert!(
+            drum.modes.iter().all(|mode| mode.amplitude > 0.0),
+            "A rim strike should ring every mode"
+        );
+    }
+
+    #[test]
+    fn test_fundamental_rings_at_the_tune() {
+        let mut drum = ModalDrum::new(SAMPLE_RATE);
+        drum.set_tune(200.0);
+        drum.set_decay(1.0);
+        drum.set_damping(1.0);
+        drum.set_strike_position(0.0);
+        drum.set_noise(0.0);
+        drum.trigger(1.0);
+
+        // Once the damped upper modes have gone only the fundamental is left
+        let output = render(&mut drum, 17640);
+        let crossings = output[13230..]
+            .windows(2)
+            .filter(|w| w[0] <= 0.0 && w[1] > 0.0)
+            .count();
+        assert!(
+            (19..=21).contains(&crossings),
+            "Expected 20 cycles of 200Hz in 0.1s, got {}",
+            crossings
+        );
+    }
+
+    #[test]
+    fn test_hits_decay_and_choke_to_silence() {
+        let mut drum = ModalDrum::new(SAMPLE_RATE);
+        drum.set_decay(0.2);
+        drum.trigger(1.0);
+        let output = render(&mut drum, 13230);
+        assert!(output[..441].iter().any(|s| s.abs() > 0.01));
+        assert!(!drum.is_active(), "Should be silent after its decay");
+        assert!(output[8820..].iter().all(|s| *s == 0.0));
+
+        drum.set_decay(2.0);
+        drum.trigger(1.0);
+        render(&mut drum, 4410);
+        drum.choke();
+        render(&mut drum, 441);
+        assert!(!drum.is_active(), "Choke should silence within 10ms");
+    }
+
+    #[test]
+    fn test_seeded_hits_repeat() {
+        let mut a = ModalDrum::new(SAMPLE_RATE);
+        let mut b = ModalDrum::new(SAMPLE_RATE);
+        for drum in [&mut a, &mut b] {
+            drum.set_noise(1.0);
+            drum.set_seed(11);
+            drum.trigger(1.0);
+        }
+        assert_eq!(render(&mut a, 4410), render(&mut b, 4410));
+    }
+}
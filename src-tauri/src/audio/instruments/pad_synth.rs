@@ -0,0 +1,250 @@
+use super::supersaw_synth::SupersawOscillator;
+use crate::audio::buffers::Interpolation;
+use crate::audio::delays::DelayLine;
+use crate::audio::envelopes::AREnvelope;
+use crate::audio::filters::{FilterMode, SVF};
+use crate::audio::oscillators::SineOscillator;
+use crate::audio::{AudioGenerator, AudioProcessor, StereoAudioGenerator};
+use crate::sequencing::ChordQuality;
+
+/// Chord notes the pad can hold; triads double the root an octave up
+const PAD_VOICES: usize = 4;
+const SAWS_PER_VOICE: usize = 5;
+
+/// Chorus delay at the centre of its sweep and how far either side it moves
+const CHORUS_DELAY_SECONDS: f32 = 0.012;
+const CHORUS_DEPTH_SECONDS: f32 = 0.004;
+
+/// Slow-swelling pad: a stack of detuned saws on each note of a chord,
+/// through a lowpass and a stereo chorus. The chord comes from the tonal
+/// module's chord qualities, so the pad can follow a generated root.
+pub struct PadSynth {
+    voices: Vec<SupersawOscillator>,
+    filter_left: SVF,
+    filter_right: SVF,
+    amp_envelope: AREnvelope,
+    chorus_left: DelayLine,
+    chorus_right: DelayLine,
+    chorus_lfo: SineOscillator,
+
+    root: f32,
+    quality: ChordQuality,
+    chorus_mix: f32,
+    velocity: f32, // Level of the last chord, 0 to 1
+    gain: f32,
+}
+
+impl PadSynth {
+    pub fn new(sample_rate: f32) -> Self {
+        let voices = (0..PAD_VOICES)
+            .map(|_| {
+                let mut voice = SupersawOscillator::new(220.0, sample_rate, SAWS_PER_VOICE);
+                voice.set_detune(0.6);
+                voice
+            })
+            .collect();
+
+        let mut amp_envelope = AREnvelope::new(sample_rate);
+        amp_envelope.set_attack_time(1.2);
+        amp_envelope.set_release_time(2.5);
+        amp_envelope.set_attack_bias(0.4);
+        amp_envelope.set_release_bias(0.6);
+
+        let max_chorus_seconds = CHORUS_DELAY_SECONDS + CHORUS_DEPTH_SECONDS + 0.01;
+        let mut chorus_left = DelayLine::new(max_chorus_seconds, sample_rate);
+        let mut chorus_right = DelayLine::new(max_chorus_seconds, sample_rate);
+        chorus_left.set_interpolation(Interpolation::Linear);
+        chorus_right.set_interpolation(Interpolation::Linear);
+
+        let mut pad = Self {
+            voices,
+            filter_left: SVF::new(3000.0, 0.7, FilterMode::Lowpass, sample_rate),
+            filter_right: SVF::new(3000.0, 0.7, FilterMode::Lowpass, sample_rate),
+            amp_envelope,
+            chorus_left,
+            chorus_right,
+            chorus_lfo: SineOscillator::new(0.5, sample_rate),
+            root: 220.0,
+            quality: ChordQuality::Minor,
+            chorus_mix: 0.5,
+            velocity: 1.0,
+            gain: 0.5,
+        };
+        pad.update_frequencies();
+        pad
+    }
+
+    pub fn trigger(&mut self, velocity: f32) {
+        self.velocity = velocity.clamp(0.0, 1.0);
+        self.amp_envelope.trigger();
+    }
+
+    /// Fast-release the pad (used by choke groups)
+    pub fn choke(&mut self) {
+        self.amp_envelope.choke();
+    }
+
+    /// Root of the chord; the other notes follow it
+    pub fn set_base_frequency(&mut self, frequency: f32) {
+        self.root = frequency.clamp(20.0, 2000.0);
+        self.update_frequencies();
+    }
+
+    pub fn set_chord_quality(&mut self, quality: ChordQuality) {
+        self.quality = quality;
+        self.update_frequencies();
+    }
+
+    pub fn set_detune(&mut self, detune: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.set_detune(detune);
+        }
+    }
+
+    pub fn set_attack(&mut self, time: f32) {
+        self.amp_envelope.set_attack_time(time);
+    }
+
+    pub fn set_release(&mut self, time: f32) {
+        self.amp_envelope.set_release_time(time);
+    }
+
+    pub fn set_filter_cutoff(&mut self, cutoff: f32) {
+        let cutoff = cutoff.clamp(20.0, 20000.0);
+        self.filter_left.set_cutoff_frequency(cutoff);
+        self.filter_right.set_cutoff_frequency(cutoff);
+    }
+
+    pub fn set_chorus_rate(&mut self, rate_hz: f32) {
+        self.chorus_lfo.set_frequency(rate_hz.clamp(0.05, 5.0));
+    }
+
+    /// Balance of the chorused signal against the dry one, 0 to 1
+    pub fn set_chorus_mix(&mut self, mix: f32) {
+        self.chorus_mix = mix.clamp(0.0, 1.0);
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.amp_envelope.is_active()
+    }
+
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain = gain;
+    }
+
+    fn update_frequencies(&mut self) {
+        // Voices past the chord's notes double the root an octave up
+        let notes = self
+            .quality
+            .frequencies(self.root)
+            .chain(std::iter::repeat(self.root * 2.0));
+        for (voice, frequency) in self.voices.iter_mut().zip(notes) {
+            voice.set_frequency(frequency);
+        }
+    }
+}
+
+impl StereoAudioGenerator for PadSynth {
+    fn next_sample(&mut self) -> (f32, f32) {
+        // Keep the chorus sweeping between chords so it doesn't restart
+        let lfo = self.chorus_lfo.next_sample();
+        if !self.is_active() {
+            return (0.0, 0.0);
+        }
+
+        let (mut left, mut right) = (0.0, 0.0);
+        for voice in self.voices.iter_mut() {
+            let (voice_left, voice_right) = voice.next_sample();
+            left += voice_left;
+            right += voice_right;
+        }
+        let left = self.filter_left.process(left / PAD_VOICES as f32);
+        let right = self.filter_right.process(right / PAD_VOICES as f32);
+
+        // The two sides sweep in opposite directions to widen the pad
+        let sweep = lfo * CHORUS_DEPTH_SECONDS;
+        let chorus_left = self.chorus_left.read_at(CHORUS_DELAY_SECONDS + sweep);
+        let chorus_right = self.chorus_right.read_at(CHORUS_DELAY_SECONDS - sweep);
+        self.chorus_left.write(left, 0.0);
+        self.chorus_right.write(right, 0.0);
+
+        let left = left + (chorus_left - left) * self.chorus_mix;
+        let right = right + (chorus_right - right) * self.chorus_mix;
+
+        let level = self.amp_envelope.next_sample() * self.velocity * self.gain;
+        (left * level, right * level)
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.set_sample_rate(sample_rate);
+        }
+        self.filter_left.set_sample_rate(sample_rate);
+        self.filter_right.set_sample_rate(sample_rate);
+        self.amp_envelope.set_sample_rate(sample_rate);
+        self.chorus_left.set_sample_rate(sample_rate);
+        self.chorus_right.set_sample_rate(sample_rate);
+        self.chorus_lfo.set_sample_rate(sample_rate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 44100.0;
+
+    fn render(pad: &mut PadSynth, samples: usize) -> Vec<f32> {
+        (0..samples).map(|_| pad.next_sample().0).collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn test_chords_swell_in_slowly() {
+        let mut pad = PadSynth::new(SAMPLE_RATE);
+        pad.trigger(1.0);
+        let output = render(&mut pad, 52920);
+
+        let onset = rms(&output[..2205]);
+        let swelled = rms(&output[48510..]);
+        assert!(swelled > 0.01, "Pad should be sounding by 1.1s, got {}", swelled);
+        assert!(
+            onset < swelled * 0.25,
+            "The first 50ms ({}) should be far quieter than the swell ({})",
+            onset,
+            swelled
+        );
+    }
+
+    #[test]
+    fn test_velocity_scales_the_level() {
+        let mut full = PadSynth::new(SAMPLE_RATE);
+        full.trigger(1.0);
+        let full_rms = rms(&render(&mut full, 52920)[44100..]);
+
+        let mut soft = PadSynth::new(SAMPLE_RATE);
+        soft.trigger(0.5);
+        let soft_rms = rms(&render(&mut soft, 52920)[44100..]);
+
+        let ratio = soft_rms / full_rms;
+        assert!(
+            (ratio - 0.5).abs() < 0.1,
+            "Half velocity should be half as loud, got a ratio of {}",
+            ratio
+        );
+    }
+
+    #[test]
+    fn test_choke_silences_a_held_chord() {
+        let mut pad = PadSynth::new(SAMPLE_RATE);
+        pad.trigger(1.0);
+        render(&mut pad, 44100);
+        pad.choke();
+        render(&mut pad, 441);
+        assert!(!pad.is_active(), "Choke should silence within 10ms");
+        assert_eq!(pad.next_sample(), (0.0, 0.0));
+    }
+}
@@ -0,0 +1,429 @@
+use crate::audio::envelopes::AREnvelope;
+use crate::audio::filters::{FilterMode, SVF};
+use crate::audio::oscillators::SampleOscillator;
+use crate::audio::samples::SampleRef;
+use crate::audio::time_stretch::{StretchQuality, TimeStretch};
+use crate::audio::{AudioGenerator, AudioProcessor, StereoAudioGenerator};
+use std::ops::{Range, RangeInclusive};
+
+/// Most samples one pad can hold across its velocity and round-robin layers
+pub const MAX_LAYERS: usize = 8;
+
+/// A sample, or a slice of one, that a pad plays for velocities in its range
+#[derive(Clone)]
+struct SampleLayer {
+    sample: SampleRef,
+    frames: Range<usize>,
+    velocity: RangeInclusive<f32>,
+}
+
+/// One-shot player for an imported sample or a slice of one. Samples are
+/// converted to the engine rate on import; if the device rate changes
+/// afterwards the player steps through at the ratio between the two.
+/// Playback can be sped up or slowed down without changing pitch, so loops
+/// follow the tempo, and shaped like the synthesized drums with a pitch
+/// envelope, an enveloped filter and reverse playback.
+///
+/// A pad plays its sample once per trigger, or loops it from the start
+/// offset until stopped or triggered again.
+///
+/// A pad can hold several layers. Each trigger picks among the layers whose
+/// velocity range covers the hit, rotating through them so repeated hits
+/// don't sound identical.
+pub struct SamplePlayer {
+    layers: Vec<SampleLayer>,
+    round_robin: usize,
+    // Layer picked by the last trigger
+    sample: Option<SampleRef>,
+    frames: Range<usize>,
+
+    // Playback state; the oscillator plays at the sample's tempo, the
+    // stretcher at any other
+    oscillator: SampleOscillator,
+    step: f64,
+    playing: bool,
+    stretch: TimeStretch,
+    pitch_envelope: AREnvelope,
+    filter_envelope: AREnvelope,
+    filter_left: SVF,
+    filter_right: SVF,
+
+    // Parameters
+    gain: f32,
+    velocity: f32,
+    // Tempo ratio, 1 plays without stretching
+    speed: f64,
+    pitch: f32,            // Semitones
+    pitch_env_amount: f32, // Semitones added at the envelope's peak
+    filter_cutoff: f32,
+    filter_env_amount: f32, // Hz added at the envelope's peak
+    reverse: bool,
+    // Fraction of the slice playback starts from
+    start_offset: f32,
+    looping: bool,
+
+    sample_rate: f32,
+}
+
+impl SamplePlayer {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut pitch_envelope = AREnvelope::new(sample_rate);
+        pitch_envelope.set_attack_time(0.001);
+        pitch_envelope.set_release_time(0.05);
+        pitch_envelope.set_release_bias(0.7); // Exponential-like
+
+        let mut filter_envelope = AREnvelope::new(sample_rate);
+        filter_envelope.set_attack_time(0.001);
+        filter_envelope.set_release_time(0.2);
+
+        Self {
+            layers: Vec::with_capacity(MAX_LAYERS),
+            round_robin: 0,
+            sample: None,
+            frames: 0..0,
+            oscillator: SampleOscillator::new(sample_rate),
+            step: 1.0,
+            playing: false,
+            stretch: TimeStretch::new(sample_rate),
+            pitch_envelope,
+            filter_envelope,
+            filter_left: SVF::new(20000.0, 0.7, FilterMode::Lowpass, sample_rate),
+            filter_right: SVF::new(20000.0, 0.7, FilterMode::Lowpass, sample_rate),
+            gain: 1.0,
+            velocity: 1.0,
+            speed: 1.0,
+            pitch: 0.0,
+            pitch_env_amount: 0.0,
+            filter_cutoff: 20000.0,
+            filter_env_amount: 0.0,
+            reverse: false,
+            start_offset: 0.0,
+            looping: false,
+            sample_rate,
+        }
+    }
+
+    /// Swap the sample and the part of it to play, e.g. one slice of a loop,
+    /// replacing any layers and stopping playback of the old one
+    pub fn set_slice(&mut self, sample: SampleRef, frames: Range<usize>) {
+        self.layers.clear();
+        self.round_robin = 0;
+        self.playing = false;
+        // Can't fail on an empty pad
+        let _ = self.add_layer(sample, frames, 0.0..=1.0);
+        self.select_layer(0);
+    }
+
+    /// Add a sample played for velocities in a range. Layers sharing a range
+    /// take turns.
+    pub fn add_layer(
+        &mut self,
+        sample: SampleRef,
+        frames: Range<usize>,
+        velocity: RangeInclusive<f32>,
+    ) -> Result<(), String> {
+        // The layers are reserved up front so the audio thread never allocates
+        if self.layers.len() == MAX_LAYERS {
+            return Err(format!("A pad holds at most {} layers", MAX_LAYERS));
+        }
+        let len = sample.len();
+        let velocity = velocity.start().clamp(0.0, 1.0)..=velocity.end().clamp(0.0, 1.0);
+        self.layers.push(SampleLayer {
+            sample,
+            frames: frames.start.min(len)..frames.end.min(len),
+            velocity,
+        });
+        Ok(())
+    }
+
+    pub fn layer_count(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn sample(&self) -> Option<&SampleRef> {
+        self.sample.as_ref()
+    }
+
+    pub fn trigger(&mut self) {
+        self.trigger_with_velocity(1.0);
+    }
+
+    /// Play the next layer covering the velocity, scaled by it; a hit no
+    /// layer covers is silent
+    pub fn trigger_with_velocity(&mut self, velocity: f32) {
+        let velocity = velocity.clamp(0.0, 1.0);
+        let covers = |layer: &SampleLayer| layer.velocity.contains(&velocity);
+        let count = self.layers.iter().filter(|layer| covers(layer)).count();
+        if count == 0 {
+            self.playing = false;
+            return;
+        }
+        let turn = self.round_robin % count;
+        self.round_robin = self.round_robin.wrapping_add(1);
+        if let Some((index, _)) = self
+            .layers
+            .iter()
+            .enumerate()
+            .filter(|(_, layer)| covers(layer))
+            .nth(turn)
+        {
+            self.select_layer(index);
+        }
+        self.velocity = velocity;
+
+        let position = self.start_position();
+        self.oscillator.start(position);
+        self.stretch.start(position);
+        self.pitch_envelope.trigger();
+        self.filter_envelope.trigger();
+        self.filter_left.reset();
+        self.filter_right.reset();
+        self.playing = self.sample.is_some() && !self.frames.is_empty();
+    }
+
+    /// Silence the pad, e.g. to end a loop
+    pub fn stop(&mut self) {
+        self.playing = false;
+        self.oscillator.stop();
+    }
+
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain = gain.clamp(0.0, 1.0);
+    }
+
+    /// Play at a different tempo without changing pitch, e.g. the project
+    /// BPM over the loop's BPM
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed.clamp(0.25, 4.0) as f64;
+    }
+
+    pub fn set_stretch_quality(&mut self, quality: StretchQuality) {
+        self.stretch.set_quality(quality);
+    }
+
+    pub fn set_grain_size(&mut self, seconds: f32) {
+        self.stretch.set_grain_size(seconds);
+    }
+
+    /// Transpose in semitones
+    pub fn set_pitch(&mut self, semitones: f32) {
+        self.pitch = semitones.clamp(-24.0, 24.0);
+    }
+
+    /// Semitones the pitch envelope sweeps down from, 0 for none
+    pub fn set_pitch_env_amount(&mut self, semitones: f32) {
+        self.pitch_env_amount = semitones.clamp(-48.0, 48.0);
+    }
+
+    pub fn set_pitch_attack(&mut self, time: f32) {
+        self.pitch_envelope.set_attack_time(time);
+    }
+
+    pub fn set_pitch_release(&mut self, time: f32) {
+        self.pitch_envelope.set_release_time(time);
+    }
+
+    pub fn set_filter_mode(&mut self, mode: FilterMode) {
+        self.filter_left.set_mode(mode);
+        self.filter_right.set_mode(mode);
+    }
+
+    pub fn set_filter_cutoff(&mut self, cutoff: f32) {
+        self.filter_cutoff = cutoff.clamp(20.0, 20000.0);
+    }
+
+    pub fn set_filter_resonance(&mut self, resonance: f32) {
+        let resonance = resonance.clamp(0.1, 10.0);
+        self.filter_left.set_resonance(resonance);
+        self.filter_right.set_resonance(resonance);
+    }
+
+    pub fn set_filter_env_amount(&mut self, amount: f32) {
+        self.filter_env_amount = amount;
+    }
+
+    pub fn set_filter_attack(&mut self, time: f32) {
+        self.filter_envelope.set_attack_time(time);
+    }
+
+    pub fn set_filter_release(&mut self, time: f32) {
+        self.filter_envelope.set_release_time(time);
+    }
+
+    /// Play from the end of the sample or slice back to its start
+    pub fn set_reverse(&mut self, reverse: bool) {
+        self.reverse = reverse;
+    }
+
+    /// Where in the slice playback starts, from 0 (its start) to 1 (its
+    /// end), counted from the end when reversed
+    pub fn set_start_offset(&mut self, offset: f32) {
+        self.start_offset = offset.clamp(0.0, 1.0);
+    }
+
+    /// Loop from the start offset instead of stopping at the end of the slice
+    pub fn set_looping(&mut self, looping: bool) {
+        self.looping = looping;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.playing
+    }
+
+    fn start_position(&self) -> f64 {
+        let len = self.frames.len().saturating_sub(1) as f64;
+        let offset = (self.start_offset as f64 * len).floor();
+        if self.reverse {
+            self.frames.end.saturating_sub(1) as f64 - offset
+        } else {
+            self.frames.start as f64 + offset
+        }
+    }
+
+    fn select_layer(&mut self, index: usize) {
+        if let Some(layer) = self.layers.get(index) {
+            self.sample = Some(layer.sample.clone());
+            self.frames = layer.frames.clone();
+            self.oscillator
+                .set_sample(layer.sample.clone(), layer.frames.clone());
+            self.update_step();
+        }
+    }
+
+    fn update_step(&mut self) {
+        self.step = match &self.sample {
+            Some(sample) => sample.sample_rate as f64 / self.sample_rate as f64,
+            None => 1.0,
+        };
+    }
+}
+
+impl StereoAudioGenerator for SamplePlayer {
+    fn next_sample(&mut self) -> (f32, f32) {
+        let Some(sample) = self.sample.as_ref().filter(|_| self.playing) else {
+            return (0.0, 0.0);
+        };
+
+        let semitones = self.pitch + self.pitch_envelope.next_sample() * self.pitch_env_amount;
+        let pitch_ratio = if semitones == 0.0 {
+            1.0
+        } else {
+            (semitones as f64 / 12.0).exp2()
+        };
+        let direction = if self.reverse { -1.0 } else { 1.0 };
+        let read_step = direction * self.step * pitch_ratio;
+
+        let (left, right) = if self.speed != 1.0 {
+            let advance = direction * self.step * self.speed;
+            let mut frame = self
+                .stretch
+                .next_frame(sample, &self.frames, advance, read_step);
+            if frame.is_none() && self.looping {
+                self.stretch.start(self.start_position());
+                frame = self
+                    .stretch
+                    .next_frame(sample, &self.frames, advance, read_step);
+            }
+            match frame {
+                Some(frame) => frame,
+                None => {
+                    self.playing = false;
+                    return (0.0, 0.0);
+                }
+            }
+        } else {
+            self.oscillator.set_rate(direction * pitch_ratio);
+            let mut frame = self.oscillator.next_frame();
+            if frame.is_none() && self.looping {
+                self.oscillator.start(self.start_position());
+                frame = self.oscillator.next_frame();
+            }
+            match frame {
+                Some(frame) => frame,
+                None => {
+                    self.playing = false;
+                    return (0.0, 0.0);
+                }
+            }
+        };
+
+        // The cutoff changes every sample, so use the cheap coefficient path
+        let cutoff =
+            self.filter_cutoff + self.filter_envelope.next_sample() * self.filter_env_amount;
+        self.filter_left.modulate_cutoff_frequency(cutoff);
+        self.filter_right.modulate_cutoff_frequency(cutoff);
+
+        let gain = self.gain * self.velocity;
+        (
+            self.filter_left.process(left) * gain,
+            self.filter_right.process(right) * gain,
+        )
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.oscillator.set_sample_rate(sample_rate);
+        self.stretch.set_sample_rate(sample_rate);
+        self.pitch_envelope.set_sample_rate(sample_rate);
+        self.filter_envelope.set_sample_rate(sample_rate);
+        self.filter_left.set_sample_rate(sample_rate);
+        self.filter_right.set_sample_rate(sample_rate);
+        self.update_step();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::samples::Sample;
+    use std::sync::Arc;
+
+    const SAMPLE_RATE: f32 = 44100.0;
+
+    /// 100 frames, silent for the first half and at full level for the second
+    fn step_sample() -> SampleRef {
+        let data: Vec<f32> = (0..100).map(|i| if i < 50 { 0.0 } else { 1.0 }).collect();
+        Arc::new(Sample::from_interleaved("step", &data, 1, SAMPLE_RATE).unwrap())
+    }
+
+    fn render(player: &mut SamplePlayer, frames: usize) -> Vec<f32> {
+        (0..frames).map(|_| player.next_sample().0).collect()
+    }
+
+    #[test]
+    fn test_one_shot_plays_the_slice_once() {
+        let mut player = SamplePlayer::new(SAMPLE_RATE);
+        player.set_slice(step_sample(), 0..100);
+        player.trigger();
+
+        let output = render(&mut player, 200);
+        assert!(output[..40].iter().all(|s| s.abs() < 1e-3));
+        assert!(
+            output[70..100].iter().all(|s| *s > 0.9),
+            "The loud half should play through"
+        );
+        assert!(output[100..].iter().all(|s| *s == 0.0));
+        assert!(!player.is_active(), "One-shot should stop at the slice end");
+    }
+
+    #[test]
+    fn test_loop_repeats_from_the_start_offset_until_stopped() {
+        let mut player = SamplePlayer::new(SAMPLE_RATE);
+        player.set_slice(step_sample(), 0..100);
+        player.set_looping(true);
+        player.set_start_offset(0.6);
+        player.trigger();
+
+        // Looping from past the step only ever reaches the loud half
+        let output = render(&mut player, 1000);
+        assert!(player.is_active(), "Loop should keep playing");
+        assert!(
+            output[20..].iter().all(|s| *s > 0.9),
+            "Loop should restart from the offset, not the silent start"
+        );
+
+        player.stop();
+        assert!(!player.is_active());
+        assert!(render(&mut player, 10).iter().all(|s| *s == 0.0));
+    }
+}
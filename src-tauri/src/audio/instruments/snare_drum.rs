@@ -1,41 +1,143 @@
 use crate::audio::envelopes::AREnvelope;
-use crate::audio::oscillators::NoiseGenerator;
-use crate::audio::AudioGenerator;
+use crate::audio::filters::{FilterMode, SVF};
+use crate::audio::oscillators::{
+    BrownNoise, NoiseColor, NoiseGenerator, PinkNoise, SineOscillator,
+};
+use crate::audio::{AudioGenerator, AudioProcessor};
 
+/// The body's second sine sits this far above the first, near the ratio of
+/// a drum head's first two modes
+const OVERTONE_RATIO: f32 = 1.84;
+
+/// Snare from a tuned body of two sines and a band of noise for the wires.
+/// The snappy control balances the two. Like the kick, the body can start
+/// above its tune and sweep down to it, and the wires can use darker noise.
 pub struct SnareDrum {
+    body_low: SineOscillator,
+    body_high: SineOscillator,
+    body_envelope: AREnvelope,
+    freq_envelope: AREnvelope,
     noise_generator: NoiseGenerator,
-    amp_envelope: AREnvelope,
+    pink_noise: PinkNoise,
+    brown_noise: BrownNoise,
+    noise_color: NoiseColor,
+    noise_filter: SVF,
+    noise_envelope: AREnvelope,
+
+    tune: f32,
+    pitch_sweep: f32, // Ratio the body starts at above its tune
+    snappy: f32,      // 0 = all body, 1 = all wires
+    velocity: f32,    // Level of the last hit, 0 to 1
+    gain: f32,
 }
 
 impl SnareDrum {
     pub fn new(sample_rate: f32) -> Self {
         let mut snare = Self {
+            body_low: SineOscillator::new(180.0, sample_rate),
+            body_high: SineOscillator::new(180.0 * OVERTONE_RATIO, sample_rate),
+            body_envelope: AREnvelope::new(sample_rate),
+            freq_envelope: AREnvelope::new(sample_rate),
             noise_generator: NoiseGenerator::new(),
-            amp_envelope: AREnvelope::new(sample_rate),
+            pink_noise: PinkNoise::new(),
+            brown_noise: BrownNoise::new(),
+            noise_color: NoiseColor::White,
+            noise_filter: SVF::new(3500.0, 1.2, FilterMode::Bandpass, sample_rate),
+            noise_envelope: AREnvelope::new(sample_rate),
+            tune: 180.0,
+            pitch_sweep: 1.0,
+            snappy: 0.5,
+            velocity: 1.0,
+            gain: 1.0,
         };
 
-        snare.amp_envelope.set_attack_time(0.001);
-        snare.amp_envelope.set_release_time(0.08);
-        snare.amp_envelope.set_attack_bias(0.5); // Linear
-        snare.amp_envelope.set_release_bias(0.7); // Exponential-like
+        snare.body_envelope.set_attack_time(0.001);
+        snare.body_envelope.set_release_time(0.12);
+        snare.body_envelope.set_attack_bias(0.5); // Linear
+        snare.body_envelope.set_release_bias(0.7); // Exponential-like
+
+        snare.freq_envelope.set_attack_time(0.001);
+        snare.freq_envelope.set_release_time(0.04);
+        snare.freq_envelope.set_attack_bias(0.7); // Exponential-like
+        snare.freq_envelope.set_release_bias(0.7); // Exponential-like
+
+        snare.noise_envelope.set_attack_time(0.001);
+        snare.noise_envelope.set_release_time(0.18);
+        snare.noise_envelope.set_attack_bias(0.5); // Linear
+        snare.noise_envelope.set_release_bias(0.7); // Exponential-like
 
         snare
     }
 
-    pub fn trigger(&mut self) {
-        self.amp_envelope.trigger();
+    pub fn trigger(&mut self, velocity: f32) {
+        self.velocity = velocity.clamp(0.0, 1.0);
+        self.body_envelope.trigger();
+        self.freq_envelope.trigger();
+        self.noise_envelope.trigger();
+        self.body_low.reset();
+        self.body_high.reset();
     }
 
-    pub fn set_amp_attack(&mut self, time: f32) {
-        self.amp_envelope.set_attack_time(time);
+    /// Fast-release the voice (used by choke groups)
+    pub fn choke(&mut self) {
+        self.body_envelope.choke();
+        self.noise_envelope.choke();
     }
 
-    pub fn set_amp_release(&mut self, time: f32) {
-        self.amp_envelope.set_release_time(time);
+    /// Frequency of the body's fundamental
+    pub fn set_tune(&mut self, frequency: f32) {
+        self.tune = frequency.clamp(80.0, 500.0);
+        self.body_low.set_frequency(self.tune);
+        self.body_high.set_frequency(self.tune * OVERTONE_RATIO);
+    }
+
+    /// Ratio of the body's starting pitch to its tune; 1 is no sweep
+    pub fn set_pitch_sweep(&mut self, ratio: f32) {
+        self.pitch_sweep = ratio.clamp(1.0, 8.0);
+    }
+
+    /// Time the body takes to sweep down to its tune
+    pub fn set_sweep_time(&mut self, time: f32) {
+        self.freq_envelope.set_release_time(time);
+    }
+
+    pub fn set_tone_decay(&mut self, time: f32) {
+        self.body_envelope.set_release_time(time);
+    }
+
+    /// Balance of the wires against the body, 0 to 1
+    pub fn set_snappy(&mut self, snappy: f32) {
+        self.snappy = snappy.clamp(0.0, 1.0);
+    }
+
+    pub fn set_noise_decay(&mut self, time: f32) {
+        self.noise_envelope.set_release_time(time);
+    }
+
+    /// Centre of the band of noise
+    pub fn set_noise_frequency(&mut self, frequency: f32) {
+        self.noise_filter
+            .set_cutoff_frequency(frequency.clamp(500.0, 12000.0));
+    }
+
+    /// Colour of the wires' noise; pink and brown are darker than white
+    pub fn set_noise_color(&mut self, color: NoiseColor) {
+        self.noise_color = color;
+    }
+
+    /// Restart the noise sequence from a seed
+    pub fn set_seed(&mut self, seed: u64) {
+        self.noise_generator.set_seed(seed);
+        self.pink_noise.set_seed(seed);
+        self.brown_noise.set_seed(seed);
     }
 
     pub fn is_active(&self) -> bool {
-        self.amp_envelope.is_active()
+        self.body_envelope.is_active() || self.noise_envelope.is_active()
+    }
+
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain = gain;
     }
 }
 
@@ -45,13 +147,38 @@ impl AudioGenerator for SnareDrum {
             return 0.0;
         }
 
-        let amp_env = self.amp_envelope.next_sample();
-        let sample = self.noise_generator.next_sample();
-        sample * amp_env
+        let body_env = self.body_envelope.next_sample();
+        let freq_env = self.freq_envelope.next_sample();
+        // Softer hits start lower, as on the kick
+        let ratio = 1.0 + (self.pitch_sweep - 1.0) * (0.5 + 0.5 * self.velocity);
+        let frequency = self.tune * (1.0 + freq_env * (ratio - 1.0));
+        self.body_low.set_frequency(frequency);
+        self.body_high.set_frequency(frequency * OVERTONE_RATIO);
+        let body = (self.body_low.next_sample() + 0.5 * self.body_high.next_sample()) * body_env;
+
+        let noise_env = self.noise_envelope.next_sample();
+        let raw_noise = match self.noise_color {
+            NoiseColor::White => self.noise_generator.next_sample(),
+            NoiseColor::Pink => self.pink_noise.next_sample(),
+            NoiseColor::Brown => self.brown_noise.next_sample(),
+        };
+        let noise = self.noise_filter.process(raw_noise) * noise_env;
+
+        // The bandpass takes the noise down a lot, so it's lifted to sit with
+        // the body at the middle of the snappy range
+        let mix = body * 0.67 * (1.0 - self.snappy) + noise * 2.0 * self.snappy;
+        mix * self.velocity * self.gain
     }
 
     fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.body_low.set_sample_rate(sample_rate);
+        self.body_high.set_sample_rate(sample_rate);
+        self.body_envelope.set_sample_rate(sample_rate);
+        self.freq_envelope.set_sample_rate(sample_rate);
         self.noise_generator.set_sample_rate(sample_rate);
-        self.amp_envelope.set_sample_rate(sample_rate);
+        self.pink_noise.set_sample_rate(sample_rate);
+        self.brown_noise.set_sample_rate(sample_rate);
+        self.noise_filter.set_sample_rate(sample_rate);
+        self.noise_envelope.set_sample_rate(sample_rate);
     }
 }
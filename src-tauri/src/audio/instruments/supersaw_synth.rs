@@ -1,6 +1,6 @@
 use crate::audio::envelopes::AREnvelope;
 use crate::audio::filters::{FilterMode, SVF};
-use crate::audio::oscillators::SawOscillator;
+use crate::audio::oscillators::{SawOscillator, SineOscillator};
 use crate::audio::{AudioGenerator, AudioProcessor, StereoAudioGenerator};
 
 /// Supersaw oscillator using multiple detuned saw oscillators
@@ -51,6 +51,21 @@ impl SupersawOscillator {
         self.stereo_width = width.clamp(0.0, 1.0);
     }
 
+    /// Each saw wanders in pitch on its own by up to this many cents
+    pub fn set_drift_amount(&mut self, cents: f32) {
+        for osc in &mut self.oscillators {
+            osc.set_drift_amount(cents);
+        }
+    }
+
+    /// Restart every saw's drift from a seed, each on its own stream
+    pub fn set_seed(&mut self, seed: u64) {
+        let mut rng = fastrand::Rng::with_seed(seed);
+        for osc in &mut self.oscillators {
+            osc.set_seed(rng.u64(..));
+        }
+    }
+
     fn update_frequencies(&mut self) {
         for (i, osc) in self.oscillators.iter_mut().enumerate() {
             if i == 0 && self.num_voices > 1 {
@@ -123,14 +138,23 @@ pub struct SupersawSynth {
     filter_right: SVF,
     amp_envelope: AREnvelope,
     filter_envelope: AREnvelope,
+    filter_fm_oscillator: SineOscillator,
 
     base_frequency: f32,
     gain: f32,
+    velocity: f32, // Per-note level set by the sequencer
     filter_cutoff: f32,
     filter_resonance: f32,
     filter_env_amount: f32,
+    filter_keytrack: f32,
+    keytrack_ratio: f32,   // Cached cutoff multiplier for the current note
+    filter_fm_amount: f32, // Audio-rate cutoff modulation depth in octaves
+    filter_fm_ratio: f32,  // FM oscillator frequency relative to the note
 }
 
+/// Note frequency at which keytracking leaves the cutoff unchanged (A4)
+const KEYTRACK_REFERENCE_FREQUENCY: f32 = 440.0;
+
 impl SupersawSynth {
     pub fn new(sample_rate: f32) -> Self {
         let mut amp_envelope = AREnvelope::new(sample_rate);
@@ -147,32 +171,52 @@ impl SupersawSynth {
             filter_right: SVF::new(1000.0, 0.7, FilterMode::Lowpass, sample_rate),
             amp_envelope,
             filter_envelope,
+            filter_fm_oscillator: SineOscillator::new(440.0, sample_rate),
 
             base_frequency: 440.0,
             gain: 0.5,
+            velocity: 1.0,
             filter_cutoff: 1000.0,
             filter_resonance: 0.7,
             filter_env_amount: 2000.0,
+            filter_keytrack: 0.0,
+            keytrack_ratio: 1.0,
+            filter_fm_amount: 0.0,
+            filter_fm_ratio: 1.0,
         }
     }
 
     pub fn trigger(&mut self) {
         if !self.amp_envelope.is_active() {
             self.oscillator.reset();
+            self.filter_fm_oscillator.reset();
         }
         self.amp_envelope.trigger();
         self.filter_envelope.trigger();
     }
 
+    /// Fast-release the voice (used by choke groups)
+    pub fn choke(&mut self) {
+        self.amp_envelope.choke();
+    }
+
     pub fn set_base_frequency(&mut self, frequency: f32) {
         self.base_frequency = frequency;
         self.oscillator.set_frequency(frequency);
+        self.filter_fm_oscillator
+            .set_frequency(frequency * self.filter_fm_ratio);
+        self.update_keytrack_ratio();
     }
 
     pub fn set_gain(&mut self, gain: f32) {
         self.gain = gain.clamp(0.0, 1.0);
     }
 
+    /// Level of the next notes (0.0 to 1.0), applied on top of gain
+    pub fn set_velocity(&mut self, velocity: f32) {
+        self.velocity = velocity.clamp(0.0, 1.0);
+    }
+
     pub fn set_detune(&mut self, detune: f32) {
         self.oscillator.set_detune(detune);
     }
@@ -181,12 +225,26 @@ impl SupersawSynth {
         self.oscillator.set_stereo_width(width);
     }
 
+    /// Analog-style pitch drift of each saw, in cents
+    pub fn set_drift_amount(&mut self, cents: f32) {
+        self.oscillator.set_drift_amount(cents);
+    }
+
+    /// Restart the pitch drift from a seed
+    pub fn set_seed(&mut self, seed: u64) {
+        self.oscillator.set_seed(seed);
+    }
+
     pub fn set_filter_cutoff(&mut self, cutoff: f32) {
         self.filter_cutoff = cutoff.clamp(20.0, 20000.0);
         self.filter_left.set_cutoff_frequency(self.filter_cutoff);
         self.filter_right.set_cutoff_frequency(self.filter_cutoff);
     }
 
+    pub fn filter_cutoff(&self) -> f32 {
+        self.filter_cutoff
+    }
+
     pub fn set_filter_resonance(&mut self, resonance: f32) {
         self.filter_resonance = resonance.clamp(0.1, 10.0);
         self.filter_left.set_resonance(self.filter_resonance);
@@ -197,6 +255,30 @@ impl SupersawSynth {
         self.filter_env_amount = amount;
     }
 
+    /// Set how much the filter cutoff follows the note frequency (0.0 = fixed, 1.0 = 100%)
+    pub fn set_filter_keytrack(&mut self, amount: f32) {
+        self.filter_keytrack = amount.clamp(0.0, 1.0);
+        self.update_keytrack_ratio();
+    }
+
+    /// Set the depth of audio-rate filter FM in octaves (0.0 = off)
+    pub fn set_filter_fm_amount(&mut self, octaves: f32) {
+        self.filter_fm_amount = octaves.clamp(0.0, 4.0);
+    }
+
+    /// Set the filter FM oscillator frequency as a ratio of the note frequency
+    pub fn set_filter_fm_ratio(&mut self, ratio: f32) {
+        self.filter_fm_ratio = ratio.clamp(0.125, 16.0);
+        self.filter_fm_oscillator
+            .set_frequency(self.base_frequency * self.filter_fm_ratio);
+    }
+
+    fn update_keytrack_ratio(&mut self) {
+        // At 100% the cutoff moves one octave for every octave of the played note
+        self.keytrack_ratio = (self.base_frequency.max(1.0) / KEYTRACK_REFERENCE_FREQUENCY)
+            .powf(self.filter_keytrack);
+    }
+
     pub fn set_amp_attack(&mut self, attack: f32) {
         self.amp_envelope.set_attack_time(attack);
     }
@@ -219,6 +301,7 @@ impl SupersawSynth {
         self.filter_right.set_sample_rate(sample_rate);
         self.amp_envelope.set_sample_rate(sample_rate);
         self.filter_envelope.set_sample_rate(sample_rate);
+        self.filter_fm_oscillator.set_sample_rate(sample_rate);
     }
 }
 
@@ -232,18 +315,31 @@ impl StereoAudioGenerator for SupersawSynth {
         let amp_env = self.amp_envelope.next_sample();
         let filter_env = self.filter_envelope.next_sample();
 
-        // Modulate filter cutoff with envelope
-        let modulated_cutoff = self.filter_cutoff + (filter_env * self.filter_env_amount);
-        self.filter_left.set_cutoff_frequency(modulated_cutoff);
-        self.filter_right.set_cutoff_frequency(modulated_cutoff);
+        // Modulate filter cutoff with keytracking and envelope; softer notes
+        // open the filter less
+        let mut modulated_cutoff = (self.filter_cutoff * self.keytrack_ratio
+            + (filter_env * self.filter_env_amount * self.velocity))
+            .min(20000.0);
+
+        // Audio-rate filter FM, exponential so the sweep is symmetric in pitch
+        if self.filter_fm_amount > 0.0 {
+            let fm = self.filter_fm_oscillator.next_sample();
+            modulated_cutoff *= (fm * self.filter_fm_amount).exp2();
+        }
+
+        // The cutoff changes every sample, so use the cheap coefficient path
+        self.filter_left.modulate_cutoff_frequency(modulated_cutoff);
+        self.filter_right
+            .modulate_cutoff_frequency(modulated_cutoff);
 
         // Process through filters
         let filtered_left = self.filter_left.process(osc_left);
         let filtered_right = self.filter_right.process(osc_right);
 
-        // Apply amplitude envelope and gain
-        let final_left = filtered_left * amp_env * self.gain;
-        let final_right = filtered_right * amp_env * self.gain;
+        // Apply amplitude envelope, gain and velocity
+        let level = amp_env * self.gain * self.velocity;
+        let final_left = filtered_left * level;
+        let final_right = filtered_right * level;
 
         (final_left.tanh(), final_right.tanh())
     }
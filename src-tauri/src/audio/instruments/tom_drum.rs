@@ -0,0 +1,251 @@
+use crate::audio::envelopes::AREnvelope;
+use crate::audio::filters::{FilterMode, SVF};
+use crate::audio::oscillators::{NoiseGenerator, SineOscillator, TZFMOscillator};
+use crate::audio::{AudioGenerator, AudioProcessor};
+
+/// Deepest pitch sweep, in semitones above the base frequency
+const MAX_SWEEP_SEMITONES: f32 = 24.0;
+
+/// The noise layer is centred this many times above the base frequency, so
+/// it follows the tuning from low to high toms
+const NOISE_FREQUENCY_RATIO: f32 = 8.0;
+
+/// The FM modulator sits at an inharmonic ratio above the tone, for a
+/// metallic, gong-like attack
+const FM_MODULATOR_RATIO: f32 = 1.41;
+
+/// Deepest FM, as a multiple of the tone's frequency
+const MAX_FM_INDEX: f32 = 4.0;
+
+/// Tuned drum: a sine sweeping down to its base frequency with a band of
+/// noise for the stick, tuned low, mid or high from the one voice. A second
+/// sine can frequency-modulate the tone through zero, dying away with the
+/// hit, for aggressive FM drums.
+pub struct TomDrum {
+    oscillator: TZFMOscillator,
+    fm_modulator: SineOscillator,
+    amp_envelope: AREnvelope,
+    sweep_envelope: AREnvelope,
+    noise: NoiseGenerator,
+    noise_filter: SVF,
+    noise_envelope: AREnvelope,
+    base_frequency: f32,
+    sweep_semitones: f32,
+    noise_amount: f32,
+    fm_amount: f32,
+    velocity: f32, // Level of the last hit, 0 to 1
+    gain: f32,
+}
+
+impl TomDrum {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut tom = Self {
+            oscillator: TZFMOscillator::new(120.0, sample_rate),
+            fm_modulator: SineOscillator::new(120.0 * FM_MODULATOR_RATIO, sample_rate),
+            amp_envelope: AREnvelope::new(sample_rate),
+            sweep_envelope: AREnvelope::new(sample_rate),
+            noise: NoiseGenerator::new(),
+            noise_filter: SVF::new(
+                120.0 * NOISE_FREQUENCY_RATIO,
+                1.5,
+                FilterMode::Bandpass,
+                sample_rate,
+            ),
+            noise_envelope: AREnvelope::new(sample_rate),
+            base_frequency: 120.0,
+            sweep_semitones: 7.0,
+            noise_amount: 0.2,
+            fm_amount: 0.0,
+            velocity: 1.0,
+            gain: 1.0,
+        };
+
+        tom.amp_envelope.set_attack_time(0.002);
+        tom.amp_envelope.set_release_time(0.4);
+        tom.amp_envelope.set_attack_bias(0.3); // Logarithmic-like
+        tom.amp_envelope.set_release_bias(0.7); // Exponential-like
+
+        tom.sweep_envelope.set_attack_time(0.001);
+        tom.sweep_envelope.set_release_time(0.12);
+        tom.sweep_envelope.set_attack_bias(0.7); // Exponential-like
+        tom.sweep_envelope.set_release_bias(0.7); // Exponential-like
+
+        tom.noise_envelope.set_attack_time(0.001);
+        tom.noise_envelope.set_release_time(0.05);
+        tom.noise_envelope.set_release_bias(0.8);
+
+        tom
+    }
+
+    /// Softer hits are quieter and sweep down from lower
+    pub fn trigger(&mut self, velocity: f32) {
+        self.velocity = velocity.clamp(0.0, 1.0);
+        self.amp_envelope.trigger();
+        self.sweep_envelope.trigger();
+        self.noise_envelope.trigger();
+        self.oscillator.reset();
+        self.fm_modulator.reset();
+    }
+
+    /// Fast-release the voice (used by choke groups)
+    pub fn choke(&mut self) {
+        self.amp_envelope.choke();
+        self.noise_envelope.choke();
+    }
+
+    pub fn set_base_frequency(&mut self, frequency: f32) {
+        self.base_frequency = frequency.clamp(30.0, 1000.0);
+        self.noise_filter
+            .set_cutoff_frequency(self.base_frequency * NOISE_FREQUENCY_RATIO);
+    }
+
+    /// Semitones above the base frequency each hit starts from
+    pub fn set_sweep_depth(&mut self, semitones: f32) {
+        self.sweep_semitones = semitones.clamp(0.0, MAX_SWEEP_SEMITONES);
+    }
+
+    /// How long the pitch takes to fall to the base frequency
+    pub fn set_sweep_time(&mut self, time: f32) {
+        self.sweep_envelope.set_release_time(time);
+    }
+
+    pub fn set_decay(&mut self, time: f32) {
+        self.amp_envelope.set_release_time(time);
+    }
+
+    /// Level of the noise layer against the tone, 0 to 1
+    pub fn set_noise_amount(&mut self, amount: f32) {
+        self.noise_amount = amount.clamp(0.0, 1.0);
+    }
+
+    pub fn set_noise_decay(&mut self, time: f32) {
+        self.noise_envelope.set_release_time(time);
+    }
+
+    /// Depth of the through-zero FM on the tone, 0 to 1
+    pub fn set_fm_amount(&mut self, amount: f32) {
+        self.fm_amount = amount.clamp(0.0, 1.0);
+    }
+
+    /// Restart the noise sequence from a seed
+    pub fn set_seed(&mut self, seed: u64) {
+        self.noise.set_seed(seed);
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.amp_envelope.is_active() || self.noise_envelope.is_active()
+    }
+
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain = gain;
+    }
+}
+
+impl AudioGenerator for TomDrum {
+    fn next_sample(&mut self) -> f32 {
+        if !self.is_active() {
+            return 0.0;
+        }
+
+        let amp_env = self.amp_envelope.next_sample();
+        let sweep_env = self.sweep_envelope.next_sample();
+        let noise_env = self.noise_envelope.next_sample();
+
+        // Sweep down from the top of the sweep in semitones, so the depth
+        // sounds the same at any tuning
+        let sweep = sweep_env * self.sweep_semitones * (0.5 + 0.5 * self.velocity);
+        let frequency = self.base_frequency * (sweep / 12.0).exp2();
+        self.oscillator.set_frequency(frequency);
+        self.fm_modulator
+            .set_frequency(frequency * FM_MODULATOR_RATIO);
+        // The FM fades with the tone, so hits start harsh and settle
+        let fm_depth = frequency * MAX_FM_INDEX * self.fm_amount * amp_env;
+        self.oscillator.set_fm_depth(fm_depth);
+        let modulator = self.fm_modulator.next_sample();
+        let tone = self.oscillator.next_sample_with_fm(modulator) * amp_env;
+
+        let noise = self.noise_filter.process(self.noise.next_sample());
+        let noise = noise * noise_env * self.noise_amount;
+
+        (tone * (1.0 - 0.5 * self.noise_amount) + noise) * self.velocity * self.gain
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.oscillator.set_sample_rate(sample_rate);
+        self.fm_modulator.set_sample_rate(sample_rate);
+        self.amp_envelope.set_sample_rate(sample_rate);
+        self.sweep_envelope.set_sample_rate(sample_rate);
+        self.noise_filter.set_sample_rate(sample_rate);
+        self.noise_envelope.set_sample_rate(sample_rate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 44100.0;
+
+    fn render(tom: &mut TomDrum, samples: usize) -> Vec<f32> {
+        (0..samples).map(|_| tom.next_sample()).collect()
+    }
+
+    fn rising_crossings(samples: &[f32]) -> usize {
+        samples.windows(2).filter(|w| w[0] <= 0.0 && w[1] > 0.0).count()
+    }
+
+    #[test]
+    fn test_hits_sweep_down_to_the_tuned_pitch() {
+        let mut tom = TomDrum::new(SAMPLE_RATE);
+        tom.set_base_frequency(400.0);
+        tom.set_sweep_depth(12.0);
+        tom.set_noise_amount(0.0);
+        tom.set_decay(1.0);
+        tom.trigger(1.0);
+
+        let output = render(&mut tom, 13230);
+        // 20ms windows at the strike and once the sweep has settled
+        let start = rising_crossings(&output[..882]);
+        let settled = rising_crossings(&output[8820..9702]);
+        assert!(
+            start > settled + 2,
+            "Strike should start above the tuned pitch: {} vs {} cycles",
+            start,
+            settled
+        );
+        assert!(
+            (7..=9).contains(&settled),
+            "Expected about 8 cycles of 400Hz in 20ms, got {}",
+            settled
+        );
+    }
+
+    #[test]
+    fn test_hits_decay_and_choke_to_silence() {
+        let mut tom = TomDrum::new(SAMPLE_RATE);
+        tom.set_decay(0.2);
+        tom.trigger(1.0);
+        render(&mut tom, 22050);
+        assert!(!tom.is_active(), "Should be silent after its decay");
+
+        tom.set_decay(2.0);
+        tom.trigger(1.0);
+        render(&mut tom, 4410);
+        tom.choke();
+        render(&mut tom, 441);
+        assert!(!tom.is_active(), "Choke should silence within 10ms");
+    }
+
+    #[test]
+    fn test_seeded_hits_repeat() {
+        let mut a = TomDrum::new(SAMPLE_RATE);
+        let mut b = TomDrum::new(SAMPLE_RATE);
+        a.set_noise_amount(1.0);
+        b.set_noise_amount(1.0);
+        a.set_seed(7);
+        b.set_seed(7);
+        a.trigger(1.0);
+        b.trigger(1.0);
+        assert_eq!(render(&mut a, 2205), render(&mut b, 2205));
+    }
+}
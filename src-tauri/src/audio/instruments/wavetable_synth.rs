@@ -0,0 +1,205 @@
+use crate::audio::envelopes::AREnvelope;
+use crate::audio::filters::{FilterMode, SVF};
+use crate::audio::oscillators::{SineOscillator, WavetableOscillator};
+use crate::audio::{AudioGenerator, AudioProcessor};
+
+/// Wavetable voice that scans from sine through triangle and saw to square.
+/// The scan position can be swept by an LFO and by an envelope on each note,
+/// then shaped by a lowpass filter.
+pub struct WavetableSynth {
+    oscillator: WavetableOscillator,
+    position_lfo: SineOscillator,
+    filter: SVF,
+    amp_envelope: AREnvelope,
+    position_envelope: AREnvelope,
+
+    gain: f32,
+    velocity: f32,            // Level of the last note, 0 to 1
+    lfo_depth: f32,           // Position swing either side of the set position
+    position_env_amount: f32, // Position added at the envelope's peak
+}
+
+impl WavetableSynth {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut amp_envelope = AREnvelope::new(sample_rate);
+        amp_envelope.set_attack_time(0.01);
+        amp_envelope.set_release_time(0.8);
+
+        let mut position_envelope = AREnvelope::new(sample_rate);
+        position_envelope.set_attack_time(0.01);
+        position_envelope.set_release_time(0.5);
+
+        Self {
+            oscillator: WavetableOscillator::new(220.0, sample_rate),
+            position_lfo: SineOscillator::new(0.5, sample_rate),
+            filter: SVF::new(4000.0, 0.7, FilterMode::Lowpass, sample_rate),
+            amp_envelope,
+            position_envelope,
+            gain: 0.5,
+            velocity: 1.0,
+            lfo_depth: 0.0,
+            position_env_amount: 0.0,
+        }
+    }
+
+    /// Softer notes are quieter and scan less far on the position envelope
+    pub fn trigger(&mut self, velocity: f32) {
+        self.velocity = velocity.clamp(0.0, 1.0);
+        if !self.amp_envelope.is_active() {
+            self.oscillator.reset();
+        }
+        self.amp_envelope.trigger();
+        self.position_envelope.trigger();
+    }
+
+    /// Fast-release the voice (used by choke groups)
+    pub fn choke(&mut self) {
+        self.amp_envelope.choke();
+    }
+
+    pub fn set_base_frequency(&mut self, frequency: f32) {
+        self.oscillator.set_frequency(frequency);
+    }
+
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain = gain.clamp(0.0, 1.0);
+    }
+
+    /// Scan position from 0 (sine) to 1 (square)
+    pub fn set_position(&mut self, position: f32) {
+        self.oscillator.set_position(position);
+    }
+
+    pub fn set_lfo_rate(&mut self, rate_hz: f32) {
+        self.position_lfo.set_frequency(rate_hz.clamp(0.01, 20.0));
+    }
+
+    /// How far the LFO swings the position either side of where it's set
+    pub fn set_lfo_depth(&mut self, depth: f32) {
+        self.lfo_depth = depth.clamp(0.0, 1.0);
+    }
+
+    /// Position the envelope adds at its peak; negative values scan down
+    pub fn set_position_env_amount(&mut self, amount: f32) {
+        self.position_env_amount = amount.clamp(-1.0, 1.0);
+    }
+
+    pub fn set_position_attack(&mut self, time: f32) {
+        self.position_envelope.set_attack_time(time);
+    }
+
+    pub fn set_position_release(&mut self, time: f32) {
+        self.position_envelope.set_release_time(time);
+    }
+
+    pub fn set_filter_cutoff(&mut self, cutoff: f32) {
+        self.filter
+            .set_cutoff_frequency(cutoff.clamp(20.0, 20000.0));
+    }
+
+    pub fn set_filter_resonance(&mut self, resonance: f32) {
+        self.filter.set_resonance(resonance.clamp(0.1, 10.0));
+    }
+
+    pub fn set_attack(&mut self, time: f32) {
+        self.amp_envelope.set_attack_time(time);
+    }
+
+    pub fn set_release(&mut self, time: f32) {
+        self.amp_envelope.set_release_time(time);
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.amp_envelope.is_active()
+    }
+}
+
+impl AudioGenerator for WavetableSynth {
+    fn next_sample(&mut self) -> f32 {
+        // Keep the LFO running between notes so it doesn't restart each time
+        let lfo = self.position_lfo.next_sample();
+        if !self.is_active() {
+            return 0.0;
+        }
+
+        let position_env = self.position_envelope.next_sample() * self.velocity;
+        let position_mod = lfo * self.lfo_depth + position_env * self.position_env_amount;
+        let sample = self.oscillator.next_sample_with_position_mod(position_mod);
+        let filtered = self.filter.process(sample);
+        filtered * self.amp_envelope.next_sample() * self.velocity * self.gain
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.oscillator.set_sample_rate(sample_rate);
+        self.position_lfo.set_sample_rate(sample_rate);
+        self.filter.set_sample_rate(sample_rate);
+        self.amp_envelope.set_sample_rate(sample_rate);
+        self.position_envelope.set_sample_rate(sample_rate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 44100.0;
+
+    fn render(synth: &mut WavetableSynth, samples: usize) -> Vec<f32> {
+        (0..samples).map(|_| synth.next_sample()).collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn test_notes_play_at_the_base_frequency() {
+        let mut synth = WavetableSynth::new(SAMPLE_RATE);
+        synth.set_base_frequency(440.0);
+        synth.trigger(1.0);
+
+        let output = render(&mut synth, 4410);
+        let crossings = output
+            .windows(2)
+            .filter(|w| w[0] <= 0.0 && w[1] > 0.0)
+            .count();
+        assert!(
+            (43..=45).contains(&crossings),
+            "Expected 44 cycles of 440Hz in 0.1s, got {}",
+            crossings
+        );
+        for sample in output {
+            assert!(sample.abs() <= 1.0, "Output out of range: {}", sample);
+        }
+    }
+
+    #[test]
+    fn test_scanning_to_square_is_fuller_than_sine() {
+        let mut sine = WavetableSynth::new(SAMPLE_RATE);
+        sine.set_position(0.0);
+        sine.trigger(1.0);
+        let sine_rms = rms(&render(&mut sine, 4410)[882..]);
+
+        let mut square = WavetableSynth::new(SAMPLE_RATE);
+        square.set_position(1.0);
+        square.trigger(1.0);
+        let square_rms = rms(&render(&mut square, 4410)[882..]);
+
+        assert!(
+            square_rms > sine_rms * 1.1,
+            "Square RMS {} should be well above sine RMS {}",
+            square_rms,
+            sine_rms
+        );
+    }
+
+    #[test]
+    fn test_choke_silences_the_voice() {
+        let mut synth = WavetableSynth::new(SAMPLE_RATE);
+        synth.trigger(1.0);
+        render(&mut synth, 4410);
+        synth.choke();
+        render(&mut synth, 441);
+        assert_eq!(synth.next_sample(), 0.0);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::audio::AudioProcessor;
+
+/// Largest sample-rate reduction factor, reached at full amount
+const MAX_RATE_REDUCTION: f32 = 16.0;
+/// Bit depth at zero and full amount
+const MAX_BITS: f32 = 16.0;
+const MIN_BITS: f32 = 6.0;
+
+/// Sample-rate and bit-depth reduction with a single amount control
+pub struct LoFi {
+    amount: f32,
+    rate_step: f32, // Fraction of a held sample advanced per input sample
+    quantize_levels: f32,
+
+    phase: f32,
+    held_sample: f32,
+}
+
+impl LoFi {
+    pub fn new() -> Self {
+        let mut lofi = Self {
+            amount: 0.0,
+            rate_step: 1.0,
+            quantize_levels: 0.0,
+            phase: 0.0,
+            held_sample: 0.0,
+        };
+        lofi.set_amount(0.0);
+        lofi
+    }
+
+    /// 0.0 is clean, 1.0 is 16x rate reduction at 6 bits
+    pub fn set_amount(&mut self, amount: f32) {
+        self.amount = amount.clamp(0.0, 1.0);
+        self.rate_step = 1.0 / (1.0 + self.amount * (MAX_RATE_REDUCTION - 1.0));
+
+        let bits = MAX_BITS - self.amount * (MAX_BITS - MIN_BITS);
+        self.quantize_levels = (bits - 1.0).exp2();
+    }
+
+    pub fn amount(&self) -> f32 {
+        self.amount
+    }
+}
+
+impl Default for LoFi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AudioProcessor for LoFi {
+    fn process(&mut self, input: f32) -> f32 {
+        if self.amount <= 0.0 {
+            return input;
+        }
+
+        // Sample and hold at the reduced rate
+        self.phase += self.rate_step;
+        if self.phase >= 1.0 {
+            self.phase -= 1.0;
+            self.held_sample = (input * self.quantize_levels).round() / self.quantize_levels;
+        }
+
+        self.held_sample
+    }
+
+    fn set_sample_rate(&mut self, _sample_rate: f32) {
+        // Reduction is relative to the host rate, nothing to recompute
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lofi_bypass_and_reduction() {
+        let mut lofi = LoFi::new();
+        for i in 0..100 {
+            let input = (i as f32 * 0.1).sin();
+            assert_eq!(lofi.process(input), input, "Zero amount should be clean");
+        }
+
+        lofi.set_amount(1.0);
+        let outputs: Vec<f32> = (0..160)
+            .map(|i| lofi.process((i as f32 * 0.05).sin()))
+            .collect();
+
+        // Full amount holds each value for 16 samples
+        let changes = outputs.windows(2).filter(|w| w[0] != w[1]).count();
+        assert!(
+            changes <= 10,
+            "Expected held samples, got {} changes",
+            changes
+        );
+
+        // And quantizes to 6 bits (32 levels per polarity)
+        for output in outputs {
+            let scaled = output * 32.0;
+            assert!((scaled - scaled.round()).abs() < 1e-4);
+        }
+    }
+}
@@ -0,0 +1,441 @@
+use crate::sequencing::TransportClock;
+
+/// Loops recorded side by side
+pub const MAX_LOOPS: usize = 4;
+
+/// Longest loop in bars
+pub const MAX_LOOP_BARS: u32 = 8;
+
+/// Buffer length per loop, allocated up front so recording never allocates
+/// on the audio thread. Eight bars of 4/4 fit down to 120 BPM.
+const MAX_LOOP_SECONDS: f32 = 16.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoopState {
+    Empty,
+    /// Waiting for the next bar line to start recording
+    Armed,
+    Recording,
+    Playing,
+    /// Playing while recording a layer on top
+    Overdubbing,
+}
+
+impl LoopState {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LoopState::Empty => "empty",
+            LoopState::Armed => "armed",
+            LoopState::Recording => "recording",
+            LoopState::Playing => "playing",
+            LoopState::Overdubbing => "overdubbing",
+        }
+    }
+}
+
+/// What the UI shows for a loop
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoopInfo {
+    pub state: LoopState,
+    pub layers: usize,
+    pub level: f32,
+    pub bars: u32,
+}
+
+struct Loop {
+    // Every layer summed
+    mix: Vec<(f32, f32)>,
+    // The newest layer on its own, so it can be taken back out
+    last_layer: Vec<(f32, f32)>,
+    state: LoopState,
+    length: usize,
+    bars: u32,
+    position: usize,
+    layers: usize,
+    // Where the newest layer starts and how much of it has been written
+    layer_start: usize,
+    layer_frames: usize,
+    level: f32,
+}
+
+impl Loop {
+    fn new(capacity: usize) -> Self {
+        Self {
+            mix: vec![(0.0, 0.0); capacity],
+            last_layer: vec![(0.0, 0.0); capacity],
+            state: LoopState::Empty,
+            length: 0,
+            bars: 0,
+            position: 0,
+            layers: 0,
+            layer_start: 0,
+            layer_frames: 0,
+            level: 1.0,
+        }
+    }
+
+    fn clear(&mut self) {
+        self.state = LoopState::Empty;
+        self.length = 0;
+        self.bars = 0;
+        self.position = 0;
+        self.layers = 0;
+        self.layer_frames = 0;
+    }
+
+    fn start_layer(&mut self) {
+        self.layer_start = self.position;
+        self.layer_frames = 0;
+    }
+
+    fn write_layer(&mut self, input: (f32, f32), overdub: bool) {
+        let (mix_left, mix_right) = self.mix[self.position];
+        self.mix[self.position] = if overdub {
+            (mix_left + input.0, mix_right + input.1)
+        } else {
+            input
+        };
+        self.last_layer[self.position] = input;
+        self.layer_frames += 1;
+    }
+}
+
+/// Records the master bus, or one system, into bar-length loops that play
+/// back in time with each other. Recording starts on a bar line of the clock
+/// passed to `process`, and lasts whole bars at its tempo.
+pub struct Looper {
+    loops: Vec<Loop>,
+    // Clock as of the last frame, and the bar it was in
+    clock: TransportClock,
+    bar: Option<u64>,
+    // Length of new recordings
+    bars: u32,
+    capacity: usize,
+    // Set whenever a loop changes state, until taken
+    changed: bool,
+    sample_rate: f32,
+}
+
+impl Looper {
+    pub fn new(sample_rate: f32) -> Self {
+        let capacity = (MAX_LOOP_SECONDS * sample_rate) as usize;
+        Self {
+            loops: (0..MAX_LOOPS).map(|_| Loop::new(capacity)).collect(),
+            clock: TransportClock::default(),
+            bar: None,
+            bars: 1,
+            capacity,
+            changed: false,
+            sample_rate,
+        }
+    }
+
+    /// Bars the next recording lasts
+    pub fn set_bars(&mut self, bars: u32) {
+        self.bars = bars.clamp(1, MAX_LOOP_BARS);
+    }
+
+    /// Record into an empty loop from the next bar line, at the tempo of the
+    /// clock last seen. Loops already recorded keep their length.
+    pub fn record(&mut self, index: usize) -> Result<(), String> {
+        let length = self.bars as usize * self.clock.frames_per_bar(self.sample_rate);
+        if length > self.capacity {
+            return Err(format!(
+                "{} bars at {} BPM is longer than the {} second loop limit",
+                self.bars, self.clock.bpm, MAX_LOOP_SECONDS
+            ));
+        }
+        let bars = self.bars;
+        let looped = self.loop_mut(index)?;
+        if looped.state != LoopState::Empty {
+            return Err(format!(
+                "Loop {} is already recorded; clear it or overdub",
+                index + 1
+            ));
+        }
+        looped.state = LoopState::Armed;
+        looped.length = length;
+        looped.bars = bars;
+        self.changed = true;
+        Ok(())
+    }
+
+    /// Start recording a layer over one pass of a playing loop, or finish the
+    /// layer early if one is being recorded
+    pub fn overdub(&mut self, index: usize) -> Result<(), String> {
+        let looped = self.loop_mut(index)?;
+        match looped.state {
+            LoopState::Playing => {
+                looped.start_layer();
+                looped.state = LoopState::Overdubbing;
+            }
+            LoopState::Overdubbing => {
+                looped.layers += 1;
+                looped.state = LoopState::Playing;
+            }
+            _ => return Err(format!("Loop {} isn't playing", index + 1)),
+        }
+        self.changed = true;
+        Ok(())
+    }
+
+    /// Take the newest layer back out, emptying the loop if it was the first.
+    /// Only one layer can be undone.
+    pub fn undo(&mut self, index: usize) -> Result<(), String> {
+        let looped = self.loop_mut(index)?;
+        let in_progress = matches!(looped.state, LoopState::Recording | LoopState::Overdubbing);
+        if looped.layer_frames == 0 {
+            return Err(format!("Loop {} has nothing to undo", index + 1));
+        }
+
+        if looped.state == LoopState::Recording || (looped.layers <= 1 && !in_progress) {
+            looped.clear();
+        } else {
+            for offset in 0..looped.layer_frames {
+                let position = (looped.layer_start + offset) % looped.length;
+                let (mix_left, mix_right) = looped.mix[position];
+                let (layer_left, layer_right) = looped.last_layer[position];
+                looped.mix[position] = (mix_left - layer_left, mix_right - layer_right);
+            }
+            if !in_progress {
+                looped.layers -= 1;
+            }
+            looped.layer_frames = 0;
+            looped.state = LoopState::Playing;
+        }
+        self.changed = true;
+        Ok(())
+    }
+
+    pub fn clear(&mut self, index: usize) -> Result<(), String> {
+        self.loop_mut(index)?.clear();
+        self.changed = true;
+        Ok(())
+    }
+
+    pub fn set_level(&mut self, index: usize, level: f32) -> Result<(), String> {
+        self.loop_mut(index)?.level = level.clamp(0.0, 1.0);
+        self.changed = true;
+        Ok(())
+    }
+
+    /// Set a level without reporting it, for fades that report once they land
+    pub fn fade_level(&mut self, index: usize, level: f32) {
+        if let Some(looped) = self.loops.get_mut(index) {
+            looped.level = level.clamp(0.0, 1.0);
+        }
+    }
+
+    pub fn loop_info(&self) -> Vec<LoopInfo> {
+        self.loops
+            .iter()
+            .map(|looped| LoopInfo {
+                state: looped.state,
+                layers: looped.layers,
+                level: looped.level,
+                bars: looped.bars,
+            })
+            .collect()
+    }
+
+    /// Report the state again on the next `take_changed`, for changes made
+    /// outside the looper such as its source
+    pub fn mark_changed(&mut self) {
+        self.changed = true;
+    }
+
+    /// Whether any loop has changed since the last call
+    pub fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    /// Record the input into any recording loops and return the loops' output.
+    /// A bar line is wherever the clock's bar changes.
+    pub fn process(&mut self, input: (f32, f32), clock: &TransportClock) -> (f32, f32) {
+        let bar = clock.bar();
+        let at_bar_line = self.bar != Some(bar);
+        self.bar = Some(bar);
+        self.clock = *clock;
+
+        let mut output = (0.0, 0.0);
+        for looped in &mut self.loops {
+            if looped.state == LoopState::Armed && at_bar_line {
+                looped.state = LoopState::Recording;
+                looped.position = 0;
+                looped.start_layer();
+                self.changed = true;
+            }
+
+            match looped.state {
+                LoopState::Empty | LoopState::Armed => continue,
+                LoopState::Recording => looped.write_layer(input, false),
+                LoopState::Playing | LoopState::Overdubbing => {
+                    let (left, right) = looped.mix[looped.position];
+                    output.0 += left * looped.level;
+                    output.1 += right * looped.level;
+                    if looped.state == LoopState::Overdubbing {
+                        looped.write_layer(input, true);
+                    }
+                }
+            }
+
+            looped.position = (looped.position + 1) % looped.length;
+            let layer_done = looped.layer_frames == looped.length;
+            if layer_done && looped.state != LoopState::Playing {
+                looped.layers += 1;
+                looped.state = LoopState::Playing;
+                self.changed = true;
+            }
+        }
+        output
+    }
+
+    /// Loops recorded at the old rate would play at the wrong speed, so
+    /// they're cleared along with the buffers being resized
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.capacity = (MAX_LOOP_SECONDS * sample_rate) as usize;
+        self.loops = (0..MAX_LOOPS).map(|_| Loop::new(self.capacity)).collect();
+        self.bar = None;
+        self.changed = true;
+    }
+
+    fn loop_mut(&mut self, index: usize) -> Result<&mut Loop, String> {
+        self.loops
+            .get_mut(index)
+            .ok_or_else(|| format!("No loop {}, there are {}", index + 1, MAX_LOOPS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // One beat per bar at 60 BPM makes a bar one second: 100 frames
+    const SAMPLE_RATE: f32 = 100.0;
+
+    /// Loops against the clock, counting frames so bar lines land exactly
+    struct Clocked {
+        looper: Looper,
+        frame: u64,
+    }
+
+    impl Clocked {
+        fn process(&mut self, input: (f32, f32)) -> (f32, f32) {
+            let clock = TransportClock {
+                bpm: 60.0,
+                beats_per_bar: 1,
+                beats: self.frame as f64 / SAMPLE_RATE as f64,
+            };
+            self.frame += 1;
+            self.looper.process(input, &clock)
+        }
+    }
+
+    impl std::ops::Deref for Clocked {
+        type Target = Looper;
+
+        fn deref(&self) -> &Looper {
+            &self.looper
+        }
+    }
+
+    impl std::ops::DerefMut for Clocked {
+        fn deref_mut(&mut self) -> &mut Looper {
+            &mut self.looper
+        }
+    }
+
+    fn looper() -> Clocked {
+        Clocked {
+            looper: Looper::new(SAMPLE_RATE),
+            frame: 0,
+        }
+    }
+
+    #[test]
+    fn test_recording_starts_on_the_bar_and_loops() {
+        let mut looper = looper();
+        looper.process((0.0, 0.0));
+        looper.record(0).unwrap();
+        assert!(looper.take_changed());
+
+        // Armed until the next bar line
+        for _ in 1..100 {
+            assert_eq!(looper.process((1.0, 1.0)), (0.0, 0.0));
+        }
+        assert_eq!(looper.loop_info()[0].state, LoopState::Armed);
+
+        // Record a ramp for one bar, then hear it back
+        for i in 0..100 {
+            looper.process((i as f32, -(i as f32)));
+        }
+        assert_eq!(looper.loop_info()[0].state, LoopState::Playing);
+        assert_eq!(looper.loop_info()[0].layers, 1);
+        for i in 0..100 {
+            assert_eq!(looper.process((0.0, 0.0)), (i as f32, -(i as f32)));
+        }
+
+        assert!(looper.record(0).is_err());
+        looper.set_level(0, 0.5).unwrap();
+        assert_eq!(looper.process((0.0, 0.0)), (0.0, 0.0));
+        assert_eq!(looper.process((0.0, 0.0)), (0.5, -0.5));
+    }
+
+    #[test]
+    fn test_overdub_layers_and_undo() {
+        let mut looper = looper();
+        // Let the looper see the clock, then record from the next bar
+        for _ in 0..100 {
+            looper.process((0.0, 0.0));
+        }
+        looper.record(0).unwrap();
+        for _ in 0..100 {
+            looper.process((1.0, 1.0));
+        }
+
+        looper.overdub(0).unwrap();
+        for _ in 0..100 {
+            looper.process((0.5, 0.5));
+        }
+        let info = looper.loop_info()[0];
+        assert_eq!((info.state, info.layers), (LoopState::Playing, 2));
+        assert_eq!(looper.process((0.0, 0.0)), (1.5, 1.5));
+
+        looper.undo(0).unwrap();
+        assert_eq!(looper.loop_info()[0].layers, 1);
+        assert_eq!(looper.process((0.0, 0.0)), (1.0, 1.0));
+        assert!(looper.undo(0).is_err());
+
+        // Undoing a half-finished overdub removes only what was written
+        looper.overdub(0).unwrap();
+        for _ in 0..50 {
+            looper.process((0.25, 0.25));
+        }
+        looper.undo(0).unwrap();
+        for _ in 0..100 {
+            assert_eq!(looper.process((0.0, 0.0)), (1.0, 1.0));
+        }
+
+        looper.clear(0).unwrap();
+        assert_eq!(looper.loop_info()[0].state, LoopState::Empty);
+        assert_eq!(looper.process((1.0, 1.0)), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_loops_longer_than_the_buffer_are_refused() {
+        let mut looper = looper();
+        looper.set_bars(MAX_LOOP_BARS);
+        looper.process((0.0, 0.0));
+        assert!(looper.record(0).is_ok());
+
+        let slow = TransportClock {
+            bpm: 20.0,
+            beats_per_bar: 4,
+            beats: 0.0,
+        };
+        looper.looper.process((0.0, 0.0), &slow);
+        assert!(looper.record(1).is_err());
+        assert!(looper.record(MAX_LOOPS).is_err());
+    }
+}
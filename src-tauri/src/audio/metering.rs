@@ -0,0 +1,156 @@
+/// Meter readings sent to the UI per second
+const METER_RATE_HZ: f32 = 30.0;
+
+/// How long the held peak stays before falling back to the current peak
+const PEAK_HOLD_SECONDS: f32 = 1.5;
+
+/// Peak meter and clip counter for the master output
+pub struct OutputMeter {
+    ceiling: f32,
+    // Highest sample since the last reading
+    peak: f32,
+    held_peak: f32,
+    hold_remaining: usize,
+    // Samples over the ceiling in the current second
+    clipped: u32,
+    reading_countdown: usize,
+    second_countdown: usize,
+    reading_ready: bool,
+    clipping_ready: Option<u32>,
+    sample_rate: f32,
+}
+
+/// Linear peak levels since the last reading
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeterReading {
+    pub peak: f32,
+    pub held_peak: f32,
+}
+
+impl OutputMeter {
+    /// Samples beyond the ceiling count as clipped
+    pub fn new(ceiling: f32, sample_rate: f32) -> Self {
+        Self {
+            ceiling,
+            peak: 0.0,
+            held_peak: 0.0,
+            hold_remaining: 0,
+            clipped: 0,
+            reading_countdown: (sample_rate / METER_RATE_HZ) as usize,
+            second_countdown: sample_rate as usize,
+            reading_ready: false,
+            clipping_ready: None,
+            sample_rate,
+        }
+    }
+
+    pub fn process(&mut self, left: f32, right: f32) {
+        for sample in [left, right] {
+            let level = sample.abs();
+            // NaN counts as clipping too, it's silenced by the limiter
+            if level > self.ceiling || level.is_nan() {
+                self.clipped += 1;
+            }
+            if level > self.peak {
+                self.peak = level;
+            }
+        }
+
+        if self.peak >= self.held_peak {
+            self.held_peak = self.peak;
+            self.hold_remaining = (PEAK_HOLD_SECONDS * self.sample_rate) as usize;
+        } else if self.hold_remaining > 0 {
+            self.hold_remaining -= 1;
+        }
+
+        self.reading_countdown = self.reading_countdown.saturating_sub(1);
+        if self.reading_countdown == 0 {
+            self.reading_ready = true;
+        }
+
+        self.second_countdown = self.second_countdown.saturating_sub(1);
+        if self.second_countdown == 0 {
+            self.second_countdown = self.sample_rate as usize;
+            if self.clipped > 0 {
+                self.clipping_ready = Some(self.clipped);
+            }
+            self.clipped = 0;
+        }
+    }
+
+    /// The levels since the last reading, once a reading is due
+    pub fn take_reading(&mut self) -> Option<MeterReading> {
+        if !std::mem::take(&mut self.reading_ready) {
+            return None;
+        }
+        let reading = MeterReading {
+            peak: self.peak,
+            held_peak: self.held_peak,
+        };
+        if self.hold_remaining == 0 {
+            self.held_peak = self.peak;
+        }
+        self.peak = 0.0;
+        self.reading_countdown = (self.sample_rate / METER_RATE_HZ) as usize;
+        Some(reading)
+    }
+
+    /// Samples clipped over the last second, if there were any
+    pub fn take_clipping(&mut self) -> Option<u32> {
+        self.clipping_ready.take()
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        *self = Self::new(self.ceiling, sample_rate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meter_holds_peaks_and_counts_clips_per_second() {
+        // 30 frames a reading, 900 a second
+        let mut meter = OutputMeter::new(0.95, 900.0);
+        meter.process(1.0, -0.5);
+        for _ in 1..30 {
+            assert!(meter.take_reading().is_none());
+            meter.process(0.25, 0.25);
+        }
+        assert_eq!(
+            meter.take_reading(),
+            Some(MeterReading {
+                peak: 1.0,
+                held_peak: 1.0
+            })
+        );
+
+        // The held peak outlasts the quieter readings that follow it
+        for _ in 0..30 {
+            meter.process(0.25, 0.25);
+        }
+        let reading = meter.take_reading().unwrap();
+        assert_eq!((reading.peak, reading.held_peak), (0.25, 1.0));
+        for _ in 0..(900 * 2) {
+            meter.process(0.25, 0.25);
+            meter.take_reading();
+        }
+        for _ in 0..30 {
+            meter.process(0.25, 0.25);
+        }
+        assert_eq!(meter.take_reading().unwrap().held_peak, 0.25);
+
+        // One clipped sample was counted in the first second and none since
+        let mut meter = OutputMeter::new(0.95, 900.0);
+        meter.process(1.0, f32::NAN);
+        for _ in 1..900 {
+            meter.process(0.0, 0.0);
+        }
+        assert_eq!(meter.take_clipping(), Some(2));
+        for _ in 0..900 {
+            meter.process(0.0, 0.0);
+        }
+        assert_eq!(meter.take_clipping(), None);
+    }
+}
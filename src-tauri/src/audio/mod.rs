@@ -1,13 +1,25 @@
+pub mod analysis;
 pub mod buffers;
 pub mod delays;
+pub mod dynamics;
 pub mod envelopes;
+pub mod evolve;
 pub mod filters;
 pub mod instruments;
+pub mod lofi;
+pub mod looper;
+pub mod metering;
 pub mod modulators;
 pub mod oscillators;
 pub mod reverbs;
+pub mod samples;
+pub mod scenes;
 pub mod server;
+pub mod slicing;
+pub mod stutter;
 pub mod systems;
+pub mod tape_stop;
+pub mod time_stretch;
 
 pub const PI: f32 = std::f32::consts::PI;
 pub const TWO_PI: f32 = 2.0 * PI;
@@ -39,9 +51,26 @@ pub trait AudioSystem: Send {
     /// Process a single stereo sample and return (left, right)
     fn next_sample(&mut self) -> (f32, f32);
 
-    /// Handle a client event - each system parses and handles its own supported events
-    fn handle_client_event(&mut self, event: &crate::events::ClientEvent) -> Result<(), String>;
+    /// Handle a client event - each system handles the events for its own variant
+    fn handle_client_event(&mut self, event: crate::events::ClientEvent) -> Result<(), String>;
 
     /// Set the sample rate for the entire system
     fn set_sample_rate(&mut self, sample_rate: f32);
+
+    /// Called when the system starts being processed, so it can announce
+    /// its tracks and state to the UI
+    fn on_activate(&mut self) {}
+
+    /// Reseed every random source in the system so output can be reproduced
+    fn set_seed(&mut self, _seed: u64) {}
+
+    /// Make a small random change, called every few bars while evolve is on.
+    /// Systems should report what they change, so the UI follows.
+    fn evolve(&mut self, _mutations: &mut evolve::Mutations) {}
+
+    /// Where the system's transport is while it's playing, so the looper and
+    /// other bar-synced processors can follow it
+    fn transport_clock(&self) -> Option<crate::sequencing::TransportClock> {
+        None
+    }
 }
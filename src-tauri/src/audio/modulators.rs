@@ -44,6 +44,11 @@ impl SampleAndHold {
         }
     }
 
+    /// Restart the random sequence from a seed
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng.seed(seed);
+    }
+
     pub fn next_sample(&mut self) -> f32 {
         self.sample_counter += 1;
 
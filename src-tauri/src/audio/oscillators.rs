@@ -1,5 +1,7 @@
-use crate::audio::{AudioGenerator, TWO_PI};
+use crate::audio::samples::SampleRef;
+use crate::audio::{AudioGenerator, StereoAudioGenerator, TWO_PI};
 use once_cell::sync::Lazy;
+use std::ops::Range;
 
 const SINE_TABLE_SIZE: usize = 8192;
 const SINE_TABLE_MASK: usize = SINE_TABLE_SIZE - 1;
@@ -61,11 +63,52 @@ static SAW_TABLES: Lazy<[Vec<f32>; 8]> = Lazy::new(|| {
     ]
 });
 
+// Fractions of a table step too small to hear are read without interpolating
+const INTERPOLATION_THRESHOLD: f32 = 1.0e-3;
+
+/// Read a table at a phase from 0 to 1, interpolating linearly between the
+/// entries either side so slow oscillators don't step through the table
+fn read_table(table: &[f32], phase: f32) -> f32 {
+    let position = phase * SINE_TABLE_SIZE as f32;
+    let index = position as usize;
+    let fraction = position - index as f32;
+    let current = table[index & SINE_TABLE_MASK];
+    if fraction < INTERPOLATION_THRESHOLD {
+        return current;
+    }
+    let next = table[(index + 1) & SINE_TABLE_MASK];
+    current + (next - current) * fraction
+}
+
+/// PolyBLEP correction for a step in a waveform at phase 0, to be added at
+/// the rising edge (or subtracted at a falling one) to keep aliasing down
+pub fn poly_blep(phase: f32, increment: f32) -> f32 {
+    if phase < increment {
+        let t = phase / increment;
+        2.0 * t - t * t - 1.0
+    } else if phase > 1.0 - increment {
+        let t = (phase - 1.0) / increment;
+        t * t + 2.0 * t + 1.0
+    } else {
+        0.0
+    }
+}
+
+/// Random step of the drift walk each sample, and how much of the walk is
+/// kept; together they settle around half the drift amount, wandering over
+/// a fraction of a second
+const DRIFT_STEP: f32 = 0.007;
+const DRIFT_LEAK: f32 = 0.9999;
+
 pub struct PhaseGenerator {
     phase: f32,
     phase_increment: f32,
     frequency: f32,
     sample_rate: f32,
+    // Slow random walk on the pitch, like an analog oscillator's
+    drift_amount: f32, // Widest drift in cents, 0 for none
+    drift: f32,        // -1 to 1 of the drift amount
+    drift_rng: fastrand::Rng,
 }
 
 impl PhaseGenerator {
@@ -75,9 +118,22 @@ impl PhaseGenerator {
             frequency: frequency,
             sample_rate,
             phase_increment: frequency / sample_rate,
+            drift_amount: 0.0,
+            drift: 0.0,
+            drift_rng: fastrand::Rng::new(),
         }
     }
 
+    /// Widest the pitch wanders from the set frequency, in cents
+    pub fn set_drift_amount(&mut self, cents: f32) {
+        self.drift_amount = cents.clamp(0.0, 50.0);
+    }
+
+    /// Restart the drift walk from a seed
+    pub fn set_seed(&mut self, seed: u64) {
+        self.drift_rng.seed(seed);
+    }
+
     pub fn set_frequency(&mut self, frequency: f32) {
         self.frequency = frequency;
         self.phase_increment = frequency / self.sample_rate;
@@ -96,9 +152,21 @@ impl PhaseGenerator {
         self.frequency
     }
 
+    /// Phase advanced each sample, as a fraction of a cycle
+    pub fn get_phase_increment(&self) -> f32 {
+        self.phase_increment
+    }
+
     pub fn next_sample(&mut self) -> f32 {
         let sample = self.phase;
-        self.phase += self.phase_increment;
+        if self.drift_amount > 0.0 {
+            let step = (self.drift_rng.f32() * 2.0 - 1.0) * DRIFT_STEP;
+            self.drift = ((self.drift + step) * DRIFT_LEAK).clamp(-1.0, 1.0);
+            let ratio = (self.drift * self.drift_amount / 1200.0).exp2();
+            self.phase += self.phase_increment * ratio;
+        } else {
+            self.phase += self.phase_increment;
+        }
 
         if self.phase >= 1.0 {
             self.phase -= 1.0;
@@ -135,9 +203,7 @@ impl SineOscillator {
 impl AudioGenerator for SineOscillator {
     fn next_sample(&mut self) -> f32 {
         let phase = self.phase_gen.next_sample();
-        let table_index = ((phase * SINE_TABLE_SIZE as f32) as usize) & SINE_TABLE_MASK;
-        let sample = SINE_TABLE[table_index];
-        sample
+        read_table(&SINE_TABLE, phase)
     }
 
     fn set_sample_rate(&mut self, sample_rate: f32) {
@@ -160,6 +226,16 @@ impl SawOscillator {
         self.phase_gen.set_frequency(frequency);
     }
 
+    /// Widest the pitch wanders from the set frequency, in cents
+    pub fn set_drift_amount(&mut self, cents: f32) {
+        self.phase_gen.set_drift_amount(cents);
+    }
+
+    /// Restart the drift walk from a seed
+    pub fn set_seed(&mut self, seed: u64) {
+        self.phase_gen.set_seed(seed);
+    }
+
     pub fn reset(&mut self) {
         self.phase_gen.reset();
     }
@@ -172,7 +248,6 @@ impl SawOscillator {
 impl AudioGenerator for SawOscillator {
     fn next_sample(&mut self) -> f32 {
         let phase = self.phase_gen.next_sample();
-        let table_index = ((phase * SINE_TABLE_SIZE as f32) as usize) & SINE_TABLE_MASK;
 
         // Select wavetable based on frequency
         let frequency = self.phase_gen.get_frequency();
@@ -194,8 +269,100 @@ impl AudioGenerator for SawOscillator {
             7
         };
 
-        let sample = SAW_TABLES[wavetable_index][table_index];
-        sample
+        read_table(&SAW_TABLES[wavetable_index], phase)
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.set_sample_rate(sample_rate);
+    }
+}
+
+/// Saw from a naive ramp with polyBLEP smoothing at the reset. Unlike the
+/// table saw its harmonics follow the frequency continuously, so it can be
+/// swept and modulated without table-switching steps.
+pub struct PolyBlepSaw {
+    phase_gen: PhaseGenerator,
+}
+
+impl PolyBlepSaw {
+    pub fn new(frequency: f32, sample_rate: f32) -> Self {
+        Self {
+            phase_gen: PhaseGenerator::new(frequency, sample_rate),
+        }
+    }
+
+    pub fn set_frequency(&mut self, frequency: f32) {
+        self.phase_gen.set_frequency(frequency);
+    }
+
+    pub fn reset(&mut self) {
+        self.phase_gen.reset();
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.phase_gen.set_sample_rate(sample_rate);
+    }
+}
+
+impl AudioGenerator for PolyBlepSaw {
+    fn next_sample(&mut self) -> f32 {
+        let increment = self.phase_gen.get_phase_increment();
+        let phase = self.phase_gen.next_sample();
+        2.0 * phase - 1.0 - poly_blep(phase, increment)
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.set_sample_rate(sample_rate);
+    }
+}
+
+/// Pulse with both edges smoothed by polyBLEP; the width is the fraction of
+/// each cycle spent high, with 0.5 a square. The width takes a per-sample
+/// modulation input for PWM.
+pub struct PolyBlepPulse {
+    phase_gen: PhaseGenerator,
+    pulse_width: f32,
+}
+
+impl PolyBlepPulse {
+    pub fn new(frequency: f32, sample_rate: f32) -> Self {
+        Self {
+            phase_gen: PhaseGenerator::new(frequency, sample_rate),
+            pulse_width: 0.5,
+        }
+    }
+
+    pub fn set_frequency(&mut self, frequency: f32) {
+        self.phase_gen.set_frequency(frequency);
+    }
+
+    pub fn set_pulse_width(&mut self, width: f32) {
+        self.pulse_width = width.clamp(0.05, 0.95);
+    }
+
+    pub fn reset(&mut self) {
+        self.phase_gen.reset();
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.phase_gen.set_sample_rate(sample_rate);
+    }
+
+    /// Next sample with the width offset by a modulation amount, without
+    /// changing the stored width
+    pub fn next_sample_with_pwm(&mut self, width_mod: f32) -> f32 {
+        let width = (self.pulse_width + width_mod).clamp(0.05, 0.95);
+        let increment = self.phase_gen.get_phase_increment();
+        let phase = self.phase_gen.next_sample();
+        let naive = if phase < width { 1.0 } else { -1.0 };
+        let falling = (phase - width).rem_euclid(1.0);
+        naive + poly_blep(phase, increment) - poly_blep(falling, increment)
+    }
+}
+
+impl AudioGenerator for PolyBlepPulse {
+    fn next_sample(&mut self) -> f32 {
+        self.next_sample_with_pwm(0.0)
     }
 
     fn set_sample_rate(&mut self, sample_rate: f32) {
@@ -213,6 +380,11 @@ impl NoiseGenerator {
             rng: fastrand::Rng::new(),
         }
     }
+
+    /// Restart the noise sequence from a seed
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng.seed(seed);
+    }
 }
 
 impl AudioGenerator for NoiseGenerator {
@@ -225,6 +397,110 @@ impl AudioGenerator for NoiseGenerator {
     }
 }
 
+/// Colour of a noise source, by how its level falls with frequency
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NoiseColor {
+    /// Flat, equal energy at every frequency
+    White,
+    /// 3 dB down per octave, equal energy in every octave
+    Pink,
+    /// 6 dB down per octave, a deep rumble
+    Brown,
+}
+
+impl NoiseColor {
+    pub fn from_index(index: u32) -> Option<Self> {
+        match index {
+            0 => Some(NoiseColor::White),
+            1 => Some(NoiseColor::Pink),
+            2 => Some(NoiseColor::Brown),
+            _ => None,
+        }
+    }
+}
+
+/// Pink noise from white through Paul Kellet's three-pole filter, accurate
+/// to within a dB or so across the audio band
+pub struct PinkNoise {
+    white: NoiseGenerator,
+    poles: [f32; 3],
+}
+
+impl PinkNoise {
+    pub fn new() -> Self {
+        Self {
+            white: NoiseGenerator::new(),
+            poles: [0.0; 3],
+        }
+    }
+
+    /// Restart the noise sequence from a seed
+    pub fn set_seed(&mut self, seed: u64) {
+        self.white.set_seed(seed);
+    }
+}
+
+impl Default for PinkNoise {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AudioGenerator for PinkNoise {
+    fn next_sample(&mut self) -> f32 {
+        let white = self.white.next_sample();
+        self.poles[0] = 0.99765 * self.poles[0] + white * 0.0990460;
+        self.poles[1] = 0.96300 * self.poles[1] + white * 0.2965164;
+        self.poles[2] = 0.57000 * self.poles[2] + white * 1.0526913;
+        let pink = self.poles.iter().sum::<f32>() + white * 0.1848;
+        // Roughly matches the level of the white noise
+        pink * 0.35
+    }
+
+    fn set_sample_rate(&mut self, _sample_rate: f32) {
+        // The filter is tuned for 44.1-48kHz and close enough at other rates
+    }
+}
+
+/// Brown noise from white through a leaky integrator, a random walk that
+/// drifts back towards zero
+pub struct BrownNoise {
+    white: NoiseGenerator,
+    last_output: f32,
+}
+
+impl BrownNoise {
+    pub fn new() -> Self {
+        Self {
+            white: NoiseGenerator::new(),
+            last_output: 0.0,
+        }
+    }
+
+    /// Restart the noise sequence from a seed
+    pub fn set_seed(&mut self, seed: u64) {
+        self.white.set_seed(seed);
+    }
+}
+
+impl Default for BrownNoise {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AudioGenerator for BrownNoise {
+    fn next_sample(&mut self) -> f32 {
+        let white = self.white.next_sample();
+        self.last_output = (self.last_output + white * 0.02) / 1.02;
+        self.last_output * 3.5
+    }
+
+    fn set_sample_rate(&mut self, _sample_rate: f32) {
+        // The walk's leak is per sample, which is close enough across rates
+    }
+}
+
 pub struct PMOscillator {
     phase_gen: PhaseGenerator,
     feedback: f32,
@@ -248,6 +524,16 @@ impl PMOscillator {
         self.feedback = feedback.clamp(0.0, 0.99);
     }
 
+    /// Widest the pitch wanders from the set frequency, in cents
+    pub fn set_drift_amount(&mut self, cents: f32) {
+        self.phase_gen.set_drift_amount(cents);
+    }
+
+    /// Restart the drift walk from a seed
+    pub fn set_seed(&mut self, seed: u64) {
+        self.phase_gen.set_seed(seed);
+    }
+
     pub fn reset(&mut self) {
         self.phase_gen.reset();
         self.last_output = 0.0;
@@ -277,6 +563,125 @@ impl AudioGenerator for PMOscillator {
     }
 }
 
+/// Casio CZ-style phase distortion: a sine read through a bent phase. The
+/// distortion moves the sine's peak earlier in the cycle, so the wave rises
+/// ever faster and falls slowly, from a pure sine at 0 towards a saw at 1.
+pub struct PhaseDistortionOscillator {
+    phase_gen: PhaseGenerator,
+    distortion: f32,
+}
+
+impl PhaseDistortionOscillator {
+    pub fn new(frequency: f32, sample_rate: f32) -> Self {
+        Self {
+            phase_gen: PhaseGenerator::new(frequency, sample_rate),
+            distortion: 0.0,
+        }
+    }
+
+    pub fn set_frequency(&mut self, frequency: f32) {
+        self.phase_gen.set_frequency(frequency);
+    }
+
+    /// Bend of the phase, from a sine (0) towards a saw (1)
+    pub fn set_distortion(&mut self, distortion: f32) {
+        self.distortion = distortion.clamp(0.0, 1.0);
+    }
+
+    pub fn reset(&mut self) {
+        self.phase_gen.reset();
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.phase_gen.set_sample_rate(sample_rate);
+    }
+}
+
+impl AudioGenerator for PhaseDistortionOscillator {
+    fn next_sample(&mut self) -> f32 {
+        let increment = self.phase_gen.get_phase_increment();
+        let phase = self.phase_gen.next_sample();
+
+        // The first quarter of the sine, up to its peak, is squeezed into
+        // the knee and the rest stretched over the remainder of the cycle.
+        // The rise never gets shorter than two samples, which keeps the
+        // aliasing of the sharpest settings down.
+        let knee = (0.25 * (1.0 - self.distortion))
+            .max(2.0 * increment)
+            .min(0.25);
+        let bent_phase = if phase < knee {
+            0.25 * phase / knee
+        } else {
+            0.25 + 0.75 * (phase - knee) / (1.0 - knee)
+        };
+
+        let table_index = ((bent_phase * SINE_TABLE_SIZE as f32) as usize) & SINE_TABLE_MASK;
+        SINE_TABLE[table_index]
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.set_sample_rate(sample_rate);
+    }
+}
+
+/// Sine with through-zero linear FM. The modulator moves the frequency by up
+/// to the depth in Hz either way, and when that takes it below zero the
+/// phase runs backwards rather than stalling, so deep FM stays in tune.
+pub struct TZFMOscillator {
+    phase: f32,
+    frequency: f32,
+    fm_depth: f32, // Hz at full modulator swing
+    sample_rate: f32,
+}
+
+impl TZFMOscillator {
+    pub fn new(frequency: f32, sample_rate: f32) -> Self {
+        Self {
+            phase: 0.0,
+            frequency,
+            fm_depth: 0.0,
+            sample_rate,
+        }
+    }
+
+    pub fn set_frequency(&mut self, frequency: f32) {
+        self.frequency = frequency;
+    }
+
+    /// How far a full-scale modulator moves the frequency, in Hz
+    pub fn set_fm_depth(&mut self, depth_hz: f32) {
+        self.fm_depth = depth_hz.max(0.0);
+    }
+
+    pub fn reset(&mut self) {
+        self.phase = 0.0;
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+    }
+
+    /// Next sample with the frequency moved by a modulator from -1 to 1
+    pub fn next_sample_with_fm(&mut self, modulator: f32) -> f32 {
+        let table_index = ((self.phase * SINE_TABLE_SIZE as f32) as usize) & SINE_TABLE_MASK;
+        let sample = SINE_TABLE[table_index];
+
+        let frequency = self.frequency + modulator * self.fm_depth;
+        self.phase = (self.phase + frequency / self.sample_rate).rem_euclid(1.0);
+        sample
+    }
+}
+
+impl AudioGenerator for TZFMOscillator {
+    fn next_sample(&mut self) -> f32 {
+        self.next_sample_with_fm(0.0)
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.set_sample_rate(sample_rate);
+    }
+}
+
 /// Hash-based noise generator that simulates Hasher.ar(Sweep.ar) from SuperCollider
 /// Creates chaotic noise by applying a hash function to a linear ramp (sweep)
 pub struct HasherNoise {
@@ -295,6 +700,12 @@ impl HasherNoise {
         self.phase_gen.reset();
     }
 
+    /// Speed of the hashed sweep. Slow sweeps hold each hash for several
+    /// samples, so the noise turns stepped and buzzy.
+    pub fn set_sweep_rate(&mut self, rate_hz: f32) {
+        self.phase_gen.set_frequency(rate_hz);
+    }
+
     pub fn set_sample_rate(&mut self, sample_rate: f32) {
         self.phase_gen.set_sample_rate(sample_rate);
     }
@@ -320,3 +731,624 @@ impl AudioGenerator for HasherNoise {
         self.set_sample_rate(sample_rate);
     }
 }
+
+/// Samples in each wavetable; shorter than the sine table since every frame
+/// is stored once per octave
+const WAVETABLE_SIZE: usize = 2048;
+const WAVETABLE_MASK: usize = WAVETABLE_SIZE - 1;
+
+/// Band-limited copies of each frame, one per octave as for the saw
+const WAVETABLE_OCTAVES: usize = 8;
+
+/// Frames in the wavetable bank, in scan order
+pub const WAVETABLE_FRAMES: usize = 4;
+
+/// Harmonic amplitudes of each frame: sine, triangle, saw and square, so
+/// scanning the bank moves from soft to bright to hollow
+fn wavetable_harmonic(frame: usize, harmonic: usize) -> f32 {
+    let n = harmonic as f32;
+    match frame {
+        0 => {
+            if harmonic == 1 {
+                1.0
+            } else {
+                0.0
+            }
+        }
+        1 => {
+            if harmonic % 2 == 1 {
+                // Alternating signs keep the triangle's corners sharp
+                let sign = if harmonic % 4 == 1 { 1.0 } else { -1.0 };
+                sign / (n * n)
+            } else {
+                0.0
+            }
+        }
+        2 => 1.0 / n,
+        _ => {
+            if harmonic % 2 == 1 {
+                1.0 / n
+            } else {
+                0.0
+            }
+        }
+    }
+}
+
+// Per frame, per octave: tables summed from each frame's harmonics up to the
+// most that octave can hold without aliasing, normalised to a peak of 1
+static WAVETABLE_BANK: Lazy<Vec<Vec<Vec<f32>>>> = Lazy::new(|| {
+    (0..WAVETABLE_FRAMES)
+        .map(|frame| {
+            (0..WAVETABLE_OCTAVES)
+                .map(|octave| {
+                    let max_harmonic = 512 >> octave;
+                    let mut table: Vec<f32> = (0..WAVETABLE_SIZE)
+                        .map(|i| {
+                            let phase = i as f32 / WAVETABLE_SIZE as f32 * TWO_PI;
+                            (1..=max_harmonic)
+                                .map(|harmonic| {
+                                    wavetable_harmonic(frame, harmonic)
+                                        * (harmonic as f32 * phase).sin()
+                                })
+                                .sum()
+                        })
+                        .collect();
+                    let peak = table.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()));
+                    if peak > 0.0 {
+                        table.iter_mut().for_each(|sample| *sample /= peak);
+                    }
+                    table
+                })
+                .collect()
+        })
+        .collect()
+});
+
+/// Octave table for a frequency, on the same bands as the saw's tables
+fn octave_table_index(frequency: f32) -> usize {
+    ((frequency / 40.0).log2().floor().max(0.0) as usize).min(WAVETABLE_OCTAVES - 1)
+}
+
+/// Scans a bank of band-limited wavetables, crossfading between neighbouring
+/// frames as the position moves from 0 (first frame) to 1 (last)
+pub struct WavetableOscillator {
+    phase_gen: PhaseGenerator,
+    position: f32,
+}
+
+impl WavetableOscillator {
+    pub fn new(frequency: f32, sample_rate: f32) -> Self {
+        Self {
+            phase_gen: PhaseGenerator::new(frequency, sample_rate),
+            position: 0.0,
+        }
+    }
+
+    pub fn set_frequency(&mut self, frequency: f32) {
+        self.phase_gen.set_frequency(frequency);
+    }
+
+    pub fn set_position(&mut self, position: f32) {
+        self.position = position.clamp(0.0, 1.0);
+    }
+
+    pub fn reset(&mut self) {
+        self.phase_gen.reset();
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.phase_gen.set_sample_rate(sample_rate);
+    }
+
+    /// Next sample with the position offset by a modulation amount, without
+    /// changing the stored position
+    pub fn next_sample_with_position_mod(&mut self, position_mod: f32) -> f32 {
+        let phase = self.phase_gen.next_sample();
+        let table_index = ((phase * WAVETABLE_SIZE as f32) as usize) & WAVETABLE_MASK;
+        let octave = octave_table_index(self.phase_gen.get_frequency());
+
+        let scan = (self.position + position_mod).clamp(0.0, 1.0) * (WAVETABLE_FRAMES - 1) as f32;
+        let frame = (scan as usize).min(WAVETABLE_FRAMES - 2);
+        let blend = scan - frame as f32;
+
+        let current = WAVETABLE_BANK[frame][octave][table_index];
+        let next = WAVETABLE_BANK[frame + 1][octave][table_index];
+        current + (next - current) * blend
+    }
+}
+
+impl AudioGenerator for WavetableOscillator {
+    fn next_sample(&mut self) -> f32 {
+        self.next_sample_with_position_mod(0.0)
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.set_sample_rate(sample_rate);
+    }
+}
+
+/// The wavetable bank's triangle frame
+const TRIANGLE_FRAME: usize = 1;
+
+/// Band-limited triangle read from the wavetable bank. It starts each cycle
+/// at zero rising, in phase with the sine, and its odd harmonics fall away
+/// fast enough for soft leads and smooth LFO shapes.
+pub struct TriangleOscillator {
+    phase_gen: PhaseGenerator,
+}
+
+impl TriangleOscillator {
+    pub fn new(frequency: f32, sample_rate: f32) -> Self {
+        Self {
+            phase_gen: PhaseGenerator::new(frequency, sample_rate),
+        }
+    }
+
+    pub fn set_frequency(&mut self, frequency: f32) {
+        self.phase_gen.set_frequency(frequency);
+    }
+
+    pub fn reset(&mut self) {
+        self.phase_gen.reset();
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.phase_gen.set_sample_rate(sample_rate);
+    }
+}
+
+impl AudioGenerator for TriangleOscillator {
+    fn next_sample(&mut self) -> f32 {
+        let phase = self.phase_gen.next_sample();
+        let table_index = ((phase * WAVETABLE_SIZE as f32) as usize) & WAVETABLE_MASK;
+        let octave = octave_table_index(self.phase_gen.get_frequency());
+        WAVETABLE_BANK[TRIANGLE_FRAME][octave][table_index]
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.set_sample_rate(sample_rate);
+    }
+}
+
+/// Plays a range of a preloaded sample once, at a variable rate, reading
+/// between frames by linear interpolation. The rate is relative to the
+/// sample's own pitch, whatever its sample rate, and a negative rate plays
+/// backwards.
+pub struct SampleOscillator {
+    sample: Option<SampleRef>,
+    frames: Range<usize>,
+    position: f64,
+    rate: f64,
+    // Frames of the sample per output sample at rate 1
+    step: f64,
+    playing: bool,
+    sample_rate: f32,
+}
+
+impl SampleOscillator {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            sample: None,
+            frames: 0..0,
+            position: 0.0,
+            rate: 1.0,
+            step: 1.0,
+            playing: false,
+            sample_rate,
+        }
+    }
+
+    /// Swap the sample and the range of frames to play, stopping playback
+    pub fn set_sample(&mut self, sample: SampleRef, frames: Range<usize>) {
+        let len = sample.len();
+        self.frames = frames.start.min(len)..frames.end.min(len);
+        self.sample = Some(sample);
+        self.playing = false;
+        self.update_step();
+    }
+
+    /// Playback rate, 1 for the sample's own pitch and 2 an octave up
+    pub fn set_rate(&mut self, rate: f64) {
+        self.rate = rate;
+    }
+
+    /// Play from a frame position within the range
+    pub fn start(&mut self, position: f64) {
+        self.position = position;
+        self.playing = self.sample.is_some() && !self.frames.is_empty();
+    }
+
+    pub fn stop(&mut self) {
+        self.playing = false;
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.update_step();
+    }
+
+    /// The next frame, or None once playback has run off either end of the
+    /// range or was never started
+    pub fn next_frame(&mut self) -> Option<(f32, f32)> {
+        let sample = self.sample.as_ref().filter(|_| self.playing)?;
+        let outside =
+            self.position < self.frames.start as f64 || self.position >= self.frames.end as f64;
+        if outside {
+            self.playing = false;
+            return None;
+        }
+        let frame = sample.interpolated_frame(&self.frames, self.position);
+        self.position += self.step * self.rate;
+        Some(frame)
+    }
+
+    fn update_step(&mut self) {
+        self.step = match &self.sample {
+            Some(sample) => sample.sample_rate as f64 / self.sample_rate as f64,
+            None => 1.0,
+        };
+    }
+}
+
+impl StereoAudioGenerator for SampleOscillator {
+    fn next_sample(&mut self) -> (f32, f32) {
+        self.next_frame().unwrap_or((0.0, 0.0))
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.set_sample_rate(sample_rate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 44100.0;
+
+    fn render(oscillator: &mut impl AudioGenerator, samples: usize) -> Vec<f32> {
+        (0..samples).map(|_| oscillator.next_sample()).collect()
+    }
+
+    fn rising_zero_crossings(signal: &[f32]) -> usize {
+        signal
+            .windows(2)
+            .filter(|pair| pair[0] < 0.0 && pair[1] >= 0.0)
+            .count()
+    }
+
+    fn mean(signal: &[f32]) -> f32 {
+        signal.iter().sum::<f32>() / signal.len() as f32
+    }
+
+    fn assert_in_range(signal: &[f32]) {
+        for &sample in signal {
+            assert!(
+                sample.is_finite() && sample.abs() <= 1.0 + 1e-6,
+                "{}",
+                sample
+            );
+        }
+    }
+
+    #[test]
+    fn test_polyblep_saw_and_pulse_are_bounded_and_in_tune() {
+        // One second, so the crossings count the cycles
+        let mut saw = PolyBlepSaw::new(441.0, SAMPLE_RATE);
+        let signal = render(&mut saw, SAMPLE_RATE as usize);
+        assert_in_range(&signal);
+        assert!(rising_zero_crossings(&signal).abs_diff(441) <= 1);
+        assert!(mean(&signal).abs() < 0.01);
+
+        let mut square = PolyBlepPulse::new(441.0, SAMPLE_RATE);
+        let signal = render(&mut square, SAMPLE_RATE as usize);
+        assert_in_range(&signal);
+        assert!(rising_zero_crossings(&signal).abs_diff(441) <= 1);
+        assert!(mean(&signal).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_pulse_width_sets_the_duty_cycle() {
+        for width in [0.1, 0.25, 0.75] {
+            let mut pulse = PolyBlepPulse::new(441.0, SAMPLE_RATE);
+            pulse.set_pulse_width(width);
+            let signal = render(&mut pulse, SAMPLE_RATE as usize);
+            assert_in_range(&signal);
+            let high = signal.iter().filter(|&&sample| sample > 0.0).count();
+            let duty = high as f32 / signal.len() as f32;
+            assert!((duty - width).abs() < 0.01, "{} for {}", duty, width);
+        }
+    }
+
+    #[test]
+    fn test_triangle_is_bounded_and_in_tune() {
+        for frequency in [55.0, 441.0, 4410.0] {
+            let mut triangle = TriangleOscillator::new(frequency, SAMPLE_RATE);
+            let signal = render(&mut triangle, SAMPLE_RATE as usize);
+            assert_in_range(&signal);
+            let cycles = rising_zero_crossings(&signal);
+            assert!(cycles.abs_diff(frequency as usize) <= 1, "{} Hz", frequency);
+            assert!(mean(&signal).abs() < 0.01);
+        }
+
+        // Starts at zero and peaks a quarter of the way through the cycle
+        let mut triangle = TriangleOscillator::new(441.0, SAMPLE_RATE);
+        let cycle = render(&mut triangle, 100);
+        assert!(cycle[0].abs() < 0.01);
+        assert!(cycle[25] > 0.99);
+        assert!(cycle[75] < -0.99);
+    }
+
+    #[test]
+    fn test_pwm_moves_the_duty_cycle_but_not_the_pitch() {
+        let mut pulse = PolyBlepPulse::new(441.0, SAMPLE_RATE);
+        let swept: Vec<f32> = (0..SAMPLE_RATE as usize)
+            .map(|i| {
+                // A 2 Hz sweep past both ends of the width's range
+                let lfo = (TWO_PI * 2.0 * i as f32 / SAMPLE_RATE).sin();
+                pulse.next_sample_with_pwm(lfo)
+            })
+            .collect();
+        assert_in_range(&swept);
+        assert!(rising_zero_crossings(&swept).abs_diff(441) <= 1);
+
+        // A fixed offset adds to the stored width
+        let mut pulse = PolyBlepPulse::new(441.0, SAMPLE_RATE);
+        pulse.set_pulse_width(0.3);
+        let signal: Vec<f32> = (0..SAMPLE_RATE as usize)
+            .map(|_| pulse.next_sample_with_pwm(0.2))
+            .collect();
+        let high = signal.iter().filter(|&&sample| sample > 0.0).count();
+        assert!((high as f32 / signal.len() as f32 - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_noise_colors_are_bounded_centred_and_tilted() {
+        let samples = 10 * SAMPLE_RATE as usize;
+        let mut white = NoiseGenerator::new();
+        let mut pink = PinkNoise::new();
+        let mut brown = BrownNoise::new();
+        white.set_seed(1);
+        pink.set_seed(1);
+        brown.set_seed(1);
+        let white = render(&mut white, samples);
+        let pink = render(&mut pink, samples);
+        let brown = render(&mut brown, samples);
+
+        // Pink matches white's level, so its rarer peaks reach further
+        let peak = |signal: &[f32]| signal.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()));
+        let rms =
+            |signal: &[f32]| (signal.iter().map(|s| s * s).sum::<f32>() / samples as f32).sqrt();
+        assert!(peak(&white) <= 1.0 && peak(&brown) <= 1.0 && peak(&pink) <= 4.0);
+        assert!((rms(&pink) / rms(&white) - 1.0).abs() < 0.2);
+        for signal in [&white, &pink, &brown] {
+            assert!(signal.iter().all(|sample| sample.is_finite()));
+            assert!(mean(signal).abs() < 0.05, "{}", mean(signal));
+        }
+
+        // Darker noise changes sign less often
+        let white_rate = rising_zero_crossings(&white);
+        let pink_rate = rising_zero_crossings(&pink);
+        let brown_rate = rising_zero_crossings(&brown);
+        assert!(
+            pink_rate < white_rate * 3 / 4,
+            "{} {}",
+            pink_rate,
+            white_rate
+        );
+        assert!(brown_rate < pink_rate / 2, "{} {}", brown_rate, pink_rate);
+
+        // Less of the energy gets past a one-pole highpass around 2 kHz
+        let highpass_share = |signal: &[f32]| {
+            let coefficient = (-TWO_PI * 2000.0 / SAMPLE_RATE).exp();
+            let mut lowpassed = 0.0;
+            let (mut high, mut total) = (0.0, 0.0);
+            for &sample in signal {
+                lowpassed = sample + (lowpassed - sample) * coefficient;
+                high += (sample - lowpassed) * (sample - lowpassed);
+                total += sample * sample;
+            }
+            high / total
+        };
+        let white_share = highpass_share(&white);
+        let pink_share = highpass_share(&pink);
+        let brown_share = highpass_share(&brown);
+        assert!(
+            pink_share < white_share * 0.5,
+            "{} {}",
+            pink_share,
+            white_share
+        );
+        assert!(
+            brown_share < pink_share * 0.5,
+            "{} {}",
+            brown_share,
+            pink_share
+        );
+    }
+
+    #[test]
+    fn test_phase_distortion_bends_a_sine_within_range() {
+        // Undistorted, it's a plain sine to within the table's resolution
+        let mut oscillator = PhaseDistortionOscillator::new(441.0, SAMPLE_RATE);
+        for (i, sample) in render(&mut oscillator, 1000).into_iter().enumerate() {
+            let expected = (TWO_PI * 441.0 * i as f32 / SAMPLE_RATE).sin();
+            assert!((sample - expected).abs() < 2e-3, "{} at {}", sample, i);
+        }
+
+        for step in 0..=10 {
+            let distortion = step as f32 / 10.0;
+            for frequency in [55.0, 441.0, 5000.0] {
+                let mut oscillator = PhaseDistortionOscillator::new(frequency, SAMPLE_RATE);
+                oscillator.set_distortion(distortion);
+                let signal = render(&mut oscillator, SAMPLE_RATE as usize);
+                assert_in_range(&signal);
+                assert!(rising_zero_crossings(&signal).abs_diff(frequency as usize) <= 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_through_zero_fm() {
+        // With no depth the modulator has no effect
+        let mut carrier = TZFMOscillator::new(441.0, SAMPLE_RATE);
+        let mut modulated = TZFMOscillator::new(441.0, SAMPLE_RATE);
+        for i in 0..1000 {
+            let modulator = (TWO_PI * 97.0 * i as f32 / SAMPLE_RATE).sin();
+            assert_eq!(
+                modulated.next_sample_with_fm(modulator),
+                carrier.next_sample()
+            );
+        }
+
+        // Pushed below zero, the phase runs backwards: a sine at -f Hz
+        let mut oscillator = TZFMOscillator::new(100.0, SAMPLE_RATE);
+        oscillator.set_fm_depth(1000.0);
+        for i in 0..SAMPLE_RATE as usize {
+            let sample = oscillator.next_sample_with_fm(-1.0);
+            let expected = (-TWO_PI * 900.0 * i as f32 / SAMPLE_RATE).sin();
+            assert!(sample.is_finite() && sample.abs() <= 1.0);
+            assert!((sample - expected).abs() < 0.01, "{} at {}", sample, i);
+        }
+
+        // And deep audio-rate FM stays finite and bounded
+        let mut oscillator = TZFMOscillator::new(100.0, SAMPLE_RATE);
+        oscillator.set_fm_depth(20000.0);
+        let signal: Vec<f32> = (0..SAMPLE_RATE as usize)
+            .map(|i| {
+                let modulator = (TWO_PI * 141.0 * i as f32 / SAMPLE_RATE).sin();
+                oscillator.next_sample_with_fm(modulator)
+            })
+            .collect();
+        assert_in_range(&signal);
+    }
+
+    #[test]
+    fn test_drift_stays_within_its_range() {
+        let increments = |generator: &mut PhaseGenerator, samples: usize| {
+            let mut last = generator.next_sample();
+            (0..samples)
+                .map(|_| {
+                    let phase = generator.next_sample();
+                    let increment = (phase - last).rem_euclid(1.0);
+                    last = phase;
+                    increment
+                })
+                .collect::<Vec<f32>>()
+        };
+
+        // No drift leaves every step at the set frequency
+        let mut steady = PhaseGenerator::new(441.0, SAMPLE_RATE);
+        for increment in increments(&mut steady, 10000) {
+            assert!((increment - 0.01).abs() < 1e-6);
+        }
+
+        let mut drifting = PhaseGenerator::new(441.0, SAMPLE_RATE);
+        drifting.set_drift_amount(20.0);
+        let steps = increments(&mut drifting, 10 * SAMPLE_RATE as usize);
+        let cents: Vec<f32> = steps
+            .iter()
+            .map(|increment| 1200.0 * (increment / 0.01).log2())
+            .collect();
+        let widest = cents.iter().fold(0.0_f32, |widest, c| widest.max(c.abs()));
+        assert!(widest <= 20.0 + 0.1, "{} cents", widest);
+        // It does wander, rather than sitting on the set pitch
+        assert!(widest > 2.0, "{} cents", widest);
+    }
+
+    #[test]
+    fn test_seeded_drift_repeats() {
+        let render = |seed: u64| {
+            let mut generator = PhaseGenerator::new(441.0, SAMPLE_RATE);
+            generator.set_drift_amount(20.0);
+            generator.set_seed(seed);
+            (0..10000)
+                .map(|_| generator.next_sample())
+                .collect::<Vec<f32>>()
+        };
+        assert_eq!(render(3), render(3));
+        assert_ne!(render(3), render(4));
+    }
+
+    fn ramp_sample(frames: usize) -> SampleRef {
+        let samples: Vec<f32> = (0..frames).map(|i| i as f32 / frames as f32).collect();
+        std::sync::Arc::new(
+            crate::audio::samples::Sample::from_interleaved("ramp", &samples, 1, SAMPLE_RATE)
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_sample_oscillator_plays_once_and_restarts() {
+        let mut oscillator = SampleOscillator::new(SAMPLE_RATE);
+        oscillator.set_sample(ramp_sample(100), 0..100);
+        oscillator.start(0.0);
+
+        let played: Vec<f32> = std::iter::from_fn(|| oscillator.next_frame())
+            .map(|(left, _)| left)
+            .collect();
+        assert_eq!(played.len(), 100);
+        assert_eq!(played[0], 0.0);
+        assert_eq!(played[99], 0.99);
+        for _ in 0..100 {
+            assert_eq!(
+                StereoAudioGenerator::next_sample(&mut oscillator),
+                (0.0, 0.0)
+            );
+        }
+
+        // Retriggering starts over, here at half speed between the frames
+        oscillator.set_rate(0.5);
+        oscillator.start(0.0);
+        let replayed: Vec<f32> = std::iter::from_fn(|| oscillator.next_frame())
+            .map(|(left, _)| left)
+            .collect();
+        assert_eq!(replayed.len(), 200);
+        assert!((replayed[1] - 0.005).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_sample_oscillator_without_frames_is_silent() {
+        let mut oscillator = SampleOscillator::new(SAMPLE_RATE);
+        oscillator.start(0.0);
+        assert_eq!(oscillator.next_frame(), None);
+
+        // An empty range, and one past the end of the sample
+        for frames in [10..10, 200..300] {
+            oscillator.set_sample(ramp_sample(100), frames);
+            oscillator.start(0.0);
+            assert_eq!(oscillator.next_frame(), None);
+            assert_eq!(
+                StereoAudioGenerator::next_sample(&mut oscillator),
+                (0.0, 0.0)
+            );
+        }
+    }
+
+    #[test]
+    fn test_table_reads_interpolate_between_entries() {
+        // Halfway between entries is where truncation was furthest off
+        for i in (0..SINE_TABLE_SIZE).step_by(97) {
+            let phase = (i as f32 + 0.5) / SINE_TABLE_SIZE as f32;
+            let expected = (TWO_PI * phase).sin();
+            assert!((read_table(&SINE_TABLE, phase) - expected).abs() < 1e-5);
+        }
+
+        // The last entry interpolates towards the first
+        for phase in [1.0 - 0.5 / SINE_TABLE_SIZE as f32, 1.0 - f32::EPSILON, 1.0] {
+            let sample = read_table(&SINE_TABLE, phase);
+            assert!((sample - (TWO_PI * phase).sin()).abs() < 1e-5, "{}", phase);
+            for table in SAW_TABLES.iter() {
+                assert!(read_table(table, phase).is_finite());
+            }
+        }
+
+        // A slow sine follows the true curve
+        let mut sine = SineOscillator::new(3.0, SAMPLE_RATE);
+        for (i, sample) in render(&mut sine, 2000).into_iter().enumerate() {
+            let expected = (TWO_PI * 3.0 * i as f32 / SAMPLE_RATE).sin();
+            assert!((sample - expected).abs() < 1e-4, "{} at {}", sample, i);
+        }
+    }
+}
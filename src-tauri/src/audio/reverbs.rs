@@ -1,102 +1,135 @@
-use std::collections::VecDeque;
-
+use crate::audio::buffers::Interpolation;
 use crate::audio::delays::DelayLine;
-use crate::audio::filters::{OnePoleFilter, OnePoleMode};
 use crate::audio::oscillators::SineOscillator;
-use crate::audio::{AudioGenerator, AudioProcessor, StereoAudioProcessor};
-
-// Fast Hadamard Transform for 4x4
-fn fast_hadamard_transform_4(signals: &mut [f32; 4]) {
-    // Stage 1: 4 -> 2 blocks
-    let mut temp = [0.0f32; 4];
-    for i in 0..2 {
-        temp[i] = signals[i] + signals[i + 2];
-        temp[i + 2] = signals[i] - signals[i + 2];
-    }
-    *signals = temp;
-
-    // Stage 2: 2 -> 1 blocks
-    for i in 0..2 {
-        let base = i * 2;
-        temp[base] = signals[base] + signals[base + 1];
-        temp[base + 1] = signals[base] - signals[base + 1];
+use crate::audio::{AudioGenerator, AudioProcessor, StereoAudioProcessor, PI};
+
+// Crossfade length when QualityReverb switches engines
+const QUALITY_CROSSFADE_TIME: f32 = 0.3;
+
+// Crossfade length when the feedback delay size changes
+const SIZE_CROSSFADE_TIME: f32 = 0.03;
+
+// LFO rates for the feedback delays, one LFO per pair of channels
+const FEEDBACK_LFO_RATES: [f32; 8] = [0.19, 0.37, 0.29, 0.41, 0.23, 0.31, 0.43, 0.47];
+
+// Fast Hadamard Transform for any power-of-two size
+fn fast_hadamard_transform<const N: usize>(signals: &mut [f32; N]) {
+    // Butterfly stages: N -> N/2 blocks down to 2 -> 1
+    let mut half = N / 2;
+    while half >= 1 {
+        for block in (0..N).step_by(half * 2) {
+            for i in block..block + half {
+                let a = signals[i];
+                let b = signals[i + half];
+                signals[i] = a + b;
+                signals[i + half] = a - b;
+            }
+        }
+        half /= 2;
     }
-    *signals = temp;
 
-    // Normalize by 1/sqrt(4) = 0.5 for energy conservation
+    // Normalize by 1/sqrt(N) for energy conservation
+    let scale = 1.0 / (N as f32).sqrt();
     for signal in signals.iter_mut() {
-        *signal *= 0.5;
+        *signal *= scale;
     }
 }
 
-// Householder transform for 4x4 feedback stage mixing
-fn householder_transform_4(signals: &mut [f32; 4]) {
+// Householder transform for feedback stage mixing
+fn householder_transform<const N: usize>(signals: &mut [f32; N]) {
     let sum: f32 = signals.iter().sum();
-    let reflection_coeff = -2.0 / 4.0;
+    let reflection_coeff = -2.0 / N as f32;
     let reflection = sum * reflection_coeff;
 
-    for i in 0..4 {
-        signals[i] += reflection;
+    for signal in signals.iter_mut() {
+        *signal += reflection;
     }
 }
 
-pub struct DiffusionStage4 {
-    delay_lines: [DelayLine; 4],
-    flip_polarity: [bool; 4],
+pub struct DiffusionStage<const N: usize> {
+    delay_lines: [DelayLine; N],
+    flip_polarity: [bool; N],
+    min_delay_seconds: f32,
+    max_delay_seconds: f32,
 }
 
-impl DiffusionStage4 {
+impl<const N: usize> DiffusionStage<N> {
     pub fn new(min_delay_seconds: f32, max_delay_seconds: f32, sample_rate: f32) -> Self {
-        let mut flip_polarity = [false; 4];
-        let mut delay_lines = VecDeque::new();
+        Self::with_rng(
+            min_delay_seconds,
+            max_delay_seconds,
+            sample_rate,
+            &mut fastrand::Rng::new(),
+        )
+    }
+
+    /// Build a stage with delay times and polarities drawn from the given generator
+    pub fn with_rng(
+        min_delay_seconds: f32,
+        max_delay_seconds: f32,
+        sample_rate: f32,
+        rng: &mut fastrand::Rng,
+    ) -> Self {
+        // Each line holds the longest delay of its channel's segment, so
+        // reseeding never needs a bigger buffer
+        let segment_size = (max_delay_seconds - min_delay_seconds) / N as f32;
+        let delay_lines = std::array::from_fn(|c| {
+            let segment_end = min_delay_seconds + ((c + 1) as f32 * segment_size);
+            DelayLine::new(segment_end, sample_rate)
+        });
+
+        let mut stage = Self {
+            delay_lines,
+            flip_polarity: [false; N],
+            min_delay_seconds,
+            max_delay_seconds,
+        };
+        stage.reseed(rng);
+        stage
+    }
 
+    /// Redraw the delay times and polarities, within the existing buffers
+    pub fn reseed(&mut self, rng: &mut fastrand::Rng) {
         // Calculate segment size
-        let total_range = max_delay_seconds - min_delay_seconds;
-        let segment_size = total_range / 4.0;
+        let total_range = self.max_delay_seconds - self.min_delay_seconds;
+        let segment_size = total_range / N as f32;
 
-        // Divide range into 4 equal segments, one channel per segment
-        for c in 0..4 {
-            let segment_start = min_delay_seconds + (c as f32 * segment_size);
+        // Divide range into N equal segments, one channel per segment
+        let channels = self.delay_lines.iter_mut().zip(&mut self.flip_polarity);
+        for (c, (delay_line, flip_polarity)) in channels.enumerate() {
+            let segment_start = self.min_delay_seconds + (c as f32 * segment_size);
             let segment_end = segment_start + segment_size;
 
             // Convert to microseconds for integer random generation
             let segment_start_us = (segment_start * 1_000_000.0) as i32;
             let segment_end_us = (segment_end * 1_000_000.0) as i32;
 
-            let random_delay_us = fastrand::i32(segment_start_us..segment_end_us) as f32;
+            let random_delay_us = rng.i32(segment_start_us..segment_end_us) as f32;
             let delay_seconds = random_delay_us / 1_000_000.0; // Convert back to seconds
 
-            let mut delay_line = DelayLine::new(delay_seconds, sample_rate);
             delay_line.set_delay_seconds(delay_seconds);
-            delay_lines.push_back(delay_line);
-            flip_polarity[c] = fastrand::bool();
+            *flip_polarity = rng.bool();
         }
+    }
 
-        Self {
-            delay_lines: [
-                delay_lines.pop_front().unwrap(),
-                delay_lines.pop_front().unwrap(),
-                delay_lines.pop_front().unwrap(),
-                delay_lines.pop_front().unwrap(),
-            ],
-            flip_polarity,
+    pub fn clear(&mut self) {
+        for delay_line in &mut self.delay_lines {
+            delay_line.clear();
         }
     }
 
-    pub fn process(&mut self, input: [f32; 4]) -> [f32; 4] {
+    pub fn process(&mut self, input: [f32; N]) -> [f32; N] {
         // Delay all channels
-        let mut delayed = [0.0f32; 4];
-        for i in 0..4 {
-            delayed[i] = AudioProcessor::process(&mut self.delay_lines[i], input[i]);
-        }
+        let mut delayed: [f32; N] =
+            std::array::from_fn(|i| AudioProcessor::process(&mut self.delay_lines[i], input[i]));
 
         // Apply Hadamard transform
-        fast_hadamard_transform_4(&mut delayed);
+        fast_hadamard_transform(&mut delayed);
 
         // Flip polarities based on random values
-        for i in 0..4 {
-            if self.flip_polarity[i] {
-                delayed[i] = -delayed[i];
+        for (sample, &flip) in delayed.iter_mut().zip(&self.flip_polarity) {
+            if flip {
+                *sample = -*sample;
             }
         }
 
@@ -104,53 +137,61 @@ impl DiffusionStage4 {
     }
 }
 
-pub struct FeedbackStage4 {
-    base_delays: [f32; 4],
-    delay_lines: [DelayLine; 4],
-    lfos: [SineOscillator; 2], // Use 2 LFOs for 4 channels
+pub struct FeedbackStage<const N: usize> {
+    base_delays: [f32; N],
+    delay_lines: [DelayLine; N],
+    lfos: Vec<SineOscillator>, // One LFO per pair of channels
     feedback: f32,
     modulation_depth: f32,
+
+    // Size changes crossfade from the current read taps to a second set,
+    // so the delay times never jump or glide
     size: f32,
+    next_size: Option<f32>,
+    target_size: f32,
+    size_fade_position: f32,
+    size_fade_step: f32,
 }
 
-impl FeedbackStage4 {
+impl<const N: usize> FeedbackStage<N> {
     pub fn new(min_delay_seconds: f32, max_delay_seconds: f32, sample_rate: f32) -> Self {
-        let mut delay_lines = VecDeque::new();
-        let mut base_delays = [0f32; 4];
+        let mut base_delays = [0f32; N];
 
-        // Create 4 delay lines with exponential distribution between min and max
-        for c in 0..4 {
-            let r = (c as f32) / 3.0; // 0 to 1 over 4 channels (0/3 to 3/3)
+        // Create N delay lines with exponential distribution between min and max
+        let delay_lines = std::array::from_fn(|c| {
+            let r = (c as f32) / (N - 1) as f32; // 0 to 1 over N channels
             let delay_seconds = min_delay_seconds * (max_delay_seconds / min_delay_seconds).powf(r);
-            delay_lines.push_back(DelayLine::new(delay_seconds * 2.5, sample_rate));
             base_delays[c] = delay_seconds; // Store in seconds
-        }
-
-        // Create 2 LFOs with different frequencies for 4 channels
-        let lfos = [
-            SineOscillator::new(0.19, sample_rate),
-            SineOscillator::new(0.37, sample_rate),
-        ];
+            let mut delay_line = DelayLine::new(delay_seconds * 2.5, sample_rate);
+            // Modulated taps move between samples
+            delay_line.set_interpolation(Interpolation::Cubic);
+            delay_line
+        });
+
+        // Create N/2 LFOs with different frequencies
+        let lfos = FEEDBACK_LFO_RATES[..N / 2]
+            .iter()
+            .map(|&rate| SineOscillator::new(rate, sample_rate))
+            .collect();
 
         Self {
             base_delays,
-            delay_lines: [
-                delay_lines.pop_front().unwrap(),
-                delay_lines.pop_front().unwrap(),
-                delay_lines.pop_front().unwrap(),
-                delay_lines.pop_front().unwrap(),
-            ],
+            delay_lines,
             lfos,
             feedback: 0.5,
             modulation_depth: 0.0,
             size: 1.0,
+            next_size: None,
+            target_size: 1.0,
+            size_fade_position: 0.0,
+            size_fade_step: 1.0 / (SIZE_CROSSFADE_TIME * sample_rate),
         }
     }
 
     pub fn set_feedback(&mut self, feedback: f32) {
         self.feedback = feedback.clamp(0.0, 1.0);
-        for i in 0..4 {
-            self.delay_lines[i].set_feedback(self.feedback);
+        for delay_line in &mut self.delay_lines {
+            delay_line.set_feedback(self.feedback);
         }
     }
 
@@ -159,255 +200,78 @@ impl FeedbackStage4 {
     }
 
     pub fn set_size(&mut self, size: f32) {
-        self.size = size.clamp(0.1, 2.0);
-    }
-
-    pub fn set_sample_rate(&mut self, sample_rate: f32) {
-        for lfo in &mut self.lfos {
-            lfo.set_sample_rate(sample_rate);
+        self.target_size = size.clamp(0.1, 2.0);
+        // A crossfade already running picks up the new target when it finishes
+        if self.next_size.is_none() {
+            self.start_size_fade();
         }
     }
 
-    pub fn process(&mut self, diffusion: [f32; 4]) -> [f32; 4] {
-        // Generate LFO values (2 LFOs shared across 4 delays)
-        // Unipolar modulation values
-        let lfo_values = [
-            (self.lfos[0].next_sample() + 1.0) * 0.5,
-            (self.lfos[1].next_sample() + 1.0) * 0.5,
-        ];
-
-        // Read current echoes from delay lines
-        let mut echoes = [0.0f32; 4];
-
-        // Apply LFO modulation to delay times (cycle through the 2 LFOs)
-        for i in 0..4 {
-            let lfo_value = lfo_values[i % 2];
-            let modulated_delay =
-                self.base_delays[i] * self.size * (1.0 + lfo_value * self.modulation_depth * 0.1);
-            echoes[i] = self.delay_lines[i].read_at(modulated_delay);
-        }
-
-        // Apply Householder transform
-        fast_hadamard_transform_4(&mut echoes);
-
-        // Write diffusion input to delay lines with echoes feedback
-        for i in 0..4 {
-            self.delay_lines[i].write(diffusion[i], echoes[i]);
+    fn start_size_fade(&mut self) {
+        if self.target_size != self.size {
+            self.next_size = Some(self.target_size);
+            self.size_fade_position = 0.0;
         }
-
-        echoes
     }
-}
 
-// Fast Hadamard Transform for 8x8
-fn fast_hadamard_transform_8(signals: &mut [f32; 8]) {
-    // Stage 1: 8 -> 4 blocks
-    let mut temp = [0.0f32; 8];
-    for i in 0..4 {
-        temp[i] = signals[i] + signals[i + 4];
-        temp[i + 4] = signals[i] - signals[i + 4];
-    }
-    *signals = temp;
+    fn advance_size_fade(&mut self) {
+        let Some(next_size) = self.next_size else {
+            return;
+        };
 
-    // Stage 2: 4 -> 2 blocks
-    for i in 0..2 {
-        temp[i] = signals[i] + signals[i + 2];
-        temp[i + 2] = signals[i] - signals[i + 2];
-        temp[i + 4] = signals[i + 4] + signals[i + 6];
-        temp[i + 6] = signals[i + 4] - signals[i + 6];
-    }
-    *signals = temp;
-
-    // Stage 3: 2 -> 1 blocks
-    for i in 0..4 {
-        let base = i * 2;
-        temp[base] = signals[base] + signals[base + 1];
-        temp[base + 1] = signals[base] - signals[base + 1];
-    }
-    *signals = temp;
-
-    // Normalize by 1/sqrt(8) for energy conservation
-    let scale = 1.0 / (8.0f32).sqrt();
-    for i in 0..8 {
-        signals[i] *= scale;
-    }
-}
-
-// Householder transform for feedback stage mixing
-fn householder_transform_8(signals: &mut [f32; 8]) {
-    let sum: f32 = signals.iter().sum();
-    let reflection_coeff = -2.0 / 8.0;
-    let reflection = sum * reflection_coeff;
-
-    for i in 0..8 {
-        signals[i] += reflection;
-    }
-}
-
-pub struct DiffusionStage8 {
-    delay_lines: [DelayLine; 8],
-    flip_polarity: [bool; 8],
-}
-
-impl DiffusionStage8 {
-    pub fn new(min_delay_seconds: f32, max_delay_seconds: f32, sample_rate: f32) -> Self {
-        let mut flip_polarity = [false; 8];
-        let mut delay_lines = VecDeque::new();
-
-        // Calculate segment size
-        let total_range = max_delay_seconds - min_delay_seconds;
-        let segment_size = total_range / 8.0;
-
-        // Divide range into 8 equal segments, one channel per segment
-        for c in 0..8 {
-            let segment_start = min_delay_seconds + (c as f32 * segment_size);
-            let segment_end = segment_start + segment_size;
-
-            // Convert to microseconds for integer random generation
-            let segment_start_us = (segment_start * 1_000_000.0) as i32;
-            let segment_end_us = (segment_end * 1_000_000.0) as i32;
-
-            let random_delay_us = fastrand::i32(segment_start_us..segment_end_us) as f32;
-            let delay_seconds = random_delay_us / 1_000_000.0; // Convert back to seconds
-
-            let mut delay_line = DelayLine::new(delay_seconds, sample_rate);
-            delay_line.set_delay_seconds(delay_seconds);
-            delay_lines.push_back(delay_line);
-            flip_polarity[c] = fastrand::bool();
-        }
-
-        Self {
-            delay_lines: [
-                delay_lines.pop_front().unwrap(),
-                delay_lines.pop_front().unwrap(),
-                delay_lines.pop_front().unwrap(),
-                delay_lines.pop_front().unwrap(),
-                delay_lines.pop_front().unwrap(),
-                delay_lines.pop_front().unwrap(),
-                delay_lines.pop_front().unwrap(),
-                delay_lines.pop_front().unwrap(),
-            ],
-            flip_polarity,
+        self.size_fade_position += self.size_fade_step;
+        if self.size_fade_position >= 1.0 {
+            self.size = next_size;
+            self.next_size = None;
+            self.start_size_fade();
         }
     }
 
-    pub fn process(&mut self, input: [f32; 8]) -> [f32; 8] {
-        // Delay all channels
-        let mut delayed = [0.0f32; 8];
-        for i in 0..8 {
-            delayed[i] = AudioProcessor::process(&mut self.delay_lines[i], input[i]);
-        }
-
-        // Apply Hadamard transform
-        fast_hadamard_transform_8(&mut delayed);
-
-        // Flip polarities based on random values
-        for i in 0..8 {
-            if self.flip_polarity[i] {
-                delayed[i] = -delayed[i];
-            }
+    pub fn clear(&mut self) {
+        for delay_line in &mut self.delay_lines {
+            delay_line.clear();
         }
-
-        delayed
-    }
-}
-
-pub struct FeedbackStage8 {
-    base_delays: [f32; 8],
-    delay_lines: [DelayLine; 8],
-    lfos: [SineOscillator; 4],
-    feedback: f32,
-    modulation_depth: f32,
-    size: f32,
-}
-
-impl FeedbackStage8 {
-    pub fn new(min_delay_seconds: f32, max_delay_seconds: f32, sample_rate: f32) -> Self {
-        let mut delay_lines = VecDeque::new();
-        let mut base_delays = [0f32; 8];
-
-        // Create 8 delay lines with exponential distribution between min and max
-        for c in 0..8 {
-            let r = (c as f32) / 7.0; // 0 to 1 over 8 channels (0/7 to 7/7)
-            let delay_seconds = min_delay_seconds * (max_delay_seconds / min_delay_seconds).powf(r);
-            delay_lines.push_back(DelayLine::new(delay_seconds * 2.5, sample_rate));
-            base_delays[c] = delay_seconds; // Store in seconds
-        }
-
-        // Create 4 LFOs with different frequencies
-        let lfos = [
-            SineOscillator::new(0.19, sample_rate),
-            SineOscillator::new(0.37, sample_rate),
-            SineOscillator::new(0.29, sample_rate),
-            SineOscillator::new(0.41, sample_rate),
-        ];
-
-        Self {
-            base_delays,
-            delay_lines: [
-                delay_lines.pop_front().unwrap(),
-                delay_lines.pop_front().unwrap(),
-                delay_lines.pop_front().unwrap(),
-                delay_lines.pop_front().unwrap(),
-                delay_lines.pop_front().unwrap(),
-                delay_lines.pop_front().unwrap(),
-                delay_lines.pop_front().unwrap(),
-                delay_lines.pop_front().unwrap(),
-            ],
-            lfos,
-            feedback: 0.5,
-            modulation_depth: 0.0,
-            size: 1.0,
-        }
-    }
-
-    pub fn set_feedback(&mut self, feedback: f32) {
-        self.feedback = feedback.clamp(0.0, 1.0);
-        for i in 0..8 {
-            self.delay_lines[i].set_feedback(self.feedback);
-        }
-    }
-
-    pub fn set_modulation_depth(&mut self, depth: f32) {
-        self.modulation_depth = depth.clamp(0.0, 1.0);
-    }
-
-    pub fn set_size(&mut self, size: f32) {
-        self.size = size.clamp(0.1, 2.0);
     }
 
     pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.size_fade_step = 1.0 / (SIZE_CROSSFADE_TIME * sample_rate);
         for lfo in &mut self.lfos {
             lfo.set_sample_rate(sample_rate);
         }
     }
 
-    pub fn process(&mut self, diffusion: [f32; 8]) -> [f32; 8] {
-        // Generate LFO values (4 LFOs shared across 8 delays)
+    pub fn process(&mut self, diffusion: [f32; N]) -> [f32; N] {
+        // Generate LFO values (N/2 LFOs shared across N delays)
         // Unipolar modulation values
-        let lfo_values = [
-            (self.lfos[0].next_sample() + 1.0) * 0.5,
-            (self.lfos[1].next_sample() + 1.0) * 0.5,
-            (self.lfos[2].next_sample() + 1.0) * 0.5,
-            (self.lfos[3].next_sample() + 1.0) * 0.5,
-        ];
+        let lfo_count = self.lfos.len();
+        let mut lfo_values = [0.0f32; N];
+        for (value, lfo) in lfo_values.iter_mut().zip(self.lfos.iter_mut()) {
+            *value = (lfo.next_sample() + 1.0) * 0.5;
+        }
 
         // Read current echoes from delay lines
-        let mut echoes = [0.0f32; 8];
+        let mut echoes = [0.0f32; N];
 
-        // Apply LFO modulation to delay times (cycle through the 4 LFOs)
-        for i in 0..8 {
-            let lfo_value = lfo_values[i % 4];
+        // Apply LFO modulation to delay times (cycle through the LFOs)
+        for i in 0..N {
+            let lfo_value = lfo_values[i % lfo_count];
             let modulated_delay =
-                self.base_delays[i] * self.size * (1.0 + lfo_value * self.modulation_depth * 0.1);
-            echoes[i] = self.delay_lines[i].read_at(modulated_delay);
+                self.base_delays[i] * (1.0 + lfo_value * self.modulation_depth * 0.1);
+            echoes[i] = self.delay_lines[i].read_at(modulated_delay * self.size);
+
+            // Blend in the taps for the new size while crossfading
+            if let Some(next_size) = self.next_size {
+                let next_echo = self.delay_lines[i].read_at(modulated_delay * next_size);
+                echoes[i] += (next_echo - echoes[i]) * self.size_fade_position;
+            }
         }
+        self.advance_size_fade();
 
         // Apply Householder transform
-        householder_transform_8(&mut echoes);
+        householder_transform(&mut echoes);
 
         // Write diffusion input to delay lines with echoes feedback
-        for i in 0..8 {
+        for i in 0..N {
             self.delay_lines[i].write(diffusion[i], echoes[i]);
         }
 
@@ -415,34 +279,58 @@ impl FeedbackStage8 {
     }
 }
 
-pub struct FDNReverb {
+/// Efficient reverb using 4x4 matrices
+pub type ReverbLite = FdnReverb<4>;
+/// Full 8-channel reverb
+pub type FDNReverb = FdnReverb<8>;
+/// 16-channel reverb for the densest tails, at roughly twice the cost of FDNReverb
+pub type FDNReverbLuxury = FdnReverb<16>;
+
+/// Feedback delay network reverb with N channels (a power of two, at least 4)
+pub struct FdnReverb<const N: usize> {
     // 4 diffusion stages with specified delay times
-    diffusion_stages: [DiffusionStage8; 4],
+    diffusion_stages: [DiffusionStage<N>; 4],
 
     // Feedback stage for late reverberation
-    feedback_stage: FeedbackStage8,
+    feedback_stage: FeedbackStage<N>,
 
     // Gain for AudioNode implementation
     gain: f32,
+
+    sample_rate: f32,
 }
 
 // Design from https://signalsmith-audio.co.uk/writing/2021/lets-write-a-reverb/
-impl FDNReverb {
+impl<const N: usize> FdnReverb<N> {
     pub fn new(sample_rate: f32) -> Self {
-        let feedback_stage = FeedbackStage8::new(0.05, 0.150, sample_rate); // 50-150ms range
+        let feedback_stage = FeedbackStage::new(0.05, 0.150, sample_rate); // 50-150ms range
 
-        // Create 4 diffusion stages with delay times: 10-25ms and 25-50ms
-        let diffusion_stages = [
-            DiffusionStage8::new(0.01, 0.025, sample_rate),
-            DiffusionStage8::new(0.01, 0.025, sample_rate),
-            DiffusionStage8::new(0.025, 0.05, sample_rate),
-            DiffusionStage8::new(0.025, 0.05, sample_rate),
-        ];
+        let diffusion_stages = Self::diffusion_stages(sample_rate, &mut fastrand::Rng::new());
 
         Self {
             diffusion_stages,
             feedback_stage,
             gain: 1.0,
+            sample_rate,
+        }
+    }
+
+    // 4 diffusion stages with delay times: 10-25ms and 25-50ms
+    fn diffusion_stages(sample_rate: f32, rng: &mut fastrand::Rng) -> [DiffusionStage<N>; 4] {
+        [
+            DiffusionStage::with_rng(0.01, 0.025, sample_rate, rng),
+            DiffusionStage::with_rng(0.01, 0.025, sample_rate, rng),
+            DiffusionStage::with_rng(0.025, 0.05, sample_rate, rng),
+            DiffusionStage::with_rng(0.025, 0.05, sample_rate, rng),
+        ]
+    }
+
+    /// Redraw the diffusion delay times and polarities from a seed. Only
+    /// the delay times change, so this doesn't allocate.
+    pub fn set_seed(&mut self, seed: u64) {
+        let mut rng = fastrand::Rng::with_seed(seed);
+        for stage in &mut self.diffusion_stages {
+            stage.reseed(&mut rng);
         }
     }
 
@@ -459,20 +347,35 @@ impl FDNReverb {
     }
 
     pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
         self.feedback_stage.set_sample_rate(sample_rate);
     }
 
     pub fn set_gain(&mut self, gain: f32) {
         self.gain = gain;
     }
+
+    /// Silence the tail, keeping every setting
+    pub fn clear(&mut self) {
+        for stage in &mut self.diffusion_stages {
+            stage.clear();
+        }
+        self.feedback_stage.clear();
+    }
+
+    fn set_params(&mut self, size: f32, feedback: f32, modulation_depth: f32) {
+        self.set_size(size);
+        self.set_feedback(feedback);
+        self.set_modulation_depth(modulation_depth);
+    }
 }
 
-impl StereoAudioProcessor for FDNReverb {
+impl<const N: usize> StereoAudioProcessor for FdnReverb<N> {
     fn process(&mut self, left: f32, right: f32) -> (f32, f32) {
-        // Scale input and distribute to 8-channel array
-        let mut reflections = [0.0f32; 8];
+        // Scale input and feed the outer channels of the array
+        let mut reflections = [0.0f32; N];
         reflections[0] = left * 0.5;
-        reflections[1] = right * 0.5;
+        reflections[N - 1] = right * 0.5;
 
         // Process through 4 diffusion stages
         for stage in &mut self.diffusion_stages {
@@ -485,7 +388,7 @@ impl StereoAudioProcessor for FDNReverb {
         // Mix down to stereo - combine odd/even channels and add reflections
         let mut out_left = 0.0;
         let mut out_right = 0.0;
-        for i in 0..4 {
+        for i in 0..N / 2 {
             out_left += (echoes[i * 2] * 0.7) + (reflections[i * 2] * 0.3);
             out_right += (echoes[i * 2 + 1] * 0.7) + (reflections[i * 2 + 1] * 0.3);
         }
@@ -498,59 +401,260 @@ impl StereoAudioProcessor for FDNReverb {
     }
 }
 
+/// CPU/quality trade-off for QualityReverb
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReverbQuality {
+    Eco,    // ReverbLite, 4 channels
+    Normal, // FDNReverb, 8 channels
+    High,   // FDNReverbLuxury, 16 channels
+}
+
+impl ReverbQuality {
+    pub fn from_index(index: u32) -> Option<Self> {
+        match index {
+            0 => Some(ReverbQuality::Eco),
+            1 => Some(ReverbQuality::Normal),
+            2 => Some(ReverbQuality::High),
+            _ => None,
+        }
+    }
+}
+
+// One of the FDN sizes, so the wrapper can hold any of them
+enum ReverbEngine {
+    Eco(Box<ReverbLite>),
+    Normal(Box<FDNReverb>),
+    High(Box<FDNReverbLuxury>),
+}
+
+impl ReverbEngine {
+    fn new(quality: ReverbQuality, sample_rate: f32) -> Self {
+        match quality {
+            ReverbQuality::Eco => ReverbEngine::Eco(Box::new(ReverbLite::new(sample_rate))),
+            ReverbQuality::Normal => ReverbEngine::Normal(Box::new(FDNReverb::new(sample_rate))),
+            ReverbQuality::High => ReverbEngine::High(Box::new(FDNReverbLuxury::new(sample_rate))),
+        }
+    }
+
+    fn as_processor(&mut self) -> &mut dyn StereoAudioProcessor {
+        match self {
+            ReverbEngine::Eco(reverb) => reverb.as_mut(),
+            ReverbEngine::Normal(reverb) => reverb.as_mut(),
+            ReverbEngine::High(reverb) => reverb.as_mut(),
+        }
+    }
+
+    fn set_params(&mut self, size: f32, feedback: f32, modulation_depth: f32) {
+        match self {
+            ReverbEngine::Eco(reverb) => reverb.set_params(size, feedback, modulation_depth),
+            ReverbEngine::Normal(reverb) => reverb.set_params(size, feedback, modulation_depth),
+            ReverbEngine::High(reverb) => reverb.set_params(size, feedback, modulation_depth),
+        }
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        match self {
+            ReverbEngine::Eco(reverb) => reverb.set_seed(seed),
+            ReverbEngine::Normal(reverb) => reverb.set_seed(seed),
+            ReverbEngine::High(reverb) => reverb.set_seed(seed),
+        }
+    }
+
+    fn clear(&mut self) {
+        match self {
+            ReverbEngine::Eco(reverb) => reverb.clear(),
+            ReverbEngine::Normal(reverb) => reverb.clear(),
+            ReverbEngine::High(reverb) => reverb.clear(),
+        }
+    }
+}
+
+/// Reverb whose FDN size can be switched while playing.
+/// The old engine keeps running and crossfades out under the new one. Every
+/// engine is built up front, so switching never allocates on the audio thread.
+pub struct QualityReverb {
+    // One engine per quality, in ReverbQuality order
+    engines: [ReverbEngine; 3],
+    quality: ReverbQuality,
+    previous: Option<ReverbQuality>,
+    // Picked while a crossfade was running; switched to once it finishes
+    queued: Option<ReverbQuality>,
+
+    // Equal-power crossfade from the previous engine to the current one
+    crossfade_samples: u32,
+    crossfade_remaining: u32,
+
+    // Parameters every engine shares
+    size: f32,
+    feedback: f32,
+    modulation_depth: f32,
+
+    sample_rate: f32,
+}
+
+impl QualityReverb {
+    pub fn new(quality: ReverbQuality, sample_rate: f32) -> Self {
+        let mut reverb = Self {
+            engines: [
+                ReverbEngine::new(ReverbQuality::Eco, sample_rate),
+                ReverbEngine::new(ReverbQuality::Normal, sample_rate),
+                ReverbEngine::new(ReverbQuality::High, sample_rate),
+            ],
+            quality,
+            previous: None,
+            queued: None,
+            crossfade_samples: 0,
+            crossfade_remaining: 0,
+            size: 1.0,
+            feedback: 0.0, // Feedback delays start with no feedback until set
+            modulation_depth: 0.0,
+            sample_rate,
+        };
+        reverb.update_params();
+        reverb.set_sample_rate(sample_rate);
+        reverb
+    }
+
+    /// The quality selected last, even if it's still waiting to fade in
+    pub fn quality(&self) -> ReverbQuality {
+        self.queued.unwrap_or(self.quality)
+    }
+
+    /// Switch to another engine, crossfading over ~300ms. A switch made
+    /// during a crossfade waits for it to finish.
+    pub fn set_quality(&mut self, quality: ReverbQuality) {
+        if self.previous.is_some() {
+            self.queued = (quality != self.quality).then_some(quality);
+            return;
+        }
+        if quality == self.quality {
+            return;
+        }
+
+        self.previous = Some(std::mem::replace(&mut self.quality, quality));
+        self.crossfade_samples = (QUALITY_CROSSFADE_TIME * self.sample_rate).max(1.0) as u32;
+        self.crossfade_remaining = self.crossfade_samples;
+    }
+
+    pub fn set_size(&mut self, size: f32) {
+        self.size = size;
+        self.update_params();
+    }
+
+    pub fn set_feedback(&mut self, feedback: f32) {
+        self.feedback = feedback;
+        self.update_params();
+    }
+
+    pub fn set_modulation_depth(&mut self, depth: f32) {
+        self.modulation_depth = depth;
+        self.update_params();
+    }
+
+    /// Reseed every engine, so the one switched to next is seeded too
+    pub fn set_seed(&mut self, seed: u64) {
+        for engine in &mut self.engines {
+            engine.set_seed(seed);
+        }
+    }
+
+    fn update_params(&mut self) {
+        for engine in &mut self.engines {
+            engine.set_params(self.size, self.feedback, self.modulation_depth);
+        }
+    }
+
+    fn engine(&mut self, quality: ReverbQuality) -> &mut dyn StereoAudioProcessor {
+        self.engines[quality as usize].as_processor()
+    }
+}
+
+impl StereoAudioProcessor for QualityReverb {
+    fn process(&mut self, left: f32, right: f32) -> (f32, f32) {
+        let (current_left, current_right) = self.engine(self.quality).process(left, right);
+
+        let Some(previous) = self.previous else {
+            return (current_left, current_right);
+        };
+
+        let (previous_left, previous_right) = self.engine(previous).process(left, right);
+
+        // 0 -> 1 over the crossfade
+        let position = 1.0 - self.crossfade_remaining as f32 / self.crossfade_samples as f32;
+        let fade_in = (position * PI * 0.5).sin();
+        let fade_out = (position * PI * 0.5).cos();
+
+        self.crossfade_remaining = self.crossfade_remaining.saturating_sub(1);
+        if self.crossfade_remaining == 0 {
+            // Silent for when it's picked again, rather than replaying an old tail
+            self.engines[previous as usize].clear();
+            self.previous = None;
+            if let Some(queued) = self.queued.take() {
+                self.set_quality(queued);
+            }
+        }
+
+        (
+            current_left * fade_in + previous_left * fade_out,
+            current_right * fade_in + previous_right * fade_out,
+        )
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        for engine in &mut self.engines {
+            engine.as_processor().set_sample_rate(sample_rate);
+        }
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::audio::TWO_PI;
+
+    #[test]
+    fn test_fdn_reverb_basic_operation() {
+        let sample_rate = 44100.0;
+        let mut reverb = FDNReverb::new(sample_rate);
+        reverb.set_seed(0);
+        reverb.set_size(1.0);
+
+        // Silence in, silence out
+        assert_eq!(
+            StereoAudioProcessor::process(&mut reverb, 0.0, 0.0),
+            (0.0, 0.0)
+        );
+
+        // Impulse, then the tail
+        let _impulse = StereoAudioProcessor::process(&mut reverb, 1.0, 0.5);
+        let mut max_amp_l = 0.0f32;
+        let mut max_amp_r = 0.0f32;
+        for _ in 0..(0.5 * sample_rate) as usize {
+            let (out_l, out_r) = StereoAudioProcessor::process(&mut reverb, 0.0, 0.0);
+            max_amp_l = max_amp_l.max(out_l.abs());
+            max_amp_r = max_amp_r.max(out_r.abs());
+        }
+
+        println!(
+            "FDNReverb test: max tail amplitude L={}, R={}",
+            max_amp_l, max_amp_r
+        );
+
+        // Reverb should be stable
+        assert!(max_amp_l < 1.0, "FDNReverb left should remain stable");
+        assert!(max_amp_r < 1.0, "FDNReverb right should remain stable");
 
-    // This test mysteriously fails, even though it's identical to the next one
-    // #[test]
-    // fn test_fdn_reverb_basic_operation() {
-    //     let sample_rate = 44100.0;
-    //     let mut reverb = FDNReverb::new(sample_rate);
-    //     reverb.set_size(1.0);
-
-    //     // Test silence
-    //     // let (out_l, out_r) = reverb.process_stereo(0.0, 0.0);
-    //     // assert_eq!(out_l, 0.0);
-    //     // assert_eq!(out_r, 0.0);
-
-    //     // Test impulse response
-    //     let (impulse_l, impulse_r) = reverb.process_stereo(1.0, 0.5);
-
-    //     let mut max_amp_l = 0.0f32;
-    //     let mut max_amp_r = 0.0f32;
-    //     let mut outputs_l = Vec::new();
-    //     let mut outputs_r = Vec::new();
-
-    //     // Process silence to hear reverb tail
-    //     for _ in 0..(0.5 * sample_rate) as usize {
-    //         let (out_l, out_r) = reverb.process_stereo(0.0, 0.0);
-    //         outputs_l.push(out_l);
-    //         outputs_r.push(out_r);
-    //         max_amp_l = max_amp_l.max(out_l.abs());
-    //         max_amp_r = max_amp_r.max(out_r.abs());
-    //     }
-
-    //     println!(
-    //         "FDNReverb test: impulse output L={}, R={}",
-    //         impulse_l, impulse_r
-    //     );
-    //     println!(
-    //         "FDNReverb test: max tail amplitude L={}, R={}",
-    //         max_amp_l, max_amp_r
-    //     );
-
-    //     // Reverb should be stable
-    //     assert!(max_amp_l < 1.0, "FDNReverb left should remain stable");
-    //     assert!(max_amp_r < 1.0, "FDNReverb right should remain stable");
-
-    //     // Should produce reverb tail
-    //     let has_tail_l = outputs_l.iter().any(|&x| x.abs() > 0.1);
-    //     let has_tail_r = outputs_r.iter().any(|&x| x.abs() > 0.1);
-    //     assert!(has_tail_l, "FDNReverb should produce left reverb tail");
-    //     assert!(has_tail_r, "FDNReverb should produce right reverb tail");
-    // }
+        // Should produce reverb tail
+        assert!(
+            max_amp_l > 0.01,
+            "FDNReverb should produce left reverb tail"
+        );
+        assert!(
+            max_amp_r > 0.01,
+            "FDNReverb should produce right reverb tail"
+        );
+    }
 
     #[test]
     fn test_fdn_reverb_modulation() {
@@ -609,102 +713,112 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_fast_hadamard_transform_8_energy_conservation() {
-        // Test that the energy is conserved when applying the 8x8 transform
-        let test_inputs = [
-            [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0],
-            [0.5; 8],
-            [1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0],
-        ];
-
-        for test_input in test_inputs.iter() {
-            let mut signals = *test_input;
+    // Random vectors from a fixed seed for the transform property tests
+    fn random_signals<const N: usize>(rng: &mut fastrand::Rng) -> [f32; N] {
+        std::array::from_fn(|_| rng.f32() * 2.0 - 1.0)
+    }
 
-            // Calculate input energy
-            let input_energy: f32 = signals.iter().map(|x| x * x).sum();
+    fn energy(signals: &[f32]) -> f32 {
+        signals.iter().map(|x| x * x).sum()
+    }
 
-            // Apply transform
-            fast_hadamard_transform_8(&mut signals);
+    fn check_hadamard_properties<const N: usize>() {
+        let mut rng = fastrand::Rng::with_seed(N as u64);
+        let scale = 1.0 / (N as f32).sqrt();
+
+        // Matches the Sylvester construction: H[i][j] = (-1)^popcount(i & j) / sqrt(N)
+        for j in 0..N {
+            let mut column = [0.0f32; N];
+            column[j] = 1.0;
+            fast_hadamard_transform(&mut column);
+            for (i, &value) in column.iter().enumerate() {
+                let sign = 1.0 - 2.0 * ((i & j).count_ones() % 2) as f32;
+                assert!(
+                    (value - sign * scale).abs() < 1e-6,
+                    "H{}[{}][{}] = {}, expected {}",
+                    N,
+                    i,
+                    j,
+                    value,
+                    sign * scale
+                );
+            }
+        }
 
-            // Calculate output energy
-            let output_energy: f32 = signals.iter().map(|x| x * x).sum();
+        for _ in 0..100 {
+            let original: [f32; N] = random_signals(&mut rng);
+            let mut signals = original;
 
+            // Energy is conserved
+            fast_hadamard_transform(&mut signals);
             assert!(
-                (input_energy - output_energy).abs() < 1e-4,
-                "Energy not conserved: input={}, output={}, diff={}",
-                input_energy,
-                output_energy,
-                (input_energy - output_energy).abs()
+                (energy(&original) - energy(&signals)).abs() < 1e-4,
+                "H{} energy not conserved: input={}, output={}",
+                N,
+                energy(&original),
+                energy(&signals)
             );
+
+            // And the normalized transform is its own inverse
+            fast_hadamard_transform(&mut signals);
+            for (i, (&result, &orig)) in signals.iter().zip(original.iter()).enumerate() {
+                assert!(
+                    (result - orig).abs() < 1e-5,
+                    "H{} not self-inverse at index {}: expected {}, got {}",
+                    N,
+                    i,
+                    orig,
+                    result
+                );
+            }
         }
     }
 
-    #[test]
-    fn test_fast_hadamard_transform_8_invertability() {
-        let original = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
-        let mut signals = original;
+    fn check_householder_properties<const N: usize>() {
+        let mut rng = fastrand::Rng::with_seed(N as u64);
 
-        // Apply transform twice
-        fast_hadamard_transform_8(&mut signals);
-        fast_hadamard_transform_8(&mut signals);
+        for _ in 0..100 {
+            let original: [f32; N] = random_signals(&mut rng);
+            let mut signals = original;
 
-        for (i, (&result, &orig)) in signals.iter().zip(original.iter()).enumerate() {
+            // The reflection is orthogonal...
+            householder_transform(&mut signals);
             assert!(
-                (result - orig).abs() < 1e-6,
-                "Invertibility test failed at index {}: expected {}, got {}",
-                i,
-                orig,
-                result
+                (energy(&original) - energy(&signals)).abs() < 1e-4,
+                "Householder{} energy not conserved: input={}, output={}",
+                N,
+                energy(&original),
+                energy(&signals)
             );
+
+            // ...and its own inverse
+            householder_transform(&mut signals);
+            for (&result, &orig) in signals.iter().zip(original.iter()) {
+                assert!((result - orig).abs() < 1e-5);
+            }
         }
+
+        // Unlike the Hadamard matrix, every output mixes in every input
+        let mut impulse = [0.0f32; N];
+        impulse[0] = 1.0;
+        householder_transform(&mut impulse);
+        assert!(impulse.iter().all(|&x| x != 0.0));
     }
 
     #[test]
-    fn test_fast_hadamard_transform_4_energy_conservation() {
-        // Test that the energy is conserved when applying the 4x4 transform
-        let test_inputs = [[1.0, 2.0, 3.0, 4.0], [0.5; 4], [1.0, 0.0, 1.0, 0.0]];
-
-        for test_input in test_inputs.iter() {
-            let mut signals = *test_input;
-
-            // Calculate input energy
-            let input_energy: f32 = signals.iter().map(|x| x * x).sum();
-
-            // Apply transform
-            fast_hadamard_transform_4(&mut signals);
-
-            // Calculate output energy
-            let output_energy: f32 = signals.iter().map(|x| x * x).sum();
-
-            assert!(
-                (input_energy - output_energy).abs() < 1e-4,
-                "Energy not conserved: input={}, output={}, diff={}",
-                input_energy,
-                output_energy,
-                (input_energy - output_energy).abs()
-            );
-        }
+    fn test_fast_hadamard_transform_properties() {
+        check_hadamard_properties::<2>();
+        check_hadamard_properties::<4>();
+        check_hadamard_properties::<8>();
+        check_hadamard_properties::<16>();
+        check_hadamard_properties::<32>();
     }
 
     #[test]
-    fn test_fast_hadamard_transform_4_invertability() {
-        let original = [1.0, 2.0, 3.0, 4.0];
-        let mut signals = original;
-
-        // Apply transform twice
-        fast_hadamard_transform_4(&mut signals);
-        fast_hadamard_transform_4(&mut signals);
-
-        for (i, (&result, &orig)) in signals.iter().zip(original.iter()).enumerate() {
-            assert!(
-                (result - orig).abs() < 1e-6,
-                "Invertibility test failed at index {}: expected {}, got {}",
-                i,
-                orig,
-                result
-            );
-        }
+    fn test_householder_transform_properties() {
+        check_householder_properties::<4>();
+        check_householder_properties::<8>();
+        check_householder_properties::<16>();
     }
 
     #[test]
@@ -797,92 +911,149 @@ mod tests {
             right_energy
         );
     }
-}
-
-pub struct ReverbLite {
-    // 4 diffusion stages with specified delay times (4x4 instead of 8x8)
-    diffusion_stages: [DiffusionStage4; 4],
-
-    // Feedback stage for late reverberation (4x4 instead of 8x8)
-    feedback_stage: FeedbackStage4,
-
-    // Gain for AudioNode implementation
-    gain: f32,
-}
 
-// ReverbLite: Efficient FDN reverb using 4x4 matrices instead of 8x8
-// Design follows same pattern as FDNReverb but with half the channels
-impl ReverbLite {
-    pub fn new(sample_rate: f32) -> Self {
-        let feedback_stage = FeedbackStage4::new(0.05, 0.150, sample_rate); // 50-150ms range
+    #[test]
+    fn test_fdn_reverb_luxury_is_stable() {
+        let sample_rate = 44100.0;
+        let mut reverb = FDNReverbLuxury::new(sample_rate);
+        reverb.set_feedback(0.7);
+        reverb.set_modulation_depth(1.0);
 
-        // Create 4 diffusion stages with delay times: 10-25ms and 25-50ms
-        // Same layout as full FDN version
-        let diffusion_stages = [
-            DiffusionStage4::new(0.01, 0.025, sample_rate),
-            DiffusionStage4::new(0.01, 0.025, sample_rate),
-            DiffusionStage4::new(0.025, 0.05, sample_rate),
-            DiffusionStage4::new(0.025, 0.05, sample_rate),
-        ];
+        let _impulse = StereoAudioProcessor::process(&mut reverb, 1.0, 0.5);
 
-        Self {
-            diffusion_stages,
-            feedback_stage,
-            gain: 1.0,
+        let mut max_amp = 0.0f32;
+        let mut has_tail = (false, false);
+        for _ in 0..sample_rate as usize {
+            let (out_l, out_r) = StereoAudioProcessor::process(&mut reverb, 0.0, 0.0);
+            max_amp = max_amp.max(out_l.abs()).max(out_r.abs());
+            has_tail.0 |= out_l.abs() > 0.01;
+            has_tail.1 |= out_r.abs() > 0.01;
         }
-    }
-
-    pub fn set_feedback(&mut self, feedback: f32) {
-        self.feedback_stage.set_feedback(feedback);
-    }
 
-    pub fn set_size(&mut self, size: f32) {
-        self.feedback_stage.set_size(size);
+        assert!(max_amp < 4.0, "16-channel reverb should remain stable");
+        assert!(
+            has_tail.0 && has_tail.1,
+            "Should produce a tail on both sides"
+        );
     }
 
-    pub fn set_modulation_depth(&mut self, depth: f32) {
-        self.feedback_stage.set_modulation_depth(depth);
-    }
+    #[test]
+    fn test_feedback_stage_size_change_is_smooth() {
+        let sample_rate = 44100.0;
+        let mut stage = FeedbackStage::<4>::new(0.05, 0.150, sample_rate);
+        stage.set_feedback(0.0);
+        stage.set_size(0.5);
+
+        let mut phase = 0.0f32;
+        let mut next_input = || {
+            phase += 220.0 / sample_rate;
+            [(phase * TWO_PI).sin(); 4]
+        };
+
+        // Fill the delays, tracking the largest sample-to-sample step
+        let mut last = stage.process(next_input())[0];
+        let mut steady_step = 0.0f32;
+        for _ in 0..(0.5 * sample_rate) as usize {
+            let out = stage.process(next_input())[0];
+            steady_step = steady_step.max((out - last).abs());
+            last = out;
+        }
 
-    pub fn set_sample_rate(&mut self, sample_rate: f32) {
-        self.feedback_stage.set_sample_rate(sample_rate);
-    }
+        // Jumping the size must not produce a larger step than the signal itself
+        stage.set_size(2.0);
+        let mut change_step = 0.0f32;
+        for _ in 0..(0.1 * sample_rate) as usize {
+            let out = stage.process(next_input())[0];
+            change_step = change_step.max((out - last).abs());
+            last = out;
+        }
 
-    pub fn set_gain(&mut self, gain: f32) {
-        self.gain = gain;
+        assert!(
+            change_step < steady_step * 1.5,
+            "Size change clicked: step {} vs steady {}",
+            change_step,
+            steady_step
+        );
     }
-}
 
-impl StereoAudioProcessor for ReverbLite {
-    fn process(&mut self, left: f32, right: f32) -> (f32, f32) {
-        // Scale input and distribute to 4-channel array
-        let mut reflections = [0.0f32; 4];
-        reflections[0] = left * 0.5;
-        reflections[3] = right * 0.5;
+    #[test]
+    fn test_quality_reverb_crossfades_between_engines() {
+        let sample_rate = 44100.0;
+        let mut reverb = QualityReverb::new(ReverbQuality::Eco, sample_rate);
+        reverb.set_feedback(0.6);
 
-        // Process through 4 diffusion stages (same as full FDN)
-        for stage in &mut self.diffusion_stages {
-            reflections = stage.process(reflections);
+        // Build a tail, then switch mid-tail
+        let _impulse = StereoAudioProcessor::process(&mut reverb, 1.0, 1.0);
+        for _ in 0..4410 {
+            StereoAudioProcessor::process(&mut reverb, 0.0, 0.0);
+        }
+        reverb.set_quality(ReverbQuality::High);
+        assert_eq!(reverb.quality(), ReverbQuality::High);
+        assert!(reverb.previous.is_some());
+
+        // The old tail fades out without a jump
+        let mut last = StereoAudioProcessor::process(&mut reverb, 0.0, 0.0).0;
+        let mut max_step = 0.0f32;
+        for _ in 0..(QUALITY_CROSSFADE_TIME * sample_rate) as usize {
+            let (out_l, _) = StereoAudioProcessor::process(&mut reverb, 0.0, 0.0);
+            max_step = max_step.max((out_l - last).abs());
+            last = out_l;
         }
+        assert!(max_step < 0.1, "Crossfade jumped by {}", max_step);
+        assert!(reverb.previous.is_none(), "Crossfade should have finished");
+    }
 
-        // Process through feedback stage
-        let echoes = self.feedback_stage.process(reflections);
+    #[test]
+    fn test_quality_switch_during_a_crossfade_waits_for_it() {
+        let sample_rate = 44100.0;
+        let crossfade = (QUALITY_CROSSFADE_TIME * sample_rate) as usize;
+        let mut reverb = QualityReverb::new(ReverbQuality::Eco, sample_rate);
+        reverb.set_feedback(0.6);
+        StereoAudioProcessor::process(&mut reverb, 1.0, 1.0);
+
+        reverb.set_quality(ReverbQuality::High);
+        for _ in 0..crossfade / 2 {
+            StereoAudioProcessor::process(&mut reverb, 0.0, 0.0);
+        }
+        reverb.set_quality(ReverbQuality::Normal);
+        assert_eq!(reverb.quality(), ReverbQuality::Normal);
+        assert_eq!(reverb.previous, Some(ReverbQuality::Eco));
 
-        // Mix down to stereo - combine pairs of channels and add reflections
-        let mut out_left = 0.0;
-        let mut out_right = 0.0;
-        for i in 0..2 {
-            out_left += (echoes[i * 2] * 0.7) + (reflections[i * 2] * 0.3);
-            out_right += (echoes[i * 2 + 1] * 0.7) + (reflections[i * 2 + 1] * 0.3);
+        // The first crossfade finishes, then the queued one starts
+        for _ in 0..crossfade {
+            StereoAudioProcessor::process(&mut reverb, 0.0, 0.0);
         }
+        assert_eq!(reverb.quality, ReverbQuality::Normal);
+        assert_eq!(reverb.previous, Some(ReverbQuality::High));
+        for _ in 0..crossfade {
+            StereoAudioProcessor::process(&mut reverb, 0.0, 0.0);
+        }
+        assert!(reverb.previous.is_none());
 
-        (out_left, out_right)
+        // Engines that faded out were cleared, so picking one again is silent
+        reverb.set_quality(ReverbQuality::Eco);
+        let (out_l, out_r) = reverb.engine(ReverbQuality::Eco).process(0.0, 0.0);
+        assert_eq!((out_l, out_r), (0.0, 0.0));
     }
 
-    fn set_sample_rate(&mut self, sample_rate: f32) {
-        self.set_sample_rate(sample_rate);
+    #[test]
+    fn test_reverb_lite_seed_reproduces_output() {
+        let sample_rate = 44100.0;
+        let render = |seed: u64| {
+            let mut reverb = ReverbLite::new(sample_rate);
+            reverb.set_seed(seed);
+            let mut output = vec![StereoAudioProcessor::process(&mut reverb, 1.0, 0.0)];
+            for _ in 0..4410 {
+                output.push(StereoAudioProcessor::process(&mut reverb, 0.0, 0.0));
+            }
+            output
+        };
+
+        assert_eq!(
+            render(42),
+            render(42),
+            "Same seed should give the same tail"
+        );
+        assert_ne!(render(42), render(43), "Different seeds should differ");
     }
 }
-
-
-
@@ -0,0 +1,318 @@
+use crate::audio::slicing;
+use crate::audio::PI;
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// Zero crossings of the sinc kernel on each side of an output sample
+const SINC_ZERO_CROSSINGS: usize = 16;
+
+/// Decoded stereo audio, shared between the library and every voice playing it
+pub struct Sample {
+    pub name: String,
+    pub sample_rate: f32,
+    left: Vec<f32>,
+    right: Vec<f32>,
+}
+
+/// Voices hold a reference, so swapping samples on the audio thread only
+/// drops a count while the library still owns the data
+pub type SampleRef = Arc<Sample>;
+
+impl Sample {
+    /// Build from interleaved samples. Mono is copied to both sides and
+    /// channels past the first two are ignored.
+    pub fn from_interleaved(
+        name: &str,
+        samples: &[f32],
+        channels: usize,
+        sample_rate: f32,
+    ) -> Result<Self, String> {
+        if channels == 0 {
+            return Err(format!("Sample '{}' has no channels", name));
+        }
+        if sample_rate <= 0.0 {
+            return Err(format!("Sample '{}' has no sample rate", name));
+        }
+
+        let frames = samples.chunks_exact(channels);
+        let left: Vec<f32> = frames.clone().map(|frame| frame[0]).collect();
+        let right: Vec<f32> = frames.map(|frame| frame[channels.min(2) - 1]).collect();
+        if left.is_empty() {
+            return Err(format!("Sample '{}' is empty", name));
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            sample_rate,
+            left,
+            right,
+        })
+    }
+
+    /// Length in frames
+    pub fn len(&self) -> usize {
+        self.left.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.left.is_empty()
+    }
+
+    pub fn duration_seconds(&self) -> f32 {
+        self.len() as f32 / self.sample_rate
+    }
+
+    /// Stereo frame at an index, silent past either end
+    pub fn frame(&self, index: usize) -> (f32, f32) {
+        match (self.left.get(index), self.right.get(index)) {
+            (Some(&left), Some(&right)) => (left, right),
+            _ => (0.0, 0.0),
+        }
+    }
+
+    /// Linearly interpolated frame at a fractional position, silent outside
+    /// the range being played
+    pub fn interpolated_frame(&self, frames: &Range<usize>, position: f64) -> (f32, f32) {
+        if position < frames.start as f64 {
+            return (0.0, 0.0);
+        }
+        let index = position as usize;
+        if index >= frames.end {
+            return (0.0, 0.0);
+        }
+        let fraction = (position - index as f64) as f32;
+        let (left_a, right_a) = self.frame(index);
+        let (left_b, right_b) = if index + 1 < frames.end {
+            self.frame(index + 1)
+        } else {
+            (0.0, 0.0)
+        };
+        (
+            left_a + (left_b - left_a) * fraction,
+            right_a + (right_b - right_a) * fraction,
+        )
+    }
+
+    /// Mono mix of the sample, for analysis
+    pub fn mono(&self) -> Vec<f32> {
+        self.left
+            .iter()
+            .zip(&self.right)
+            .map(|(left, right)| 0.5 * (left + right))
+            .collect()
+    }
+
+    /// A copy converted to another rate with the windowed-sinc resampler
+    pub fn resampled(&self, sample_rate: f32) -> Self {
+        if sample_rate == self.sample_rate {
+            return Self {
+                name: self.name.clone(),
+                sample_rate,
+                left: self.left.clone(),
+                right: self.right.clone(),
+            };
+        }
+
+        Self {
+            name: self.name.clone(),
+            sample_rate,
+            left: resample(&self.left, self.sample_rate, sample_rate),
+            right: resample(&self.right, self.sample_rate, sample_rate),
+        }
+    }
+}
+
+// The frames would flood command error messages, which print the event
+impl std::fmt::Debug for Sample {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Sample")
+            .field("name", &self.name)
+            .field("sample_rate", &self.sample_rate)
+            .field("frames", &self.len())
+            .finish()
+    }
+}
+
+/// Blackman-windowed sinc interpolation between sample rates. When
+/// downsampling the kernel is widened to low-pass below the new Nyquist.
+pub fn resample(input: &[f32], from_rate: f32, to_rate: f32) -> Vec<f32> {
+    if input.is_empty() || from_rate <= 0.0 || to_rate <= 0.0 {
+        return Vec::new();
+    }
+
+    let ratio = from_rate as f64 / to_rate as f64;
+    let cutoff = (1.0 / ratio).min(1.0);
+    let half_width = SINC_ZERO_CROSSINGS as f64 / cutoff;
+    let output_len = (input.len() as f64 / ratio).round() as usize;
+
+    (0..output_len)
+        .map(|n| {
+            let center = n as f64 * ratio;
+            let first = (center - half_width).ceil().max(0.0) as usize;
+            let last = ((center + half_width).floor() as usize).min(input.len() - 1);
+
+            let mut sum = 0.0;
+            for (k, &sample) in input.iter().enumerate().take(last + 1).skip(first) {
+                let distance = center - k as f64;
+                sum += sample as f64
+                    * cutoff
+                    * sinc(distance * cutoff)
+                    * blackman(distance / half_width);
+            }
+            sum as f32
+        })
+        .collect()
+}
+
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-9 {
+        1.0
+    } else {
+        let x = x * PI as f64;
+        x.sin() / x
+    }
+}
+
+/// Blackman window over -1..1
+fn blackman(x: f64) -> f64 {
+    if x.abs() >= 1.0 {
+        return 0.0;
+    }
+    let phase = PI as f64 * (x + 1.0);
+    0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos()
+}
+
+/// Imported samples by name, already converted to the engine rate, with the
+/// frames each sample is sliced at
+pub struct SampleLibrary {
+    samples: BTreeMap<String, SampleRef>,
+    slice_markers: BTreeMap<String, Vec<usize>>,
+}
+
+impl SampleLibrary {
+    pub fn new() -> Self {
+        Self {
+            samples: BTreeMap::new(),
+            slice_markers: BTreeMap::new(),
+        }
+    }
+
+    /// Add a sample, replacing any with the same name along with its slices
+    pub fn insert(&mut self, sample: Sample) -> SampleRef {
+        let sample = Arc::new(sample);
+        self.slice_markers.remove(&sample.name);
+        self.samples
+            .insert(sample.name.clone(), Arc::clone(&sample));
+        sample
+    }
+
+    /// Cut a sample at its strongest transients, replacing any markers it had
+    pub fn detect_slices(&mut self, name: &str, max_slices: usize) -> Result<&[usize], String> {
+        let sample = self.get_or_err(name)?;
+        let markers = slicing::detect_slices(&sample.mono(), sample.sample_rate, max_slices);
+        self.slice_markers.insert(name.to_string(), markers);
+        Ok(self.slice_markers(name))
+    }
+
+    /// Replace a sample's slice markers, e.g. after adjusting them by hand
+    pub fn set_slice_markers(&mut self, name: &str, markers: Vec<usize>) -> Result<(), String> {
+        let sample = self.get_or_err(name)?;
+        slicing::validate_markers(&markers, sample.len())?;
+        self.slice_markers.insert(name.to_string(), markers);
+        Ok(())
+    }
+
+    /// Start frames of a sample's slices, empty if it hasn't been sliced
+    pub fn slice_markers(&self, name: &str) -> &[usize] {
+        self.slice_markers.get(name).map_or(&[], Vec::as_slice)
+    }
+
+    fn get_or_err(&self, name: &str) -> Result<SampleRef, String> {
+        self.get(name)
+            .ok_or_else(|| format!("No imported sample named '{}'", name))
+    }
+
+    pub fn get(&self, name: &str) -> Option<SampleRef> {
+        self.samples.get(name).cloned()
+    }
+
+    /// Every sample, sorted by name
+    pub fn samples(&self) -> Vec<SampleRef> {
+        self.samples.values().cloned().collect()
+    }
+}
+
+impl Default for SampleLibrary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::TWO_PI;
+
+    fn sine(frequency: f32, sample_rate: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| (TWO_PI * frequency * i as f32 / sample_rate).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_resample_keeps_frequency_and_level() {
+        let input = sine(1000.0, 44100.0, 4410);
+        let output = resample(&input, 44100.0, 48000.0);
+        assert_eq!(output.len(), 4800);
+
+        // Away from the edges the output matches a sine rendered at the new rate
+        let expected = sine(1000.0, 48000.0, 4800);
+        for i in 100..4700 {
+            assert!(
+                (output[i] - expected[i]).abs() < 0.01,
+                "sample {} was {}, expected {}",
+                i,
+                output[i],
+                expected[i]
+            );
+        }
+    }
+
+    #[test]
+    fn test_downsampling_filters_above_new_nyquist() {
+        // 20 kHz can't exist at 22.05 kHz, so it must be removed, not aliased
+        let input = sine(20000.0, 44100.0, 4410);
+        let output = resample(&input, 44100.0, 22050.0);
+        let peak = output[50..2150]
+            .iter()
+            .fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!(peak < 0.05, "aliased peak was {}", peak);
+    }
+
+    #[test]
+    fn test_sample_channels_and_library() {
+        let mono = Sample::from_interleaved("mono", &[0.5, -0.5], 1, 48000.0).unwrap();
+        assert_eq!(mono.frame(1), (-0.5, -0.5));
+        let quad = Sample::from_interleaved("quad", &[0.1, 0.2, 0.3, 0.4], 4, 48000.0).unwrap();
+        assert_eq!(quad.frame(0), (0.1, 0.2));
+        assert_eq!(quad.frame(1), (0.0, 0.0));
+        assert!(Sample::from_interleaved("empty", &[], 2, 48000.0).is_err());
+
+        let mut library = SampleLibrary::new();
+        let imported = library.insert(mono.resampled(24000.0));
+        assert_eq!(imported.sample_rate, 24000.0);
+        assert_eq!(imported.len(), 1);
+        assert!(library.get("mono").is_some());
+        assert_eq!(library.samples().len(), 1);
+
+        // Markers are checked against the sample and dropped when it's replaced
+        let long = Sample::from_interleaved("long", &[0.0; 100], 1, 48000.0).unwrap();
+        library.insert(long);
+        assert!(library.set_slice_markers("long", vec![0, 100]).is_err());
+        assert!(library.set_slice_markers("long", vec![0, 50]).is_ok());
+        assert_eq!(library.slice_markers("long"), &[0, 50]);
+        library.insert(Sample::from_interleaved("long", &[0.0; 10], 1, 48000.0).unwrap());
+        assert!(library.slice_markers("long").is_empty());
+    }
+}
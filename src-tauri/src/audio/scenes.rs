@@ -0,0 +1,140 @@
+use crate::audio::looper::MAX_LOOPS;
+use std::collections::HashMap;
+
+/// Longest crossfade between scenes
+pub const MAX_SCENE_FADE_SECONDS: f32 = 30.0;
+
+/// One system's mixer channel as saved in a scene
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChannelSnapshot {
+    pub gain: f32,
+    pub muted: bool,
+}
+
+impl ChannelSnapshot {
+    /// What the channel actually passes, so mutes can be faded like levels
+    fn level(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.gain
+        }
+    }
+}
+
+/// The performance state of the master section: mixer channels, the master
+/// filter and the looper levels. Which systems are active isn't saved, so
+/// recalling a scene never restarts a sequencer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scene {
+    pub channels: HashMap<String, ChannelSnapshot>,
+    pub filter_position: f32,
+    pub loop_levels: [f32; MAX_LOOPS],
+}
+
+impl Scene {
+    /// The mixer channel partway from this scene to another. Channels fade
+    /// unmuted, so a mute only lands once the fade ends.
+    pub fn channel_towards(&self, to: &Scene, name: &str, amount: f32) -> Option<ChannelSnapshot> {
+        let target = to.channels.get(name)?;
+        if amount >= 1.0 {
+            return Some(*target);
+        }
+        let start = self.channels.get(name).unwrap_or(target).level();
+        Some(ChannelSnapshot {
+            gain: lerp(start, target.level(), amount),
+            muted: false,
+        })
+    }
+
+    pub fn filter_position_towards(&self, to: &Scene, amount: f32) -> f32 {
+        lerp(self.filter_position, to.filter_position, amount)
+    }
+
+    pub fn loop_level_towards(&self, to: &Scene, index: usize, amount: f32) -> f32 {
+        lerp(self.loop_levels[index], to.loop_levels[index], amount)
+    }
+}
+
+fn lerp(from: f32, to: f32, amount: f32) -> f32 {
+    from + (to - from) * amount.clamp(0.0, 1.0)
+}
+
+/// A crossfade from the state at recall time to a saved scene
+pub struct SceneFade {
+    pub from: Scene,
+    pub to: Scene,
+    frames: usize,
+    elapsed: usize,
+}
+
+impl SceneFade {
+    pub fn new(from: Scene, to: Scene, seconds: f32, sample_rate: f32) -> Self {
+        let frames = (seconds.clamp(0.0, MAX_SCENE_FADE_SECONDS) * sample_rate) as usize;
+        Self {
+            from,
+            to,
+            frames: frames.max(1),
+            elapsed: 0,
+        }
+    }
+
+    /// Step one frame, returning how far through the fade it now is
+    pub fn advance(&mut self) -> f32 {
+        self.elapsed = (self.elapsed + 1).min(self.frames);
+        self.elapsed as f32 / self.frames as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scene(gain: f32, muted: bool, filter_position: f32) -> Scene {
+        Scene {
+            channels: HashMap::from([("drums".to_string(), ChannelSnapshot { gain, muted })]),
+            filter_position,
+            loop_levels: [1.0; MAX_LOOPS],
+        }
+    }
+
+    #[test]
+    fn test_fading_to_a_mute_lands_the_mute_at_the_end() {
+        let from = scene(1.0, false, -1.0);
+        let to = scene(0.5, true, 1.0);
+        let mut fade = SceneFade::new(from.clone(), to.clone(), 1.0, 4.0);
+
+        let amount = fade.advance();
+        assert_eq!(amount, 0.25);
+        let channel = fade
+            .from
+            .channel_towards(&fade.to, "drums", amount)
+            .unwrap();
+        assert_eq!(
+            channel,
+            ChannelSnapshot {
+                gain: 0.75,
+                muted: false
+            }
+        );
+        assert_eq!(from.filter_position_towards(&to, amount), -0.5);
+
+        for _ in 0..3 {
+            fade.advance();
+        }
+        assert_eq!(fade.advance(), 1.0);
+        let channel = from.channel_towards(&to, "drums", 1.0).unwrap();
+        assert_eq!(
+            channel,
+            ChannelSnapshot {
+                gain: 0.5,
+                muted: true
+            }
+        );
+
+        // Muted channels fade up from silence
+        let channel = to.channel_towards(&from, "drums", 0.5).unwrap();
+        assert_eq!(channel.gain, 0.5);
+        assert!(from.channel_towards(&to, "ambient", 0.5).is_none());
+    }
+}
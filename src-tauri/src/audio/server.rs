@@ -1,14 +1,76 @@
-use crate::audio::AudioSystem;
+use crate::audio::evolve::Evolver;
+use crate::audio::filters::DJFilter;
+use crate::audio::looper::{Looper, MAX_LOOPS};
+use crate::audio::scenes::{ChannelSnapshot, Scene, SceneFade};
+use crate::audio::stutter::Stutter;
+use crate::audio::tape_stop::TapeStop;
+use crate::audio::{AudioSystem, StereoAudioProcessor};
+use crate::events::{
+    ClientEvent, EvolveEvent, LooperEvent, MasterEvent, MasterFilterEvent, MasterSystemEvent,
+    MixerEvent, SceneEvent, ServerEvent, StutterEvent, TapeStopEvent,
+};
+use crate::sequencing::TransportClock;
 use std::collections::HashMap;
 
-/// Global audio server that manages multiple audio systems
+/// Reserved system name for events handled by the server's master section
+pub const MASTER_SYSTEM_NAME: &str = "master";
+
+/// A registered system and its mixer channel
+struct SystemSlot {
+    system: Box<dyn AudioSystem>,
+    /// Inactive systems aren't processed at all
+    active: bool,
+    gain: f32,
+    /// Muted systems keep running (so sequencers stay in time) but are silent
+    muted: bool,
+    /// Mutates the system every few bars while it's active
+    evolver: Evolver,
+}
+
+/// Global audio server that manages multiple audio systems.
+/// Any number of systems can be active at once, summed through per-system
+/// gain and mute.
 pub struct AudioServer {
     /// Registered systems by name
-    systems: HashMap<String, Box<dyn AudioSystem>>,
+    systems: HashMap<String, SystemSlot>,
 
-    /// Currently active system
+    /// Most recently switched-to system
     current_system: Option<String>,
 
+    /// Master DJ filter, the final insert before the output limiter
+    master_filter: DJFilter,
+
+    /// Position of the playing system's transport, which the bar-synced
+    /// master processors follow. Runs on at its last tempo when nothing plays.
+    clock: TransportClock,
+
+    /// Live looper, played back into the mix ahead of the master filter
+    looper: Looper,
+
+    /// System the looper records, or None for the whole mix
+    looper_source: Option<String>,
+
+    /// Momentary beat repeat over the mix and loops
+    stutter: Stutter,
+
+    /// Tape stop and spin-up, after the stutter so it slows the repeats too
+    tape_stop: TapeStop,
+
+    /// Overall output level, after the master filter
+    master_gain: f32,
+
+    /// Saved scenes by name
+    scenes: HashMap<String, Scene>,
+
+    /// Most recently recalled scene
+    current_scene: Option<String>,
+
+    /// Crossfade in progress towards a recalled scene
+    scene_fade: Option<SceneFade>,
+
+    /// Set whenever the scenes change, until taken
+    scenes_changed: bool,
+
     /// Sample rate
     sample_rate: f32,
 }
@@ -18,22 +80,49 @@ impl AudioServer {
         Self {
             systems: HashMap::new(),
             current_system: None,
+            master_filter: DJFilter::new(sample_rate),
+            clock: TransportClock::default(),
+            looper: Looper::new(sample_rate),
+            looper_source: None,
+            stutter: Stutter::new(sample_rate),
+            tape_stop: TapeStop::new(sample_rate),
+            master_gain: 1.0,
+            scenes: HashMap::new(),
+            current_scene: None,
+            scene_fade: None,
+            scenes_changed: false,
             sample_rate,
         }
     }
 
-    /// Add a system to the server
+    /// Add a system to the server, inactive at unity gain
     pub fn add_system(&mut self, name: String, mut system: Box<dyn AudioSystem>) {
         system.set_sample_rate(self.sample_rate);
-        self.systems.insert(name, system);
+        self.systems.insert(
+            name,
+            SystemSlot {
+                system,
+                active: false,
+                gain: 1.0,
+                muted: false,
+                evolver: Evolver::new(),
+            },
+        );
     }
 
-    /// Switch to a different system immediately
+    /// Switch to a different system immediately, deactivating every other system
     pub fn switch_to_system(&mut self, name: &str) -> Result<(), String> {
         if !self.systems.contains_key(name) {
             return Err(format!("System '{}' not found", name));
         }
 
+        for (slot_name, slot) in self.systems.iter_mut() {
+            slot.active = slot_name == name;
+            if slot.active {
+                slot.system.on_activate();
+            }
+        }
+
         // Set new current system
         self.current_system = Some(name.to_string());
         Ok(())
@@ -44,27 +133,111 @@ impl AudioServer {
         self.current_system.as_deref()
     }
 
+    /// Layer a system in or out without touching the others
+    pub fn set_system_active(&mut self, name: &str, active: bool) -> Result<(), String> {
+        let slot = self.slot_mut(name)?;
+        if active && !slot.active {
+            slot.system.on_activate();
+        }
+        slot.active = active;
+        Ok(())
+    }
+
+    pub fn set_system_gain(&mut self, name: &str, gain: f32) -> Result<(), String> {
+        self.slot_mut(name)?.gain = gain.clamp(0.0, 2.0);
+        Ok(())
+    }
+
+    pub fn set_system_muted(&mut self, name: &str, muted: bool) -> Result<(), String> {
+        self.slot_mut(name)?.muted = muted;
+        Ok(())
+    }
+
+    /// Names of the systems currently being processed
+    pub fn get_active_system_names(&self) -> Vec<&str> {
+        self.systems
+            .iter()
+            .filter(|(_, slot)| slot.active)
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    fn slot_mut(&mut self, name: &str) -> Result<&mut SystemSlot, String> {
+        self.systems
+            .get_mut(name)
+            .ok_or_else(|| format!("System '{}' not found", name))
+    }
+
+    /// Follow the current system's transport, or else any active system
+    /// that's playing
+    fn advance_clock(&mut self) {
+        let current = self
+            .current_system
+            .as_ref()
+            .and_then(|name| self.systems.get(name));
+        let reported = current
+            .into_iter()
+            .chain(self.systems.values())
+            .filter(|slot| slot.active)
+            .find_map(|slot| slot.system.transport_clock());
+        match reported {
+            Some(clock) => self.clock = clock,
+            None => self.clock.advance(self.sample_rate),
+        }
+    }
+
     /// Process a single stereo sample
     pub fn next_sample(&mut self) -> (f32, f32) {
-        // Process current system if one is selected
-        if let Some(current_name) = &self.current_system {
-            if let Some(current_system) = self.systems.get_mut(current_name) {
-                current_system.next_sample()
-            } else {
-                (0.0, 0.0)
-            }
-        } else {
-            (0.0, 0.0)
+        self.advance_scene_fade();
+        self.advance_clock();
+
+        // Sum every active system through its mixer channel
+        let mut left = 0.0;
+        let mut right = 0.0;
+        let mut source = (0.0, 0.0);
+        for (name, slot) in self.systems.iter_mut() {
+            if !slot.active {
+                continue;
+            }
+            if let Some(mutations) = slot.evolver.tick(&self.clock) {
+                slot.system.evolve(mutations);
+            }
+            let (system_left, system_right) = slot.system.next_sample();
+            if !slot.muted {
+                let channel = (system_left * slot.gain, system_right * slot.gain);
+                left += channel.0;
+                right += channel.1;
+                if self.looper_source.as_ref() == Some(name) {
+                    source = channel;
+                }
+            }
         }
-    }
 
+        // The looper hears the mix before its own playback, so overdubs
+        // don't record the loops back into themselves
+        let looper_input = match self.looper_source {
+            Some(_) => source,
+            None => (left, right),
+        };
+        let (loop_left, loop_right) = self.looper.process(looper_input, &self.clock);
+
+        // Master section
+        let (left, right) = self.stutter.process(left + loop_left, right + loop_right);
+        let (left, right) = self.tape_stop.process(left, right);
+        let (left, right) = self.master_filter.process(left, right);
+        (left * self.master_gain, right * self.master_gain)
+    }
 
     /// Set sample rate for all systems
     pub fn set_sample_rate(&mut self, sample_rate: f32) {
         self.sample_rate = sample_rate;
+        self.master_filter.set_sample_rate(sample_rate);
+        self.looper.set_sample_rate(sample_rate);
+        self.stutter.set_sample_rate(sample_rate);
+        self.tape_stop.set_sample_rate(sample_rate);
 
-        for system in self.systems.values_mut() {
-            system.set_sample_rate(sample_rate);
+        for slot in self.systems.values_mut() {
+            slot.system.set_sample_rate(sample_rate);
         }
     }
 
@@ -73,12 +246,399 @@ impl AudioServer {
         self.systems.keys().map(|s| s.as_str()).collect()
     }
 
-    /// Send a client event to a specific system
-    pub fn send_client_event(&mut self, event: &crate::events::ClientEvent) -> Result<(), String> {
-        if let Some(system) = self.systems.get_mut(&event.system) {
-            system.handle_client_event(event)
-        } else {
-            Err(format!("System '{}' not found", &event.system))
+    /// Send a client event to the system it names
+    pub fn send_client_event(&mut self, event: ClientEvent) -> Result<(), String> {
+        if let ClientEvent::Master(event) = event {
+            return self.handle_master_event(event);
+        }
+
+        let slot = self
+            .systems
+            .get_mut(event.system_name())
+            .ok_or_else(|| format!("System '{}' not found", event.system_name()))?;
+        slot.system.handle_client_event(event)
+    }
+
+    /// Handle events addressed to the master section
+    fn handle_master_event(&mut self, event: MasterEvent) -> Result<(), String> {
+        match event {
+            MasterEvent::Filter(event) => self.handle_master_filter_event(event),
+            MasterEvent::System(event) => self.handle_master_system_event(event),
+            MasterEvent::Mixer(event) => return self.handle_master_mixer_event(event),
+            MasterEvent::Looper(event) => return self.handle_looper_event(event),
+            MasterEvent::Scene(event) => return self.handle_scene_event(event),
+            MasterEvent::Stutter(event) => return self.handle_stutter_event(event),
+            MasterEvent::TapeStop(event) => self.handle_tape_stop_event(event),
+            MasterEvent::Evolve(event) => return self.handle_evolve_event(event),
+        }
+        Ok(())
+    }
+
+    /// Reseed every system so a session can be reproduced
+    pub fn set_seed(&mut self, seed: u64) {
+        for slot in self.systems.values_mut() {
+            slot.system.set_seed(seed);
+        }
+    }
+
+    pub fn set_master_gain(&mut self, gain: f32) {
+        self.master_gain = gain.clamp(0.0, 2.0);
+    }
+
+    fn handle_master_system_event(&mut self, event: MasterSystemEvent) {
+        match event {
+            MasterSystemEvent::SetSeed { data } => self.set_seed(data),
+            MasterSystemEvent::SetMasterGain { parameter } => self.set_master_gain(parameter),
+        }
+    }
+
+    fn handle_master_mixer_event(&mut self, event: MixerEvent) -> Result<(), String> {
+        match event {
+            MixerEvent::SetActive { data, parameter } => {
+                self.set_system_active(&data, parameter != 0.0)
+            }
+            MixerEvent::SetGain { data, parameter } => self.set_system_gain(&data, parameter),
+            MixerEvent::SetMute { data, parameter } => {
+                self.set_system_muted(&data, parameter != 0.0)
+            }
+        }
+    }
+
+    /// The mixer, master filter and looper levels as they are now
+    pub fn capture_scene(&self) -> Scene {
+        let channels = self
+            .systems
+            .iter()
+            .map(|(name, slot)| {
+                let channel = ChannelSnapshot {
+                    gain: slot.gain,
+                    muted: slot.muted,
+                };
+                (name.clone(), channel)
+            })
+            .collect();
+        let mut loop_levels = [1.0; MAX_LOOPS];
+        for (level, info) in loop_levels.iter_mut().zip(self.looper.loop_info()) {
+            *level = info.level;
+        }
+        Scene {
+            channels,
+            filter_position: self.master_filter.position(),
+            loop_levels,
+        }
+    }
+
+    /// Save the current state under a name, replacing any scene already there
+    pub fn save_scene(&mut self, name: &str) {
+        let scene = self.capture_scene();
+        self.scenes.insert(name.to_string(), scene);
+        self.current_scene = Some(name.to_string());
+        self.scenes_changed = true;
+    }
+
+    /// Move to a saved scene, crossfading over the given seconds or jumping
+    /// straight there when zero. Systems added since it was saved are left
+    /// as they are.
+    pub fn recall_scene(&mut self, name: &str, fade_seconds: f32) -> Result<(), String> {
+        let to = self
+            .scenes
+            .get(name)
+            .cloned()
+            .ok_or_else(|| format!("Scene '{}' not found", name))?;
+        let from = self.capture_scene();
+        self.scene_fade = Some(SceneFade::new(from, to, fade_seconds, self.sample_rate));
+        self.current_scene = Some(name.to_string());
+        self.scenes_changed = true;
+        if fade_seconds <= 0.0 {
+            self.advance_scene_fade();
+        }
+        Ok(())
+    }
+
+    pub fn delete_scene(&mut self, name: &str) -> Result<(), String> {
+        self.scenes
+            .remove(name)
+            .ok_or_else(|| format!("Scene '{}' not found", name))?;
+        if self.current_scene.as_deref() == Some(name) {
+            self.current_scene = None;
+        }
+        self.scenes_changed = true;
+        Ok(())
+    }
+
+    /// Move any scene crossfade on by a frame
+    fn advance_scene_fade(&mut self) {
+        let Some(fade) = self.scene_fade.as_mut() else {
+            return;
+        };
+        let amount = fade.advance();
+        for (name, slot) in self.systems.iter_mut() {
+            if let Some(channel) = fade.from.channel_towards(&fade.to, name, amount) {
+                slot.gain = channel.gain;
+                slot.muted = channel.muted;
+            }
         }
+        self.master_filter
+            .set_position(fade.from.filter_position_towards(&fade.to, amount));
+        for index in 0..MAX_LOOPS {
+            let level = fade.from.loop_level_towards(&fade.to, index, amount);
+            self.looper.fade_level(index, level);
+        }
+
+        if amount >= 1.0 {
+            self.scene_fade = None;
+            self.looper.mark_changed();
+        }
+    }
+
+    /// The saved scenes and where the mixer is heading, if they've changed
+    /// since they were last reported
+    pub fn take_scene_state(&mut self) -> Option<ServerEvent> {
+        if !std::mem::take(&mut self.scenes_changed) {
+            return None;
+        }
+        let mut names: Vec<&str> = self.scenes.keys().map(|name| name.as_str()).collect();
+        names.sort_unstable();
+        let target = match &self.scene_fade {
+            Some(fade) => fade.to.clone(),
+            None => self.capture_scene(),
+        };
+        Some(ServerEvent::scene_state(
+            &names,
+            self.current_scene.as_deref(),
+            &target,
+        ))
+    }
+
+    fn handle_scene_event(&mut self, event: SceneEvent) -> Result<(), String> {
+        match event {
+            SceneEvent::Save { data } => {
+                self.save_scene(&data);
+                Ok(())
+            }
+            SceneEvent::Recall { data, parameter } => {
+                self.recall_scene(&data, parameter.unwrap_or(0.0))
+            }
+            SceneEvent::Delete { data } => self.delete_scene(&data),
+            SceneEvent::GetScenes => {
+                self.scenes_changed = true;
+                Ok(())
+            }
+        }
+    }
+
+    /// The looper's state if any loop has changed since it was last reported
+    pub fn take_looper_state(&mut self) -> Option<ServerEvent> {
+        if !self.looper.take_changed() {
+            return None;
+        }
+        let source = self.looper_source.as_deref().unwrap_or(MASTER_SYSTEM_NAME);
+        Some(ServerEvent::looper_state(source, &self.looper.loop_info()))
+    }
+
+    fn handle_looper_event(&mut self, event: LooperEvent) -> Result<(), String> {
+        match event {
+            LooperEvent::Record { data } => self.looper.record(data),
+            LooperEvent::Overdub { data } => self.looper.overdub(data),
+            LooperEvent::Undo { data } => self.looper.undo(data),
+            LooperEvent::Clear { data } => self.looper.clear(data),
+            LooperEvent::SetLevel { data, parameter } => self.looper.set_level(data, parameter),
+            LooperEvent::SetBars { parameter } => {
+                self.looper.set_bars(parameter as u32);
+                Ok(())
+            }
+            LooperEvent::SetSource { data } => {
+                if data == MASTER_SYSTEM_NAME {
+                    self.looper_source = None;
+                } else if self.systems.contains_key(&data) {
+                    self.looper_source = Some(data);
+                } else {
+                    return Err(format!("System '{}' not found", data));
+                }
+                self.looper.mark_changed();
+                Ok(())
+            }
+        }
+    }
+
+    fn handle_stutter_event(&mut self, event: StutterEvent) -> Result<(), String> {
+        match event {
+            StutterEvent::Press { parameter } => {
+                return self.stutter.press(parameter as u32, &self.clock)
+            }
+            StutterEvent::Release => self.stutter.release(),
+            StutterEvent::SetPitch { parameter } => self.stutter.set_pitch(parameter),
+            StutterEvent::SetDecay { parameter } => self.stutter.set_decay(parameter),
+        }
+        Ok(())
+    }
+
+    fn handle_evolve_event(&mut self, event: EvolveEvent) -> Result<(), String> {
+        match event {
+            EvolveEvent::SetEnabled { data, parameter } => {
+                self.slot_mut(&data)?.evolver.set_enabled(parameter != 0.0)
+            }
+            EvolveEvent::SetEveryBars { data, parameter } => self
+                .slot_mut(&data)?
+                .evolver
+                .set_every_bars(parameter.round().max(1.0) as u32),
+            EvolveEvent::SetIntensity { data, parameter } => {
+                self.slot_mut(&data)?.evolver.set_intensity(parameter)
+            }
+            EvolveEvent::SetSeed { data, parameter } => {
+                self.slot_mut(&data)?.evolver.set_seed(parameter as u64)
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_tape_stop_event(&mut self, event: TapeStopEvent) {
+        match event {
+            TapeStopEvent::Stop { parameter } => self.tape_stop.stop(parameter),
+            TapeStopEvent::Start { parameter } => self.tape_stop.start(parameter),
+        }
+    }
+
+    fn handle_master_filter_event(&mut self, event: MasterFilterEvent) {
+        match event {
+            MasterFilterEvent::SetPosition { parameter } => {
+                self.master_filter.set_position(parameter)
+            }
+            MasterFilterEvent::SetResonance { parameter } => {
+                self.master_filter.set_resonance(parameter)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Outputs a constant level on both channels
+    struct Constant(f32);
+
+    impl AudioSystem for Constant {
+        fn next_sample(&mut self) -> (f32, f32) {
+            (self.0, self.0)
+        }
+
+        fn handle_client_event(&mut self, _event: ClientEvent) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn set_sample_rate(&mut self, _sample_rate: f32) {}
+    }
+
+    /// Plays silence with its transport running at 60 BPM, one beat per bar
+    struct Playing {
+        frames: u64,
+    }
+
+    impl AudioSystem for Playing {
+        fn next_sample(&mut self) -> (f32, f32) {
+            self.frames += 1;
+            (0.0, 0.0)
+        }
+
+        fn handle_client_event(&mut self, _event: ClientEvent) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn set_sample_rate(&mut self, _sample_rate: f32) {}
+
+        fn transport_clock(&self) -> Option<TransportClock> {
+            Some(TransportClock {
+                bpm: 60.0,
+                beats_per_bar: 1,
+                beats: self.frames as f64 / 100.0,
+            })
+        }
+    }
+
+    #[test]
+    fn test_active_systems_are_summed_through_gain_and_mute() {
+        let mut server = AudioServer::new(44100.0);
+        server.add_system("drums".to_string(), Box::new(Constant(0.25)));
+        server.add_system("ambient".to_string(), Box::new(Constant(0.5)));
+
+        server.switch_to_system("drums").unwrap();
+        assert_eq!(server.next_sample(), (0.25, 0.25));
+
+        server.set_system_active("ambient", true).unwrap();
+        server.set_system_gain("ambient", 0.5).unwrap();
+        assert_eq!(server.next_sample(), (0.5, 0.5));
+
+        server.set_system_muted("drums", true).unwrap();
+        assert_eq!(server.next_sample(), (0.25, 0.25));
+
+        // Switching still solos a single system
+        server.switch_to_system("drums").unwrap();
+        assert_eq!(server.get_active_system_names(), vec!["drums"]);
+        assert!(server.set_system_gain("missing", 1.0).is_err());
+
+        server.set_system_muted("drums", false).unwrap();
+        server.set_master_gain(2.0);
+        assert_eq!(server.next_sample(), (0.5, 0.5));
+    }
+
+    #[test]
+    fn test_looper_follows_the_playing_system() {
+        use crate::audio::looper::LoopState;
+
+        // Half a bar into the system's transport
+        let mut server = AudioServer::new(100.0);
+        server.add_system("riff".to_string(), Box::new(Playing { frames: 50 }));
+        server.switch_to_system("riff").unwrap();
+        server.next_sample();
+        server.looper.record(0).unwrap();
+
+        // Recording waits for the system's next bar line, then lasts one of its bars
+        for _ in 1..50 {
+            server.next_sample();
+        }
+        assert_eq!(server.looper.loop_info()[0].state, LoopState::Armed);
+        for _ in 0..100 {
+            server.next_sample();
+        }
+        assert_eq!(server.looper.loop_info()[0].state, LoopState::Playing);
+    }
+
+    #[test]
+    fn test_scenes_recall_instantly_or_crossfade() {
+        let mut server = AudioServer::new(100.0);
+        server.add_system("drums".to_string(), Box::new(Constant(1.0)));
+        server.add_system("ambient".to_string(), Box::new(Constant(1.0)));
+        server.switch_to_system("drums").unwrap();
+        server.set_system_active("ambient", true).unwrap();
+        server.set_system_muted("ambient", true).unwrap();
+        server.save_scene("intro");
+
+        server.set_system_gain("drums", 0.5).unwrap();
+        server.set_system_muted("ambient", false).unwrap();
+        server.save_scene("drop");
+        assert!(server.take_scene_state().is_some());
+        assert!(server.take_scene_state().is_none());
+
+        server.recall_scene("intro", 0.0).unwrap();
+        assert_eq!(server.next_sample(), (1.0, 1.0));
+        assert!(server.take_scene_state().is_some());
+
+        // One second at 100Hz: halfway through the drums are at 0.75 and
+        // the ambient channel has faded halfway up
+        server.recall_scene("drop", 1.0).unwrap();
+        for _ in 0..49 {
+            server.next_sample();
+        }
+        let (left, _) = server.next_sample();
+        assert!((left - 1.25).abs() < 1e-5);
+        for _ in 0..50 {
+            server.next_sample();
+        }
+        assert_eq!(server.next_sample(), (1.5, 1.5));
+
+        assert!(server.recall_scene("breakdown", 0.0).is_err());
+        server.delete_scene("drop").unwrap();
+        assert!(server.recall_scene("drop", 0.0).is_err());
     }
 }
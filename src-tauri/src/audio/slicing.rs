@@ -0,0 +1,169 @@
+use std::ops::Range;
+
+/// Most slices a loop is cut into, one per sampler pad
+pub const MAX_SLICES: usize = 16;
+
+/// Frames per energy measurement when looking for transients
+const ONSET_HOP: usize = 256;
+
+/// Hops averaged as the level a transient has to rise above
+const ONSET_HISTORY: usize = 4;
+
+/// Rise over the recent level that counts as a transient
+const MIN_ONSET_RISE_DB: f32 = 6.0;
+
+/// Shortest slice, so a flam or a rattle doesn't split into several
+const MIN_SLICE_SECONDS: f32 = 0.05;
+
+/// Start frames of up to `max_slices` slices, cut at the strongest transients.
+/// The first slice always starts at frame 0.
+pub fn detect_slices(samples: &[f32], sample_rate: f32, max_slices: usize) -> Vec<usize> {
+    if samples.is_empty() || max_slices == 0 {
+        return Vec::new();
+    }
+
+    let levels_db: Vec<f32> = samples
+        .chunks(ONSET_HOP)
+        .map(|hop| {
+            let power = hop.iter().map(|s| s * s).sum::<f32>() / hop.len() as f32;
+            10.0 * (power + 1e-10).log10()
+        })
+        .collect();
+
+    // Rise of each hop over the average of the hops before it
+    let rises: Vec<f32> = (0..levels_db.len())
+        .map(|i| {
+            let history = &levels_db[i.saturating_sub(ONSET_HISTORY)..i];
+            if history.is_empty() {
+                return 0.0;
+            }
+            let recent = history.iter().sum::<f32>() / history.len() as f32;
+            levels_db[i] - recent
+        })
+        .collect();
+
+    let min_gap = ((MIN_SLICE_SECONDS * sample_rate) as usize / ONSET_HOP).max(1);
+    let mut onsets: Vec<(usize, f32)> = (1..rises.len())
+        .filter(|&i| {
+            let is_peak =
+                rises[i] >= rises[i - 1] && rises.get(i + 1).is_none_or(|&next| rises[i] > next);
+            is_peak && rises[i] >= MIN_ONSET_RISE_DB && i >= min_gap
+        })
+        .map(|i| (i, rises[i]))
+        .collect();
+
+    // Keep the strongest transients that aren't crowding a stronger one
+    onsets.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let mut hops: Vec<usize> = vec![0];
+    for (hop, _) in onsets {
+        if hops.len() == max_slices {
+            break;
+        }
+        if hops.iter().all(|&kept| kept.abs_diff(hop) >= min_gap) {
+            hops.push(hop);
+        }
+    }
+    hops.sort_unstable();
+
+    hops.into_iter()
+        .map(|hop| {
+            if hop == 0 {
+                0
+            } else {
+                attack_start(samples, hop * ONSET_HOP)
+            }
+        })
+        .collect()
+}
+
+/// First frame in a hop reaching half its peak, so slices start on the hit
+/// rather than up to a hop early
+fn attack_start(samples: &[f32], hop_start: usize) -> usize {
+    let hop = &samples[hop_start..(hop_start + ONSET_HOP).min(samples.len())];
+    let peak = hop.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    hop.iter()
+        .position(|s| s.abs() >= 0.5 * peak)
+        .map_or(hop_start, |offset| hop_start + offset)
+}
+
+/// Check hand-adjusted markers: increasing start frames inside the sample
+pub fn validate_markers(markers: &[usize], len: usize) -> Result<(), String> {
+    if markers.is_empty() || markers.len() > MAX_SLICES {
+        return Err(format!("Slices need 1 to {} markers", MAX_SLICES));
+    }
+    if markers.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err("Slice markers must be in increasing order".to_string());
+    }
+    if markers[markers.len() - 1] >= len {
+        return Err(format!(
+            "Slice markers must be before the end frame {}",
+            len
+        ));
+    }
+    Ok(())
+}
+
+/// Frame range of each slice, the last running to the end of the sample
+pub fn slice_ranges(markers: &[usize], len: usize) -> Vec<Range<usize>> {
+    markers
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| start..markers.get(i + 1).copied().unwrap_or(len))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decaying noise bursts starting at the given frames
+    fn hits(len: usize, starts: &[usize]) -> Vec<f32> {
+        let mut rng = fastrand::Rng::with_seed(7);
+        let mut samples = vec![0.0; len];
+        for &start in starts {
+            for (i, sample) in samples[start..].iter_mut().take(4000).enumerate() {
+                *sample += (rng.f32() * 2.0 - 1.0) * (-(i as f32) / 800.0).exp();
+            }
+        }
+        samples
+    }
+
+    #[test]
+    fn test_slices_start_on_hits() {
+        let starts = [0, 11025, 22050, 27563, 33075];
+        let samples = hits(44100, &starts);
+        let slices = detect_slices(&samples, 44100.0, MAX_SLICES);
+        assert_eq!(slices.len(), starts.len(), "slices at {:?}", slices);
+        for (slice, start) in slices.iter().zip(starts) {
+            assert!(
+                slice.abs_diff(start) < 64,
+                "slice {} for hit {}",
+                slice,
+                start
+            );
+        }
+    }
+
+    #[test]
+    fn test_slice_count_keeps_strongest_hits() {
+        let mut samples = hits(44100, &[0, 11025, 22050, 33075]);
+        // Make the hit at 22050 the quietest
+        for sample in &mut samples[22050..26050] {
+            *sample *= 0.1;
+        }
+        let slices = detect_slices(&samples, 44100.0, 3);
+        assert_eq!(slices.len(), 3);
+        assert!(!slices.iter().any(|&slice| slice.abs_diff(22050) < 64));
+    }
+
+    #[test]
+    fn test_markers_and_ranges() {
+        assert!(validate_markers(&[0, 100, 200], 300).is_ok());
+        assert!(validate_markers(&[], 300).is_err());
+        assert!(validate_markers(&[0, 200, 100], 300).is_err());
+        assert!(validate_markers(&[0, 300], 300).is_err());
+        assert!(validate_markers(&[0; MAX_SLICES + 1], 300).is_err());
+
+        assert_eq!(slice_ranges(&[10, 100], 300), vec![10..100, 100..300]);
+    }
+}
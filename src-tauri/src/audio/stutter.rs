@@ -0,0 +1,229 @@
+use crate::audio::StereoAudioProcessor;
+use crate::sequencing::TransportClock;
+
+/// A quarter note at this tempo is the longest slice, so it sets the history
+/// length; slower tempos repeat as much as the history holds
+const MIN_BPM: f32 = 40.0;
+
+/// Note divisions a repeat can loop, 4 being a quarter note
+pub const STUTTER_DIVISIONS: [u32; 4] = [4, 8, 16, 32];
+
+/// Fade at each end of a repeat and on press and release, to avoid clicks
+const DECLICK_SECONDS: f32 = 0.002;
+
+/// Momentary beat repeat: while pressed, loops the last slice of its input
+/// with each repeat optionally pitched and quieter than the one before. The
+/// slice length follows the tempo of the clock it's pressed with.
+pub struct Stutter {
+    // Recent input, frozen while a slice is repeating
+    history: Vec<(f32, f32)>,
+    write_position: usize,
+    // Frames written, up to the history length
+    filled: usize,
+
+    pressed: bool,
+    slice_start: usize,
+    slice_length: usize,
+    frame_in_repeat: usize,
+    repeat: u32,
+    // Playback rate and gain of the current repeat
+    repeat_rate: f32,
+    repeat_gain: f32,
+    // 0 is dry, 1 is the repeats; ramps on press and release
+    wet: f32,
+
+    pitch: f32, // Semitones per repeat
+    decay: f32, // Gain of each repeat relative to the last
+    declick_frames: usize,
+    sample_rate: f32,
+}
+
+impl Stutter {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut stutter = Self {
+            history: Vec::new(),
+            write_position: 0,
+            filled: 0,
+            pressed: false,
+            slice_start: 0,
+            slice_length: 1,
+            frame_in_repeat: 0,
+            repeat: 0,
+            repeat_rate: 1.0,
+            repeat_gain: 1.0,
+            wet: 0.0,
+            pitch: 0.0,
+            decay: 1.0,
+            declick_frames: 1,
+            sample_rate,
+        };
+        stutter.set_sample_rate(sample_rate);
+        stutter
+    }
+
+    /// Start repeating the last 1/division of a bar at the clock's tempo.
+    /// Pressing again while held re-slices the same frozen audio, so 4 then 8
+    /// then 16 builds a roll.
+    pub fn press(&mut self, division: u32, clock: &TransportClock) -> Result<(), String> {
+        if !STUTTER_DIVISIONS.contains(&division) {
+            return Err(format!(
+                "Stutter division must be one of {:?}, got {}",
+                STUTTER_DIVISIONS, division
+            ));
+        }
+        let quarter = clock.frames_per_beat(self.sample_rate) as f32;
+        let length = (quarter * 4.0 / division as f32) as usize;
+        self.slice_length = length.min(self.filled).max(1);
+        self.slice_start =
+            (self.write_position + self.history.len() - self.slice_length) % self.history.len();
+        self.frame_in_repeat = 0;
+        self.repeat = 0;
+        self.repeat_rate = 1.0;
+        self.repeat_gain = 1.0;
+        self.pressed = true;
+        Ok(())
+    }
+
+    /// Go back to the dry input
+    pub fn release(&mut self) {
+        self.pressed = false;
+    }
+
+    /// Semitones each repeat is shifted from the one before, -12 to 12
+    pub fn set_pitch(&mut self, semitones: f32) {
+        self.pitch = semitones.clamp(-12.0, 12.0);
+    }
+
+    /// Gain of each repeat relative to the one before: 1 holds, 0 plays once
+    pub fn set_decay(&mut self, decay: f32) {
+        self.decay = decay.clamp(0.0, 1.0);
+    }
+
+    fn slice_sample(&self) -> (f32, f32) {
+        // Pitched repeats read faster or slower but keep the slice's timing
+        let offset = (self.frame_in_repeat as f32 * self.repeat_rate) % self.slice_length as f32;
+        let index = offset as usize;
+        let fraction = offset - index as f32;
+        let (left_a, right_a) = self.history_at(index);
+        let (left_b, right_b) = self.history_at((index + 1) % self.slice_length);
+        (
+            left_a + (left_b - left_a) * fraction,
+            right_a + (right_b - right_a) * fraction,
+        )
+    }
+
+    fn history_at(&self, slice_offset: usize) -> (f32, f32) {
+        self.history[(self.slice_start + slice_offset) % self.history.len()]
+    }
+}
+
+impl StereoAudioProcessor for Stutter {
+    fn process(&mut self, left: f32, right: f32) -> (f32, f32) {
+        let step = 1.0 / self.declick_frames as f32;
+        self.wet = if self.pressed {
+            (self.wet + step).min(1.0)
+        } else {
+            (self.wet - step).max(0.0)
+        };
+
+        if self.wet == 0.0 {
+            self.history[self.write_position] = (left, right);
+            self.write_position = (self.write_position + 1) % self.history.len();
+            self.filled = (self.filled + 1).min(self.history.len());
+            return (left, right);
+        }
+
+        // Fade each repeat in and out so the loop point doesn't click
+        let from_end = self.slice_length - self.frame_in_repeat;
+        let window = (self.frame_in_repeat.min(from_end) as f32 * step).min(1.0);
+        let gain = self.repeat_gain * window;
+        let (repeat_left, repeat_right) = self.slice_sample();
+
+        self.frame_in_repeat += 1;
+        if self.frame_in_repeat >= self.slice_length {
+            self.frame_in_repeat = 0;
+            self.repeat += 1;
+            self.repeat_rate = (self.pitch * self.repeat as f32 / 12.0).exp2();
+            self.repeat_gain *= self.decay;
+        }
+
+        let dry = 1.0 - self.wet;
+        (
+            left * dry + repeat_left * gain * self.wet,
+            right * dry + repeat_right * gain * self.wet,
+        )
+    }
+
+    /// Clears the history, since it was recorded at the old rate
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        let capacity = (60.0 / MIN_BPM * sample_rate) as usize + 1;
+        self.history = vec![(0.0, 0.0); capacity];
+        self.write_position = 0;
+        self.filled = 0;
+        self.pressed = false;
+        self.wet = 0.0;
+        self.declick_frames = ((DECLICK_SECONDS * sample_rate) as usize).max(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 60 BPM at 1kHz makes a quarter note 1000 frames
+    const SAMPLE_RATE: f32 = 1000.0;
+    const CLOCK: TransportClock = TransportClock {
+        bpm: 60.0,
+        beats_per_bar: 4,
+        beats: 0.0,
+    };
+
+    fn stutter_after_ramp() -> Stutter {
+        let mut stutter = Stutter::new(SAMPLE_RATE);
+        for i in 0..1000 {
+            stutter.process(i as f32, i as f32);
+        }
+        stutter
+    }
+
+    #[test]
+    fn test_press_repeats_the_last_slice_until_release() {
+        let mut stutter = stutter_after_ramp();
+        assert!(stutter.press(3, &CLOCK).is_err());
+
+        // An eighth is the last 500 frames of the ramp
+        stutter.press(8, &CLOCK).unwrap();
+        let outputs: Vec<f32> = (0..1500).map(|_| stutter.process(-1.0, -1.0).0).collect();
+        for repeat in 0..3 {
+            let middle = outputs[repeat * 500 + 250];
+            assert!(
+                (middle - 750.0).abs() < 1e-3,
+                "repeat {} gave {}",
+                repeat,
+                middle
+            );
+        }
+
+        // Release fades back to the dry input
+        stutter.release();
+        for _ in 0..10 {
+            stutter.process(-1.0, -1.0);
+        }
+        assert_eq!(stutter.process(-1.0, -1.0), (-1.0, -1.0));
+    }
+
+    #[test]
+    fn test_repeats_decay_and_shift_pitch() {
+        let mut stutter = stutter_after_ramp();
+        stutter.set_decay(0.5);
+        stutter.set_pitch(12.0);
+        stutter.press(8, &CLOCK).unwrap();
+
+        let outputs: Vec<f32> = (0..1000).map(|_| stutter.process(0.0, 0.0).0).collect();
+        // The second repeat is half as loud and reads at double speed, so
+        // its 100th frame is 200 frames into the slice
+        assert!((outputs[100] - 600.0).abs() < 1e-3);
+        assert!((outputs[600] - 700.0 * 0.5).abs() < 1e-3);
+    }
+}
@@ -1,6 +1,71 @@
-use crate::audio::instruments::{ChordSynth, ClapDrum, HiHat, KickDrum, SupersawSynth};
-use crate::audio::reverbs::ReverbLite;
-use crate::audio::{AudioGenerator, AudioSystem, StereoAudioGenerator, StereoAudioProcessor};
+use crate::audio::dynamics::Ducker;
+use crate::audio::evolve::Mutations;
+use crate::audio::filters::{FilterMode, ParametricEQ};
+use crate::audio::instruments::{
+    AcidBass, Bass808, ChordSynth, ClapDrum, Clave, Cowbell, CymbalMode, CymbalSynth, FM6Algorithm,
+    FM6Voice, FormantVoice, GlitchPerc, HatMode, HiHat, KickDrum, ModalDrum, PadSynth,
+    SamplePlayer, SnareDrum, SupersawSynth, TomDrum, WavetableSynth, FM6_OPERATORS, FM_OPERATORS,
+};
+use crate::audio::lofi::LoFi;
+use crate::audio::oscillators::NoiseColor;
+use crate::audio::reverbs::{QualityReverb, ReverbQuality};
+use crate::audio::slicing::MAX_SLICES;
+use crate::audio::systems::ChokeGroups;
+use crate::audio::time_stretch::StretchQuality;
+use crate::audio::{
+    AudioGenerator, AudioProcessor, AudioSystem, StereoAudioGenerator, StereoAudioProcessor,
+};
+use crate::events::{
+    AcidEvent, AuditionerEvent, AuditionerSystemEvent, Bass808Event, ChordEvent, ClapEvent,
+    ClaveEvent, ClientEvent, CowbellEvent, CymbalEvent, EqEvent, Fm6Event, FormantEvent,
+    GlitchEvent, HihatEvent, KickEvent, ModalDrumEvent, PadSynthEvent, ReverbEvent, SamplerEvent,
+    ServerEvent, ServerEventSender, SnareEvent, SupersawEvent, TomEvent, WavetableEvent,
+};
+use crate::sequencing::ChordQuality;
+
+/// The closed and open hats start out choking each other
+const HAT_CHOKE_GROUP: u32 = 1;
+
+/// Velocities above this play accented notes on the synths with an accent
+const ACCENT_VELOCITY: f32 = 0.5;
+
+/// Check an operator index sent with a per-operator FM event
+fn fm6_operator(op: usize) -> Result<usize, String> {
+    if op < FM6_OPERATORS {
+        Ok(op)
+    } else {
+        Err(format!(
+            "No FM operator {}, there are {}",
+            op, FM6_OPERATORS
+        ))
+    }
+}
+
+/// Instrument nodes that share choke groups and lo-fi controls
+const INSTRUMENT_NODES: [&str; 19] = [
+    "kick",
+    "clap",
+    "snare",
+    "hihat",
+    "open_hat",
+    "tom",
+    "modal_drum",
+    "cowbell",
+    "clave",
+    "glitch",
+    "cymbal",
+    "chord",
+    "supersaw",
+    "pad_synth",
+    "bass808",
+    "acid",
+    "wavetable",
+    "fm6",
+    "formant",
+];
+
+/// Number of sample pads, one for each slice of a fully sliced loop
+pub const SAMPLER_PADS: usize = MAX_SLICES;
 
 /// Auditioner system for testing and tweaking instruments
 /// Allows triggering individual instruments without sequencing
@@ -8,281 +73,1056 @@ pub struct AuditionerSystem {
     // Audio nodes for different instruments
     kick: KickDrum,
     clap: ClapDrum,
+    snare: SnareDrum,
     hihat: HiHat,
+    open_hat: HiHat,
+    tom: TomDrum,
+    modal_drum: ModalDrum,
+    cowbell: Cowbell,
+    clave: Clave,
+    glitch: GlitchPerc,
+    cymbal: CymbalSynth,
     chord: ChordSynth,
     supersaw: SupersawSynth,
-    reverb: ReverbLite,
+    pad_synth: PadSynth,
+    bass808: Bass808,
+    acid: AcidBass,
+    wavetable: WavetableSynth,
+    fm6: FM6Voice,
+    formant: FormantVoice,
+    sampler_pads: Vec<SamplePlayer>,
+    sampler_gain: f32,
+    // Loops are stretched from their own tempo to the project tempo;
+    // a loop BPM of 0 plays them unstretched
+    sampler_bpm: f32,
+    sampler_loop_bpm: f32,
+    reverb: QualityReverb,
+
+    // Instruments that cut each other off when triggered
+    choke_groups: ChokeGroups,
+
+    // Per-instrument lo-fi stages
+    kick_lofi: LoFi,
+    clap_lofi: LoFi,
+    snare_lofi: LoFi,
+    hihat_lofi: LoFi,
+    open_hat_lofi: LoFi,
+    tom_lofi: LoFi,
+    modal_drum_lofi: LoFi,
+    cowbell_lofi: LoFi,
+    clave_lofi: LoFi,
+    glitch_lofi: LoFi,
+    cymbal_lofi: LoFi,
+    chord_lofi_left: LoFi,
+    chord_lofi_right: LoFi,
+    supersaw_lofi_left: LoFi,
+    supersaw_lofi_right: LoFi,
+    pad_synth_lofi_left: LoFi,
+    pad_synth_lofi_right: LoFi,
+    bass808_lofi: LoFi,
+    acid_lofi: LoFi,
+    wavetable_lofi: LoFi,
+    fm6_lofi: LoFi,
+    formant_lofi: LoFi,
+
+    // Master EQ (one per channel)
+    eq_left: ParametricEQ,
+    eq_right: ParametricEQ,
 
     // Send/return levels for reverb
     reverb_send: f32,
     reverb_return: f32,
 
+    // Pulls the reverb return down under the dry signal
+    reverb_ducker: Ducker,
+
+    event_sender: ServerEventSender,
     sample_rate: f32,
 }
 
 impl AuditionerSystem {
-    pub fn new(sample_rate: f32) -> Self {
-        Self {
+    pub fn new(sample_rate: f32, event_sender: ServerEventSender) -> Self {
+        let mut system = Self {
             kick: KickDrum::new(sample_rate),
             clap: ClapDrum::new(sample_rate),
+            snare: SnareDrum::new(sample_rate),
             hihat: HiHat::new(sample_rate),
+            open_hat: HiHat::new(sample_rate),
+            tom: TomDrum::new(sample_rate),
+            modal_drum: ModalDrum::new(sample_rate),
+            cowbell: Cowbell::new(sample_rate),
+            clave: Clave::new(sample_rate),
+            glitch: GlitchPerc::new(sample_rate),
+            cymbal: CymbalSynth::new(sample_rate),
             chord: ChordSynth::new(sample_rate),
             supersaw: SupersawSynth::new(sample_rate),
-            reverb: ReverbLite::new(sample_rate),
+            pad_synth: PadSynth::new(sample_rate),
+            bass808: Bass808::new(sample_rate),
+            acid: AcidBass::new(sample_rate),
+            wavetable: WavetableSynth::new(sample_rate),
+            fm6: FM6Voice::new(sample_rate),
+            formant: FormantVoice::new(sample_rate),
+            sampler_pads: (0..SAMPLER_PADS)
+                .map(|_| SamplePlayer::new(sample_rate))
+                .collect(),
+            sampler_gain: 1.0,
+            sampler_bpm: 120.0,
+            sampler_loop_bpm: 0.0,
+            reverb: QualityReverb::new(ReverbQuality::Eco, sample_rate),
+            choke_groups: ChokeGroups::new(),
+            kick_lofi: LoFi::new(),
+            clap_lofi: LoFi::new(),
+            snare_lofi: LoFi::new(),
+            hihat_lofi: LoFi::new(),
+            open_hat_lofi: LoFi::new(),
+            tom_lofi: LoFi::new(),
+            modal_drum_lofi: LoFi::new(),
+            cowbell_lofi: LoFi::new(),
+            clave_lofi: LoFi::new(),
+            glitch_lofi: LoFi::new(),
+            cymbal_lofi: LoFi::new(),
+            chord_lofi_left: LoFi::new(),
+            chord_lofi_right: LoFi::new(),
+            supersaw_lofi_left: LoFi::new(),
+            supersaw_lofi_right: LoFi::new(),
+            pad_synth_lofi_left: LoFi::new(),
+            pad_synth_lofi_right: LoFi::new(),
+            bass808_lofi: LoFi::new(),
+            acid_lofi: LoFi::new(),
+            wavetable_lofi: LoFi::new(),
+            fm6_lofi: LoFi::new(),
+            formant_lofi: LoFi::new(),
+            eq_left: ParametricEQ::new(sample_rate),
+            eq_right: ParametricEQ::new(sample_rate),
             reverb_send: 0.3,   // Default 30% send to reverb
             reverb_return: 0.5, // Default 50% reverb return
+            reverb_ducker: Ducker::new(sample_rate),
+            event_sender,
             sample_rate,
-        }
+        };
+
+        system.open_hat.set_mode(HatMode::Open);
+        system.choke_groups.set_group("hihat", HAT_CHOKE_GROUP);
+        system.choke_groups.set_group("open_hat", HAT_CHOKE_GROUP);
+        system
     }
 
     pub fn set_reverb_send(&mut self, send: f32) {
         self.reverb_send = send.clamp(0.0, 1.0);
+        self.sync_parameter("system", "set_reverb_send", self.reverb_send);
     }
 
     pub fn set_reverb_return(&mut self, return_level: f32) {
         self.reverb_return = return_level.clamp(0.0, 1.0);
+        self.sync_parameter("system", "set_reverb_return", self.reverb_return);
+    }
+
+    /// Tell the UI about a value the engine settled on, so its control doesn't drift
+    fn sync_parameter(&self, node: &str, event: &str, value: f32) {
+        self.event_sender.send(ServerEvent::parameter_changed(
+            "auditioner",
+            node,
+            event,
+            value,
+        ));
+    }
+
+    /// Fast-release every node the triggered node chokes
+    fn choke_group_members(&mut self, trigger_node: &str) {
+        for node in INSTRUMENT_NODES {
+            if self.choke_groups.chokes(trigger_node, node) {
+                self.choke_node(node);
+            }
+        }
+    }
+
+    fn choke_node(&mut self, node: &str) {
+        match node {
+            "kick" => self.kick.choke(),
+            "clap" => self.clap.choke(),
+            "snare" => self.snare.choke(),
+            "hihat" => self.hihat.choke(),
+            "open_hat" => self.open_hat.choke(),
+            "tom" => self.tom.choke(),
+            "modal_drum" => self.modal_drum.choke(),
+            "cowbell" => self.cowbell.choke(),
+            "clave" => self.clave.choke(),
+            "glitch" => self.glitch.choke(),
+            "cymbal" => self.cymbal.choke(),
+            "chord" => self.chord.choke(),
+            "supersaw" => self.supersaw.choke(),
+            "pad_synth" => self.pad_synth.choke(),
+            "bass808" => self.bass808.choke(),
+            "acid" => self.acid.choke(),
+            "wavetable" => self.wavetable.choke(),
+            "fm6" => self.fm6.choke(),
+            "formant" => self.formant.choke(),
+            _ => {}
+        }
+    }
+
+    fn set_choke_group(&mut self, node: &str, group: f32) {
+        self.choke_groups.set_group(node, group.max(0.0) as u32);
+    }
+
+    fn handle_kick_event(&mut self, event: KickEvent) {
+        match event {
+            KickEvent::Trigger { parameter } => {
+                self.choke_group_members("kick");
+                self.kick.trigger(parameter.unwrap_or(1.0));
+            }
+            KickEvent::SetGain { parameter } => self.kick.set_gain(parameter),
+            KickEvent::SetChokeGroup { parameter } => self.set_choke_group("kick", parameter),
+            KickEvent::SetLofiAmount { parameter } => self.kick_lofi.set_amount(parameter),
+            KickEvent::SetBaseFrequency { parameter } => self.kick.set_base_frequency(parameter),
+            KickEvent::SetFrequencyRatio { parameter } => self.kick.set_frequency_ratio(parameter),
+            KickEvent::SetAmpAttack { parameter } => self.kick.set_amp_attack(parameter),
+            KickEvent::SetAmpRelease { parameter } => self.kick.set_amp_release(parameter),
+            KickEvent::SetFreqAttack { parameter } => self.kick.set_freq_attack(parameter),
+            KickEvent::SetFreqRelease { parameter } => self.kick.set_freq_release(parameter),
+            KickEvent::SetClickLevel { parameter } => self.kick.set_click_level(parameter),
+            KickEvent::SetClickDecay { parameter } => self.kick.set_click_decay(parameter),
+        }
+    }
+
+    fn handle_clap_event(&mut self, event: ClapEvent) {
+        match event {
+            ClapEvent::Trigger { parameter } => {
+                self.choke_group_members("clap");
+                self.clap.trigger(parameter.unwrap_or(1.0));
+            }
+            ClapEvent::SetGain { parameter } => self.clap.set_gain(parameter),
+            ClapEvent::SetChokeGroup { parameter } => self.set_choke_group("clap", parameter),
+            ClapEvent::SetLofiAmount { parameter } => self.clap_lofi.set_amount(parameter),
+        }
     }
 
-    fn handle_kick_event(&mut self, event: &crate::events::ClientEvent) -> Result<(), String> {
-        match event.event.as_str() {
-            "trigger" => {
-                self.kick.trigger();
-                Ok(())
+    fn handle_snare_event(&mut self, event: SnareEvent) -> Result<(), String> {
+        match event {
+            SnareEvent::Trigger { parameter } => {
+                self.choke_group_members("snare");
+                self.snare.trigger(parameter.unwrap_or(1.0));
             }
-            "set_gain" => {
-                self.kick.set_gain(event.param());
-                Ok(())
+            SnareEvent::SetGain { parameter } => self.snare.set_gain(parameter),
+            SnareEvent::SetChokeGroup { parameter } => self.set_choke_group("snare", parameter),
+            SnareEvent::SetLofiAmount { parameter } => self.snare_lofi.set_amount(parameter),
+            SnareEvent::SetTune { parameter } => self.snare.set_tune(parameter),
+            SnareEvent::SetToneDecay { parameter } => self.snare.set_tone_decay(parameter),
+            SnareEvent::SetSnappy { parameter } => self.snare.set_snappy(parameter),
+            SnareEvent::SetNoiseDecay { parameter } => self.snare.set_noise_decay(parameter),
+            SnareEvent::SetNoiseFrequency { parameter } => {
+                self.snare.set_noise_frequency(parameter)
+            }
+            SnareEvent::SetNoiseColor { parameter } => {
+                let index = parameter.round().max(0.0) as u32;
+                let color = NoiseColor::from_index(index)
+                    .ok_or_else(|| format!("Unknown noise color: {}", index))?;
+                self.snare.set_noise_color(color);
+            }
+            SnareEvent::SetPitchSweep { parameter } => self.snare.set_pitch_sweep(parameter),
+            SnareEvent::SetSweepTime { parameter } => self.snare.set_sweep_time(parameter),
+        }
+        Ok(())
+    }
+
+    fn handle_hihat_event(&mut self, event: HihatEvent) {
+        match event {
+            HihatEvent::Trigger { parameter } => {
+                self.choke_group_members("hihat");
+                self.hihat.trigger(parameter.unwrap_or(1.0));
             }
-            "set_base_frequency" => {
-                self.kick.set_base_frequency(event.param());
-                Ok(())
+            HihatEvent::SetGain { parameter } => self.hihat.set_gain(parameter),
+            HihatEvent::SetChokeGroup { parameter } => self.set_choke_group("hihat", parameter),
+            HihatEvent::SetLofiAmount { parameter } => self.hihat_lofi.set_amount(parameter),
+            HihatEvent::SetLength { parameter } => self.hihat.set_length(parameter),
+        }
+    }
+
+    fn handle_open_hat_event(&mut self, event: HihatEvent) {
+        match event {
+            HihatEvent::Trigger { parameter } => {
+                self.choke_group_members("open_hat");
+                self.open_hat.trigger(parameter.unwrap_or(1.0));
             }
-            "set_frequency_ratio" => {
-                self.kick.set_frequency_ratio(event.param());
-                Ok(())
+            HihatEvent::SetGain { parameter } => self.open_hat.set_gain(parameter),
+            HihatEvent::SetChokeGroup { parameter } => self.set_choke_group("open_hat", parameter),
+            HihatEvent::SetLofiAmount { parameter } => self.open_hat_lofi.set_amount(parameter),
+            HihatEvent::SetLength { parameter } => self.open_hat.set_length(parameter),
+        }
+    }
+
+    fn handle_tom_event(&mut self, event: TomEvent) {
+        match event {
+            TomEvent::Trigger { parameter } => {
+                self.choke_group_members("tom");
+                self.tom.trigger(parameter.unwrap_or(1.0));
             }
-            "set_amp_attack" => {
-                self.kick.set_amp_attack(event.param());
-                Ok(())
+            TomEvent::SetGain { parameter } => self.tom.set_gain(parameter),
+            TomEvent::SetChokeGroup { parameter } => self.set_choke_group("tom", parameter),
+            TomEvent::SetLofiAmount { parameter } => self.tom_lofi.set_amount(parameter),
+            TomEvent::SetBaseFrequency { parameter } => self.tom.set_base_frequency(parameter),
+            TomEvent::SetSweepDepth { parameter } => self.tom.set_sweep_depth(parameter),
+            TomEvent::SetSweepTime { parameter } => self.tom.set_sweep_time(parameter),
+            TomEvent::SetDecay { parameter } => self.tom.set_decay(parameter),
+            TomEvent::SetNoiseAmount { parameter } => self.tom.set_noise_amount(parameter),
+            TomEvent::SetNoiseDecay { parameter } => self.tom.set_noise_decay(parameter),
+            TomEvent::SetFmAmount { parameter } => self.tom.set_fm_amount(parameter),
+        }
+    }
+
+    fn handle_modal_drum_event(&mut self, event: ModalDrumEvent) {
+        match event {
+            ModalDrumEvent::Trigger { parameter } => {
+                self.choke_group_members("modal_drum");
+                self.modal_drum.trigger(parameter.unwrap_or(1.0));
             }
-            "set_amp_release" => {
-                self.kick.set_amp_release(event.param());
-                Ok(())
+            ModalDrumEvent::SetGain { parameter } => self.modal_drum.set_gain(parameter),
+            ModalDrumEvent::SetChokeGroup { parameter } => {
+                self.set_choke_group("modal_drum", parameter)
             }
-            "set_freq_attack" => {
-                self.kick.set_freq_attack(event.param());
-                Ok(())
+            ModalDrumEvent::SetLofiAmount { parameter } => {
+                self.modal_drum_lofi.set_amount(parameter)
             }
-            "set_freq_release" => {
-                self.kick.set_freq_release(event.param());
-                Ok(())
+            ModalDrumEvent::SetTune { parameter } => self.modal_drum.set_tune(parameter),
+            ModalDrumEvent::SetDecay { parameter } => self.modal_drum.set_decay(parameter),
+            ModalDrumEvent::SetDamping { parameter } => self.modal_drum.set_damping(parameter),
+            ModalDrumEvent::SetStrikePosition { parameter } => {
+                self.modal_drum.set_strike_position(parameter)
             }
-            _ => Err(format!("Unknown kick event: {}", event.event)),
+            ModalDrumEvent::SetNoise { parameter } => self.modal_drum.set_noise(parameter),
         }
     }
 
-    fn handle_clap_event(&mut self, event: &crate::events::ClientEvent) -> Result<(), String> {
-        match event.event.as_str() {
-            "trigger" => {
-                self.clap.trigger();
-                Ok(())
+    fn handle_cowbell_event(&mut self, event: CowbellEvent) {
+        match event {
+            CowbellEvent::Trigger { parameter } => {
+                self.choke_group_members("cowbell");
+                self.cowbell.trigger(parameter.unwrap_or(1.0));
             }
-            "set_gain" => {
-                self.clap.set_gain(event.param());
-                Ok(())
+            CowbellEvent::SetGain { parameter } => self.cowbell.set_gain(parameter),
+            CowbellEvent::SetChokeGroup { parameter } => self.set_choke_group("cowbell", parameter),
+            CowbellEvent::SetLofiAmount { parameter } => self.cowbell_lofi.set_amount(parameter),
+            CowbellEvent::SetTuning { parameter } => self.cowbell.set_tuning(parameter),
+            CowbellEvent::SetDecay { parameter } => self.cowbell.set_decay(parameter),
+        }
+    }
+
+    fn handle_clave_event(&mut self, event: ClaveEvent) {
+        match event {
+            ClaveEvent::Trigger { parameter } => {
+                self.choke_group_members("clave");
+                self.clave.trigger(parameter.unwrap_or(1.0));
             }
-            _ => Err(format!("Unknown clap event: {}", event.event)),
+            ClaveEvent::SetGain { parameter } => self.clave.set_gain(parameter),
+            ClaveEvent::SetChokeGroup { parameter } => self.set_choke_group("clave", parameter),
+            ClaveEvent::SetLofiAmount { parameter } => self.clave_lofi.set_amount(parameter),
+            ClaveEvent::SetTuning { parameter } => self.clave.set_tuning(parameter),
+            ClaveEvent::SetDecay { parameter } => self.clave.set_decay(parameter),
         }
     }
 
-    fn handle_hihat_event(&mut self, event: &crate::events::ClientEvent) -> Result<(), String> {
-        match event.event.as_str() {
-            "trigger" => {
-                self.hihat.trigger();
-                Ok(())
+    fn handle_glitch_event(&mut self, event: GlitchEvent) {
+        match event {
+            GlitchEvent::Trigger { parameter } => {
+                self.choke_group_members("glitch");
+                self.glitch.trigger(parameter.unwrap_or(1.0));
             }
-            "set_gain" => {
-                self.hihat.set_gain(event.param());
-                Ok(())
+            GlitchEvent::SetGain { parameter } => self.glitch.set_gain(parameter),
+            GlitchEvent::SetChokeGroup { parameter } => self.set_choke_group("glitch", parameter),
+            GlitchEvent::SetLofiAmount { parameter } => self.glitch_lofi.set_amount(parameter),
+            GlitchEvent::SetTexture { parameter } => self.glitch.set_texture(parameter),
+            GlitchEvent::SetCrush { parameter } => self.glitch.set_crush(parameter),
+            GlitchEvent::SetDecay { parameter } => self.glitch.set_decay(parameter),
+            GlitchEvent::SetRandomness { parameter } => self.glitch.set_randomness(parameter),
+        }
+    }
+
+    fn handle_cymbal_event(&mut self, event: CymbalEvent) -> Result<(), String> {
+        match event {
+            CymbalEvent::Trigger { parameter } => {
+                self.choke_group_members("cymbal");
+                self.cymbal.trigger(parameter.unwrap_or(1.0));
             }
-            "set_length" => {
-                self.hihat.set_length(event.param());
-                Ok(())
+            CymbalEvent::SetGain { parameter } => self.cymbal.set_gain(parameter),
+            CymbalEvent::SetChokeGroup { parameter } => self.set_choke_group("cymbal", parameter),
+            CymbalEvent::SetLofiAmount { parameter } => self.cymbal_lofi.set_amount(parameter),
+            CymbalEvent::SetMode { parameter } => {
+                let index = parameter.round().max(0.0) as u32;
+                let mode = CymbalMode::from_index(index)
+                    .ok_or_else(|| format!("Unknown cymbal mode: {}", index))?;
+                self.cymbal.set_mode(mode);
             }
-            _ => Err(format!("Unknown hihat event: {}", event.event)),
+            CymbalEvent::SetTone { parameter } => self.cymbal.set_tone(parameter),
+            CymbalEvent::SetDecay { parameter } => self.cymbal.set_decay(parameter),
+            CymbalEvent::SetSizzle { parameter } => self.cymbal.set_sizzle(parameter),
         }
+        Ok(())
     }
 
-    fn handle_chord_event(&mut self, event: &crate::events::ClientEvent) -> Result<(), String> {
-        match event.event.as_str() {
-            "trigger" => {
-                self.chord.trigger();
-                Ok(())
+    fn handle_chord_event(&mut self, event: ChordEvent) -> Result<(), String> {
+        match event {
+            ChordEvent::Trigger { parameter } => {
+                self.choke_group_members("chord");
+                self.chord.trigger(parameter.unwrap_or(1.0));
             }
-            "set_gain" => {
-                self.chord.set_gain(event.param());
-                Ok(())
+            ChordEvent::SetGain { parameter } => self.chord.set_gain(parameter),
+            ChordEvent::SetChokeGroup { parameter } => self.set_choke_group("chord", parameter),
+            ChordEvent::SetLofiAmount { parameter } => {
+                self.chord_lofi_left.set_amount(parameter);
+                self.chord_lofi_right.set_amount(parameter);
             }
-            "set_base_frequency" => {
-                self.chord.set_base_frequency(event.param());
-                Ok(())
+            ChordEvent::SetStereoWidth { parameter } => self.chord.set_stereo_width(parameter),
+            ChordEvent::SetBaseFrequency { parameter } => self.chord.set_base_frequency(parameter),
+            ChordEvent::SetChordQuality { parameter } => {
+                let index = parameter.round().max(0.0) as u32;
+                let quality = ChordQuality::from_index(index)
+                    .ok_or_else(|| format!("Unknown chord quality: {}", index))?;
+                self.chord.set_chord_quality(quality);
             }
-            "set_modulation_index" => {
-                self.chord.set_modulation_index(event.param());
-                Ok(())
+            ChordEvent::SetChordRatios { data } => {
+                if data.is_empty() || data.iter().any(|ratio| *ratio <= 0.0) {
+                    return Err(format!("Chord ratios must be positive: {:?}", data));
+                }
+                self.chord.set_chord_ratios(&data);
             }
-            "set_feedback" => {
-                self.chord.set_feedback(event.param());
-                Ok(())
+            ChordEvent::SetInversion { parameter } => self
+                .chord
+                .set_inversion(parameter.round().max(0.0) as usize),
+            ChordEvent::SetSpread { parameter } => {
+                self.chord.set_spread(parameter.round().max(0.0) as u32)
             }
-            "set_attack" => {
-                self.chord.set_attack(event.param());
-                Ok(())
+            ChordEvent::SetModulationIndex { parameter } => {
+                self.chord.set_modulation_index(parameter)
             }
-            "set_release" => {
-                self.chord.set_release(event.param());
-                Ok(())
+            ChordEvent::SetUnisonVoices { parameter } => self
+                .chord
+                .set_unison_voices(parameter.round().max(1.0) as usize),
+            ChordEvent::SetUnisonDetune { parameter } => self.chord.set_unison_detune(parameter),
+            ChordEvent::SetDriftAmount { parameter } => self.chord.set_drift_amount(parameter),
+            ChordEvent::SetModAmount {
+                data: [src, dst],
+                parameter,
+            } => {
+                if src >= FM_OPERATORS || dst >= FM_OPERATORS {
+                    return Err(format!("No FM operator pair {} -> {}", src, dst));
+                }
+                self.chord.set_mod_amount(src, dst, parameter);
             }
-            _ => Err(format!("Unknown chord event: {}", event.event)),
+            ChordEvent::SetFeedback { parameter } => self.chord.set_feedback(parameter),
+            ChordEvent::SetAttack { parameter } => self.chord.set_attack(parameter),
+            ChordEvent::SetRelease { parameter } => self.chord.set_release(parameter),
         }
+        Ok(())
     }
 
-    fn handle_supersaw_event(&mut self, event: &crate::events::ClientEvent) -> Result<(), String> {
-        match event.event.as_str() {
-            "trigger" => {
+    fn handle_supersaw_event(&mut self, event: SupersawEvent) {
+        match event {
+            SupersawEvent::Trigger { parameter } => {
+                self.choke_group_members("supersaw");
+                self.supersaw.set_velocity(parameter.unwrap_or(1.0));
                 self.supersaw.trigger();
-                Ok(())
             }
-            "set_gain" => {
-                self.supersaw.set_gain(event.param());
-                Ok(())
+            SupersawEvent::SetGain { parameter } => self.supersaw.set_gain(parameter),
+            SupersawEvent::SetChokeGroup { parameter } => {
+                self.set_choke_group("supersaw", parameter)
+            }
+            SupersawEvent::SetLofiAmount { parameter } => {
+                self.supersaw_lofi_left.set_amount(parameter);
+                self.supersaw_lofi_right.set_amount(parameter);
+            }
+            SupersawEvent::SetBaseFrequency { parameter } => {
+                self.supersaw.set_base_frequency(parameter)
             }
-            "set_base_frequency" => {
-                self.supersaw.set_base_frequency(event.param());
-                Ok(())
+            SupersawEvent::SetDetune { parameter } => self.supersaw.set_detune(parameter),
+            SupersawEvent::SetStereoWidth { parameter } => {
+                self.supersaw.set_stereo_width(parameter)
             }
-            "set_detune" => {
-                self.supersaw.set_detune(event.param());
-                Ok(())
+            SupersawEvent::SetDriftAmount { parameter } => {
+                self.supersaw.set_drift_amount(parameter)
             }
-            "set_stereo_width" => {
-                self.supersaw.set_stereo_width(event.param());
-                Ok(())
+            SupersawEvent::SetFilterCutoff { parameter } => {
+                self.supersaw.set_filter_cutoff(parameter)
             }
-            "set_filter_cutoff" => {
-                self.supersaw.set_filter_cutoff(event.param());
-                Ok(())
+            SupersawEvent::SetFilterResonance { parameter } => {
+                self.supersaw.set_filter_resonance(parameter)
             }
-            "set_filter_resonance" => {
-                self.supersaw.set_filter_resonance(event.param());
-                Ok(())
+            SupersawEvent::SetFilterEnvAmount { parameter } => {
+                self.supersaw.set_filter_env_amount(parameter)
             }
-            "set_filter_env_amount" => {
-                self.supersaw.set_filter_env_amount(event.param());
-                Ok(())
+            SupersawEvent::SetFilterKeytrack { parameter } => {
+                self.supersaw.set_filter_keytrack(parameter)
             }
-            "set_amp_attack" => {
-                self.supersaw.set_amp_attack(event.param());
-                Ok(())
+            SupersawEvent::SetFilterFmAmount { parameter } => {
+                self.supersaw.set_filter_fm_amount(parameter)
             }
-            "set_amp_release" => {
-                self.supersaw.set_amp_release(event.param());
-                Ok(())
+            SupersawEvent::SetFilterFmRatio { parameter } => {
+                self.supersaw.set_filter_fm_ratio(parameter)
             }
-            "set_filter_attack" => {
-                self.supersaw.set_filter_attack(event.param());
-                Ok(())
+            SupersawEvent::SetAmpAttack { parameter } => self.supersaw.set_amp_attack(parameter),
+            SupersawEvent::SetAmpRelease { parameter } => self.supersaw.set_amp_release(parameter),
+            SupersawEvent::SetFilterAttack { parameter } => {
+                self.supersaw.set_filter_attack(parameter)
             }
-            "set_filter_release" => {
-                self.supersaw.set_filter_release(event.param());
-                Ok(())
+            SupersawEvent::SetFilterRelease { parameter } => {
+                self.supersaw.set_filter_release(parameter)
             }
-            _ => Err(format!("Unknown supersaw event: {}", event.event)),
         }
     }
 
-    fn handle_reverb_event(&mut self, event: &crate::events::ClientEvent) -> Result<(), String> {
-        match event.event.as_str() {
-            "set_size" => {
-                self.reverb.set_size(event.param());
-                Ok(())
+    fn handle_pad_synth_event(&mut self, event: PadSynthEvent) -> Result<(), String> {
+        match event {
+            PadSynthEvent::Trigger { parameter } => {
+                self.choke_group_members("pad_synth");
+                self.pad_synth.trigger(parameter.unwrap_or(1.0));
             }
-            "set_modulation_depth" => {
-                self.reverb.set_modulation_depth(event.param());
-                Ok(())
+            PadSynthEvent::SetGain { parameter } => self.pad_synth.set_gain(parameter),
+            PadSynthEvent::SetChokeGroup { parameter } => {
+                self.set_choke_group("pad_synth", parameter)
             }
-            "set_feedback" => {
-                self.reverb.set_feedback(event.param());
-                Ok(())
+            PadSynthEvent::SetLofiAmount { parameter } => {
+                self.pad_synth_lofi_left.set_amount(parameter);
+                self.pad_synth_lofi_right.set_amount(parameter);
             }
-            _ => Err(format!("Unknown reverb event: {}", event.event)),
+            PadSynthEvent::SetBaseFrequency { parameter } => {
+                self.pad_synth.set_base_frequency(parameter)
+            }
+            PadSynthEvent::SetChordQuality { parameter } => {
+                let index = parameter.round().max(0.0) as u32;
+                let quality = ChordQuality::from_index(index)
+                    .ok_or_else(|| format!("Unknown chord quality: {}", index))?;
+                self.pad_synth.set_chord_quality(quality);
+            }
+            PadSynthEvent::SetDetune { parameter } => self.pad_synth.set_detune(parameter),
+            PadSynthEvent::SetAttack { parameter } => self.pad_synth.set_attack(parameter),
+            PadSynthEvent::SetRelease { parameter } => self.pad_synth.set_release(parameter),
+            PadSynthEvent::SetFilterCutoff { parameter } => {
+                self.pad_synth.set_filter_cutoff(parameter)
+            }
+            PadSynthEvent::SetChorusRate { parameter } => self.pad_synth.set_chorus_rate(parameter),
+            PadSynthEvent::SetChorusMix { parameter } => self.pad_synth.set_chorus_mix(parameter),
         }
+        Ok(())
     }
 
-    fn handle_system_event(&mut self, event: &crate::events::ClientEvent) -> Result<(), String> {
-        match event.event.as_str() {
-            "set_reverb_send" => {
-                self.set_reverb_send(event.param());
-                Ok(())
+    fn handle_bass808_event(&mut self, event: Bass808Event) {
+        match event {
+            Bass808Event::Trigger { parameter } => {
+                self.choke_group_members("bass808");
+                self.bass808.trigger(
+                    parameter.unwrap_or(1.0),
+                    parameter.is_some_and(|velocity| velocity > ACCENT_VELOCITY),
+                );
+            }
+            Bass808Event::SetGain { parameter } => self.bass808.set_gain(parameter),
+            Bass808Event::SetChokeGroup { parameter } => self.set_choke_group("bass808", parameter),
+            Bass808Event::SetLofiAmount { parameter } => self.bass808_lofi.set_amount(parameter),
+            Bass808Event::SetBaseFrequency { parameter } => {
+                self.bass808.set_base_frequency(parameter)
+            }
+            Bass808Event::SetGlide { parameter } => self.bass808.set_glide(parameter),
+            Bass808Event::SetShape { parameter } => self.bass808.set_shape(parameter),
+            Bass808Event::SetPhaseDistortion { parameter } => {
+                self.bass808.set_phase_distortion(parameter)
             }
-            "set_reverb_return" => {
-                self.set_reverb_return(event.param());
-                Ok(())
+            Bass808Event::SetDecay { parameter } => self.bass808.set_decay(parameter),
+            Bass808Event::SetDrive { parameter } => self.bass808.set_drive(parameter),
+            Bass808Event::SetAccent { parameter } => self.bass808.set_accent(parameter),
+            Bass808Event::SetKickDuck { parameter } => {
+                self.choke_groups
+                    .set_one_way("kick", "bass808", parameter > 0.5)
+            }
+        }
+    }
+
+    fn handle_acid_event(&mut self, event: AcidEvent) {
+        match event {
+            AcidEvent::Trigger { parameter, data } => {
+                let slide = data.is_some_and(|slide| slide > 0.5);
+                // A slide carries on the ringing note, so it doesn't choke
+                if !slide {
+                    self.choke_group_members("acid");
+                }
+                self.acid.trigger(
+                    parameter.unwrap_or(1.0),
+                    parameter.is_some_and(|velocity| velocity > ACCENT_VELOCITY),
+                    slide,
+                );
+            }
+            AcidEvent::SetGain { parameter } => self.acid.set_gain(parameter),
+            AcidEvent::SetChokeGroup { parameter } => self.set_choke_group("acid", parameter),
+            AcidEvent::SetLofiAmount { parameter } => self.acid_lofi.set_amount(parameter),
+            AcidEvent::SetBaseFrequency { parameter } => self.acid.set_base_frequency(parameter),
+            AcidEvent::SetSlideTime { parameter } => self.acid.set_slide_time(parameter),
+            AcidEvent::SetWaveform { parameter } => self.acid.set_waveform(parameter),
+            AcidEvent::SetCutoff { parameter } => self.acid.set_cutoff(parameter),
+            AcidEvent::SetResonance { parameter } => self.acid.set_resonance(parameter),
+            AcidEvent::SetEnvMod { parameter } => self.acid.set_env_mod(parameter),
+            AcidEvent::SetDecay { parameter } => self.acid.set_decay(parameter),
+            AcidEvent::SetAccent { parameter } => self.acid.set_accent(parameter),
+        }
+    }
+
+    fn handle_wavetable_event(&mut self, event: WavetableEvent) {
+        match event {
+            WavetableEvent::Trigger { parameter } => {
+                self.choke_group_members("wavetable");
+                self.wavetable.trigger(parameter.unwrap_or(1.0));
+            }
+            WavetableEvent::SetGain { parameter } => self.wavetable.set_gain(parameter),
+            WavetableEvent::SetChokeGroup { parameter } => {
+                self.set_choke_group("wavetable", parameter)
+            }
+            WavetableEvent::SetLofiAmount { parameter } => {
+                self.wavetable_lofi.set_amount(parameter)
+            }
+            WavetableEvent::SetBaseFrequency { parameter } => {
+                self.wavetable.set_base_frequency(parameter)
+            }
+            WavetableEvent::SetPosition { parameter } => self.wavetable.set_position(parameter),
+            WavetableEvent::SetLfoRate { parameter } => self.wavetable.set_lfo_rate(parameter),
+            WavetableEvent::SetLfoDepth { parameter } => self.wavetable.set_lfo_depth(parameter),
+            WavetableEvent::SetPositionEnvAmount { parameter } => {
+                self.wavetable.set_position_env_amount(parameter)
+            }
+            WavetableEvent::SetPositionAttack { parameter } => {
+                self.wavetable.set_position_attack(parameter)
+            }
+            WavetableEvent::SetPositionRelease { parameter } => {
+                self.wavetable.set_position_release(parameter)
+            }
+            WavetableEvent::SetFilterCutoff { parameter } => {
+                self.wavetable.set_filter_cutoff(parameter)
+            }
+            WavetableEvent::SetFilterResonance { parameter } => {
+                self.wavetable.set_filter_resonance(parameter)
+            }
+            WavetableEvent::SetAttack { parameter } => self.wavetable.set_attack(parameter),
+            WavetableEvent::SetRelease { parameter } => self.wavetable.set_release(parameter),
+        }
+    }
+
+    fn handle_fm6_event(&mut self, event: Fm6Event) -> Result<(), String> {
+        match event {
+            Fm6Event::Trigger { parameter } => {
+                self.choke_group_members("fm6");
+                self.fm6.trigger(parameter.unwrap_or(1.0));
+            }
+            Fm6Event::SetGain { parameter } => self.fm6.set_gain(parameter),
+            Fm6Event::SetChokeGroup { parameter } => self.set_choke_group("fm6", parameter),
+            Fm6Event::SetLofiAmount { parameter } => self.fm6_lofi.set_amount(parameter),
+            Fm6Event::SetBaseFrequency { parameter } => self.fm6.set_base_frequency(parameter),
+            Fm6Event::SetAlgorithm { parameter } => {
+                let index = parameter.round().max(0.0) as u32;
+                let algorithm = FM6Algorithm::from_index(index)
+                    .ok_or_else(|| format!("Unknown FM algorithm: {}", index))?;
+                self.fm6.set_algorithm(algorithm);
+            }
+            Fm6Event::SetFeedback { parameter } => self.fm6.set_feedback(parameter),
+            Fm6Event::SetOpRatio { data, parameter } => {
+                self.fm6.set_op_ratio(fm6_operator(data)?, parameter)
+            }
+            Fm6Event::SetOpLevel { data, parameter } => {
+                self.fm6.set_op_level(fm6_operator(data)?, parameter)
+            }
+            Fm6Event::SetOpAttack { data, parameter } => {
+                self.fm6.set_op_attack(fm6_operator(data)?, parameter)
+            }
+            Fm6Event::SetOpDecay { data, parameter } => {
+                self.fm6.set_op_decay(fm6_operator(data)?, parameter)
+            }
+            Fm6Event::SetOpSustain { data, parameter } => {
+                self.fm6.set_op_sustain(fm6_operator(data)?, parameter)
+            }
+            Fm6Event::SetAttack { parameter } => self.fm6.set_attack(parameter),
+            Fm6Event::SetRelease { parameter } => self.fm6.set_release(parameter),
+        }
+        Ok(())
+    }
+
+    fn handle_formant_event(&mut self, event: FormantEvent) {
+        match event {
+            FormantEvent::Trigger { parameter } => {
+                self.choke_group_members("formant");
+                self.formant.trigger(parameter.unwrap_or(1.0));
+            }
+            FormantEvent::SetGain { parameter } => self.formant.set_gain(parameter),
+            FormantEvent::SetChokeGroup { parameter } => self.set_choke_group("formant", parameter),
+            FormantEvent::SetLofiAmount { parameter } => self.formant_lofi.set_amount(parameter),
+            FormantEvent::SetBaseFrequency { parameter } => {
+                self.formant.set_base_frequency(parameter)
+            }
+            FormantEvent::SetVowel { parameter } => self.formant.set_vowel(parameter),
+            FormantEvent::SetFormantShift { parameter } => {
+                self.formant.set_formant_shift(parameter)
+            }
+            FormantEvent::SetWaveform { parameter } => self.formant.set_waveform(parameter),
+            FormantEvent::SetPwmDepth { parameter } => self.formant.set_pwm_depth(parameter),
+            FormantEvent::SetPwmRate { parameter } => self.formant.set_pwm_rate(parameter),
+            FormantEvent::SetResonance { parameter } => self.formant.set_resonance(parameter),
+            FormantEvent::SetAttack { parameter } => self.formant.set_attack(parameter),
+            FormantEvent::SetRelease { parameter } => self.formant.set_release(parameter),
+        }
+    }
+
+    fn handle_sampler_event(&mut self, event: SamplerEvent) -> Result<(), String> {
+        match event {
+            SamplerEvent::Trigger { parameter, data } => self
+                .sampler_pad(parameter.max(0.0) as usize)?
+                .trigger_with_velocity(data.unwrap_or(1.0)),
+            SamplerEvent::Stop { parameter } => {
+                self.sampler_pad(parameter.max(0.0) as usize)?.stop()
+            }
+            SamplerEvent::SetGain { parameter } => self.sampler_gain = parameter.clamp(0.0, 1.0),
+            SamplerEvent::SetBpm { parameter } => {
+                self.sampler_bpm = parameter.max(1.0);
+                self.update_sampler_speed();
+            }
+            SamplerEvent::SetLoopBpm { parameter } => {
+                self.sampler_loop_bpm = parameter.max(0.0);
+                self.update_sampler_speed();
+            }
+            SamplerEvent::SetStretchQuality { parameter } => {
+                let index = parameter.round().max(0.0) as u32;
+                let quality = StretchQuality::from_index(index)
+                    .ok_or_else(|| format!("Unknown stretch quality: {}", index))?;
+                for pad in &mut self.sampler_pads {
+                    pad.set_stretch_quality(quality);
+                }
+            }
+            SamplerEvent::SetGrainSize { parameter } => {
+                for pad in &mut self.sampler_pads {
+                    pad.set_grain_size(parameter);
+                }
+            }
+            SamplerEvent::SetPitch { parameter } => {
+                for pad in &mut self.sampler_pads {
+                    pad.set_pitch(parameter);
+                }
+            }
+            SamplerEvent::SetPitchEnvAmount { parameter } => {
+                for pad in &mut self.sampler_pads {
+                    pad.set_pitch_env_amount(parameter);
+                }
+            }
+            SamplerEvent::SetPitchAttack { parameter } => {
+                for pad in &mut self.sampler_pads {
+                    pad.set_pitch_attack(parameter);
+                }
+            }
+            SamplerEvent::SetPitchRelease { parameter } => {
+                for pad in &mut self.sampler_pads {
+                    pad.set_pitch_release(parameter);
+                }
+            }
+            SamplerEvent::SetFilterMode { parameter } => {
+                let mode = match parameter.round() as i32 {
+                    0 => FilterMode::Lowpass,
+                    1 => FilterMode::Highpass,
+                    2 => FilterMode::Bandpass,
+                    other => return Err(format!("Unknown filter mode: {}", other)),
+                };
+                for pad in &mut self.sampler_pads {
+                    pad.set_filter_mode(mode);
+                }
+            }
+            SamplerEvent::SetFilterCutoff { parameter } => {
+                for pad in &mut self.sampler_pads {
+                    pad.set_filter_cutoff(parameter);
+                }
+            }
+            SamplerEvent::SetFilterResonance { parameter } => {
+                for pad in &mut self.sampler_pads {
+                    pad.set_filter_resonance(parameter);
+                }
+            }
+            SamplerEvent::SetFilterEnvAmount { parameter } => {
+                for pad in &mut self.sampler_pads {
+                    pad.set_filter_env_amount(parameter);
+                }
+            }
+            SamplerEvent::SetFilterAttack { parameter } => {
+                for pad in &mut self.sampler_pads {
+                    pad.set_filter_attack(parameter);
+                }
+            }
+            SamplerEvent::SetFilterRelease { parameter } => {
+                for pad in &mut self.sampler_pads {
+                    pad.set_filter_release(parameter);
+                }
+            }
+            SamplerEvent::SetReverse { parameter } => {
+                for pad in &mut self.sampler_pads {
+                    pad.set_reverse(parameter >= 0.5);
+                }
+            }
+            SamplerEvent::SetStartOffset { parameter } => {
+                for pad in &mut self.sampler_pads {
+                    pad.set_start_offset(parameter);
+                }
+            }
+            SamplerEvent::SetPlayMode { parameter } => {
+                for pad in &mut self.sampler_pads {
+                    pad.set_looping(parameter >= 0.5);
+                }
+            }
+            SamplerEvent::AssignSample {
+                pad,
+                sample,
+                frames,
+            } => self.sampler_pad(pad)?.set_slice(sample, frames),
+            SamplerEvent::AddLayer {
+                pad,
+                sample,
+                frames,
+                velocity,
+            } => self.sampler_pad(pad)?.add_layer(sample, frames, velocity)?,
+        }
+        Ok(())
+    }
+
+    fn update_sampler_speed(&mut self) {
+        let speed = if self.sampler_loop_bpm > 0.0 {
+            self.sampler_bpm / self.sampler_loop_bpm
+        } else {
+            1.0
+        };
+        for pad in &mut self.sampler_pads {
+            pad.set_speed(speed);
+        }
+    }
+
+    fn sampler_pad(&mut self, pad: usize) -> Result<&mut SamplePlayer, String> {
+        self.sampler_pads
+            .get_mut(pad)
+            .ok_or_else(|| format!("No sampler pad {}, there are {}", pad, SAMPLER_PADS))
+    }
+
+    fn handle_reverb_event(&mut self, event: ReverbEvent) -> Result<(), String> {
+        match event {
+            ReverbEvent::SetSize { parameter } => self.reverb.set_size(parameter),
+            ReverbEvent::SetModulationDepth { parameter } => {
+                self.reverb.set_modulation_depth(parameter)
+            }
+            ReverbEvent::SetFeedback { parameter } => self.reverb.set_feedback(parameter),
+            ReverbEvent::SetQuality { parameter } => {
+                let index = parameter.round().max(0.0) as u32;
+                let quality = ReverbQuality::from_index(index)
+                    .ok_or_else(|| format!("Unknown reverb quality: {}", index))?;
+                self.reverb.set_quality(quality);
+            }
+            ReverbEvent::SetDuckAmount { parameter } => self.reverb_ducker.set_amount(parameter),
+            ReverbEvent::SetDuckRelease { parameter } => {
+                self.reverb_ducker.set_release_time(parameter)
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_eq_event(&mut self, event: EqEvent) {
+        for eq in [&mut self.eq_left, &mut self.eq_right] {
+            match event {
+                EqEvent::SetLowFrequency { parameter } => eq.set_low_frequency(parameter),
+                EqEvent::SetLowGain { parameter } => eq.set_low_gain(parameter),
+                EqEvent::SetMidFrequency { parameter } => eq.set_mid_frequency(parameter),
+                EqEvent::SetMidGain { parameter } => eq.set_mid_gain(parameter),
+                EqEvent::SetMidQ { parameter } => eq.set_mid_q(parameter),
+                EqEvent::SetHighFrequency { parameter } => eq.set_high_frequency(parameter),
+                EqEvent::SetHighGain { parameter } => eq.set_high_gain(parameter),
+            }
+        }
+    }
+
+    fn handle_system_event(&mut self, event: AuditionerSystemEvent) {
+        match event {
+            AuditionerSystemEvent::SetReverbSend { parameter } => self.set_reverb_send(parameter),
+            AuditionerSystemEvent::SetReverbReturn { parameter } => {
+                self.set_reverb_return(parameter)
             }
-            _ => Err(format!("Unknown system event: {}", event.event)),
         }
     }
 }
 
 impl AudioSystem for AuditionerSystem {
-    fn handle_client_event(&mut self, event: &crate::events::ClientEvent) -> Result<(), String> {
-        match event.node.as_str() {
-            "kick" => self.handle_kick_event(event),
-            "clap" => self.handle_clap_event(event),
-            "hihat" => self.handle_hihat_event(event),
-            "chord" => self.handle_chord_event(event),
-            "supersaw" => self.handle_supersaw_event(event),
-            "reverb" => self.handle_reverb_event(event),
-            "system" => self.handle_system_event(event),
-            _ => Err(format!("Unknown node '{}' for auditioner system", event.node)),
+    fn set_seed(&mut self, seed: u64) {
+        // Each random source gets its own stream derived from the seed
+        let mut rng = fastrand::Rng::with_seed(seed);
+        self.clap.set_seed(rng.u64(..));
+        self.snare.set_seed(rng.u64(..));
+        self.hihat.set_seed(rng.u64(..));
+        self.open_hat.set_seed(rng.u64(..));
+        self.reverb.set_seed(rng.u64(..));
+        self.tom.set_seed(rng.u64(..));
+        self.modal_drum.set_seed(rng.u64(..));
+        self.glitch.set_seed(rng.u64(..));
+        self.kick.set_seed(rng.u64(..));
+        self.chord.set_seed(rng.u64(..));
+        self.supersaw.set_seed(rng.u64(..));
+    }
+
+    /// Nudge the reverb send and drift the supersaw filter
+    fn evolve(&mut self, mutations: &mut Mutations) {
+        self.set_reverb_send(mutations.nudge(self.reverb_send, 0.1, 0.0, 1.0));
+        let cutoff = mutations.drift_octaves(self.supersaw.filter_cutoff(), 0.5);
+        self.supersaw.set_filter_cutoff(cutoff);
+        self.sync_parameter(
+            "supersaw",
+            "set_filter_cutoff",
+            self.supersaw.filter_cutoff(),
+        );
+    }
+
+    fn handle_client_event(&mut self, event: ClientEvent) -> Result<(), String> {
+        let ClientEvent::Auditioner(event) = event else {
+            return Err(format!(
+                "Auditioner system can't handle {} events",
+                event.system_name()
+            ));
+        };
+
+        match event {
+            AuditionerEvent::Kick(event) => self.handle_kick_event(event),
+            AuditionerEvent::Clap(event) => self.handle_clap_event(event),
+            AuditionerEvent::Snare(event) => self.handle_snare_event(event)?,
+            AuditionerEvent::Hihat(event) => self.handle_hihat_event(event),
+            AuditionerEvent::OpenHat(event) => self.handle_open_hat_event(event),
+            AuditionerEvent::Tom(event) => self.handle_tom_event(event),
+            AuditionerEvent::ModalDrum(event) => self.handle_modal_drum_event(event),
+            AuditionerEvent::Cowbell(event) => self.handle_cowbell_event(event),
+            AuditionerEvent::Clave(event) => self.handle_clave_event(event),
+            AuditionerEvent::Glitch(event) => self.handle_glitch_event(event),
+            AuditionerEvent::Cymbal(event) => self.handle_cymbal_event(event)?,
+            AuditionerEvent::Chord(event) => self.handle_chord_event(event)?,
+            AuditionerEvent::Supersaw(event) => self.handle_supersaw_event(event),
+            AuditionerEvent::PadSynth(event) => self.handle_pad_synth_event(event)?,
+            AuditionerEvent::Bass808(event) => self.handle_bass808_event(event),
+            AuditionerEvent::Acid(event) => self.handle_acid_event(event),
+            AuditionerEvent::Wavetable(event) => self.handle_wavetable_event(event),
+            AuditionerEvent::Fm6(event) => self.handle_fm6_event(event)?,
+            AuditionerEvent::Formant(event) => self.handle_formant_event(event),
+            AuditionerEvent::Reverb(event) => self.handle_reverb_event(event)?,
+            AuditionerEvent::Eq(event) => self.handle_eq_event(event),
+            AuditionerEvent::Sampler(event) => self.handle_sampler_event(event)?,
+            AuditionerEvent::System(event) => self.handle_system_event(event),
         }
+        Ok(())
     }
 
     fn next_sample(&mut self) -> (f32, f32) {
-        // Generate samples from mono instruments
-        let kick_sample = self.kick.next_sample();
-        let clap_sample = self.clap.next_sample();
-        let hihat_sample = self.hihat.next_sample();
-        let chord_sample = self.chord.next_sample();
-        
-        // Generate stereo sample from supersaw
+        // Generate samples from mono instruments, each through its lo-fi stage
+        let kick_sample = self.kick_lofi.process(self.kick.next_sample());
+        let clap_sample = self.clap_lofi.process(self.clap.next_sample());
+        let snare_sample = self.snare_lofi.process(self.snare.next_sample());
+        let hihat_sample = self.hihat_lofi.process(self.hihat.next_sample());
+        let open_hat_sample = self.open_hat_lofi.process(self.open_hat.next_sample());
+        let tom_sample = self.tom_lofi.process(self.tom.next_sample());
+        let modal_drum_sample = self.modal_drum_lofi.process(self.modal_drum.next_sample());
+        let cowbell_sample = self.cowbell_lofi.process(self.cowbell.next_sample());
+        let clave_sample = self.clave_lofi.process(self.clave.next_sample());
+        let glitch_sample = self.glitch_lofi.process(self.glitch.next_sample());
+        let cymbal_sample = self.cymbal_lofi.process(self.cymbal.next_sample());
+        let bass808_sample = self.bass808_lofi.process(self.bass808.next_sample());
+        let acid_sample = self.acid_lofi.process(self.acid.next_sample());
+        let wavetable_sample = self.wavetable_lofi.process(self.wavetable.next_sample());
+        let fm6_sample = self.fm6_lofi.process(self.fm6.next_sample());
+        let formant_sample = self.formant_lofi.process(self.formant.next_sample());
+
+        // Generate stereo samples from the chord, supersaw and pad
+        let (chord_left, chord_right) = self.chord.next_sample();
+        let chord_left = self.chord_lofi_left.process(chord_left);
+        let chord_right = self.chord_lofi_right.process(chord_right);
         let (supersaw_left, supersaw_right) = self.supersaw.next_sample();
+        let supersaw_left = self.supersaw_lofi_left.process(supersaw_left);
+        let supersaw_right = self.supersaw_lofi_right.process(supersaw_right);
+        let (pad_synth_left, pad_synth_right) = self.pad_synth.next_sample();
+        let pad_synth_left = self.pad_synth_lofi_left.process(pad_synth_left);
+        let pad_synth_right = self.pad_synth_lofi_right.process(pad_synth_right);
+
+        // Sum the sample pads
+        let (sampler_left, sampler_right) = self
+            .sampler_pads
+            .iter_mut()
+            .map(|pad| pad.next_sample())
+            .fold((0.0, 0.0), |(left, right), (l, r)| (left + l, right + r));
 
         // Mix all instruments
+        let mono_mix = kick_sample
+            + clap_sample
+            + snare_sample
+            + hihat_sample
+            + open_hat_sample
+            + tom_sample
+            + modal_drum_sample
+            + cowbell_sample
+            + clave_sample
+            + glitch_sample
+            + cymbal_sample
+            + bass808_sample
+            + acid_sample
+            + wavetable_sample
+            + fm6_sample
+            + formant_sample;
         let dry_signal = (
-            kick_sample + clap_sample + hihat_sample + chord_sample + supersaw_left,
-            kick_sample + clap_sample + hihat_sample + chord_sample + supersaw_right,
+            mono_mix
+                + chord_left
+                + supersaw_left
+                + pad_synth_left
+                + sampler_left * self.sampler_gain,
+            mono_mix
+                + chord_right
+                + supersaw_right
+                + pad_synth_right
+                + sampler_right * self.sampler_gain,
         );
 
         // Send to reverb and mix with dry signal
-        let reverb_input = (dry_signal.0 * self.reverb_send, dry_signal.1 * self.reverb_send);
+        let reverb_input = (
+            dry_signal.0 * self.reverb_send,
+            dry_signal.1 * self.reverb_send,
+        );
         let reverb_output = self.reverb.process(reverb_input.0, reverb_input.1);
 
-        // Final mix: dry signal + reverb return
+        // Duck the return under the dry signal so tails bloom between hits
+        let duck_gain = self
+            .reverb_ducker
+            .process(0.5 * (dry_signal.0 + dry_signal.1));
+        let reverb_return = self.reverb_return * duck_gain;
+
+        // Final mix: dry signal + reverb return, through the master EQ
         (
-            dry_signal.0 + reverb_output.0 * self.reverb_return,
-            dry_signal.1 + reverb_output.1 * self.reverb_return,
+            self.eq_left
+                .process(dry_signal.0 + reverb_output.0 * reverb_return),
+            self.eq_right
+                .process(dry_signal.1 + reverb_output.1 * reverb_return),
         )
     }
 
-
     fn set_sample_rate(&mut self, sample_rate: f32) {
         self.sample_rate = sample_rate;
         self.kick.set_sample_rate(sample_rate);
         self.clap.set_sample_rate(sample_rate);
+        self.snare.set_sample_rate(sample_rate);
         self.hihat.set_sample_rate(sample_rate);
+        self.open_hat.set_sample_rate(sample_rate);
+        self.tom.set_sample_rate(sample_rate);
+        self.modal_drum.set_sample_rate(sample_rate);
+        self.cowbell.set_sample_rate(sample_rate);
+        self.clave.set_sample_rate(sample_rate);
+        self.glitch.set_sample_rate(sample_rate);
+        self.cymbal.set_sample_rate(sample_rate);
         self.chord.set_sample_rate(sample_rate);
         self.supersaw.set_sample_rate(sample_rate);
+        self.pad_synth.set_sample_rate(sample_rate);
+        self.bass808.set_sample_rate(sample_rate);
+        self.acid.set_sample_rate(sample_rate);
+        self.wavetable.set_sample_rate(sample_rate);
+        self.fm6.set_sample_rate(sample_rate);
+        self.formant.set_sample_rate(sample_rate);
+        for pad in &mut self.sampler_pads {
+            pad.set_sample_rate(sample_rate);
+        }
         self.reverb.set_sample_rate(sample_rate);
+        self.reverb_ducker.set_sample_rate(sample_rate);
+        self.eq_left.set_sample_rate(sample_rate);
+        self.eq_right.set_sample_rate(sample_rate);
     }
 }
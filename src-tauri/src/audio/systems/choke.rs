@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+/// Assigns a system's nodes to choke groups.
+/// Triggering one member of a group fast-releases the other members.
+/// One-way links choke a node without it choking back, for ducking a bass
+/// under the kick.
+pub struct ChokeGroups {
+    groups: HashMap<String, u32>,
+    // Trigger and target of each link; checked on every trigger, so the
+    // names are static to compare without allocating
+    one_way: Vec<(&'static str, &'static str)>,
+}
+
+impl ChokeGroups {
+    pub fn new() -> Self {
+        Self {
+            groups: HashMap::new(),
+            one_way: Vec::new(),
+        }
+    }
+
+    /// Put a node in a group; group 0 removes it from any group
+    pub fn set_group(&mut self, node: &str, group: u32) {
+        if group == 0 {
+            self.groups.remove(node);
+        } else {
+            self.groups.insert(node.to_string(), group);
+        }
+    }
+
+    pub fn group_of(&self, node: &str) -> Option<u32> {
+        self.groups.get(node).copied()
+    }
+
+    /// Make triggering `trigger_node` choke `target_node`, whatever their groups
+    pub fn set_one_way(
+        &mut self,
+        trigger_node: &'static str,
+        target_node: &'static str,
+        enabled: bool,
+    ) {
+        let link = (trigger_node, target_node);
+        self.one_way.retain(|&existing| existing != link);
+        if enabled {
+            self.one_way.push(link);
+        }
+    }
+
+    /// True when triggering `trigger_node` should choke `other_node`
+    pub fn chokes(&self, trigger_node: &str, other_node: &str) -> bool {
+        if trigger_node == other_node {
+            return false;
+        }
+        let shares_group = self
+            .group_of(trigger_node)
+            .is_some_and(|group| self.group_of(other_node) == Some(group));
+        shares_group
+            || self
+                .one_way
+                .iter()
+                .any(|&(from, to)| from == trigger_node && to == other_node)
+    }
+}
+
+impl Default for ChokeGroups {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_members_of_a_group_choke_each_other() {
+        let mut groups = ChokeGroups::new();
+        groups.set_group("hihat", 1);
+        groups.set_group("clap", 1);
+        groups.set_group("kick", 2);
+
+        assert!(groups.chokes("hihat", "clap"));
+        assert!(groups.chokes("clap", "hihat"));
+        assert!(!groups.chokes("hihat", "hihat"));
+        assert!(!groups.chokes("hihat", "kick"));
+        assert!(!groups.chokes("chord", "supersaw"));
+
+        groups.set_group("clap", 0);
+        assert_eq!(groups.group_of("clap"), None);
+        assert!(!groups.chokes("hihat", "clap"));
+    }
+
+    #[test]
+    fn test_one_way_links_only_choke_forwards() {
+        let mut groups = ChokeGroups::new();
+        groups.set_one_way("kick", "bass808", true);
+
+        assert!(groups.chokes("kick", "bass808"));
+        assert!(!groups.chokes("bass808", "kick"));
+
+        groups.set_one_way("kick", "bass808", false);
+        assert!(!groups.chokes("kick", "bass808"));
+    }
+}
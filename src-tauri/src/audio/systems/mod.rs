@@ -1,5 +1,9 @@
 pub mod auditioner;
+pub mod choke;
+pub mod registry;
 pub mod trance_riff;
 
 pub use auditioner::AuditionerSystem;
+pub use choke::ChokeGroups;
+pub use registry::{SystemInfo, SystemRegistry};
 pub use trance_riff::TranceRiffSystem;
@@ -0,0 +1,154 @@
+use crate::audio::server::MASTER_SYSTEM_NAME;
+use crate::audio::systems::{AuditionerSystem, TranceRiffSystem};
+use crate::audio::AudioSystem;
+use crate::events::ServerEventSender;
+
+/// Builds a system for the given sample rate, with a sender for its server events
+pub type SystemConstructor = Box<dyn Fn(f32, ServerEventSender) -> Box<dyn AudioSystem>>;
+
+/// Name and description of a registered system, as listed to the UI
+#[derive(Debug, Clone, PartialEq)]
+pub struct SystemInfo {
+    pub name: String,
+    pub description: String,
+}
+
+struct SystemEntry {
+    info: SystemInfo,
+    constructor: SystemConstructor,
+}
+
+/// Systems the audio server can host, in registration order.
+/// The first registered system is the one active at startup.
+pub struct SystemRegistry {
+    entries: Vec<SystemEntry>,
+}
+
+impl SystemRegistry {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// A registry holding the systems that ship with the app
+    pub fn with_builtin_systems() -> Self {
+        let mut registry = Self::new();
+        registry
+            .register(
+                "auditioner",
+                "Trigger and tweak each instrument on its own",
+                |sample_rate, event_sender| {
+                    Box::new(AuditionerSystem::new(sample_rate, event_sender))
+                },
+            )
+            .unwrap();
+        registry
+            .register(
+                "trance_riff",
+                "Sequenced supersaw riffs with groove and metronome",
+                |sample_rate, event_sender| {
+                    Box::new(TranceRiffSystem::new(sample_rate, event_sender))
+                },
+            )
+            .unwrap();
+        registry
+    }
+
+    /// Add a system, replacing any existing system with the same name
+    pub fn register<F>(
+        &mut self,
+        name: &str,
+        description: &str,
+        constructor: F,
+    ) -> Result<(), String>
+    where
+        F: Fn(f32, ServerEventSender) -> Box<dyn AudioSystem> + 'static,
+    {
+        if name == MASTER_SYSTEM_NAME {
+            return Err(format!("System name '{}' is reserved", name));
+        }
+
+        let entry = SystemEntry {
+            info: SystemInfo {
+                name: name.to_string(),
+                description: description.to_string(),
+            },
+            constructor: Box::new(constructor),
+        };
+        if let Some(existing) = self.entries.iter_mut().find(|e| e.info.name == name) {
+            *existing = entry;
+        } else {
+            self.entries.push(entry);
+        }
+        Ok(())
+    }
+
+    pub fn systems(&self) -> Vec<SystemInfo> {
+        self.entries.iter().map(|e| e.info.clone()).collect()
+    }
+
+    /// Construct every registered system, paired with its name
+    pub fn build(
+        &self,
+        sample_rate: f32,
+        event_sender: &ServerEventSender,
+    ) -> Vec<(String, Box<dyn AudioSystem>)> {
+        self.entries
+            .iter()
+            .map(|e| {
+                let system = (e.constructor)(sample_rate, event_sender.clone());
+                (e.info.name.clone(), system)
+            })
+            .collect()
+    }
+}
+
+impl Default for SystemRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{ClientEvent, ServerEventQueue};
+
+    struct Silence;
+
+    impl AudioSystem for Silence {
+        fn next_sample(&mut self) -> (f32, f32) {
+            (0.0, 0.0)
+        }
+
+        fn handle_client_event(&mut self, _event: ClientEvent) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn set_sample_rate(&mut self, _sample_rate: f32) {}
+    }
+
+    #[test]
+    fn test_register_replaces_by_name_and_keeps_order() {
+        let mut registry = SystemRegistry::with_builtin_systems();
+        registry
+            .register("drone", "Silence", |_, _| Box::new(Silence))
+            .unwrap();
+        registry
+            .register("auditioner", "Replaced", |_, _| Box::new(Silence))
+            .unwrap();
+        assert!(registry
+            .register(MASTER_SYSTEM_NAME, "Nope", |_, _| Box::new(Silence))
+            .is_err());
+
+        let names: Vec<String> = registry.systems().into_iter().map(|s| s.name).collect();
+        assert_eq!(names, vec!["auditioner", "trance_riff", "drone"]);
+        assert_eq!(registry.systems()[0].description, "Replaced");
+
+        let queue = ServerEventQueue::new();
+        let built = registry.build(44100.0, &queue.sender());
+        assert_eq!(built.len(), 3);
+        assert_eq!(built[2].0, "drone");
+    }
+}
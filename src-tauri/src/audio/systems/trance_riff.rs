@@ -1,27 +1,61 @@
-use crate::audio::instruments::SupersawSynth;
-use crate::audio::{AudioSystem, StereoAudioGenerator};
-use crate::sequencing::{PPQNClock, TonalSequencer};
+use crate::audio::evolve::Mutations;
+use crate::audio::instruments::{Metronome, SupersawSynth};
+use crate::audio::{AudioGenerator, AudioSystem, StereoAudioGenerator};
+use crate::events::{
+    ClientEvent, MetronomeEvent, PatternStep, ServerEvent, ServerEventSender, SynthEvent,
+    TranceRiffEvent, TranceRiffSystemEvent,
+};
+use crate::sequencing::{
+    ClockSource, Groove, GrooveTemplate, PPQNClock, TonalSequencer, Transport, TransportClock,
+};
+
+/// Track id the riff's steps are reported under
+const RIFF_TRACK: &str = "supersaw";
+
+/// Velocity a note silenced by evolve comes back at
+const GHOST_VELOCITY: f32 = 0.4;
+
+/// How often the beat phase is reported while playing, enough for smooth
+/// animation without flooding the UI
+const BEAT_PHASE_RATE_HZ: f32 = 30.0;
 
 /// Main TranceRiff system using TonalSequencer
 pub struct TranceRiffSystem {
     synth: SupersawSynth,
+    metronome: Metronome,
+    metronome_enabled: bool,
     sequencer: TonalSequencer,
     ppqn_clock: PPQNClock,
-    is_paused: bool,
+    transport: Transport,
+    groove: Groove,
+    // Note held back by the groove: (frequency, velocity) and samples to wait
+    pending_note: Option<(f32, f32)>,
+    pending_delay_samples: u32,
+    // Samples until the next beat phase report
+    beat_phase_countdown: u32,
+    event_sender: ServerEventSender,
     sample_rate: f32,
 }
 
 impl TranceRiffSystem {
-    pub fn new(sample_rate: f32) -> Self {
+    pub fn new(sample_rate: f32, event_sender: ServerEventSender) -> Self {
         let bpm = 138.0; // Classic trance BPM
         let mut ppqn_clock = PPQNClock::new(sample_rate);
         ppqn_clock.set_bpm(bpm);
+        let transport = Transport::new(ppqn_clock.ppqn());
 
         Self {
             synth: SupersawSynth::new(sample_rate),
+            metronome: Metronome::new(sample_rate),
+            metronome_enabled: false,
             sequencer: TonalSequencer::new(),
             ppqn_clock,
-            is_paused: false,
+            transport,
+            groove: Groove::new(),
+            pending_note: None,
+            pending_delay_samples: 0,
+            beat_phase_countdown: 0,
+            event_sender,
             sample_rate,
         }
     }
@@ -31,109 +65,222 @@ impl TranceRiffSystem {
     }
 
     pub fn set_paused(&mut self, paused: bool) {
-        self.is_paused = paused;
+        if paused {
+            self.transport.pause();
+        } else {
+            self.transport.continue_playback();
+        }
+    }
+
+    pub fn play(&mut self) {
+        self.transport.play();
+    }
+
+    /// Stop playback and rewind to the first bar
+    pub fn stop(&mut self) {
+        self.transport.stop();
+        self.sequencer.reset();
+        self.pending_note = None;
+        self.ppqn_clock.reset();
+        self.send_position();
+    }
+
+    /// Jump to the start of a bar (1-based)
+    pub fn locate(&mut self, bar: u32) {
+        self.transport.locate_bar(bar);
+        self.sequencer
+            .locate_pulse(self.transport.bar_start_pulse(bar));
+        self.pending_note = None;
+        self.ppqn_clock.reset();
+        self.send_position();
+    }
+
+    fn samples_per_step(&self) -> f32 {
+        // Steps are 16th notes
+        60.0 * self.sample_rate / (self.ppqn_clock.bpm() * 4.0)
+    }
+
+    /// Queue a note, delayed by the given number of samples.
+    /// A note still waiting from an earlier step plays immediately.
+    fn schedule_note(&mut self, frequency: f32, velocity: f32, delay_samples: u32) {
+        if let Some((pending_frequency, pending_velocity)) = self.pending_note.take() {
+            self.play_note(pending_frequency, pending_velocity);
+        }
+        self.pending_note = Some((frequency, velocity));
+        self.pending_delay_samples = delay_samples;
+    }
+
+    fn play_note(&mut self, frequency: f32, velocity: f32) {
+        self.synth.set_base_frequency(frequency);
+        self.synth.set_velocity(velocity);
+        self.synth.trigger();
+    }
+
+    fn send_groove_templates(&self) {
+        self.event_sender.send(ServerEvent::with_data(
+            "trance_riff",
+            "system",
+            "groove_templates",
+            serde_json::json!({
+                "templates": self.groove.template_names(),
+                "current": self.groove.current_name(),
+            }),
+        ));
+    }
+
+    fn send_position(&self) {
+        let position = self.transport.position();
+        self.event_sender.send(ServerEvent::with_param_and_data(
+            "trance_riff",
+            "system",
+            "transport_position",
+            if self.transport.is_playing() {
+                1.0
+            } else {
+                0.0
+            },
+            serde_json::json!({
+                "bar": position.bar,
+                "beat": position.beat,
+                "step": position.step,
+            }),
+        ));
+    }
+
+    fn send_beat_phase(&mut self) {
+        if self.beat_phase_countdown > 0 {
+            self.beat_phase_countdown -= 1;
+            return;
+        }
+        self.beat_phase_countdown = (self.sample_rate / BEAT_PHASE_RATE_HZ) as u32;
+
+        let beat_phase = self.transport.beat_phase(self.ppqn_clock.pulse_phase());
+        self.event_sender
+            .send(ServerEvent::beat_phase("trance_riff", beat_phase));
     }
 
     pub fn set_sequence(&mut self, sequence: Vec<(f32, u32, f32)>) {
         self.sequencer.set_sequence(sequence);
+        self.send_pattern();
     }
 
-    fn handle_synth_event(&mut self, event: &crate::events::ClientEvent) -> Result<(), String> {
-        match event.event.as_str() {
-            "trigger" => {
-                self.synth.trigger();
-                Ok(())
-            }
-            "set_gain" => {
-                self.synth.set_gain(event.param());
-                Ok(())
-            }
-            "set_base_frequency" => {
-                self.synth.set_base_frequency(event.param());
-                Ok(())
-            }
-            "set_detune" => {
-                self.synth.set_detune(event.param());
-                Ok(())
-            }
-            "set_stereo_width" => {
-                self.synth.set_stereo_width(event.param());
-                Ok(())
-            }
-            "set_filter_cutoff" => {
-                self.synth.set_filter_cutoff(event.param());
-                Ok(())
-            }
-            "set_filter_resonance" => {
-                self.synth.set_filter_resonance(event.param());
-                Ok(())
-            }
-            "set_filter_env_amount" => {
-                self.synth.set_filter_env_amount(event.param());
-                Ok(())
-            }
-            "set_amp_attack" => {
-                self.synth.set_amp_attack(event.param());
-                Ok(())
+    fn send_pattern(&self) {
+        // Rests are stored as zero-frequency notes
+        let steps: Vec<PatternStep> = self
+            .sequencer
+            .sequence()
+            .iter()
+            .map(|&(frequency, length, velocity)| PatternStep {
+                velocity: if frequency > 0.0 { velocity } else { 0.0 },
+                frequency: (frequency > 0.0).then_some(frequency),
+                length,
+            })
+            .collect();
+        self.event_sender.send(ServerEvent::pattern_updated(
+            "trance_riff",
+            RIFF_TRACK,
+            &steps,
+        ));
+    }
+
+    /// The index of a random note, skipping rests
+    fn random_note(&self, mutations: &mut Mutations) -> Option<usize> {
+        let notes = || {
+            self.sequencer
+                .sequence()
+                .iter()
+                .enumerate()
+                .filter(|(_, note)| note.0 > 0.0)
+        };
+        let count = notes().count();
+        if count == 0 {
+            return None;
+        }
+        notes().nth(mutations.index(count)).map(|(index, _)| index)
+    }
+
+    fn handle_synth_event(&mut self, event: SynthEvent) {
+        match event {
+            SynthEvent::Trigger => self.synth.trigger(),
+            SynthEvent::SetGain { parameter } => self.synth.set_gain(parameter),
+            SynthEvent::SetBaseFrequency { parameter } => self.synth.set_base_frequency(parameter),
+            SynthEvent::SetDetune { parameter } => self.synth.set_detune(parameter),
+            SynthEvent::SetStereoWidth { parameter } => self.synth.set_stereo_width(parameter),
+            SynthEvent::SetFilterCutoff { parameter } => self.synth.set_filter_cutoff(parameter),
+            SynthEvent::SetFilterResonance { parameter } => {
+                self.synth.set_filter_resonance(parameter)
             }
-            "set_amp_release" => {
-                self.synth.set_amp_release(event.param());
-                Ok(())
+            SynthEvent::SetFilterEnvAmount { parameter } => {
+                self.synth.set_filter_env_amount(parameter)
             }
-            "set_filter_attack" => {
-                self.synth.set_filter_attack(event.param());
-                Ok(())
+            SynthEvent::SetFilterKeytrack { parameter } => {
+                self.synth.set_filter_keytrack(parameter)
             }
-            "set_filter_release" => {
-                self.synth.set_filter_release(event.param());
-                Ok(())
+            SynthEvent::SetFilterFmAmount { parameter } => {
+                self.synth.set_filter_fm_amount(parameter)
             }
-            _ => Err(format!("Unknown synth event: {}", event.event)),
+            SynthEvent::SetFilterFmRatio { parameter } => self.synth.set_filter_fm_ratio(parameter),
+            SynthEvent::SetAmpAttack { parameter } => self.synth.set_amp_attack(parameter),
+            SynthEvent::SetAmpRelease { parameter } => self.synth.set_amp_release(parameter),
+            SynthEvent::SetFilterAttack { parameter } => self.synth.set_filter_attack(parameter),
+            SynthEvent::SetFilterRelease { parameter } => self.synth.set_filter_release(parameter),
         }
     }
 
-    fn handle_system_event(&mut self, event: &crate::events::ClientEvent) -> Result<(), String> {
-        match event.event.as_str() {
-            "set_bpm" => {
-                self.set_bpm(event.param());
-                Ok(())
+    fn handle_metronome_event(&mut self, event: MetronomeEvent) {
+        match event {
+            MetronomeEvent::SetEnabled { parameter } => self.metronome_enabled = parameter != 0.0,
+            MetronomeEvent::SetVolume { parameter } => self.metronome.set_volume(parameter),
+            MetronomeEvent::SetAccentDownbeat { parameter } => {
+                self.metronome.set_accent_downbeat(parameter != 0.0)
             }
-            "set_paused" => {
-                self.set_paused(event.param() > 0.5);
-                Ok(())
+            MetronomeEvent::SetCountInBars { parameter } => self
+                .transport
+                .set_count_in_bars(parameter.round().max(0.0) as u32),
+        }
+    }
+
+    fn handle_system_event(&mut self, event: TranceRiffSystemEvent) -> Result<(), String> {
+        match event {
+            TranceRiffSystemEvent::SetBpm { parameter } => self.set_bpm(parameter),
+            TranceRiffSystemEvent::SetPaused { parameter } => self.set_paused(parameter > 0.5),
+            TranceRiffSystemEvent::Play => self.play(),
+            TranceRiffSystemEvent::Stop => self.stop(),
+            TranceRiffSystemEvent::Continue => self.transport.continue_playback(),
+            TranceRiffSystemEvent::Locate { parameter } => self.locate(parameter.max(1.0) as u32),
+            TranceRiffSystemEvent::GetPosition => self.send_position(),
+            TranceRiffSystemEvent::SetGroove { data } => self.groove.select(&data)?,
+            TranceRiffSystemEvent::SetGrooveStrength { parameter } => {
+                self.groove.set_strength(parameter)
             }
-            "set_sequence" => {
-                // This will be sent from frontend with sequence data
-                if let Some(data) = &event.data {
-                    if let Some(sequence_data) = data.as_array() {
-                        let mut sequence = Vec::new();
-                        for item in sequence_data.iter() {
-                            if let Some(note) = item.as_array() {
-                                if note.len() >= 3 {
-                                    let freq = note[0].as_f64().unwrap_or(0.0) as f32;
-                                    let duration_pulses = note[1].as_f64().unwrap_or(0.0) as u32;
-                                    let velocity = note[2].as_f64().unwrap_or(1.0) as f32;
-                                    sequence.push((freq, duration_pulses, velocity));
-                                }
-                            }
-                        }
-                        self.set_sequence(sequence);
-                    }
-                }
-                Ok(())
+            TranceRiffSystemEvent::LoadGroove { data } => {
+                let template = GrooveTemplate::from_json(&data)?;
+                let name = template.name.clone();
+                self.groove.add_template(template);
+                self.groove.select(&name)?;
+                self.send_groove_templates();
             }
-            "reset_sequence" => {
-                self.sequencer.reset();
-                Ok(())
+            TranceRiffSystemEvent::GetGrooveTemplates => self.send_groove_templates(),
+            TranceRiffSystemEvent::SetSequence { data } => self.set_sequence(data),
+            TranceRiffSystemEvent::ResetSequence => self.sequencer.reset(),
+            TranceRiffSystemEvent::SetClockSource { parameter } => {
+                self.ppqn_clock.set_source(if parameter > 0.5 {
+                    ClockSource::External
+                } else {
+                    ClockSource::Internal
+                })
             }
-            _ => Err(format!("Unknown system event: {}", event.event)),
+            TranceRiffSystemEvent::SyncClock { data, parameter } => self
+                .ppqn_clock
+                .sync_external(data * self.ppqn_clock.ppqn() as f64, parameter),
         }
+        Ok(())
     }
 }
 
 impl AudioSystem for TranceRiffSystem {
     fn next_sample(&mut self) -> (f32, f32) {
-        if self.is_paused {
+        if !self.transport.is_running() {
             return (0.0, 0.0);
         }
 
@@ -141,34 +288,135 @@ impl AudioSystem for TranceRiffSystem {
         let is_new_pulse = self.ppqn_clock.tick();
 
         if is_new_pulse {
-            // Process pulse event in sequencer
-            let (should_trigger, frequency, velocity) = self.sequencer.on_pulse();
+            // Report the timeline position once per step
+            let is_step_start = self.transport.on_pulse();
+            if is_step_start {
+                self.send_position();
+            }
 
-            // Trigger new notes when needed
-            if should_trigger && frequency > 0.0 {
-                self.synth.set_base_frequency(frequency);
-                self.synth.trigger();
+            // The count-in always clicks; during playback only when enabled
+            let is_count_in = self.transport.last_pulse_was_count_in();
+            if is_count_in || self.metronome_enabled {
+                if let Some(is_downbeat) = self.transport.beat_on_last_pulse() {
+                    self.metronome.trigger(is_downbeat);
+                }
+            }
+
+            if !is_count_in {
+                // Process pulse event in sequencer
+                let (should_trigger, frequency, velocity) = self.sequencer.on_pulse();
+
+                if should_trigger {
+                    if let Some(index) = self.sequencer.started_index() {
+                        self.event_sender.send(ServerEvent::step_changed(
+                            "trance_riff",
+                            RIFF_TRACK,
+                            index,
+                        ));
+                    }
+                }
+
+                // Trigger new notes when needed, shifted by the groove
+                if should_trigger && frequency > 0.0 {
+                    let step = self.transport.current_step();
+                    let velocity = (velocity + self.groove.velocity_offset(step)).clamp(0.0, 1.0);
+                    let delay_samples = if is_step_start {
+                        (self.groove.timing_offset(step) * self.samples_per_step()) as u32
+                    } else {
+                        0
+                    };
+                    self.schedule_note(frequency, velocity, delay_samples);
+                }
+            }
+        }
+
+        if self.transport.is_playing() {
+            self.send_beat_phase();
+        }
+
+        if let Some((frequency, velocity)) = self.pending_note {
+            if self.pending_delay_samples == 0 {
+                self.pending_note = None;
+                self.play_note(frequency, velocity);
+            } else {
+                self.pending_delay_samples -= 1;
             }
         }
 
         // Generate audio sample
-        self.synth.next_sample()
+        let (left, right) = self.synth.next_sample();
+        let click = self.metronome.next_sample();
+        (left + click, right + click)
     }
 
-    fn handle_client_event(&mut self, event: &crate::events::ClientEvent) -> Result<(), String> {
-        match event.node.as_str() {
-            "supersaw" => self.handle_synth_event(event),
-            "system" => self.handle_system_event(event),
-            _ => Err(format!(
-                "Unknown node '{}' for trance riff system",
-                event.node
-            )),
+    fn handle_client_event(&mut self, event: ClientEvent) -> Result<(), String> {
+        let ClientEvent::TranceRiff(event) = event else {
+            return Err(format!(
+                "Trance riff system can't handle {} events",
+                event.system_name()
+            ));
+        };
+
+        match event {
+            TranceRiffEvent::Supersaw(event) => self.handle_synth_event(event),
+            TranceRiffEvent::System(event) => self.handle_system_event(event)?,
+            TranceRiffEvent::Metronome(event) => self.handle_metronome_event(event),
         }
+        Ok(())
+    }
+
+    /// Silence a note or bring a silenced one back, nudge a velocity and
+    /// drift the filter. Notes keep their pitch, so the riff stays in key.
+    fn evolve(&mut self, mutations: &mut Mutations) {
+        let mut pattern_changed = false;
+        if mutations.chance(0.5) {
+            if let Some(index) = self.random_note(mutations) {
+                let (frequency, length, velocity) = self.sequencer.sequence()[index];
+                let velocity = if velocity > 0.0 { 0.0 } else { GHOST_VELOCITY };
+                self.sequencer.replace(index, frequency, length, velocity);
+                pattern_changed = true;
+            }
+        }
+        if let Some(index) = self.random_note(mutations) {
+            let (frequency, length, velocity) = self.sequencer.sequence()[index];
+            // Silenced notes are left for the flip to bring back
+            if velocity > 0.0 {
+                let velocity = mutations.nudge(velocity, 0.2, 0.05, 1.0);
+                self.sequencer.replace(index, frequency, length, velocity);
+                pattern_changed = true;
+            }
+        }
+        if pattern_changed {
+            self.send_pattern();
+        }
+
+        let cutoff = mutations.drift_octaves(self.synth.filter_cutoff(), 0.5);
+        self.synth.set_filter_cutoff(cutoff);
+        self.event_sender.send(ServerEvent::parameter_changed(
+            "trance_riff",
+            "supersaw",
+            "set_filter_cutoff",
+            self.synth.filter_cutoff(),
+        ));
+    }
+
+    fn on_activate(&mut self) {
+        self.event_sender
+            .send(ServerEvent::track_list("trance_riff", &[RIFF_TRACK]));
+        self.send_pattern();
     }
 
     fn set_sample_rate(&mut self, sample_rate: f32) {
         self.sample_rate = sample_rate;
         self.synth.set_sample_rate(sample_rate);
+        self.metronome.set_sample_rate(sample_rate);
         self.ppqn_clock.set_sample_rate(sample_rate);
     }
+
+    fn transport_clock(&self) -> Option<TransportClock> {
+        self.transport.is_playing().then(|| {
+            self.transport
+                .clock(self.ppqn_clock.bpm(), self.ppqn_clock.pulse_phase())
+        })
+    }
 }
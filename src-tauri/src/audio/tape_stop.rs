@@ -0,0 +1,195 @@
+use crate::audio::StereoAudioProcessor;
+
+/// Longest stop or spin-up
+pub const MAX_TAPE_RAMP_SECONDS: f32 = 4.0;
+
+/// Buffer behind the live input. A stop falls half its ramp behind, so this
+/// leaves room for a spin-up started part way through a stop.
+const TAPE_BUFFER_SECONDS: f32 = 2.0 * MAX_TAPE_RAMP_SECONDS;
+
+/// Rate below which the output fades out, so a stopped tape doesn't hold DC
+const FADE_RATE: f32 = 0.1;
+
+/// Crossfade back to the live input once a spin-up reaches full speed
+const REJOIN_SECONDS: f32 = 0.005;
+
+/// Tape stop: slows playback of the recent input down to a halt, and spins
+/// it back up again. Once up to speed it crossfades back to the live input,
+/// which it has fallen behind while slowed.
+pub struct TapeStop {
+    buffer: Vec<(f32, f32)>,
+    write_position: usize,
+    // Frames the playback head is behind the newest input
+    lag: f32,
+    rate: f32,
+    target_rate: f32,
+    rate_step: f32,
+    // Playing from the buffer rather than the live input
+    engaged: bool,
+    // 0 is the live input, 1 is the tape
+    wet: f32,
+    rejoin_frames: usize,
+    sample_rate: f32,
+}
+
+impl TapeStop {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut tape_stop = Self {
+            buffer: Vec::new(),
+            write_position: 0,
+            lag: 0.0,
+            rate: 1.0,
+            target_rate: 1.0,
+            rate_step: 0.0,
+            engaged: false,
+            wet: 0.0,
+            rejoin_frames: 1,
+            sample_rate,
+        };
+        tape_stop.set_sample_rate(sample_rate);
+        tape_stop
+    }
+
+    /// Slow to a stop over the given seconds; zero cuts out at once
+    pub fn stop(&mut self, seconds: f32) {
+        self.engaged = true;
+        self.ramp_to(0.0, seconds);
+    }
+
+    /// Spin back up to speed over the given seconds. From a full stop it
+    /// picks up the live input; part way through a stop it speeds up from
+    /// where it is.
+    pub fn start(&mut self, seconds: f32) {
+        if !self.engaged {
+            return;
+        }
+        if self.rate == 0.0 {
+            self.lag = 0.0;
+        }
+        self.ramp_to(1.0, seconds);
+    }
+
+    /// Whether the tape is stopped or slowing to a stop
+    pub fn is_stopping(&self) -> bool {
+        self.engaged && self.target_rate == 0.0
+    }
+
+    fn ramp_to(&mut self, rate: f32, seconds: f32) {
+        let frames = seconds.clamp(0.0, MAX_TAPE_RAMP_SECONDS) * self.sample_rate;
+        self.target_rate = rate;
+        self.rate_step = 1.0 / frames.max(1.0);
+    }
+
+    fn read(&self) -> (f32, f32) {
+        // The newest input is one behind the write position
+        let length = self.buffer.len();
+        let position = (self.write_position + length - 1) as f32 - self.lag;
+        let index = position as usize;
+        let fraction = position - index as f32;
+        let (left_a, right_a) = self.buffer[index % length];
+        let (left_b, right_b) = self.buffer[(index + 1) % length];
+        (
+            left_a + (left_b - left_a) * fraction,
+            right_a + (right_b - right_a) * fraction,
+        )
+    }
+}
+
+impl StereoAudioProcessor for TapeStop {
+    fn process(&mut self, left: f32, right: f32) -> (f32, f32) {
+        self.buffer[self.write_position] = (left, right);
+        self.write_position = (self.write_position + 1) % self.buffer.len();
+        if !self.engaged && self.wet == 0.0 {
+            return (left, right);
+        }
+
+        if self.rate < self.target_rate {
+            self.rate = (self.rate + self.rate_step).min(self.target_rate);
+        } else {
+            self.rate = (self.rate - self.rate_step).max(self.target_rate);
+        }
+        let max_lag = (self.buffer.len() - 2) as f32;
+        self.lag = (self.lag + 1.0 - self.rate).min(max_lag);
+        if self.engaged && self.target_rate == 1.0 && self.rate == 1.0 {
+            self.engaged = false;
+        }
+
+        let step = 1.0 / self.rejoin_frames as f32;
+        self.wet = if self.engaged {
+            (self.wet + step).min(1.0)
+        } else {
+            (self.wet - step).max(0.0)
+        };
+
+        let (tape_left, tape_right) = self.read();
+        let tape_gain = (self.rate / FADE_RATE).min(1.0) * self.wet;
+        let dry = 1.0 - self.wet;
+        if self.wet == 0.0 {
+            self.lag = 0.0;
+        }
+        (
+            left * dry + tape_left * tape_gain,
+            right * dry + tape_right * tape_gain,
+        )
+    }
+
+    /// Clears the buffer and returns to the live input
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.buffer = vec![(0.0, 0.0); (TAPE_BUFFER_SECONDS * sample_rate) as usize + 2];
+        self.write_position = 0;
+        self.lag = 0.0;
+        self.rate = 1.0;
+        self.target_rate = 1.0;
+        self.engaged = false;
+        self.wet = 0.0;
+        self.rejoin_frames = ((REJOIN_SECONDS * sample_rate) as usize).max(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 1000.0;
+
+    #[test]
+    fn test_stop_slows_to_silence_and_start_rejoins_the_input() {
+        let mut tape_stop = TapeStop::new(SAMPLE_RATE);
+        for i in 0..1000 {
+            let input = i as f32;
+            assert_eq!(tape_stop.process(input, input), (input, input));
+        }
+
+        // Halfway through a one second stop the tape has fallen an eighth
+        // of a second behind
+        tape_stop.stop(1.0);
+        assert!(tape_stop.is_stopping());
+        let mut last = 0.0;
+        for i in 1000..1500 {
+            last = tape_stop.process(i as f32, i as f32).0;
+        }
+        assert!((last - 1374.0).abs() < 1.0, "got {}", last);
+        for i in 1500..2000 {
+            tape_stop.process(i as f32, i as f32);
+        }
+        assert_eq!(tape_stop.process(1.0, 1.0), (0.0, 0.0));
+
+        // A spin-up ends back on the live input
+        tape_stop.start(0.5);
+        assert!(!tape_stop.is_stopping());
+        for _ in 0..600 {
+            tape_stop.process(0.5, 0.5);
+        }
+        assert_eq!(tape_stop.process(0.25, 0.25), (0.25, 0.25));
+    }
+
+    #[test]
+    fn test_start_does_nothing_while_running() {
+        let mut tape_stop = TapeStop::new(SAMPLE_RATE);
+        tape_stop.start(1.0);
+        for _ in 0..10 {
+            assert_eq!(tape_stop.process(0.5, -0.5), (0.5, -0.5));
+        }
+    }
+}
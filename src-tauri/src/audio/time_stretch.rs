@@ -0,0 +1,306 @@
+use crate::audio::samples::Sample;
+use crate::audio::TWO_PI;
+use std::ops::Range;
+
+/// Frames compared when WSOLA lines a grain up with the last one; every
+/// fourth frame is close enough and keeps sixteen pads affordable
+const CORRELATION_STRIDE: usize = 4;
+
+/// How far a grain may be moved to line up, as a fraction of the grain size
+const WSOLA_TOLERANCE: f32 = 0.25;
+
+const MIN_GRAIN_SECONDS: f32 = 0.01;
+const MAX_GRAIN_SECONDS: f32 = 0.2;
+
+/// How grains are placed when stretching
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StretchQuality {
+    /// Grains read straight from where the stretched position has got to.
+    /// Cheap, but phasey on tonal material.
+    Granular,
+    /// Each grain is nudged to the offset that best continues the previous
+    /// one (waveform-similarity overlap-add), which keeps tones clean
+    Wsola,
+}
+
+impl StretchQuality {
+    pub fn from_index(index: u32) -> Option<Self> {
+        match index {
+            0 => Some(StretchQuality::Granular),
+            1 => Some(StretchQuality::Wsola),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Grain {
+    // Source frame the grain starts reading from
+    start: f64,
+    // Output frames played so far
+    age: usize,
+    active: bool,
+    // The first grain after a trigger skips its fade-in so attacks stay sharp
+    first: bool,
+}
+
+const IDLE_GRAIN: Grain = Grain {
+    start: 0.0,
+    age: 0,
+    active: false,
+    first: false,
+};
+
+/// Changes a sample's speed without changing its pitch by overlap-adding
+/// Hann-windowed grains, two at a time at 50% overlap
+pub struct TimeStretch {
+    quality: StretchQuality,
+    grain_seconds: f32,
+    grain_frames: usize,
+
+    // Stretched position in the source, in source frames
+    analysis_position: f64,
+    // Output frames until the next grain starts
+    countdown: usize,
+    grains: [Grain; 2],
+    // Start of the grain most recently begun
+    previous_start: f64,
+
+    sample_rate: f32,
+}
+
+impl TimeStretch {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut stretch = Self {
+            quality: StretchQuality::Wsola,
+            grain_seconds: 0.05,
+            grain_frames: 0,
+            analysis_position: 0.0,
+            countdown: 0,
+            grains: [IDLE_GRAIN; 2],
+            previous_start: 0.0,
+            sample_rate,
+        };
+        stretch.update_grain_frames();
+        stretch
+    }
+
+    pub fn set_quality(&mut self, quality: StretchQuality) {
+        self.quality = quality;
+    }
+
+    /// Longer grains smear transients less often but make them echo;
+    /// shorter ones suit drums, longer ones pads
+    pub fn set_grain_size(&mut self, seconds: f32) {
+        self.grain_seconds = seconds.clamp(MIN_GRAIN_SECONDS, MAX_GRAIN_SECONDS);
+        self.update_grain_frames();
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.update_grain_frames();
+    }
+
+    /// Start reading from a source frame, as on a trigger
+    pub fn start(&mut self, position: f64) {
+        self.analysis_position = position;
+        self.previous_start = position;
+        self.countdown = 0;
+        self.grains = [IDLE_GRAIN; 2];
+    }
+
+    /// Next stretched frame of `frames` within the sample, or None once the
+    /// range has been played out. The stretched position moves `advance`
+    /// source frames per output frame, setting the tempo, while grains read at
+    /// `read_step`, setting the pitch. Both are negative to play in reverse.
+    pub fn next_frame(
+        &mut self,
+        sample: &Sample,
+        frames: &Range<usize>,
+        advance: f64,
+        read_step: f64,
+    ) -> Option<(f32, f32)> {
+        let hop = self.grain_frames / 2;
+        if self.countdown == 0 {
+            let in_range = self.analysis_position >= frames.start as f64
+                && self.analysis_position < frames.end as f64;
+            if in_range {
+                self.begin_grain(sample, frames, read_step);
+                self.analysis_position += hop as f64 * advance;
+            } else if !self.grains.iter().any(|grain| grain.active) {
+                return None;
+            }
+            self.countdown = hop;
+        }
+        self.countdown -= 1;
+
+        let grain_frames = self.grain_frames;
+        let mut output = (0.0, 0.0);
+        for grain in self.grains.iter_mut().filter(|grain| grain.active) {
+            let phase = grain.age as f32 / grain_frames as f32;
+            let window = if grain.first && phase < 0.5 {
+                1.0
+            } else {
+                0.5 - 0.5 * (TWO_PI * phase).cos()
+            };
+            let (left, right) =
+                sample.interpolated_frame(frames, grain.start + grain.age as f64 * read_step);
+            output.0 += left * window;
+            output.1 += right * window;
+
+            grain.age += 1;
+            grain.active = grain.age < grain_frames;
+        }
+        Some(output)
+    }
+
+    fn begin_grain(&mut self, sample: &Sample, frames: &Range<usize>, read_step: f64) {
+        let is_first = !self.grains.iter().any(|grain| grain.active);
+        let start = match self.quality {
+            StretchQuality::Wsola if !is_first => {
+                self.best_aligned_start(sample, frames, read_step)
+            }
+            _ => self.analysis_position,
+        };
+
+        // With 50% overlap the idle slot is always free by the time it's needed
+        let slot = self
+            .grains
+            .iter()
+            .position(|grain| !grain.active)
+            .unwrap_or(0);
+        self.grains[slot] = Grain {
+            start,
+            age: 0,
+            active: true,
+            first: is_first,
+        };
+        self.previous_start = start;
+    }
+
+    /// Offset near the stretched position whose opening best matches how the
+    /// previous grain would have carried on, so the overlap doesn't cancel
+    fn best_aligned_start(&self, sample: &Sample, frames: &Range<usize>, read_step: f64) -> f64 {
+        let hop = self.grain_frames / 2;
+        let natural = self.previous_start + hop as f64 * read_step;
+        let tolerance = (self.grain_frames as f32 * WSOLA_TOLERANCE) as i64;
+
+        let mut best = (self.analysis_position, f32::MIN);
+        for offset in -tolerance..=tolerance {
+            let candidate = self.analysis_position + offset as f64;
+            if candidate < frames.start as f64 || candidate >= frames.end as f64 {
+                continue;
+            }
+            let similarity: f32 = (0..hop)
+                .step_by(CORRELATION_STRIDE)
+                .map(|k| {
+                    let k = k as f64 * read_step;
+                    let (a_left, a_right) = sample.interpolated_frame(frames, natural + k);
+                    let (b_left, b_right) = sample.interpolated_frame(frames, candidate + k);
+                    a_left * b_left + a_right * b_right
+                })
+                .sum();
+            if similarity > best.1 {
+                best = (candidate, similarity);
+            }
+        }
+        best.0
+    }
+
+    fn update_grain_frames(&mut self) {
+        // Even, so two grains at half a grain apart sum to unity
+        self.grain_frames = ((self.grain_seconds * self.sample_rate) as usize / 2).max(1) * 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 48000.0;
+
+    fn sine(frequency: f32, seconds: f32) -> Sample {
+        let samples: Vec<f32> = (0..(seconds * SAMPLE_RATE) as usize)
+            .map(|i| (TWO_PI * frequency * i as f32 / SAMPLE_RATE).sin())
+            .collect();
+        Sample::from_interleaved("sine", &samples, 1, SAMPLE_RATE).unwrap()
+    }
+
+    fn render(quality: StretchQuality, sample: &Sample, speed: f64) -> Vec<f32> {
+        let mut stretch = TimeStretch::new(SAMPLE_RATE);
+        stretch.set_quality(quality);
+        stretch.start(0.0);
+        let frames = 0..sample.len();
+        std::iter::from_fn(|| stretch.next_frame(sample, &frames, speed, 1.0))
+            .map(|(left, _)| left)
+            .collect()
+    }
+
+    fn rising_zero_crossings(samples: &[f32]) -> usize {
+        samples
+            .windows(2)
+            .filter(|pair| pair[0] < 0.0 && pair[1] >= 0.0)
+            .count()
+    }
+
+    #[test]
+    fn test_stretch_changes_length_not_pitch() {
+        let sample = sine(440.0, 1.0);
+        for quality in [StretchQuality::Granular, StretchQuality::Wsola] {
+            let slow = render(quality, &sample, 0.5);
+            let seconds = slow.len() as f32 / SAMPLE_RATE;
+            assert!(
+                (seconds - 2.0).abs() < 0.1,
+                "{:?} lasted {}s",
+                quality,
+                seconds
+            );
+
+            // Still 440 Hz: count cycles over the middle second. Unaligned
+            // granular overlaps partly cancel, which can hide a few crossings.
+            let middle = &slow[(0.5 * SAMPLE_RATE) as usize..(1.5 * SAMPLE_RATE) as usize];
+            let cycles = rising_zero_crossings(middle);
+            assert!(
+                cycles.abs_diff(440) <= 20,
+                "{:?} had {} cycles",
+                quality,
+                cycles
+            );
+        }
+    }
+
+    #[test]
+    fn test_wsola_keeps_tone_level_steady() {
+        let sample = sine(440.0, 1.0);
+        let slow = render(StretchQuality::Wsola, &sample, 0.75);
+
+        // Aligned grains don't cancel, so every 10 ms block keeps near full level
+        let block = (0.01 * SAMPLE_RATE) as usize;
+        for chunk in slow[block..slow.len() - 10 * block].chunks(block) {
+            let peak = chunk.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+            assert!(peak > 0.8, "block peak dropped to {}", peak);
+        }
+    }
+
+    #[test]
+    fn test_reverse_stretch_plays_to_start() {
+        let sample = sine(440.0, 0.5);
+        let mut stretch = TimeStretch::new(SAMPLE_RATE);
+        stretch.start((sample.len() - 1) as f64);
+        let frames = 0..sample.len();
+        let output: Vec<f32> =
+            std::iter::from_fn(|| stretch.next_frame(&sample, &frames, -0.5, -1.0))
+                .map(|(left, _)| left)
+                .collect();
+        let seconds = output.len() as f32 / SAMPLE_RATE;
+        assert!((seconds - 1.0).abs() < 0.1, "lasted {}s", seconds);
+    }
+
+    #[test]
+    fn test_first_grain_keeps_attack() {
+        let sample = sine(440.0, 0.5);
+        let output = render(StretchQuality::Granular, &sample, 0.5);
+        let expected = (TWO_PI * 440.0 * 10.0 / SAMPLE_RATE).sin();
+        assert!((output[10] - expected).abs() < 1e-3);
+    }
+}
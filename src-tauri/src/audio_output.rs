@@ -1,118 +1,223 @@
-use crate::audio::server::AudioServer;
-use crate::audio::systems::{AuditionerSystem, TranceRiffSystem};
-use crate::commands::{ClientCommand, ClientCommandReceiver};
-use crate::events::ServerEventSender;
+use crate::audio::systems::SystemRegistry;
+use crate::backend::{AudioBackend, AudioEngine};
+use crate::commands::ClientCommandReceiver;
+use crate::events::{ServerEvent, ServerEventSender};
 use cpal::{traits::*, Sample};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
 
+/// Keeps a backend running with the engine built for it
 pub struct AudioOutput {
-    _stream: cpal::Stream,
+    _backend: Box<dyn AudioBackend>,
 }
 
 impl AudioOutput {
     pub fn new(
+        mut backend: Box<dyn AudioBackend>,
+        registry: &SystemRegistry,
         command_receiver: ClientCommandReceiver,
         event_sender: ServerEventSender,
     ) -> Result<Self, Box<dyn std::error::Error>> {
-        let host = cpal::default_host();
-        let device = host
-            .default_output_device()
-            .ok_or("No output device available")?;
+        let engine = AudioEngine::new(
+            registry,
+            backend.sample_rate(),
+            command_receiver,
+            event_sender,
+        )?;
+        backend.start(engine)?;
+
+        Ok(AudioOutput { _backend: backend })
+    }
+}
 
-        let config = device.default_output_config()?;
+/// How often the stream is checked for errors and default device changes
+const DEVICE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Plays the engine on the default output device. If the stream fails or the
+/// default device changes (an interface unplugged or plugged in), the stream
+/// is reopened on the current default device. The engine survives the switch,
+/// so the active system and its state carry over.
+pub struct CpalBackend {
+    sample_rate: f32,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl CpalBackend {
+    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
+        let (_, config) = default_output()?;
         let sample_rate = config.sample_rate().0 as f32;
 
         println!("Audio device sample rate: {}", sample_rate);
 
-        // Create audio server with available systems
-        let mut audio_server = AudioServer::new(sample_rate);
+        Ok(Self {
+            sample_rate,
+            running: Arc::new(AtomicBool::new(false)),
+            thread: None,
+        })
+    }
+}
 
-        // Create and add auditioner system
-        let auditioner_system = AuditionerSystem::new(sample_rate);
-        audio_server.add_system("auditioner".to_string(), Box::new(auditioner_system));
+impl AudioBackend for CpalBackend {
+    fn sample_rate(&self) -> f32 {
+        self.sample_rate
+    }
 
-        // Create and add trance riff system
-        let trance_riff_system = TranceRiffSystem::new(sample_rate);
-        audio_server.add_system("trance_riff".to_string(), Box::new(trance_riff_system));
+    fn start(&mut self, engine: AudioEngine) -> Result<(), Box<dyn std::error::Error>> {
+        let event_sender = engine.event_sender();
+        let engine = Arc::new(Mutex::new(engine));
+        let running = Arc::clone(&self.running);
+        running.store(true, Ordering::Relaxed);
+
+        // Streams aren't Send, so one thread opens, watches and replaces them
+        let (opened_sender, opened_receiver) = mpsc::channel();
+        self.thread = Some(std::thread::spawn(move || {
+            let mut current = match open_stream(&engine) {
+                Ok(stream) => {
+                    let _ = opened_sender.send(Ok(()));
+                    Some(stream)
+                }
+                Err(e) => {
+                    let _ = opened_sender.send(Err(e.to_string()));
+                    return;
+                }
+            };
 
-        // Start with auditioner as default
-        audio_server.switch_to_system("auditioner").unwrap();
+            while running.load(Ordering::Relaxed) {
+                std::thread::sleep(DEVICE_POLL_INTERVAL);
 
-        let stream = match config.sample_format() {
-            cpal::SampleFormat::F32 => {
-                Self::run::<f32>(&device, &config.into(), audio_server, command_receiver)?
-            }
-            cpal::SampleFormat::I16 => {
-                Self::run::<i16>(&device, &config.into(), audio_server, command_receiver)?
-            }
-            cpal::SampleFormat::U16 => {
-                Self::run::<u16>(&device, &config.into(), audio_server, command_receiver)?
+                let needs_reopen = match &current {
+                    Some(stream) => {
+                        stream.failed.load(Ordering::Relaxed)
+                            || default_device_name().is_some_and(|name| name != stream.device_name)
+                    }
+                    None => true,
+                };
+                if !needs_reopen {
+                    continue;
+                }
+
+                // Close the old stream before opening its replacement
+                current = None;
+                match open_stream(&engine) {
+                    Ok(stream) => {
+                        println!("Audio output moved to {}", stream.device_name);
+                        event_sender.send(ServerEvent::device_changed(
+                            &stream.device_name,
+                            stream.sample_rate,
+                            stream.channels,
+                        ));
+                        current = Some(stream);
+                    }
+                    // Retried on the next poll, e.g. until a device is plugged back in
+                    Err(e) => eprintln!("Failed to reopen audio output: {}", e),
+                }
             }
-            _ => return Err("Unsupported sample format".into()),
-        };
+        }));
 
-        stream.play()?;
+        opened_receiver
+            .recv()
+            .map_err(|_| "Audio output thread exited")?
+            .map_err(|e| e.into())
+    }
+}
 
-        Ok(AudioOutput { _stream: stream })
+impl Drop for CpalBackend {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
     }
+}
 
-    fn run<T>(
-        device: &cpal::Device,
-        config: &cpal::StreamConfig,
-        audio_server: AudioServer,
-        command_receiver: ClientCommandReceiver,
-    ) -> Result<cpal::Stream, cpal::BuildStreamError>
-    where
-        T: Sample + cpal::SizedSample + cpal::FromSample<f32>,
-    {
-        let channels = config.channels as usize;
-        assert!(channels == 2, "Must be stereo");
-
-        let stream = device.build_output_stream(
-            config,
-            {
-                let mut audio_server = audio_server;
-                move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
-                    // Process pending commands at the start of the buffer
-                    command_receiver.process_commands(|command| match command {
-                        ClientCommand::SendClientEvent(client_event) => {
-                            if let Err(e) = audio_server.send_client_event(&client_event) {
-                                eprintln!("Error sending client event: {}", e);
-                            }
-                        }
-                        ClientCommand::SwitchSystem(system_name) => {
-                            if let Err(e) = audio_server.switch_to_system(&system_name) {
-                                eprintln!("Error switching system: {}", e);
-                            }
-                        }
-                    });
-
-                    // Process audio sample-by-sample (stereo only)
-                    for frame in data.chunks_mut(2) {
-                        // Process stereo sample
-                        let (left, right) = audio_server.next_sample();
-
-                        // Apply limiting and NaN protection
-                        let left_limited = if left.is_finite() {
-                            left.clamp(-0.95, 0.95)
-                        } else {
-                            0.0
-                        };
-                        let right_limited = if right.is_finite() {
-                            right.clamp(-0.95, 0.95)
-                        } else {
-                            0.0
-                        };
-
-                        // Write stereo output
-                        frame[0] = T::from_sample(left_limited);
-                        frame[1] = T::from_sample(right_limited);
-                    }
-                }
-            },
-            |err| eprintln!("Audio stream error: {}", err),
-            None,
-        )?;
+/// A playing stream and the device it plays on
+struct OpenStream {
+    _stream: cpal::Stream,
+    device_name: String,
+    sample_rate: f32,
+    channels: usize,
+    /// Set by the stream's error callback
+    failed: Arc<AtomicBool>,
+}
+
+fn default_output(
+) -> Result<(cpal::Device, cpal::SupportedStreamConfig), Box<dyn std::error::Error>> {
+    let host = cpal::default_host();
+    let device = host
+        .default_output_device()
+        .ok_or("No output device available")?;
+    let config = device.default_output_config()?;
+    Ok((device, config))
+}
+
+fn default_device_name() -> Option<String> {
+    cpal::default_host()
+        .default_output_device()
+        .and_then(|device| device.name().ok())
+}
 
-        Ok(stream)
+/// Open the default device and start playing the engine on it
+fn open_stream(engine: &Arc<Mutex<AudioEngine>>) -> Result<OpenStream, Box<dyn std::error::Error>> {
+    let (device, config) = default_output()?;
+    let sample_rate = config.sample_rate().0 as f32;
+    let channels = config.channels() as usize;
+    {
+        let mut engine = engine.lock().unwrap();
+        engine.set_sample_rate(sample_rate);
+        engine.set_device_channels(channels);
     }
+
+    let failed = Arc::new(AtomicBool::new(false));
+    let engine = Arc::clone(engine);
+    let stream_config: cpal::StreamConfig = config.clone().into();
+    let stream = match config.sample_format() {
+        cpal::SampleFormat::F32 => run::<f32>(&device, &stream_config, engine, &failed)?,
+        cpal::SampleFormat::I16 => run::<i16>(&device, &stream_config, engine, &failed)?,
+        cpal::SampleFormat::U16 => run::<u16>(&device, &stream_config, engine, &failed)?,
+        _ => return Err("Unsupported sample format".into()),
+    };
+    stream.play()?;
+
+    Ok(OpenStream {
+        _stream: stream,
+        device_name: device
+            .name()
+            .unwrap_or_else(|_| "unknown device".to_string()),
+        sample_rate,
+        channels,
+        failed,
+    })
+}
+
+fn run<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    engine: Arc<Mutex<AudioEngine>>,
+    failed: &Arc<AtomicBool>,
+) -> Result<cpal::Stream, cpal::BuildStreamError>
+where
+    T: Sample + cpal::SizedSample + cpal::FromSample<f32>,
+{
+    let channels = config.channels as usize;
+    let failed = Arc::clone(failed);
+    device.build_output_stream(
+        config,
+        move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+            // Only contended while the stream is being replaced
+            let Ok(mut engine) = engine.try_lock() else {
+                data.fill(T::EQUILIBRIUM);
+                return;
+            };
+
+            engine.process_buffer(data, channels, T::from_sample);
+        },
+        move |err| {
+            eprintln!("Audio stream error: {}", err);
+            failed.store(true, Ordering::Relaxed);
+        },
+        None,
+    )
 }
@@ -0,0 +1,632 @@
+use crate::audio::metering::OutputMeter;
+use crate::audio::server::AudioServer;
+use crate::audio::systems::SystemRegistry;
+use crate::commands::{ClientCommand, ClientCommandReceiver};
+use crate::events::{ServerEvent, ServerEventSender, WireNames};
+use crate::session::{LoggedCommand, SessionRecorder, SessionReplay};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Something that pulls audio from an `AudioEngine`: a sound card, a timer
+/// thread, or a test rendering into memory
+pub trait AudioBackend {
+    /// Sample rate the engine must be built for
+    fn sample_rate(&self) -> f32;
+
+    /// Take ownership of the engine and start pulling audio from it
+    fn start(&mut self, engine: AudioEngine) -> Result<(), Box<dyn std::error::Error>>;
+}
+
+/// Where the engine's stereo output lands on the device's channels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OutputRouting {
+    /// Device channel for the left output, counting from 0
+    pub left_channel: usize,
+    /// Device channel for the right output, counting from 0
+    pub right_channel: usize,
+    /// Sum to mono at -3 dB and send it to both channels
+    pub mono: bool,
+}
+
+impl Default for OutputRouting {
+    fn default() -> Self {
+        Self {
+            left_channel: 0,
+            right_channel: 1,
+            mono: false,
+        }
+    }
+}
+
+impl OutputRouting {
+    /// Default routing for a device, folding down to mono on single-channel devices
+    pub fn for_channels(channels: usize) -> Self {
+        if channels >= 2 {
+            Self::default()
+        } else {
+            Self {
+                left_channel: 0,
+                right_channel: 0,
+                mono: true,
+            }
+        }
+    }
+
+    pub fn fits(&self, channels: usize) -> bool {
+        self.left_channel < channels && self.right_channel < channels
+    }
+
+    /// Output for one device channel; unmapped channels are silent
+    pub fn channel_sample(&self, channel: usize, left: f32, right: f32) -> f32 {
+        if self.mono {
+            let is_mapped = channel == self.left_channel || channel == self.right_channel;
+            return if is_mapped {
+                (left + right) * std::f32::consts::FRAC_1_SQRT_2
+            } else {
+                0.0
+            };
+        }
+
+        let mut sample = 0.0;
+        if channel == self.left_channel {
+            sample += left;
+        }
+        if channel == self.right_channel {
+            sample += right;
+        }
+        sample
+    }
+}
+
+/// The audio server plus its command and event plumbing, independent of
+/// whatever is consuming the audio
+pub struct AudioEngine {
+    server: AudioServer,
+    sample_rate: f32,
+    command_receiver: ClientCommandReceiver,
+    event_sender: ServerEventSender,
+    /// Longest a client event waited since the last ping
+    max_event_latency: Duration,
+    routing: OutputRouting,
+    /// Channels on the device being played, which routing must fit within
+    device_channels: usize,
+    /// Levels and clipping ahead of the limiter
+    meter: OutputMeter,
+    /// Frames rendered since the engine started
+    frame: u64,
+    /// Session log being recorded, with the frame recording started on
+    recorder: Option<(SessionRecorder, u64)>,
+    /// Session log being replayed, with the frame replay started on
+    replay: Option<(SessionReplay, u64)>,
+}
+
+impl AudioEngine {
+    /// Build every registered system and start with the first one
+    pub fn new(
+        registry: &SystemRegistry,
+        sample_rate: f32,
+        command_receiver: ClientCommandReceiver,
+        event_sender: ServerEventSender,
+    ) -> Result<Self, String> {
+        let mut server = AudioServer::new(sample_rate);
+        let systems = registry.build(sample_rate, &event_sender);
+        let default_system = systems.first().map(|(name, _)| name.clone());
+        for (name, system) in systems {
+            server.add_system(name, system);
+        }
+
+        if let Some(name) = default_system {
+            server.switch_to_system(&name)?;
+        }
+
+        Ok(Self {
+            server,
+            sample_rate,
+            command_receiver,
+            event_sender,
+            max_event_latency: Duration::ZERO,
+            routing: OutputRouting::default(),
+            device_channels: 2,
+            meter: OutputMeter::new(LIMIT_CEILING, sample_rate),
+            frame: 0,
+            recorder: None,
+            replay: None,
+        })
+    }
+
+    /// Retune every system for a new device, keeping their state
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        if sample_rate != self.sample_rate {
+            self.sample_rate = sample_rate;
+            self.server.set_sample_rate(sample_rate);
+            self.meter.set_sample_rate(sample_rate);
+        }
+    }
+
+    /// Tell the engine how many channels the device has, falling back to the
+    /// default routing if the current one no longer fits
+    pub fn set_device_channels(&mut self, channels: usize) {
+        self.device_channels = channels;
+        if !self.routing.fits(channels) {
+            self.routing = OutputRouting::for_channels(channels);
+        }
+    }
+
+    /// A sender for reporting to the UI from outside the audio callback
+    pub fn event_sender(&self) -> ServerEventSender {
+        self.event_sender.clone()
+    }
+
+    /// Apply pending commands, and any replayed commands now due; call at
+    /// the start of each buffer
+    fn process_commands(&mut self) {
+        let server = &mut self.server;
+        let event_sender = &self.event_sender;
+        let max_event_latency = &mut self.max_event_latency;
+        let routing = &mut self.routing;
+        let device_channels = self.device_channels;
+        let frame = self.frame;
+        let recorder = &mut self.recorder;
+        let replay = &mut self.replay;
+        self.command_receiver.process_commands(|command, latency| {
+            if let Some((recorder, started)) = recorder.as_ref() {
+                recorder.record(frame - started, &command);
+            }
+            match command {
+                ClientCommand::SendClientEvent(_) | ClientCommand::SwitchSystem(_) => {
+                    if matches!(command, ClientCommand::SendClientEvent(_)) {
+                        *max_event_latency = (*max_event_latency).max(latency);
+                    }
+                    apply_logged_command(server, event_sender, command);
+                }
+                ClientCommand::Ping(id) => {
+                    event_sender.send(ServerEvent::pong(id, latency, *max_event_latency));
+                    *max_event_latency = Duration::ZERO;
+                }
+                ClientCommand::SetOutputRouting(new_routing) => {
+                    if new_routing.fits(device_channels) {
+                        *routing = new_routing;
+                    } else {
+                        event_sender.send(ServerEvent::command_error(
+                            &format!("{:?}", new_routing),
+                            &format!("The output device has {} channels", device_channels),
+                        ));
+                    }
+                }
+                ClientCommand::SetSessionRecorder(new_recorder) => {
+                    *recorder = new_recorder.map(|new_recorder| (new_recorder, frame));
+                }
+                ClientCommand::Replay(new_replay) => *replay = Some((new_replay, frame)),
+            }
+        });
+        self.apply_replay();
+    }
+
+    fn apply_replay(&mut self) {
+        let Some((replay, started)) = self.replay.as_mut() else {
+            return;
+        };
+        while let Some(command) = replay.next_due(self.frame - *started) {
+            if let Some((recorder, recording_started)) = &self.recorder {
+                recorder.record(self.frame - recording_started, &command);
+            }
+            apply_logged_command(&mut self.server, &self.event_sender, command);
+        }
+        if replay.is_finished() {
+            self.replay = None;
+        }
+    }
+
+    /// Apply a logged command straight away, as a replay does when it falls due
+    pub fn apply_logged_command(&mut self, command: ClientCommand) {
+        if let Some((recorder, started)) = &self.recorder {
+            recorder.record(self.frame - started, &command);
+        }
+        apply_logged_command(&mut self.server, &self.event_sender, command);
+    }
+
+    /// Next stereo frame, limited and with NaN protection
+    fn next_frame(&mut self) -> (f32, f32) {
+        let (left, right) = self.server.next_sample();
+        self.meter.process(left, right);
+        (limit(left), limit(right))
+    }
+
+    /// Apply pending commands, fill an interleaved buffer with `channels`
+    /// channels, spreading the stereo output across them, then report any
+    /// state the UI follows. Every backend renders through here, once per
+    /// buffer.
+    pub fn process_buffer<T>(
+        &mut self,
+        data: &mut [T],
+        channels: usize,
+        convert: impl Fn(f32) -> T,
+    ) {
+        self.process_commands();
+        for frame in data.chunks_mut(channels) {
+            let (left, right) = self.next_frame();
+            for (channel, sample) in frame.iter_mut().enumerate() {
+                *sample = convert(self.routing.channel_sample(channel, left, right));
+            }
+            self.frame += 1;
+        }
+        if let Some(looper_state) = self.server.take_looper_state() {
+            self.event_sender.send(looper_state);
+        }
+        if let Some(scene_state) = self.server.take_scene_state() {
+            self.event_sender.send(scene_state);
+        }
+        if let Some(reading) = self.meter.take_reading() {
+            self.event_sender.send(ServerEvent::output_meter(reading));
+        }
+        if let Some(clipped_samples) = self.meter.take_clipping() {
+            self.event_sender
+                .send(ServerEvent::clipping_detected(clipped_samples));
+        }
+    }
+}
+
+/// Apply a command that changes the audio, reporting a rejection to the UI.
+/// Other commands aren't logged, so they never reach here.
+fn apply_logged_command(
+    server: &mut AudioServer,
+    event_sender: &ServerEventSender,
+    command: ClientCommand,
+) {
+    match command {
+        ClientCommand::SendClientEvent(client_event) => {
+            // The server takes the event, so its names are kept to report
+            // a rejection
+            let names = WireNames::of(&client_event);
+            if let Err(e) = server.send_client_event(client_event) {
+                eprintln!("Error sending client event: {}", e);
+                event_sender.send(ServerEvent::command_error(&names.to_string(), &e));
+            }
+        }
+        ClientCommand::SwitchSystem(system_name) => {
+            if let Err(e) = server.switch_to_system(&system_name) {
+                eprintln!("Error switching system: {}", e);
+                event_sender.send(ServerEvent::command_error(
+                    &format!("switch_system {}", system_name),
+                    &e,
+                ));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Highest output level; anything over it is clipped
+const LIMIT_CEILING: f32 = 0.95;
+
+fn limit(sample: f32) -> f32 {
+    if sample.is_finite() {
+        sample.clamp(-LIMIT_CEILING, LIMIT_CEILING)
+    } else {
+        0.0
+    }
+}
+
+/// Frames per buffer for backends that aren't tied to a device
+const DEFAULT_BUFFER_FRAMES: usize = 512;
+
+/// Runs the engine in real time on a timer thread and discards the audio,
+/// so the whole pipeline works on machines without a sound card
+pub struct NullBackend {
+    sample_rate: f32,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl NullBackend {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            sample_rate,
+            running: Arc::new(AtomicBool::new(false)),
+            thread: None,
+        }
+    }
+}
+
+impl AudioBackend for NullBackend {
+    fn sample_rate(&self) -> f32 {
+        self.sample_rate
+    }
+
+    fn start(&mut self, mut engine: AudioEngine) -> Result<(), Box<dyn std::error::Error>> {
+        let running = Arc::clone(&self.running);
+        running.store(true, Ordering::Relaxed);
+        let buffer_duration =
+            Duration::from_secs_f32(DEFAULT_BUFFER_FRAMES as f32 / self.sample_rate);
+        self.thread = Some(std::thread::spawn(move || {
+            let mut buffer = vec![0.0; DEFAULT_BUFFER_FRAMES * 2];
+            while running.load(Ordering::Relaxed) {
+                engine.process_buffer(&mut buffer, 2, |sample| sample);
+                std::thread::sleep(buffer_duration);
+            }
+        }));
+        Ok(())
+    }
+}
+
+impl Drop for NullBackend {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Renders only when asked, as fast as possible, for deterministic tests
+/// and bouncing to a file
+pub struct OfflineBackend {
+    sample_rate: f32,
+    buffer_frames: usize,
+    engine: Option<AudioEngine>,
+}
+
+impl OfflineBackend {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            sample_rate,
+            buffer_frames: DEFAULT_BUFFER_FRAMES,
+            engine: None,
+        }
+    }
+
+    /// Commands are applied once per buffer, as on a device with this buffer size
+    pub fn with_buffer_frames(mut self, buffer_frames: usize) -> Self {
+        self.buffer_frames = buffer_frames.max(1);
+        self
+    }
+
+    /// Render `frames` stereo frames as interleaved samples.
+    /// Renders silence if the backend hasn't been started.
+    pub fn render(&mut self, frames: usize) -> Vec<f32> {
+        let mut output = vec![0.0; frames * 2];
+        if let Some(engine) = self.engine.as_mut() {
+            for buffer in output.chunks_mut(self.buffer_frames * 2) {
+                engine.process_buffer(buffer, 2, |sample| sample);
+            }
+        }
+        output
+    }
+
+    /// Render a logged session, applying each command on exactly the frame
+    /// it was logged at rather than at a buffer boundary, then `tail_frames`
+    /// more so the last sounds can ring out
+    pub fn render_session(&mut self, log: Vec<LoggedCommand>, tail_frames: usize) -> Vec<f32> {
+        let mut replay = SessionReplay::new(log);
+        let mut output = Vec::new();
+        let mut rendered = 0;
+        while let Some(frame) = replay.next_frame() {
+            output.extend(self.render((frame - rendered) as usize));
+            rendered = frame;
+            let Some(engine) = self.engine.as_mut() else {
+                break;
+            };
+            while let Some(command) = replay.next_due(frame) {
+                engine.apply_logged_command(command);
+            }
+        }
+        output.extend(self.render(tail_frames));
+        output
+    }
+}
+
+impl AudioBackend for OfflineBackend {
+    fn sample_rate(&self) -> f32 {
+        self.sample_rate
+    }
+
+    fn start(&mut self, engine: AudioEngine) -> Result<(), Box<dyn std::error::Error>> {
+        self.engine = Some(engine);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::ClientCommandQueue;
+    use crate::events::ServerEventQueue;
+
+    fn start_offline() -> (OfflineBackend, ClientCommandQueue, ServerEventQueue) {
+        let command_queue = ClientCommandQueue::new();
+        let event_queue = ServerEventQueue::new();
+        let mut backend = OfflineBackend::new(44100.0).with_buffer_frames(64);
+        let engine = AudioEngine::new(
+            &SystemRegistry::with_builtin_systems(),
+            backend.sample_rate(),
+            command_queue.receiver(),
+            event_queue.sender(),
+        )
+        .unwrap();
+        backend.start(engine).unwrap();
+        (backend, command_queue, event_queue)
+    }
+
+    fn received_events(event_queue: &ServerEventQueue) -> Vec<String> {
+        let mut names = Vec::new();
+        event_queue
+            .receiver()
+            .process_events(|event| names.push(format!("{}_{}", event.node, event.event)));
+        names
+    }
+
+    /// Render as the cpal callback does, into a 4-channel 16-bit buffer
+    fn render_on_device(backend: &mut OfflineBackend, frames: usize) {
+        let engine = backend.engine.as_mut().unwrap();
+        engine.set_device_channels(4);
+        let mut buffer = vec![0i16; frames * 4];
+        engine.process_buffer(&mut buffer, 4, |sample| (sample * i16::MAX as f32) as i16);
+    }
+
+    fn master_event(node: &str, event: &str, data: serde_json::Value) -> ClientCommand {
+        let event = crate::events::ClientEvent::from_parts("master", node, event, None, Some(data))
+            .unwrap();
+        ClientCommand::SendClientEvent(event)
+    }
+
+    #[test]
+    fn test_offline_backend_drives_commands_and_events() {
+        let (mut backend, command_queue, event_queue) = start_offline();
+        let sender = command_queue.sender();
+
+        sender.send(ClientCommand::SwitchSystem("missing".to_string()));
+        sender.send(ClientCommand::Ping(7));
+        let output = backend.render(256);
+
+        assert_eq!(output.len(), 512);
+        assert!(output.iter().all(|s| s.is_finite() && s.abs() <= 0.95));
+        let events = received_events(&event_queue);
+        assert!(events.contains(&"system_command_error".to_string()));
+        assert!(events.contains(&"system_pong".to_string()));
+    }
+
+    #[test]
+    fn test_output_routing() {
+        let (left, right) = (0.5, 0.25);
+        let routing = OutputRouting {
+            left_channel: 2,
+            right_channel: 3,
+            mono: false,
+        };
+        let frame: Vec<f32> = (0..4)
+            .map(|channel| routing.channel_sample(channel, left, right))
+            .collect();
+        assert_eq!(frame, vec![0.0, 0.0, 0.5, 0.25]);
+        assert!(routing.fits(4));
+        assert!(!routing.fits(2));
+
+        // Equal-power fold-down: correlated signals sum to +3 dB, not +6 dB
+        let mono = OutputRouting {
+            mono: true,
+            ..OutputRouting::default()
+        };
+        let folded = mono.channel_sample(1, 0.5, 0.5);
+        assert!((folded - 0.5 * std::f32::consts::SQRT_2).abs() < 1e-6);
+        assert_eq!(mono.channel_sample(2, 0.5, 0.5), 0.0);
+
+        assert_eq!(
+            OutputRouting::for_channels(1).channel_sample(0, 0.5, 0.5),
+            folded
+        );
+    }
+
+    #[test]
+    fn test_routing_that_doesnt_fit_the_device_is_rejected() {
+        let (mut backend, command_queue, event_queue) = start_offline();
+        command_queue
+            .sender()
+            .send(ClientCommand::SetOutputRouting(OutputRouting {
+                left_channel: 2,
+                right_channel: 3,
+                mono: false,
+            }));
+        backend.render(64);
+
+        assert_eq!(received_events(&event_queue), vec!["system_command_error"]);
+        assert_eq!(
+            backend.engine.as_ref().unwrap().routing,
+            OutputRouting::default()
+        );
+    }
+
+    #[test]
+    fn test_device_buffers_report_looper_state() {
+        let (mut backend, command_queue, event_queue) = start_offline();
+        command_queue
+            .sender()
+            .send(master_event("looper", "record", 0.into()));
+        render_on_device(&mut backend, 64);
+
+        assert!(received_events(&event_queue).contains(&"looper_state".to_string()));
+    }
+
+    #[test]
+    fn test_device_buffers_report_scene_state() {
+        let (mut backend, command_queue, event_queue) = start_offline();
+        command_queue
+            .sender()
+            .send(master_event("scene", "save", "verse".into()));
+        render_on_device(&mut backend, 64);
+
+        assert!(received_events(&event_queue).contains(&"scene_state".to_string()));
+    }
+
+    #[test]
+    fn test_device_buffers_report_meter_and_clipping() {
+        let (mut backend, _commands, event_queue) = start_offline();
+        // An over that the limiter hides, as a loud system would produce
+        backend.engine.as_mut().unwrap().meter.process(1.5, -1.5);
+        render_on_device(&mut backend, 44100);
+
+        let events = received_events(&event_queue);
+        assert!(events.contains(&"system_output_meter".to_string()));
+        assert!(events.contains(&"system_clipping_detected".to_string()));
+    }
+
+    #[test]
+    fn test_replayed_session_renders_like_the_original() {
+        let gain = |value| {
+            let event = crate::events::ClientEvent::from_parts(
+                "auditioner",
+                "kick",
+                "set_gain",
+                Some(value),
+                None,
+            )
+            .unwrap();
+            ClientCommand::SendClientEvent(event)
+        };
+        let trigger = || {
+            let event =
+                crate::events::ClientEvent::from_parts("auditioner", "kick", "trigger", None, None)
+                    .unwrap();
+            ClientCommand::SendClientEvent(event)
+        };
+
+        // Played live, commands land on buffer boundaries
+        let (mut backend, command_queue, _events) = start_offline();
+        let sender = command_queue.sender();
+        let path = std::env::temp_dir().join(format!("replay-{}.jsonl", std::process::id()));
+        let writer = crate::session::SessionLogWriter::create(&path).unwrap();
+        sender.send(ClientCommand::SetSessionRecorder(Some(writer.recorder())));
+        sender.send(gain(0.5));
+        sender.send(trigger());
+        let mut live = backend.render(256);
+        sender.send(trigger());
+        live.extend(backend.render(768));
+        sender.send(ClientCommand::SetSessionRecorder(None));
+        backend.render(64);
+        assert_eq!(writer.finish(), Ok(3));
+
+        let log = crate::session::read_session_log(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(log[2].frame, 256);
+        let (mut offline, _commands, _events) = start_offline();
+        assert_eq!(offline.render_session(log, 1024 - 256), live);
+    }
+
+    #[test]
+    fn test_device_buffers_advance_the_frame_count() {
+        let (mut backend, _commands, _events) = start_offline();
+        render_on_device(&mut backend, 64);
+        assert_eq!(backend.engine.as_ref().unwrap().frame, 64);
+        backend.render(32);
+        assert_eq!(backend.engine.as_ref().unwrap().frame, 96);
+    }
+
+    #[test]
+    fn test_offline_render_is_deterministic() {
+        let render = || {
+            let (mut backend, _commands, _events) = start_offline();
+            backend.render(1024)
+        };
+        assert_eq!(render(), render());
+    }
+}
@@ -1,36 +1,76 @@
-use crossbeam::queue::SegQueue;
+use crossbeam::queue::ArrayQueue;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Most commands held before new ones are dropped
+const COMMAND_QUEUE_CAPACITY: usize = 1024;
 
 #[derive(Debug, Clone)]
 pub enum ClientCommand {
     SendClientEvent(crate::events::ClientEvent),
     SwitchSystem(String),
+    /// Latency probe, answered with a pong server event carrying the id
+    Ping(u64),
+    SetOutputRouting(crate::backend::OutputRouting),
+    /// Start logging applied commands to a session log, or stop with None
+    SetSessionRecorder(Option<crate::session::SessionRecorder>),
+    /// Feed a logged session back through the engine, starting now
+    Replay(crate::session::SessionReplay),
+}
+
+/// A command stamped when it left the UI thread
+struct TimedCommand {
+    command: ClientCommand,
+    sent_at: Instant,
+}
+
+/// Snapshot of the command queue's load
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandQueueStats {
+    /// Commands waiting for the audio thread
+    pub pending: usize,
+    /// Most commands ever waiting at once
+    pub high_water_mark: usize,
+    /// Commands dropped because the queue was full
+    pub dropped: u64,
+}
+
+/// Queue shared by the senders and receiver, plus its load counters
+struct CommandChannel {
+    queue: ArrayQueue<TimedCommand>,
+    high_water_mark: AtomicUsize,
+    dropped: AtomicU64,
 }
 
 /// Lock-free command queue for audio parameter changes
-/// Uses a multiple-producer, single-consumer queue from crossbeam
+/// Uses a bounded multiple-producer, single-consumer queue from crossbeam
 pub struct ClientCommandQueue {
-    queue: Arc<SegQueue<ClientCommand>>,
+    channel: Arc<CommandChannel>,
 }
 
 impl ClientCommandQueue {
     pub fn new() -> Self {
         Self {
-            queue: Arc::new(SegQueue::new()),
+            channel: Arc::new(CommandChannel {
+                queue: ArrayQueue::new(COMMAND_QUEUE_CAPACITY),
+                high_water_mark: AtomicUsize::new(0),
+                dropped: AtomicU64::new(0),
+            }),
         }
     }
 
     /// Get a handle for sending commands (for UI thread)
     pub fn sender(&self) -> ClientCommandSender {
         ClientCommandSender {
-            queue: Arc::clone(&self.queue),
+            channel: Arc::clone(&self.channel),
         }
     }
 
     /// Get a handle for receiving commands (for audio thread)
     pub fn receiver(&self) -> ClientCommandReceiver {
         ClientCommandReceiver {
-            queue: Arc::clone(&self.queue),
+            channel: Arc::clone(&self.channel),
         }
     }
 }
@@ -38,33 +78,55 @@ impl ClientCommandQueue {
 /// Sender handle for UI thread
 #[derive(Clone)]
 pub struct ClientCommandSender {
-    queue: Arc<SegQueue<ClientCommand>>,
+    channel: Arc<CommandChannel>,
 }
 
 impl ClientCommandSender {
-    /// Send a command to the audio thread (non-blocking)
+    /// Send a command to the audio thread (non-blocking).
+    /// When the audio thread has fallen behind and the queue is full the
+    /// command is dropped and counted.
     pub fn send(&self, command: ClientCommand) {
-        self.queue.push(command);
+        let timed = TimedCommand {
+            command,
+            sent_at: Instant::now(),
+        };
+        if self.channel.queue.push(timed).is_err() {
+            self.channel.dropped.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        self.channel
+            .high_water_mark
+            .fetch_max(self.channel.queue.len(), Ordering::Relaxed);
+    }
+
+    /// Current depth, high-water mark and drop count
+    pub fn stats(&self) -> CommandQueueStats {
+        CommandQueueStats {
+            pending: self.channel.queue.len(),
+            high_water_mark: self.channel.high_water_mark.load(Ordering::Relaxed),
+            dropped: self.channel.dropped.load(Ordering::Relaxed),
+        }
     }
 }
 
 /// Receiver handle for audio thread
 pub struct ClientCommandReceiver {
-    queue: Arc<SegQueue<ClientCommand>>,
+    channel: Arc<CommandChannel>,
 }
 
 impl ClientCommandReceiver {
-    /// Process all pending commands, applying them to the drum machine
+    /// Process all pending commands, applying them to the drum machine.
+    /// Each command comes with how long it waited between send and apply.
     /// This should be called at the start of each audio block
     pub fn process_commands<F>(&self, mut apply_command: F)
     where
-        F: FnMut(ClientCommand),
+        F: FnMut(ClientCommand, Duration),
     {
         // Process up to 64 commands per audio block to avoid spending too much time
         // in command processing during the audio callback
         for _ in 0..64 {
-            if let Some(command) = self.queue.pop() {
-                apply_command(command);
+            if let Some(timed) = self.channel.queue.pop() {
+                apply_command(timed.command, timed.sent_at.elapsed());
             } else {
                 break;
             }
@@ -77,3 +139,39 @@ impl Default for ClientCommandQueue {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_track_depth_and_drops() {
+        let queue = ClientCommandQueue::new();
+        let sender = queue.sender();
+        let receiver = queue.receiver();
+
+        for id in 0..(COMMAND_QUEUE_CAPACITY + 10) as u64 {
+            sender.send(ClientCommand::Ping(id));
+        }
+        assert_eq!(
+            sender.stats(),
+            CommandQueueStats {
+                pending: COMMAND_QUEUE_CAPACITY,
+                high_water_mark: COMMAND_QUEUE_CAPACITY,
+                dropped: 10,
+            }
+        );
+
+        // The oldest commands survive, and the high-water mark outlives a drain
+        let mut first = None;
+        receiver.process_commands(|command, _| {
+            if let (None, ClientCommand::Ping(id)) = (first, command) {
+                first = Some(id);
+            }
+        });
+        assert_eq!(first, Some(0));
+        let stats = sender.stats();
+        assert_eq!(stats.pending, COMMAND_QUEUE_CAPACITY - 64);
+        assert_eq!(stats.high_water_mark, COMMAND_QUEUE_CAPACITY);
+    }
+}
@@ -0,0 +1,234 @@
+use crate::audio::systems::auditioner::SAMPLER_PADS;
+use crate::events::ClientEvent;
+use std::collections::BTreeMap;
+
+/// Auditioner nodes a note can trigger
+pub const DRUM_MAP_NODES: [&str; 20] = [
+    "kick",
+    "clap",
+    "snare",
+    "hihat",
+    "open_hat",
+    "tom",
+    "modal_drum",
+    "cowbell",
+    "clave",
+    "glitch",
+    "cymbal",
+    "chord",
+    "supersaw",
+    "pad_synth",
+    "bass808",
+    "acid",
+    "wavetable",
+    "fm6",
+    "formant",
+    "sampler",
+];
+
+/// Highest MIDI note number
+const MAX_NOTE: u8 = 127;
+
+/// What a note plays: an instrument, or one of the sample pads
+#[derive(Debug, Clone, PartialEq)]
+pub struct DrumTarget {
+    pub node: String,
+    /// Sample pad, only for the sampler
+    pub pad: Option<usize>,
+}
+
+impl DrumTarget {
+    pub fn new(node: &str, pad: Option<usize>) -> Result<Self, String> {
+        if !DRUM_MAP_NODES.contains(&node) {
+            return Err(format!("Notes can't trigger unknown instrument '{}'", node));
+        }
+        match (node, pad) {
+            ("sampler", None) => Err("Sampler notes need a pad".to_string()),
+            ("sampler", Some(pad)) if pad >= SAMPLER_PADS => Err(format!(
+                "No sampler pad {}, there are {}",
+                pad, SAMPLER_PADS
+            )),
+            ("sampler", _) => Ok(Self {
+                node: node.to_string(),
+                pad,
+            }),
+            (_, Some(_)) => Err(format!("Only the sampler has pads, not '{}'", node)),
+            (_, None) => Ok(Self {
+                node: node.to_string(),
+                pad: None,
+            }),
+        }
+    }
+}
+
+/// Which auditioner instrument each incoming MIDI note triggers
+#[derive(Debug, Clone, PartialEq)]
+pub struct DrumMap {
+    notes: BTreeMap<u8, DrumTarget>,
+}
+
+impl DrumMap {
+    /// The General MIDI drum notes the auditioner has instruments for. The
+    /// pedal hat plays the closed one.
+    pub fn general_midi() -> Self {
+        let notes = [
+            (36, "kick"),
+            (38, "snare"),
+            (39, "clap"),
+            (40, "snare"),
+            (42, "hihat"),
+            (44, "hihat"),
+            (46, "open_hat"),
+            (75, "clave"),
+        ]
+        .into_iter()
+        .map(|(note, node)| {
+            (
+                note,
+                DrumTarget {
+                    node: node.to_string(),
+                    pad: None,
+                },
+            )
+        })
+        .collect();
+        Self { notes }
+    }
+
+    /// Parse a map keyed by note number:
+    /// `{ "36": { "node": "kick" }, "60": { "node": "sampler", "pad": 0 } }`
+    pub fn from_json(value: &serde_json::Value) -> Result<Self, String> {
+        let object = value.as_object().ok_or("Drum map must be a JSON object")?;
+        let mut notes = BTreeMap::new();
+        for (note, target) in object {
+            let note = note
+                .parse::<u8>()
+                .ok()
+                .filter(|&note| note <= MAX_NOTE)
+                .ok_or_else(|| format!("Drum map note '{}' must be 0 to {}", note, MAX_NOTE))?;
+            let node = target
+                .get("node")
+                .and_then(|n| n.as_str())
+                .ok_or_else(|| format!("Drum map note {} needs a node", note))?;
+            let pad = match target.get("pad") {
+                None | Some(serde_json::Value::Null) => None,
+                Some(pad) => Some(
+                    pad.as_u64()
+                        .ok_or_else(|| format!("Drum map note {} pad must be a number", note))?
+                        as usize,
+                ),
+            };
+            notes.insert(note, DrumTarget::new(node, pad)?);
+        }
+        Ok(Self { notes })
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let notes = self
+            .notes
+            .iter()
+            .map(|(note, target)| {
+                let mut entry = serde_json::Map::new();
+                entry.insert("node".to_string(), serde_json::json!(&target.node));
+                if let Some(pad) = target.pad {
+                    entry.insert("pad".to_string(), serde_json::json!(pad));
+                }
+                (note.to_string(), serde_json::Value::Object(entry))
+            })
+            .collect();
+        serde_json::Value::Object(notes)
+    }
+
+    pub fn get(&self, note: u8) -> Option<&DrumTarget> {
+        self.notes.get(&note)
+    }
+
+    /// Map a note to a target, replacing what it played before
+    pub fn set(&mut self, note: u8, target: DrumTarget) -> Result<(), String> {
+        if note > MAX_NOTE {
+            return Err(format!(
+                "MIDI notes run from 0 to {}, not {}",
+                MAX_NOTE, note
+            ));
+        }
+        self.notes.insert(note, target);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, note: u8) {
+        self.notes.remove(&note);
+    }
+
+    /// Trigger for a note-on with a MIDI velocity (1-127), or None for an
+    /// unmapped note. Sample pads take the velocity as data, everything else
+    /// as the parameter.
+    pub fn trigger_event(&self, note: u8, velocity: u8) -> Option<Result<ClientEvent, String>> {
+        let target = self.get(note)?;
+        let velocity = velocity.min(MAX_NOTE) as f32 / MAX_NOTE as f32;
+        let (parameter, data) = match target.pad {
+            Some(pad) => (Some(pad as f32), Some(serde_json::json!(velocity))),
+            None => (Some(velocity), None),
+        };
+        Some(ClientEvent::from_parts(
+            "auditioner",
+            &target.node,
+            "trigger",
+            parameter,
+            data,
+        ))
+    }
+}
+
+impl Default for DrumMap {
+    fn default() -> Self {
+        Self::general_midi()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{AuditionerEvent, KickEvent};
+
+    #[test]
+    fn test_drum_map_round_trips_through_json() {
+        let mut map = DrumMap::general_midi();
+        map.set(60, DrumTarget::new("sampler", Some(3)).unwrap())
+            .unwrap();
+        map.remove(44);
+
+        let parsed = DrumMap::from_json(&map.to_json()).unwrap();
+        assert_eq!(parsed, map);
+        assert_eq!(parsed.get(36).unwrap().node, "kick");
+        assert_eq!(parsed.get(60).unwrap().pad, Some(3));
+        assert!(parsed.get(44).is_none());
+    }
+
+    #[test]
+    fn test_invalid_targets_are_rejected() {
+        assert!(DrumTarget::new("gong", None).is_err());
+        assert!(DrumTarget::new("sampler", None).is_err());
+        assert!(DrumTarget::new("sampler", Some(SAMPLER_PADS)).is_err());
+        assert!(DrumTarget::new("kick", Some(0)).is_err());
+        assert!(DrumMap::from_json(&serde_json::json!({ "128": { "node": "kick" } })).is_err());
+        assert!(DrumMap::from_json(&serde_json::json!({ "36": {} })).is_err());
+    }
+
+    #[test]
+    fn test_notes_become_triggers() {
+        let mut map = DrumMap::general_midi();
+        map.set(60, DrumTarget::new("sampler", Some(2)).unwrap())
+            .unwrap();
+        assert!(map.trigger_event(61, 100).is_none());
+        assert!(matches!(map.trigger_event(36, 100), Some(Ok(_))));
+        assert!(matches!(map.trigger_event(60, 127), Some(Ok(_))));
+
+        // The velocity comes through as the trigger's parameter
+        assert!(matches!(
+            map.trigger_event(36, 64),
+            Some(Ok(ClientEvent::Auditioner(AuditionerEvent::Kick(
+                KickEvent::Trigger { parameter: Some(velocity) }
+            )))) if (velocity - 64.0 / 127.0).abs() < 1e-6
+        ));
+    }
+}
@@ -1,79 +1,1288 @@
-use crossbeam::queue::SegQueue;
-use std::sync::Arc;
+use crossbeam::queue::{ArrayQueue, SegQueue};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, OnceLock};
+use std::thread::Thread;
+use std::time::Duration;
 
-/// Client event - sent from frontend to backend
+/// Client event - sent from frontend to backend.
+/// Organized as system -> node -> event to mirror events.ts, so unknown
+/// names are rejected when the event is parsed rather than by each system.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "system", rename_all = "snake_case")]
+pub enum ClientEvent {
+    Auditioner(AuditionerEvent),
+    TranceRiff(TranceRiffEvent),
+    /// Handled by the audio server's master section
+    Master(MasterEvent),
+    /// Any system registered from another crate, left untyped
+    #[cfg(feature = "plugins")]
+    #[serde(skip)]
+    Plugin(PluginEvent),
+}
+
+/// Systems with typed events; with the plugins feature anything else is a plugin
+#[cfg(feature = "plugins")]
+const TYPED_SYSTEM_NAMES: [&str; 3] = [
+    "auditioner",
+    "trance_riff",
+    crate::audio::server::MASTER_SYSTEM_NAME,
+];
+
+/// Event for a plugin system, in the loose form the frontend sends
+#[cfg(feature = "plugins")]
 #[derive(Debug, Clone)]
-pub struct ClientEvent {
-    /// Target system (e.g., "drum_machine", "euclidean", "auditioner")
+pub struct PluginEvent {
     pub system: String,
-    /// Target node within system (e.g., "kick", "clap", "system") 
     pub node: String,
-    /// Event name (e.g., "trigger", "set_gain", "set_bpm")
     pub event: String,
-    /// Optional event parameter (for booleans: 0.0 = false, 1.0 = true)
     pub parameter: Option<f32>,
-    /// Optional data payload for complex events (serialized JSON)
     pub data: Option<serde_json::Value>,
 }
 
 impl ClientEvent {
-    /// Create a simple event with just a parameter
-    pub fn new(system: &str, node: &str, event: &str, parameter: f32) -> Self {
-        Self {
-            system: system.to_string(),
-            node: node.to_string(),
-            event: event.to_string(),
-            parameter: Some(parameter),
-            data: None,
+    /// Parse an event from the loose system/node/event form the frontend sends
+    pub fn from_parts(
+        system: &str,
+        node: &str,
+        event: &str,
+        parameter: Option<f32>,
+        data: Option<serde_json::Value>,
+    ) -> Result<Self, String> {
+        // Plugin systems validate their own events
+        #[cfg(feature = "plugins")]
+        if !TYPED_SYSTEM_NAMES.contains(&system) {
+            return Ok(ClientEvent::Plugin(PluginEvent {
+                system: system.to_string(),
+                node: node.to_string(),
+                event: event.to_string(),
+                parameter,
+                data,
+            }));
         }
+
+        let value = serde_json::json!({
+            "system": system,
+            "node": node,
+            "event": event,
+            "parameter": parameter,
+            "data": data,
+        });
+        serde_json::from_value(value)
+            .map_err(|e| format!("Invalid event '{}/{}/{}': {}", system, node, event, e))
     }
 
-    /// Create an event with data payload
-    pub fn with_data(system: &str, node: &str, event: &str, data: serde_json::Value) -> Self {
-        Self {
-            system: system.to_string(),
-            node: node.to_string(),
-            event: event.to_string(),
-            parameter: None,
-            data: Some(data),
+    /// The loose system/node/event form, which `from_parts` parses back.
+    /// Sample assignments carry audio rather than wire data, so they fail.
+    pub fn to_json(&self) -> Result<serde_json::Value, String> {
+        #[cfg(feature = "plugins")]
+        if let ClientEvent::Plugin(event) = self {
+            return Ok(serde_json::json!({
+                "system": event.system,
+                "node": event.node,
+                "event": event.event,
+                "parameter": event.parameter,
+                "data": event.data,
+            }));
         }
+
+        serde_json::to_value(self)
+            .map_err(|e| format!("Can't encode {} event: {}", self.system_name(), e))
     }
 
-    /// Create an event with both parameter and data
-    pub fn with_param_and_data(system: &str, node: &str, event: &str, parameter: f32, data: serde_json::Value) -> Self {
-        Self {
-            system: system.to_string(),
-            node: node.to_string(),
-            event: event.to_string(),
-            parameter: Some(parameter),
-            data: Some(data),
+    /// Name of the target system, as registered with the audio server
+    pub fn system_name(&self) -> &str {
+        match self {
+            ClientEvent::Auditioner(_) => "auditioner",
+            ClientEvent::TranceRiff(_) => "trance_riff",
+            ClientEvent::Master(_) => crate::audio::server::MASTER_SYSTEM_NAME,
+            #[cfg(feature = "plugins")]
+            ClientEvent::Plugin(event) => &event.system,
         }
     }
+}
 
-    /// Create a trigger event (no parameter needed)
-    pub fn trigger(system: &str, node: &str) -> Self {
-        Self {
-            system: system.to_string(),
-            node: node.to_string(),
-            event: "trigger".to_string(),
-            parameter: None,
-            data: None,
+/// Room for the start of an event's wire form, which is where its names are
+const WIRE_NAMES_BYTES: usize = 96;
+
+/// The system/node/event names of an event, as the frontend sent them. The
+/// start of the event's wire form is written into a fixed buffer, so taking
+/// the names on the audio thread never allocates; they're only read out
+/// when needed.
+pub struct WireNames {
+    prefix: [u8; WIRE_NAMES_BYTES],
+    len: usize,
+}
+
+impl WireNames {
+    pub fn of(event: &ClientEvent) -> Self {
+        let mut prefix = [0; WIRE_NAMES_BYTES];
+        let mut unwritten = &mut prefix[..];
+        // Anything past the names that doesn't fit is cut off
+        match event {
+            #[cfg(feature = "plugins")]
+            ClientEvent::Plugin(event) => {
+                use std::io::Write;
+                let _ = write!(
+                    unwritten,
+                    "{{\"system\":\"{}\",\"node\":\"{}\",\"event\":\"{}\"",
+                    event.system, event.node, event.event
+                );
+            }
+            event => {
+                let _ = serde_json::to_writer(&mut unwritten, event);
+            }
         }
+        let len = WIRE_NAMES_BYTES - unwritten.len();
+        Self { prefix, len }
     }
+}
 
-    /// Get parameter as boolean (0.0 = false, non-zero = true)
-    pub fn as_bool(&self) -> bool {
-        self.parameter.map(|p| p != 0.0).unwrap_or(false)
+impl std::fmt::Display for WireNames {
+    /// `system/node/event`
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        // The prefix reads {"system":"...","node":"...","event":"..." and
+        // names are never escaped, so each sits after every fourth quote
+        let prefix = String::from_utf8_lossy(&self.prefix[..self.len]);
+        let names: Vec<&str> = prefix.split('"').skip(3).step_by(4).take(3).collect();
+        write!(f, "{}", names.join("/"))
     }
+}
 
-    /// Get parameter value, defaulting to 0.0 if None
-    pub fn param(&self) -> f32 {
-        self.parameter.unwrap_or(0.0)
-    }
+// ============================================================================
+// AUDITIONER SYSTEM
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "node", rename_all = "snake_case")]
+pub enum AuditionerEvent {
+    System(AuditionerSystemEvent),
+    Kick(KickEvent),
+    Clap(ClapEvent),
+    Snare(SnareEvent),
+    Hihat(HihatEvent),
+    /// The open hat takes the same events as the closed one
+    OpenHat(HihatEvent),
+    Tom(TomEvent),
+    ModalDrum(ModalDrumEvent),
+    Cowbell(CowbellEvent),
+    Clave(ClaveEvent),
+    Glitch(GlitchEvent),
+    Cymbal(CymbalEvent),
+    Chord(ChordEvent),
+    Supersaw(SupersawEvent),
+    PadSynth(PadSynthEvent),
+    Bass808(Bass808Event),
+    Acid(AcidEvent),
+    Wavetable(WavetableEvent),
+    Fm6(Fm6Event),
+    Formant(FormantEvent),
+    Reverb(ReverbEvent),
+    Eq(EqEvent),
+    Sampler(SamplerEvent),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AuditionerSystemEvent {
+    SetReverbSend { parameter: f32 },
+    SetReverbReturn { parameter: f32 },
+}
+
+/// Instrument events; choke group 0 removes the node from any group
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum KickEvent {
+    /// Parameter is an optional velocity from 0 to 1, full when missing
+    Trigger {
+        #[serde(default)]
+        parameter: Option<f32>,
+    },
+    SetGain {
+        parameter: f32,
+    },
+    SetChokeGroup {
+        parameter: f32,
+    },
+    SetLofiAmount {
+        parameter: f32,
+    },
+    SetBaseFrequency {
+        parameter: f32,
+    },
+    SetFrequencyRatio {
+        parameter: f32,
+    },
+    SetAmpAttack {
+        parameter: f32,
+    },
+    SetAmpRelease {
+        parameter: f32,
+    },
+    SetFreqAttack {
+        parameter: f32,
+    },
+    SetFreqRelease {
+        parameter: f32,
+    },
+    SetClickLevel {
+        parameter: f32,
+    },
+    SetClickDecay {
+        parameter: f32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ClapEvent {
+    /// Parameter is an optional velocity from 0 to 1, full when missing
+    Trigger {
+        #[serde(default)]
+        parameter: Option<f32>,
+    },
+    SetGain {
+        parameter: f32,
+    },
+    SetChokeGroup {
+        parameter: f32,
+    },
+    SetLofiAmount {
+        parameter: f32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum HihatEvent {
+    /// Parameter is an optional velocity from 0 to 1, full when missing
+    Trigger {
+        #[serde(default)]
+        parameter: Option<f32>,
+    },
+    SetGain {
+        parameter: f32,
+    },
+    SetChokeGroup {
+        parameter: f32,
+    },
+    SetLofiAmount {
+        parameter: f32,
+    },
+    SetLength {
+        parameter: f32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum SnareEvent {
+    /// Parameter is an optional velocity from 0 to 1, full when missing
+    Trigger {
+        #[serde(default)]
+        parameter: Option<f32>,
+    },
+    SetGain {
+        parameter: f32,
+    },
+    SetChokeGroup {
+        parameter: f32,
+    },
+    SetLofiAmount {
+        parameter: f32,
+    },
+    SetTune {
+        parameter: f32,
+    },
+    SetToneDecay {
+        parameter: f32,
+    },
+    /// Balance of the wires against the body, 0 to 1
+    SetSnappy {
+        parameter: f32,
+    },
+    SetNoiseDecay {
+        parameter: f32,
+    },
+    SetNoiseFrequency {
+        parameter: f32,
+    },
+    /// 0 = white, 1 = pink, 2 = brown
+    SetNoiseColor {
+        parameter: f32,
+    },
+    /// Ratio the body starts at above its tune; 1 is no sweep
+    SetPitchSweep {
+        parameter: f32,
+    },
+    SetSweepTime {
+        parameter: f32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TomEvent {
+    /// Parameter is an optional velocity from 0 to 1, full when missing
+    Trigger {
+        #[serde(default)]
+        parameter: Option<f32>,
+    },
+    SetGain {
+        parameter: f32,
+    },
+    SetChokeGroup {
+        parameter: f32,
+    },
+    SetLofiAmount {
+        parameter: f32,
+    },
+    SetBaseFrequency {
+        parameter: f32,
+    },
+    /// Semitones above the base frequency each hit starts from, up to 24
+    SetSweepDepth {
+        parameter: f32,
+    },
+    SetSweepTime {
+        parameter: f32,
+    },
+    SetDecay {
+        parameter: f32,
+    },
+    SetNoiseAmount {
+        parameter: f32,
+    },
+    SetNoiseDecay {
+        parameter: f32,
+    },
+    /// Depth of through-zero FM on the tone, 0 to 1
+    SetFmAmount {
+        parameter: f32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ModalDrumEvent {
+    /// Parameter is an optional velocity from 0 to 1, full when missing
+    Trigger {
+        #[serde(default)]
+        parameter: Option<f32>,
+    },
+    SetGain {
+        parameter: f32,
+    },
+    SetChokeGroup {
+        parameter: f32,
+    },
+    SetLofiAmount {
+        parameter: f32,
+    },
+    /// Frequency of the fundamental mode
+    SetTune {
+        parameter: f32,
+    },
+    SetDecay {
+        parameter: f32,
+    },
+    /// How much faster the upper modes die, 0 to 1
+    SetDamping {
+        parameter: f32,
+    },
+    /// 0 strikes the centre, 1 the rim
+    SetStrikePosition {
+        parameter: f32,
+    },
+    /// Level of the noise burst in the strike
+    SetNoise {
+        parameter: f32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum CowbellEvent {
+    /// Parameter is an optional velocity from 0 to 1, full when missing
+    Trigger {
+        #[serde(default)]
+        parameter: Option<f32>,
+    },
+    SetGain {
+        parameter: f32,
+    },
+    SetChokeGroup {
+        parameter: f32,
+    },
+    SetLofiAmount {
+        parameter: f32,
+    },
+    /// Frequency of the lower oscillator, 540Hz on the 808
+    SetTuning {
+        parameter: f32,
+    },
+    SetDecay {
+        parameter: f32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ClaveEvent {
+    /// Parameter is an optional velocity from 0 to 1, full when missing
+    Trigger {
+        #[serde(default)]
+        parameter: Option<f32>,
+    },
+    SetGain {
+        parameter: f32,
+    },
+    SetChokeGroup {
+        parameter: f32,
+    },
+    SetLofiAmount {
+        parameter: f32,
+    },
+    /// Pitch of the ping, around 2.5kHz for a clave
+    SetTuning {
+        parameter: f32,
+    },
+    SetDecay {
+        parameter: f32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum GlitchEvent {
+    /// Parameter is an optional velocity from 0 to 1, full when missing
+    Trigger {
+        #[serde(default)]
+        parameter: Option<f32>,
+    },
+    SetGain {
+        parameter: f32,
+    },
+    SetChokeGroup {
+        parameter: f32,
+    },
+    SetLofiAmount {
+        parameter: f32,
+    },
+    /// Grain of the noise, 0 = stepped and buzzy, 1 = fine
+    SetTexture {
+        parameter: f32,
+    },
+    SetCrush {
+        parameter: f32,
+    },
+    SetDecay {
+        parameter: f32,
+    },
+    /// How far each hit strays from the set texture, crush and decay
+    SetRandomness {
+        parameter: f32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum CymbalEvent {
+    /// Parameter is an optional velocity from 0 to 1, full when missing
+    Trigger {
+        #[serde(default)]
+        parameter: Option<f32>,
+    },
+    SetGain {
+        parameter: f32,
+    },
+    SetChokeGroup {
+        parameter: f32,
+    },
+    SetLofiAmount {
+        parameter: f32,
+    },
+    /// 0 = ride, 1 = crash
+    SetMode {
+        parameter: f32,
+    },
+    /// Frequency of the lowest operator
+    SetTone {
+        parameter: f32,
+    },
+    SetDecay {
+        parameter: f32,
+    },
+    /// Length of the noisy wash at the start of each hit
+    SetSizzle {
+        parameter: f32,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ChordEvent {
+    /// Parameter is an optional velocity from 0 to 1, full when missing
+    Trigger {
+        #[serde(default)]
+        parameter: Option<f32>,
+    },
+    SetGain {
+        parameter: f32,
+    },
+    SetChokeGroup {
+        parameter: f32,
+    },
+    SetLofiAmount {
+        parameter: f32,
+    },
+    SetBaseFrequency {
+        parameter: f32,
+    },
+    /// 0 = major, 1 = minor, 2 = sus2, 3 = sus4, 4 = major 7th,
+    /// 5 = minor 7th, 6 = dominant 7th
+    SetChordQuality {
+        parameter: f32,
+    },
+    /// Data lists each note of a custom chord as a ratio to the root
+    SetChordRatios {
+        data: Vec<f32>,
+    },
+    /// Number of the lowest notes lifted an octave
+    SetInversion {
+        parameter: f32,
+    },
+    /// Octaves every other note is lifted, 0 to 2
+    SetSpread {
+        parameter: f32,
+    },
+    /// Spread of the voices across the stereo field, 0 (mono) to 1
+    SetStereoWidth {
+        parameter: f32,
+    },
+    SetModulationIndex {
+        parameter: f32,
+    },
+    /// Detuned copies of each voice, 1 (off) to 8
+    SetUnisonVoices {
+        parameter: f32,
+    },
+    /// Cents the outermost unison copies sit from the note
+    SetUnisonDetune {
+        parameter: f32,
+    },
+    /// Cents each operator's pitch wanders, like an analog oscillator
+    SetDriftAmount {
+        parameter: f32,
+    },
+    /// Data is the [source, destination] operator pair, 0 to 3, and
+    /// parameter how much the source modulates the destination; operator 0
+    /// is the carrier
+    SetModAmount {
+        data: [usize; 2],
+        parameter: f32,
+    },
+    SetFeedback {
+        parameter: f32,
+    },
+    SetAttack {
+        parameter: f32,
+    },
+    SetRelease {
+        parameter: f32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Bass808Event {
+    /// Parameter is an optional velocity; above 0.5 also accents the note
+    Trigger {
+        #[serde(default)]
+        parameter: Option<f32>,
+    },
+    SetGain {
+        parameter: f32,
+    },
+    SetChokeGroup {
+        parameter: f32,
+    },
+    SetLofiAmount {
+        parameter: f32,
+    },
+    SetBaseFrequency {
+        parameter: f32,
+    },
+    /// Seconds to slide between notes
+    SetGlide {
+        parameter: f32,
+    },
+    /// 0 = sine, 1 = triangle
+    SetShape {
+        parameter: f32,
+    },
+    /// Bends the sine towards a saw, 0 to 1
+    SetPhaseDistortion {
+        parameter: f32,
+    },
+    SetDecay {
+        parameter: f32,
+    },
+    SetDrive {
+        parameter: f32,
+    },
+    SetAccent {
+        parameter: f32,
+    },
+    /// Above 0.5 the kick chokes the 808, without the 808 choking the kick
+    SetKickDuck {
+        parameter: f32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AcidEvent {
+    /// Parameter is an optional velocity, above 0.5 also accents the note;
+    /// data 1 slides from the ringing one
+    Trigger {
+        #[serde(default)]
+        parameter: Option<f32>,
+        #[serde(default)]
+        data: Option<f32>,
+    },
+    SetGain {
+        parameter: f32,
+    },
+    SetChokeGroup {
+        parameter: f32,
+    },
+    SetLofiAmount {
+        parameter: f32,
+    },
+    SetBaseFrequency {
+        parameter: f32,
+    },
+    /// Seconds a slide takes
+    SetSlideTime {
+        parameter: f32,
+    },
+    /// 0 = saw, 1 = square
+    SetWaveform {
+        parameter: f32,
+    },
+    SetCutoff {
+        parameter: f32,
+    },
+    SetResonance {
+        parameter: f32,
+    },
+    /// Depth of the filter envelope, 0 to 1
+    SetEnvMod {
+        parameter: f32,
+    },
+    SetDecay {
+        parameter: f32,
+    },
+    SetAccent {
+        parameter: f32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum WavetableEvent {
+    /// Parameter is an optional velocity from 0 to 1, full when missing
+    Trigger {
+        #[serde(default)]
+        parameter: Option<f32>,
+    },
+    SetGain {
+        parameter: f32,
+    },
+    SetChokeGroup {
+        parameter: f32,
+    },
+    SetLofiAmount {
+        parameter: f32,
+    },
+    SetBaseFrequency {
+        parameter: f32,
+    },
+    /// 0 = sine, 1 = square, scanning through triangle and saw
+    SetPosition {
+        parameter: f32,
+    },
+    SetLfoRate {
+        parameter: f32,
+    },
+    /// Position swing either side of the set position, 0 to 1
+    SetLfoDepth {
+        parameter: f32,
+    },
+    /// Position the envelope adds at its peak, -1 to 1
+    SetPositionEnvAmount {
+        parameter: f32,
+    },
+    SetPositionAttack {
+        parameter: f32,
+    },
+    SetPositionRelease {
+        parameter: f32,
+    },
+    SetFilterCutoff {
+        parameter: f32,
+    },
+    SetFilterResonance {
+        parameter: f32,
+    },
+    SetAttack {
+        parameter: f32,
+    },
+    SetRelease {
+        parameter: f32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Fm6Event {
+    /// Parameter is an optional velocity from 0 to 1, full when missing
+    Trigger {
+        #[serde(default)]
+        parameter: Option<f32>,
+    },
+    SetGain {
+        parameter: f32,
+    },
+    SetChokeGroup {
+        parameter: f32,
+    },
+    SetLofiAmount {
+        parameter: f32,
+    },
+    SetBaseFrequency {
+        parameter: f32,
+    },
+    /// 0 = serial, 1 = two stacks, 2 = three pairs, 3 = one modulator on
+    /// three carriers, 4 = all carriers
+    SetAlgorithm {
+        parameter: f32,
+    },
+    /// Feedback of the top operator onto itself
+    SetFeedback {
+        parameter: f32,
+    },
+    /// Data is the operator, 0 to 5; parameter its multiple of the note
+    SetOpRatio {
+        data: usize,
+        parameter: f32,
+    },
+    /// Data is the operator; parameter is a carrier's loudness or a
+    /// modulator's depth, 0 to 1
+    SetOpLevel {
+        data: usize,
+        parameter: f32,
+    },
+    SetOpAttack {
+        data: usize,
+        parameter: f32,
+    },
+    SetOpDecay {
+        data: usize,
+        parameter: f32,
+    },
+    /// Data is the operator; parameter the level it holds after its decay
+    SetOpSustain {
+        data: usize,
+        parameter: f32,
+    },
+    SetAttack {
+        parameter: f32,
+    },
+    SetRelease {
+        parameter: f32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum FormantEvent {
+    /// Parameter is an optional velocity from 0 to 1, full when missing
+    Trigger {
+        #[serde(default)]
+        parameter: Option<f32>,
+    },
+    SetGain {
+        parameter: f32,
+    },
+    SetChokeGroup {
+        parameter: f32,
+    },
+    SetLofiAmount {
+        parameter: f32,
+    },
+    SetBaseFrequency {
+        parameter: f32,
+    },
+    /// 0 = A, 1 = E, 2 = I, 3 = O, 4 = U; values between morph
+    SetVowel {
+        parameter: f32,
+    },
+    /// Multiplies every formant, 0.5 to 2
+    SetFormantShift {
+        parameter: f32,
+    },
+    /// 0 = saw, 1 = pulse
+    SetWaveform {
+        parameter: f32,
+    },
+    /// How far an LFO sweeps the pulse's width, 0 to 1
+    SetPwmDepth {
+        parameter: f32,
+    },
+    SetPwmRate {
+        parameter: f32,
+    },
+    SetResonance {
+        parameter: f32,
+    },
+    SetAttack {
+        parameter: f32,
+    },
+    SetRelease {
+        parameter: f32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum SupersawEvent {
+    /// Parameter is an optional velocity from 0 to 1, full when missing
+    Trigger {
+        #[serde(default)]
+        parameter: Option<f32>,
+    },
+    SetGain {
+        parameter: f32,
+    },
+    SetChokeGroup {
+        parameter: f32,
+    },
+    SetLofiAmount {
+        parameter: f32,
+    },
+    SetBaseFrequency {
+        parameter: f32,
+    },
+    SetDetune {
+        parameter: f32,
+    },
+    SetStereoWidth {
+        parameter: f32,
+    },
+    /// Cents each saw's pitch wanders, like an analog oscillator
+    SetDriftAmount {
+        parameter: f32,
+    },
+    SetFilterCutoff {
+        parameter: f32,
+    },
+    SetFilterResonance {
+        parameter: f32,
+    },
+    SetFilterEnvAmount {
+        parameter: f32,
+    },
+    SetFilterKeytrack {
+        parameter: f32,
+    },
+    SetFilterFmAmount {
+        parameter: f32,
+    },
+    SetFilterFmRatio {
+        parameter: f32,
+    },
+    SetAmpAttack {
+        parameter: f32,
+    },
+    SetAmpRelease {
+        parameter: f32,
+    },
+    SetFilterAttack {
+        parameter: f32,
+    },
+    SetFilterRelease {
+        parameter: f32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum PadSynthEvent {
+    /// Parameter is an optional velocity from 0 to 1, full when missing
+    Trigger {
+        #[serde(default)]
+        parameter: Option<f32>,
+    },
+    SetGain {
+        parameter: f32,
+    },
+    SetChokeGroup {
+        parameter: f32,
+    },
+    SetLofiAmount {
+        parameter: f32,
+    },
+    /// Root of the chord
+    SetBaseFrequency {
+        parameter: f32,
+    },
+    /// 0 = major, 1 = minor, 2 = sus2, 3 = sus4, 4 = major 7th,
+    /// 5 = minor 7th, 6 = dominant 7th
+    SetChordQuality {
+        parameter: f32,
+    },
+    SetDetune {
+        parameter: f32,
+    },
+    SetAttack {
+        parameter: f32,
+    },
+    SetRelease {
+        parameter: f32,
+    },
+    SetFilterCutoff {
+        parameter: f32,
+    },
+    SetChorusRate {
+        parameter: f32,
+    },
+    SetChorusMix {
+        parameter: f32,
+    },
+}
+
+/// Quality index: 0 = eco (4 channels), 1 = normal (8), 2 = high (16)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[allow(clippy::enum_variant_names)] // Names mirror the wire events
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ReverbEvent {
+    SetSize { parameter: f32 },
+    SetModulationDepth { parameter: f32 },
+    SetFeedback { parameter: f32 },
+    SetQuality { parameter: f32 },
+    SetDuckAmount { parameter: f32 },
+    SetDuckRelease { parameter: f32 },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum EqEvent {
+    SetLowFrequency { parameter: f32 },
+    SetLowGain { parameter: f32 },
+    SetMidFrequency { parameter: f32 },
+    SetMidGain { parameter: f32 },
+    SetMidQ { parameter: f32 },
+    SetHighFrequency { parameter: f32 },
+    SetHighGain { parameter: f32 },
+}
+
+/// Sample pads; triggers carry the pad index as their parameter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum SamplerEvent {
+    /// Data is an optional velocity from 0 to 1, full when missing
+    Trigger {
+        parameter: f32,
+        #[serde(default)]
+        data: Option<f32>,
+    },
+    /// Parameter is the pad to silence
+    Stop {
+        parameter: f32,
+    },
+    SetGain {
+        parameter: f32,
+    },
+    /// Tempo loops are stretched to
+    SetBpm {
+        parameter: f32,
+    },
+    /// Tempo the assigned loops were recorded at; 0 plays them unstretched
+    SetLoopBpm {
+        parameter: f32,
+    },
+    /// 0 = granular, 1 = WSOLA
+    SetStretchQuality {
+        parameter: f32,
+    },
+    /// Stretch grain length in seconds
+    SetGrainSize {
+        parameter: f32,
+    },
+    /// Transpose in semitones
+    SetPitch {
+        parameter: f32,
+    },
+    /// Semitones the pitch envelope sweeps from
+    SetPitchEnvAmount {
+        parameter: f32,
+    },
+    SetPitchAttack {
+        parameter: f32,
+    },
+    SetPitchRelease {
+        parameter: f32,
+    },
+    /// 0 = lowpass, 1 = highpass, 2 = bandpass
+    SetFilterMode {
+        parameter: f32,
+    },
+    SetFilterCutoff {
+        parameter: f32,
+    },
+    SetFilterResonance {
+        parameter: f32,
+    },
+    /// Hz the filter envelope adds to the cutoff
+    SetFilterEnvAmount {
+        parameter: f32,
+    },
+    SetFilterAttack {
+        parameter: f32,
+    },
+    SetFilterRelease {
+        parameter: f32,
+    },
+    /// 1 plays backwards, 0 forwards
+    SetReverse {
+        parameter: f32,
+    },
+    /// Fraction of the slice playback starts from, 0 to 1
+    SetStartOffset {
+        parameter: f32,
+    },
+    /// 0 = one-shot, 1 = loop from the start offset until stopped
+    SetPlayMode {
+        parameter: f32,
+    },
+    /// Sent by the assign commands with a sample, or a slice of one, from the library
+    #[serde(skip)]
+    AssignSample {
+        pad: usize,
+        sample: crate::audio::samples::SampleRef,
+        frames: std::ops::Range<usize>,
+    },
+    /// Sent by the add_sample_layer command to stack a sample onto a pad
+    #[serde(skip)]
+    AddLayer {
+        pad: usize,
+        sample: crate::audio::samples::SampleRef,
+        frames: std::ops::Range<usize>,
+        velocity: std::ops::RangeInclusive<f32>,
+    },
+}
+
+// ============================================================================
+// TRANCE RIFF SYSTEM
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "node", rename_all = "snake_case")]
+pub enum TranceRiffEvent {
+    System(TranceRiffSystemEvent),
+    Metronome(MetronomeEvent),
+    Supersaw(SynthEvent),
+}
+
+/// Locate takes a 1-based bar, set_groove a template name, load_groove a
+/// template (see `GrooveTemplate::from_json`) and set_sequence notes as
+/// (frequency, duration in pulses, velocity). set_clock_source 1 follows an
+/// external transport reported through sync_clock, as a position in beats
+/// (data) at a tempo (parameter); 0 goes back to the internal tempo.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TranceRiffSystemEvent {
+    SetBpm { parameter: f32 },
+    SetPaused { parameter: f32 },
+    Play,
+    Stop,
+    Continue,
+    Locate { parameter: f32 },
+    GetPosition,
+    SetGroove { data: String },
+    SetGrooveStrength { parameter: f32 },
+    LoadGroove { data: serde_json::Value },
+    GetGrooveTemplates,
+    SetSequence { data: Vec<(f32, u32, f32)> },
+    ResetSequence,
+    SetClockSource { parameter: f32 },
+    SyncClock { data: f64, parameter: f32 },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[allow(clippy::enum_variant_names)] // Names mirror the wire events
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum MetronomeEvent {
+    SetEnabled { parameter: f32 },
+    SetVolume { parameter: f32 },
+    SetAccentDownbeat { parameter: f32 },
+    SetCountInBars { parameter: f32 },
+}
+
+/// Supersaw events without the auditioner's choke group and lo-fi controls
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum SynthEvent {
+    Trigger,
+    SetGain { parameter: f32 },
+    SetBaseFrequency { parameter: f32 },
+    SetDetune { parameter: f32 },
+    SetStereoWidth { parameter: f32 },
+    SetFilterCutoff { parameter: f32 },
+    SetFilterResonance { parameter: f32 },
+    SetFilterEnvAmount { parameter: f32 },
+    SetFilterKeytrack { parameter: f32 },
+    SetFilterFmAmount { parameter: f32 },
+    SetFilterFmRatio { parameter: f32 },
+    SetAmpAttack { parameter: f32 },
+    SetAmpRelease { parameter: f32 },
+    SetFilterAttack { parameter: f32 },
+    SetFilterRelease { parameter: f32 },
+}
+
+// ============================================================================
+// MASTER SECTION
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "node", rename_all = "snake_case")]
+pub enum MasterEvent {
+    Filter(MasterFilterEvent),
+    System(MasterSystemEvent),
+    Mixer(MixerEvent),
+    Looper(LooperEvent),
+    Scene(SceneEvent),
+    Stutter(StutterEvent),
+    TapeStop(TapeStopEvent),
+    Evolve(EvolveEvent),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum MasterFilterEvent {
+    SetPosition { parameter: f32 },
+    SetResonance { parameter: f32 },
+}
+
+/// Per-system mixer channels; data names the system
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+#[allow(clippy::enum_variant_names)] // Names mirror the wire events
+pub enum MixerEvent {
+    SetActive { data: String, parameter: f32 },
+    SetGain { data: String, parameter: f32 },
+    SetMute { data: String, parameter: f32 },
+}
+
+/// Loop controls, timed in bars of the playing system's transport; data is
+/// the loop index, except for set_source where it names the system to record
+/// ("master" for the whole mix)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum LooperEvent {
+    Record { data: usize },
+    Overdub { data: usize },
+    Undo { data: usize },
+    Clear { data: usize },
+    SetLevel { data: usize, parameter: f32 },
+    SetBars { parameter: f32 },
+    SetSource { data: String },
+}
+
+/// Scene snapshots; data names the scene
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum SceneEvent {
+    Save {
+        data: String,
+    },
+    /// Parameter is the crossfade time in seconds, instant when missing
+    Recall {
+        data: String,
+        #[serde(default)]
+        parameter: Option<f32>,
+    },
+    Delete {
+        data: String,
+    },
+    GetScenes,
+}
+
+/// Beat repeat on the master bus, held between press and release
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum StutterEvent {
+    /// Parameter is the note division to repeat, at the playing system's
+    /// tempo: 4, 8, 16 or 32
+    Press {
+        parameter: f32,
+    },
+    Release,
+    SetPitch {
+        parameter: f32,
+    },
+    SetDecay {
+        parameter: f32,
+    },
+}
+
+/// Tape stop gestures; parameter is the ramp time in seconds, up to 4
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TapeStopEvent {
+    Stop { parameter: f32 },
+    Start { parameter: f32 },
+}
+
+/// Generative drift per system; data names the system. Seeds are whole
+/// numbers, exact up to 2^24.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::enum_variant_names)] // Names mirror the wire events
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum EvolveEvent {
+    SetEnabled { data: String, parameter: f32 },
+    SetEveryBars { data: String, parameter: f32 },
+    SetIntensity { data: String, parameter: f32 },
+    SetSeed { data: String, parameter: f32 },
+}
+
+/// The seed is integer data; seeds above 2^24 don't survive an f32 param
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum MasterSystemEvent {
+    SetSeed {
+        data: u64,
+    },
+    /// Output level ahead of the limiter, 0 to 2
+    SetMasterGain {
+        parameter: f32,
+    },
+}
+
+/// Most telemetry events held before the oldest are dropped
+const TELEMETRY_QUEUE_CAPACITY: usize = 256;
+
+/// Delivery class for server events
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventPriority {
+    /// State changes the UI must not miss (steps, triggers, transport)
+    Critical,
+    /// Meters, CPU and other readouts; dropped oldest-first under load
+    Telemetry,
 }
 
 /// Server event - sent from backend to frontend
-/// Mirrors ClientEvent structure for symmetry
 #[derive(Debug, Clone)]
 pub struct ServerEvent {
     /// Source system (e.g., "drum_machine", "euclidean", "auditioner")
@@ -86,6 +1295,20 @@ pub struct ServerEvent {
     pub parameter: Option<f32>,
     /// Optional data payload for complex events (serialized JSON)
     pub data: Option<serde_json::Value>,
+    /// Only the latest pending event with the same system, node and event is delivered
+    pub coalesce: bool,
+    pub priority: EventPriority,
+}
+
+/// One step of a track's pattern as shown in the UI. Drum tracks leave the
+/// frequency unset; tonal tracks can hold a step for several pulses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PatternStep {
+    /// 0.0 for a rest
+    pub velocity: f32,
+    pub frequency: Option<f32>,
+    /// Length in the system's pulses
+    pub length: u32,
 }
 
 impl ServerEvent {
@@ -97,6 +1320,8 @@ impl ServerEvent {
             event: event.to_string(),
             parameter: Some(parameter),
             data: None,
+            coalesce: false,
+            priority: EventPriority::Critical,
         }
     }
 
@@ -108,50 +1333,262 @@ impl ServerEvent {
             event: event.to_string(),
             parameter: None,
             data: Some(data),
+            coalesce: false,
+            priority: EventPriority::Critical,
         }
     }
 
     /// Create an event with both parameter and data
-    pub fn with_param_and_data(system: &str, node: &str, event: &str, parameter: f32, data: serde_json::Value) -> Self {
+    pub fn with_param_and_data(
+        system: &str,
+        node: &str,
+        event: &str,
+        parameter: f32,
+        data: serde_json::Value,
+    ) -> Self {
         Self {
             system: system.to_string(),
             node: node.to_string(),
             event: event.to_string(),
             parameter: Some(parameter),
             data: Some(data),
+            coalesce: false,
+            priority: EventPriority::Critical,
         }
     }
 
+    /// A parameter the engine changed itself (clamping, modulation, presets).
+    /// Named after the event that sets it, so the UI control bound to that
+    /// event can follow.
+    pub fn parameter_changed(system: &str, node: &str, event: &str, value: f32) -> Self {
+        Self::new(system, node, event, value).coalescing()
+    }
+
+    /// Report a command the audio thread rejected, so the UI can show why
+    pub fn command_error(command: &str, message: &str) -> Self {
+        Self::with_data(
+            crate::audio::server::MASTER_SYSTEM_NAME,
+            "system",
+            "command_error",
+            serde_json::json!({ "event": command, "message": message }),
+        )
+    }
+
+    /// Answer a latency probe with how long it waited to be applied, and the
+    /// longest any client event waited since the previous probe
+    pub fn pong(id: u64, queue_latency: Duration, max_event_latency: Duration) -> Self {
+        Self::with_data(
+            crate::audio::server::MASTER_SYSTEM_NAME,
+            "system",
+            "pong",
+            serde_json::json!({
+                "id": id,
+                "queue_ms": queue_latency.as_secs_f64() * 1000.0,
+                "max_event_queue_ms": max_event_latency.as_secs_f64() * 1000.0,
+            }),
+        )
+    }
+
+    /// A track's playhead moved to a new step (note index for tonal tracks)
+    pub fn step_changed(system: &str, track_id: &str, step: usize) -> Self {
+        Self::with_data(
+            system,
+            "system",
+            "step_changed",
+            serde_json::json!({ "track_id": track_id, "step": step }),
+        )
+    }
+
+    /// Smooth transport position for animation, sent many times a second.
+    /// Only the latest matters, so it coalesces on the telemetry queue.
+    pub fn beat_phase(system: &str, beat_phase: crate::sequencing::BeatPhase) -> Self {
+        Self::with_data(
+            system,
+            "system",
+            "beat_phase",
+            serde_json::json!({
+                "bar": beat_phase.bar,
+                "beat": beat_phase.beat,
+                "phase": beat_phase.phase,
+            }),
+        )
+        .coalescing()
+        .telemetry()
+    }
+
+    /// A track's pattern changed, or its system was activated
+    pub fn pattern_updated(system: &str, track_id: &str, steps: &[PatternStep]) -> Self {
+        let steps: Vec<serde_json::Value> = steps
+            .iter()
+            .map(|step| {
+                serde_json::json!({
+                    "velocity": step.velocity,
+                    "frequency": step.frequency,
+                    "length": step.length,
+                })
+            })
+            .collect();
+        Self::with_data(
+            system,
+            "system",
+            "pattern_updated",
+            serde_json::json!({ "track_id": track_id, "steps": steps }),
+        )
+    }
+
+    /// The tracks a system will report steps for, sent when it's activated
+    pub fn track_list(system: &str, track_ids: &[&str]) -> Self {
+        Self::with_data(
+            system,
+            "system",
+            "tracks",
+            serde_json::json!({ "tracks": track_ids }),
+        )
+    }
+
+    /// The output stream was reopened, on a new device or after an error
+    pub fn device_changed(device_name: &str, sample_rate: f32, channels: usize) -> Self {
+        Self::with_data(
+            crate::audio::server::MASTER_SYSTEM_NAME,
+            "system",
+            "device_changed",
+            serde_json::json!({
+                "name": device_name,
+                "sample_rate": sample_rate,
+                "channels": channels,
+            }),
+        )
+    }
+
+    /// Output peak levels, linear and before the limiter, ~30 times a second
+    pub fn output_meter(reading: crate::audio::metering::MeterReading) -> Self {
+        Self::with_data(
+            crate::audio::server::MASTER_SYSTEM_NAME,
+            "system",
+            "output_meter",
+            serde_json::json!({ "peak": reading.peak, "held_peak": reading.held_peak }),
+        )
+        .coalescing()
+        .telemetry()
+    }
+
+    /// Samples the limiter had to clip over the last second
+    pub fn clipping_detected(clipped_samples: u32) -> Self {
+        Self::new(
+            crate::audio::server::MASTER_SYSTEM_NAME,
+            "system",
+            "clipping_detected",
+            clipped_samples as f32,
+        )
+    }
+
+    /// Every loop's state, sent whenever one changes
+    pub fn looper_state(source: &str, loops: &[crate::audio::looper::LoopInfo]) -> Self {
+        let loops: Vec<serde_json::Value> = loops
+            .iter()
+            .map(|info| {
+                serde_json::json!({
+                    "state": info.state.name(),
+                    "layers": info.layers,
+                    "level": info.level,
+                    "bars": info.bars,
+                })
+            })
+            .collect();
+        Self::with_data(
+            crate::audio::server::MASTER_SYSTEM_NAME,
+            "looper",
+            "state",
+            serde_json::json!({ "source": source, "loops": loops }),
+        )
+    }
+
+    /// The saved scene names, the last one saved or recalled, and the mixer
+    /// channels that scene leaves behind
+    pub fn scene_state(
+        scenes: &[&str],
+        current: Option<&str>,
+        target: &crate::audio::scenes::Scene,
+    ) -> Self {
+        let channels: serde_json::Map<String, serde_json::Value> = target
+            .channels
+            .iter()
+            .map(|(name, channel)| {
+                let value = serde_json::json!({ "gain": channel.gain, "muted": channel.muted });
+                (name.clone(), value)
+            })
+            .collect();
+        Self::with_data(
+            crate::audio::server::MASTER_SYSTEM_NAME,
+            "scene",
+            "state",
+            serde_json::json!({
+                "scenes": scenes,
+                "current": current,
+                "channels": channels,
+                "filter_position": target.filter_position,
+            }),
+        )
+    }
+
     /// Get parameter value, defaulting to 0.0 if None
     pub fn param(&self) -> f32 {
         self.parameter.unwrap_or(0.0)
     }
+
+    /// Mark a high-rate event (meters, modulator values) where only the
+    /// latest value matters, so a backlog collapses to one update
+    pub fn coalescing(mut self) -> Self {
+        self.coalesce = true;
+        self
+    }
+
+    /// Send on the telemetry queue so a flood can't delay critical events
+    pub fn telemetry(mut self) -> Self {
+        self.priority = EventPriority::Telemetry;
+        self
+    }
+
+    fn same_source(&self, other: &ServerEvent) -> bool {
+        self.system == other.system && self.node == other.node && self.event == other.event
+    }
+}
+
+/// Queues shared by the sender and receiver, plus the thread to wake on send
+struct EventChannel {
+    critical: SegQueue<ServerEvent>,
+    telemetry: ArrayQueue<ServerEvent>,
+    waiter: OnceLock<Thread>,
 }
 
 /// Lock-free event queue for audio -> UI communication
 /// Uses a single-producer, single-consumer queue from crossbeam
 pub struct ServerEventQueue {
-    queue: Arc<SegQueue<ServerEvent>>,
+    channel: Arc<EventChannel>,
 }
 
 impl ServerEventQueue {
     pub fn new() -> Self {
         Self {
-            queue: Arc::new(SegQueue::new()),
+            channel: Arc::new(EventChannel {
+                critical: SegQueue::new(),
+                telemetry: ArrayQueue::new(TELEMETRY_QUEUE_CAPACITY),
+                waiter: OnceLock::new(),
+            }),
         }
     }
 
     /// Get a handle for sending events (for audio thread)
     pub fn sender(&self) -> ServerEventSender {
         ServerEventSender {
-            queue: Arc::clone(&self.queue),
+            channel: Arc::clone(&self.channel),
         }
     }
 
     /// Get a handle for receiving events (for UI thread)
     pub fn receiver(&self) -> ServerEventReceiver {
         ServerEventReceiver {
-            queue: Arc::clone(&self.queue),
+            channel: Arc::clone(&self.channel),
         }
     }
 }
@@ -159,30 +1596,64 @@ impl ServerEventQueue {
 /// Sender handle for audio thread
 #[derive(Clone)]
 pub struct ServerEventSender {
-    queue: Arc<SegQueue<ServerEvent>>,
+    channel: Arc<EventChannel>,
 }
 
 impl ServerEventSender {
-    /// Send an event to the UI thread (non-blocking)
+    /// Send an event to the UI thread and wake it (non-blocking)
     pub fn send(&self, event: ServerEvent) {
-        self.queue.push(event);
+        match event.priority {
+            EventPriority::Critical => self.channel.critical.push(event),
+            EventPriority::Telemetry => {
+                // A full queue drops its oldest reading rather than growing
+                self.channel.telemetry.force_push(event);
+            }
+        }
+        if let Some(waiter) = self.channel.waiter.get() {
+            waiter.unpark();
+        }
     }
 }
 
 /// Receiver handle for UI thread
 pub struct ServerEventReceiver {
-    queue: Arc<SegQueue<ServerEvent>>,
+    channel: Arc<EventChannel>,
 }
 
 impl ServerEventReceiver {
-    /// Process all pending events, emitting them via Tauri
-    /// This should be called once per audio buffer
+    /// Block until an event is sent or the timeout passes.
+    /// Always call from the same thread; it is the one senders wake.
+    pub fn wait_for_events(&self, timeout: Duration) {
+        self.channel.waiter.get_or_init(std::thread::current);
+        if self.channel.critical.is_empty() && self.channel.telemetry.is_empty() {
+            std::thread::park_timeout(timeout);
+        }
+    }
+
+    /// Process all pending events, emitting them via Tauri.
+    /// Critical events go first, then telemetry. Coalescing events are
+    /// collapsed to the latest one per source.
     pub fn process_events<F>(&self, mut emit_event: F)
     where
         F: FnMut(ServerEvent),
     {
-        // Process all available events
-        while let Some(event) = self.queue.pop() {
+        let mut pending: Vec<ServerEvent> = Vec::new();
+        let critical = std::iter::from_fn(|| self.channel.critical.pop());
+        let telemetry = std::iter::from_fn(|| self.channel.telemetry.pop());
+        for event in critical.chain(telemetry) {
+            if event.coalesce {
+                if let Some(existing) = pending
+                    .iter_mut()
+                    .find(|p| p.coalesce && p.same_source(&event))
+                {
+                    *existing = event;
+                    continue;
+                }
+            }
+            pending.push(event);
+        }
+
+        for event in pending {
             emit_event(event);
         }
     }
@@ -193,3 +1664,178 @@ impl Default for ServerEventQueue {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_event_parses_into_variants() {
+        let event = ClientEvent::from_parts("auditioner", "kick", "set_gain", Some(0.5), None);
+        assert!(matches!(
+            event,
+            Ok(ClientEvent::Auditioner(AuditionerEvent::Kick(
+                KickEvent::SetGain { parameter }
+            ))) if parameter == 0.5
+        ));
+
+        let trigger = ClientEvent::from_parts("auditioner", "hihat", "trigger", None, None);
+        assert!(matches!(
+            trigger,
+            Ok(ClientEvent::Auditioner(AuditionerEvent::Hihat(
+                HihatEvent::Trigger { parameter: None }
+            )))
+        ));
+
+        let soft = ClientEvent::from_parts("auditioner", "hihat", "trigger", Some(0.25), None);
+        assert!(matches!(
+            soft,
+            Ok(ClientEvent::Auditioner(AuditionerEvent::Hihat(
+                HihatEvent::Trigger { parameter: Some(velocity) }
+            ))) if velocity == 0.25
+        ));
+
+        let seed = ClientEvent::from_parts("master", "system", "set_seed", None, Some(42.into()));
+        assert!(matches!(
+            seed,
+            Ok(ClientEvent::Master(MasterEvent::System(
+                MasterSystemEvent::SetSeed { data: 42 }
+            )))
+        ));
+
+        let ratios = serde_json::json!([1.0, 1.25, 1.5]);
+        let chord = ClientEvent::from_parts(
+            "auditioner",
+            "chord",
+            "set_chord_ratios",
+            None,
+            Some(ratios),
+        );
+        assert!(matches!(
+            chord,
+            Ok(ClientEvent::Auditioner(AuditionerEvent::Chord(
+                ChordEvent::SetChordRatios { ref data }
+            ))) if data == &[1.0, 1.25, 1.5]
+        ));
+
+        let sync = ClientEvent::from_parts(
+            "trance_riff",
+            "system",
+            "sync_clock",
+            Some(140.0),
+            Some(12.5.into()),
+        );
+        assert!(matches!(
+            sync,
+            Ok(ClientEvent::TranceRiff(TranceRiffEvent::System(
+                TranceRiffSystemEvent::SyncClock { data, parameter }
+            ))) if data == 12.5 && parameter == 140.0
+        ));
+
+        // Unknown names and missing parameters are rejected when parsing;
+        // with plugins on, unknown systems are left for the plugins to check
+        #[cfg(not(feature = "plugins"))]
+        assert!(ClientEvent::from_parts("drums", "kick", "trigger", None, None).is_err());
+        assert!(ClientEvent::from_parts("auditioner", "gong", "trigger", None, None).is_err());
+        assert!(ClientEvent::from_parts("auditioner", "kick", "explode", None, None).is_err());
+        assert!(ClientEvent::from_parts("auditioner", "kick", "set_gain", None, None).is_err());
+    }
+
+    #[test]
+    fn test_wire_names_are_the_names_sent() {
+        let record = ClientEvent::from_parts("master", "looper", "record", None, Some(0.into()));
+        assert_eq!(
+            WireNames::of(&record.unwrap()).to_string(),
+            "master/looper/record"
+        );
+
+        // Long data doesn't fit, but the names come first
+        let notes = serde_json::json!(vec![(440.0, 4, 1.0); 64]);
+        let sequence =
+            ClientEvent::from_parts("trance_riff", "system", "set_sequence", None, Some(notes));
+        assert_eq!(
+            WireNames::of(&sequence.unwrap()).to_string(),
+            "trance_riff/system/set_sequence"
+        );
+    }
+
+    #[cfg(feature = "plugins")]
+    #[test]
+    fn test_unknown_systems_parse_as_plugin_events() {
+        let event = ClientEvent::from_parts("drone", "pad", "set_gain", Some(0.5), None).unwrap();
+        assert_eq!(event.system_name(), "drone");
+        assert_eq!(WireNames::of(&event).to_string(), "drone/pad/set_gain");
+        assert!(matches!(
+            event,
+            ClientEvent::Plugin(PluginEvent { ref node, parameter: Some(p), .. })
+                if node == "pad" && p == 0.5
+        ));
+
+        let trigger = ClientEvent::from_parts("drums", "kick", "trigger", None, None);
+        assert!(matches!(
+            trigger,
+            Ok(ClientEvent::Plugin(PluginEvent { ref system, parameter: None, .. }))
+                if system == "drums"
+        ));
+    }
+
+    #[test]
+    fn test_telemetry_flood_does_not_drop_critical_events() {
+        let queue = ServerEventQueue::new();
+        let sender = queue.sender();
+        let receiver = queue.receiver();
+
+        for i in 0..TELEMETRY_QUEUE_CAPACITY * 4 {
+            sender.send(ServerEvent::new("drums", "kick", "meter", i as f32).telemetry());
+            if i % 100 == 0 {
+                let step = ServerEvent::new("drums", "system", "step_changed", i as f32);
+                sender.send(step);
+            }
+        }
+
+        let mut received = Vec::new();
+        receiver.process_events(|event| received.push(event));
+
+        // Every step arrives, ahead of any telemetry
+        let steps: Vec<f32> = received
+            .iter()
+            .take_while(|e| e.priority == EventPriority::Critical)
+            .map(|e| e.param())
+            .collect();
+        assert_eq!(steps, (0..11).map(|i| i as f32 * 100.0).collect::<Vec<_>>());
+
+        // Telemetry is bounded and keeps the newest readings
+        let meters = &received[steps.len()..];
+        assert_eq!(meters.len(), TELEMETRY_QUEUE_CAPACITY);
+        assert_eq!(
+            meters.last().unwrap().param(),
+            (TELEMETRY_QUEUE_CAPACITY * 4 - 1) as f32
+        );
+    }
+
+    #[test]
+    fn test_coalescing_events_keep_only_the_latest() {
+        let queue = ServerEventQueue::new();
+        let sender = queue.sender();
+        let receiver = queue.receiver();
+
+        sender.send(ServerEvent::new("drums", "kick", "meter", 0.1).coalescing());
+        sender.send(ServerEvent::new("drums", "system", "step_changed", 1.0));
+        sender.send(ServerEvent::new("drums", "kick", "meter", 0.5).coalescing());
+        sender.send(ServerEvent::new("drums", "system", "step_changed", 2.0));
+        sender.send(ServerEvent::new("drums", "kick", "meter", 0.9).coalescing());
+
+        let mut received = Vec::new();
+        receiver.wait_for_events(Duration::from_secs(1)); // Returns at once, events are pending
+        receiver.process_events(|event| received.push((event.param(), event.event)));
+
+        assert_eq!(
+            received,
+            vec![
+                (0.9, "meter".to_string()),
+                (1.0, "step_changed".to_string()),
+                (2.0, "step_changed".to_string()),
+            ]
+        );
+    }
+}
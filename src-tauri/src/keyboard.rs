@@ -0,0 +1,171 @@
+use crate::events::ClientEvent;
+
+/// Physical keys (`KeyboardEvent.code`) laid out like a piano from C: the
+/// home row plays the white keys and the row above it the black keys
+const NOTE_KEYS: [(&str, u8); 18] = [
+    ("KeyA", 0),
+    ("KeyW", 1),
+    ("KeyS", 2),
+    ("KeyE", 3),
+    ("KeyD", 4),
+    ("KeyF", 5),
+    ("KeyT", 6),
+    ("KeyG", 7),
+    ("KeyY", 8),
+    ("KeyH", 9),
+    ("KeyU", 10),
+    ("KeyJ", 11),
+    ("KeyK", 12),
+    ("KeyO", 13),
+    ("KeyL", 14),
+    ("KeyP", 15),
+    ("Semicolon", 16),
+    ("Quote", 17),
+];
+
+const OCTAVE_DOWN_KEY: &str = "KeyZ";
+const OCTAVE_UP_KEY: &str = "KeyX";
+
+/// Octave of the A key, so the keyboard starts at middle C (note 60)
+const DEFAULT_OCTAVE: i32 = 4;
+const MIN_OCTAVE: i32 = -1;
+const MAX_OCTAVE: i32 = 9;
+
+/// Auditioner synths the keyboard can play pitched notes on
+pub const KEYBOARD_SYNTHS: [&str; 8] = [
+    "supersaw",
+    "pad_synth",
+    "chord",
+    "bass808",
+    "acid",
+    "wavetable",
+    "fm6",
+    "formant",
+];
+
+/// What a key press did
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyAction {
+    Note(u8),
+    Octave(i32),
+}
+
+/// Turns computer key presses into MIDI notes, keeping the octave shift
+/// between presses so every window plays the same notes
+pub struct QwertyKeyboard {
+    octave: i32,
+    synth: &'static str,
+}
+
+impl QwertyKeyboard {
+    pub fn new() -> Self {
+        Self {
+            octave: DEFAULT_OCTAVE,
+            synth: KEYBOARD_SYNTHS[0],
+        }
+    }
+
+    pub fn octave(&self) -> i32 {
+        self.octave
+    }
+
+    pub fn synth(&self) -> &'static str {
+        self.synth
+    }
+
+    /// Choose which synth plays notes the drum map doesn't cover
+    pub fn set_synth(&mut self, node: &str) -> Result<(), String> {
+        self.synth = KEYBOARD_SYNTHS
+            .iter()
+            .find(|&&synth| synth == node)
+            .ok_or_else(|| format!("The keyboard can't play notes on '{}'", node))?;
+        Ok(())
+    }
+
+    /// The note or octave change for a key, or None for keys that do nothing.
+    /// Notes above the MIDI range are dropped.
+    pub fn key_down(&mut self, code: &str) -> Option<KeyAction> {
+        match code {
+            OCTAVE_DOWN_KEY => {
+                self.octave = (self.octave - 1).max(MIN_OCTAVE);
+                Some(KeyAction::Octave(self.octave))
+            }
+            OCTAVE_UP_KEY => {
+                self.octave = (self.octave + 1).min(MAX_OCTAVE);
+                Some(KeyAction::Octave(self.octave))
+            }
+            _ => {
+                let &(_, semitone) = NOTE_KEYS.iter().find(|(key, _)| *key == code)?;
+                let note = 12 * (self.octave + 1) + semitone as i32;
+                u8::try_from(note)
+                    .ok()
+                    .filter(|&note| note <= 127)
+                    .map(KeyAction::Note)
+            }
+        }
+    }
+
+    /// Tune the keyboard's synth to a note and play it at a MIDI velocity
+    pub fn synth_events(&self, note: u8, velocity: u8) -> Result<Vec<ClientEvent>, String> {
+        let velocity = velocity.min(127) as f32 / 127.0;
+        Ok(vec![
+            ClientEvent::from_parts(
+                "auditioner",
+                self.synth,
+                "set_base_frequency",
+                Some(note_frequency(note)),
+                None,
+            )?,
+            ClientEvent::from_parts("auditioner", self.synth, "trigger", Some(velocity), None)?,
+        ])
+    }
+}
+
+impl Default for QwertyKeyboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Equal-tempered frequency of a MIDI note, A4 (69) at 440 Hz
+pub fn note_frequency(note: u8) -> f32 {
+    440.0 * ((note as f32 - 69.0) / 12.0).exp2()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_play_notes_from_the_octave() {
+        let mut keyboard = QwertyKeyboard::new();
+        assert_eq!(keyboard.key_down("KeyA"), Some(KeyAction::Note(60)));
+        assert_eq!(keyboard.key_down("KeyW"), Some(KeyAction::Note(61)));
+        assert_eq!(keyboard.key_down("KeyK"), Some(KeyAction::Note(72)));
+        assert_eq!(keyboard.key_down("KeyQ"), None);
+
+        assert_eq!(keyboard.key_down("KeyZ"), Some(KeyAction::Octave(3)));
+        assert_eq!(keyboard.key_down("KeyZ"), Some(KeyAction::Octave(2)));
+        // Octave 2 reaches the General MIDI kick
+        assert_eq!(keyboard.key_down("KeyA"), Some(KeyAction::Note(36)));
+
+        for _ in 0..20 {
+            keyboard.key_down("KeyX");
+        }
+        assert_eq!(keyboard.octave(), MAX_OCTAVE);
+        assert_eq!(keyboard.key_down("KeyG"), Some(KeyAction::Note(127)));
+        assert_eq!(keyboard.key_down("KeyY"), None);
+    }
+
+    #[test]
+    fn test_synth_choice_and_tuning() {
+        let mut keyboard = QwertyKeyboard::new();
+        assert!(keyboard.set_synth("chord").is_ok());
+        assert_eq!(keyboard.synth(), "chord");
+        assert!(keyboard.set_synth("kick").is_err());
+        assert_eq!(keyboard.synth(), "chord");
+
+        assert_eq!(note_frequency(69), 440.0);
+        assert!((note_frequency(60) - 261.63).abs() < 0.01);
+    }
+}
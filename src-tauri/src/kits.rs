@@ -0,0 +1,162 @@
+/// Auditioner nodes whose parameters make up a kit
+pub const KIT_NODES: [&str; 11] = [
+    "kick",
+    "clap",
+    "snare",
+    "hihat",
+    "open_hat",
+    "tom",
+    "modal_drum",
+    "cowbell",
+    "clave",
+    "glitch",
+    "cymbal",
+];
+
+/// Longest kit name, which also becomes its file name
+const MAX_KIT_NAME_LENGTH: usize = 64;
+
+/// One instrument parameter, addressed like the client event that sets it
+#[derive(Debug, Clone, PartialEq)]
+pub struct KitParameter {
+    pub node: String,
+    pub event: String,
+    pub value: f32,
+}
+
+/// A named bundle of drum instrument parameters, kept apart from patterns so
+/// sounds and sequences can be recombined
+#[derive(Debug, Clone, PartialEq)]
+pub struct Kit {
+    pub name: String,
+    pub parameters: Vec<KitParameter>,
+}
+
+impl Kit {
+    /// Parse a kit keyed by node, then by the event that sets each parameter:
+    /// `{ "name": "tight", "kick": { "set_gain": 0.8 }, "hihat": { "set_gain": 0.5 } }`
+    pub fn from_json(value: &serde_json::Value) -> Result<Self, String> {
+        let name = value
+            .get("name")
+            .and_then(|n| n.as_str())
+            .ok_or("Kit needs a name")?;
+        Self::validate_name(name)?;
+
+        let object = value.as_object().ok_or("Kit must be a JSON object")?;
+        let mut parameters = Vec::new();
+        for (node, events) in object {
+            if node == "name" {
+                continue;
+            }
+            if !KIT_NODES.contains(&node.as_str()) {
+                return Err(format!("Kit '{}' has unknown instrument '{}'", name, node));
+            }
+
+            let events = events
+                .as_object()
+                .ok_or_else(|| format!("Kit '{}' {} parameters must be an object", name, node))?;
+            for (event, value) in events {
+                if event == "trigger" {
+                    return Err(format!("Kit '{}' can't store {} triggers", name, node));
+                }
+                let value = value
+                    .as_f64()
+                    .ok_or_else(|| format!("Kit '{}' {} {} must be a number", name, node, event))?;
+                parameters.push(KitParameter {
+                    node: node.clone(),
+                    event: event.clone(),
+                    value: value as f32,
+                });
+            }
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            parameters,
+        })
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut kit = serde_json::Map::new();
+        kit.insert(
+            "name".to_string(),
+            serde_json::Value::String(self.name.clone()),
+        );
+        for parameter in &self.parameters {
+            let node = kit
+                .entry(parameter.node.clone())
+                .or_insert_with(|| serde_json::json!({}));
+            if let Some(events) = node.as_object_mut() {
+                events.insert(parameter.event.clone(), serde_json::json!(parameter.value));
+            }
+        }
+        serde_json::Value::Object(kit)
+    }
+
+    /// File a kit with this name is stored in within the kits directory
+    pub fn file_name_for(name: &str) -> Result<String, String> {
+        Self::validate_name(name)?;
+        Ok(format!("{}.json", name))
+    }
+
+    /// Names become file names, so keep them to a portable character set
+    fn validate_name(name: &str) -> Result<(), String> {
+        let is_valid = !name.trim().is_empty()
+            && name.len() <= MAX_KIT_NAME_LENGTH
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, ' ' | '-' | '_'));
+        if is_valid {
+            Ok(())
+        } else {
+            Err(format!(
+                "Kit name '{}' must be 1 to {} letters, digits, spaces, '-' or '_'",
+                name, MAX_KIT_NAME_LENGTH
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kit_round_trips_through_json() {
+        let kit = Kit {
+            name: "tight".to_string(),
+            parameters: vec![
+                KitParameter {
+                    node: "kick".to_string(),
+                    event: "set_gain".to_string(),
+                    value: 0.5,
+                },
+                KitParameter {
+                    node: "hihat".to_string(),
+                    event: "set_gain".to_string(),
+                    value: 0.25,
+                },
+            ],
+        };
+
+        let parsed = Kit::from_json(&kit.to_json()).unwrap();
+        assert_eq!(parsed.name, "tight");
+        assert_eq!(parsed.parameters.len(), 2);
+        assert!(parsed.parameters.contains(&kit.parameters[0]));
+        assert_eq!(Kit::file_name_for(&parsed.name).unwrap(), "tight.json");
+    }
+
+    #[test]
+    fn test_invalid_kits_are_rejected() {
+        let invalid = [
+            serde_json::json!({ "kick": { "set_gain": 0.5 } }),
+            serde_json::json!({ "name": "../escape" }),
+            serde_json::json!({ "name": "pads", "chord": { "set_gain": 0.5 } }),
+            serde_json::json!({ "name": "hits", "kick": { "trigger": 1.0 } }),
+            serde_json::json!({ "name": "text", "kick": { "set_gain": "loud" } }),
+        ];
+        for kit in invalid {
+            assert!(Kit::from_json(&kit).is_err(), "accepted {}", kit);
+        }
+    }
+}
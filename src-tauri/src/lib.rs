@@ -1,21 +1,58 @@
 mod audio;
 mod audio_output;
+mod backend;
 mod commands;
+mod drum_map;
 mod events;
+mod keyboard;
+mod kits;
+mod sample_import;
 mod sequencing;
+mod session;
 
-use audio_output::AudioOutput;
-use commands::{ClientCommand, ClientCommandQueue};
+use audio::samples::SampleLibrary;
+use audio::systems::{SystemInfo, SystemRegistry};
+use audio_output::{AudioOutput, CpalBackend};
+use backend::{AudioBackend, AudioEngine, NullBackend, OfflineBackend, OutputRouting};
+use commands::{ClientCommand, ClientCommandQueue, ClientCommandSender, CommandQueueStats};
+use drum_map::{DrumMap, DrumTarget};
 use events::ServerEventQueue;
+use keyboard::{KeyAction, QwertyKeyboard};
+use kits::Kit;
+use session::{SessionLogWriter, SessionReplay};
 use std::process::ExitCode;
 use std::sync::Mutex;
 use std::time::Duration;
 use sysinfo::{Pid, System};
 use tauri::{Emitter, Manager, State};
 
+/// What a crate needs to build and register its own audio systems.
+/// Systems registered this way receive their events as `ClientEvent::Plugin`.
+#[cfg(feature = "plugins")]
+pub mod plugins {
+    pub use crate::audio::evolve::Mutations;
+    pub use crate::audio::systems::{SystemInfo, SystemRegistry};
+    pub use crate::audio::{
+        AudioGenerator, AudioProcessor, AudioSystem, StereoAudioGenerator, StereoAudioProcessor,
+    };
+    pub use crate::events::{ClientEvent, PluginEvent, ServerEvent, ServerEventSender};
+    pub use crate::run_with_systems;
+}
+
+/// Sample rate the engine runs at when there's no audio device
+const NULL_BACKEND_SAMPLE_RATE: f32 = 44100.0;
+
 // App state containing only thread-safe communication channels
 struct AppAudioState {
     command_queue: ClientCommandQueue,
+    systems: Vec<SystemInfo>,
+    samples: SampleLibrary,
+    drum_map: DrumMap,
+    keyboard: QwertyKeyboard,
+    // Rate the engine started at, which imported samples are converted to
+    sample_rate: f32,
+    // Session log being recorded, if any
+    session_log: Option<SessionLogWriter>,
 }
 
 type AppState = Mutex<AppAudioState>;
@@ -27,6 +64,9 @@ fn start_event_emitter(
 ) {
     std::thread::spawn(move || {
         loop {
+            // Sleep until the audio thread sends something
+            event_receiver.wait_for_events(Duration::from_millis(250));
+
             event_receiver.process_events(|event| {
                 // Create event name from system.node.event
                 let event_name = format!("{}_{}_{}", event.system, event.node, event.event);
@@ -42,9 +82,6 @@ fn start_event_emitter(
 
                 let _ = app_handle.emit(&event_name, payload);
             });
-
-            // Small sleep to avoid busy waiting
-            std::thread::sleep(Duration::from_millis(16)); // ~60 FPS
         }
     });
 }
@@ -81,6 +118,25 @@ fn start_cpu_monitor(app_handle: tauri::AppHandle) {
     });
 }
 
+fn command_queue_stats_payload(stats: CommandQueueStats) -> serde_json::Value {
+    serde_json::json!({
+        "pending": stats.pending,
+        "high_water_mark": stats.high_water_mark,
+        "dropped": stats.dropped
+    })
+}
+
+/// Reports command queue load every second so an overloaded audio thread is visible
+fn start_command_queue_monitor(sender: ClientCommandSender, app_handle: tauri::AppHandle) {
+    std::thread::spawn(move || loop {
+        let _ = app_handle.emit(
+            "command_queue_stats",
+            command_queue_stats_payload(sender.stats()),
+        );
+        std::thread::sleep(Duration::from_secs(1));
+    });
+}
+
 #[tauri::command]
 fn send_client_event(
     system_name: String,
@@ -90,17 +146,17 @@ fn send_client_event(
     data: Option<serde_json::Value>,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
-    let app_state = state.lock().unwrap();
-    let sender = app_state.command_queue.sender();
-
-    let client_event = crate::events::ClientEvent {
-        system: system_name,
-        node: node_name,
-        event: event_name,
+    // Unknown names and missing parameters are reported back to the caller
+    let client_event = crate::events::ClientEvent::from_parts(
+        &system_name,
+        &node_name,
+        &event_name,
         parameter,
         data,
-    };
+    )?;
 
+    let app_state = state.lock().unwrap();
+    let sender = app_state.command_queue.sender();
     sender.send(ClientCommand::SendClientEvent(client_event));
     Ok(())
 }
@@ -113,8 +169,609 @@ fn switch_audio_system(system_name: String, state: State<'_, AppState>) -> Resul
     Ok(())
 }
 
+/// Names and descriptions of every registered system, in registration order
+#[tauri::command]
+fn list_audio_systems(state: State<'_, AppState>) -> Vec<serde_json::Value> {
+    let app_state = state.lock().unwrap();
+    app_state
+        .systems
+        .iter()
+        .map(|system| {
+            serde_json::json!({
+                "name": system.name,
+                "description": system.description
+            })
+        })
+        .collect()
+}
+
+#[tauri::command]
+fn get_command_queue_stats(state: State<'_, AppState>) -> serde_json::Value {
+    let app_state = state.lock().unwrap();
+    command_queue_stats_payload(app_state.command_queue.sender().stats())
+}
+
+/// Route the stereo output to other device channels (counting from 0), or fold
+/// it down to mono on both; rejected with a command error if the device is too narrow
+#[tauri::command]
+fn set_output_routing(
+    left_channel: usize,
+    right_channel: usize,
+    mono: bool,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let app_state = state.lock().unwrap();
+    let sender = app_state.command_queue.sender();
+    sender.send(ClientCommand::SetOutputRouting(OutputRouting {
+        left_channel,
+        right_channel,
+        mono,
+    }));
+    Ok(())
+}
+
+/// Send a latency probe; the audio thread answers with a master_system_pong event
+#[tauri::command]
+fn ping(id: u64, state: State<'_, AppState>) -> Result<(), String> {
+    let app_state = state.lock().unwrap();
+    let sender = app_state.command_queue.sender();
+    sender.send(ClientCommand::Ping(id));
+    Ok(())
+}
+
+/// Measure a reverb setting offline: RT60 in seconds (null if the tail doesn't
+/// decay 25 dB) and the peak envelope of its impulse response for plotting.
+/// Rendering takes a while, so it runs on a blocking thread; the tail length
+/// and point count are capped.
+#[tauri::command]
+async fn analyze_reverb(
+    quality: u32,
+    size: f32,
+    feedback: f32,
+    modulation_depth: f32,
+    seconds: f32,
+    points: usize,
+) -> Result<serde_json::Value, String> {
+    let seconds = seconds.clamp(0.0, audio::analysis::MAX_REVERB_ANALYSIS_SECONDS);
+    let points = points.min(audio::analysis::MAX_REVERB_ANALYSIS_POINTS);
+    let analysis = tauri::async_runtime::spawn_blocking(move || {
+        audio::analysis::analyze_reverb(quality, size, feedback, modulation_depth, seconds, points)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    Ok(serde_json::json!({
+        "rt60": analysis.rt60,
+        "envelope": analysis.envelope,
+        "sample_rate": audio::analysis::ANALYSIS_SAMPLE_RATE
+    }))
+}
+
+/// Magnitude response of a filter setting, for plotting
+#[tauri::command]
+fn analyze_filter(
+    mode: String,
+    frequency: f32,
+    q: f32,
+    gain_db: Option<f32>,
+    points: usize,
+) -> Result<serde_json::Value, String> {
+    let (frequencies, magnitudes) =
+        audio::analysis::filter_response(&mode, frequency, q, gain_db.unwrap_or(0.0), points)?;
+    Ok(serde_json::json!({
+        "frequencies": frequencies,
+        "magnitudes_db": magnitudes
+    }))
+}
+
+/// Load a groove template from a JSON file and send it to a system.
+/// The file is validated here so errors reach the UI; returns the template name.
+#[tauri::command]
+fn load_groove_file(
+    path: String,
+    system_name: String,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read groove file '{}': {}", path, e))?;
+    let groove: serde_json::Value = serde_json::from_str(&contents)
+        .map_err(|e| format!("Invalid JSON in groove file '{}': {}", path, e))?;
+    let template = crate::sequencing::GrooveTemplate::from_json(&groove)?;
+    let client_event = crate::events::ClientEvent::from_parts(
+        &system_name,
+        "system",
+        "load_groove",
+        None,
+        Some(groove),
+    )?;
+
+    let app_state = state.lock().unwrap();
+    let sender = app_state.command_queue.sender();
+    sender.send(ClientCommand::SendClientEvent(client_event));
+
+    Ok(template.name)
+}
+
+/// Directory kits are saved in, created on first use
+fn kits_dir(app_handle: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    let dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("No app data directory: {}", e))?
+        .join("kits");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create kits directory '{}': {}", dir.display(), e))?;
+    Ok(dir)
+}
+
+/// Save a kit, replacing any kit with the same name; returns the kit name
+#[tauri::command]
+fn save_kit(kit: serde_json::Value, app_handle: tauri::AppHandle) -> Result<String, String> {
+    let kit = Kit::from_json(&kit)?;
+    let path = kits_dir(&app_handle)?.join(Kit::file_name_for(&kit.name)?);
+    let contents = serde_json::to_string_pretty(&kit.to_json())
+        .map_err(|e| format!("Failed to encode kit '{}': {}", kit.name, e))?;
+    std::fs::write(&path, contents)
+        .map_err(|e| format!("Failed to write kit file '{}': {}", path.display(), e))?;
+    Ok(kit.name)
+}
+
+/// Names of the saved kits, sorted; unreadable files are skipped
+#[tauri::command]
+fn list_kits(app_handle: tauri::AppHandle) -> Result<Vec<String>, String> {
+    let dir = kits_dir(&app_handle)?;
+    let entries = std::fs::read_dir(&dir)
+        .map_err(|e| format!("Failed to read kits directory '{}': {}", dir.display(), e))?;
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| {
+            let contents = std::fs::read_to_string(entry.ok()?.path()).ok()?;
+            let value = serde_json::from_str(&contents).ok()?;
+            Kit::from_json(&value).ok().map(|kit| kit.name)
+        })
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// Apply a saved kit to the auditioner and return it, so the UI can show its values
+#[tauri::command]
+fn load_kit(
+    name: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let path = kits_dir(&app_handle)?.join(Kit::file_name_for(&name)?);
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read kit file '{}': {}", path.display(), e))?;
+    let value: serde_json::Value = serde_json::from_str(&contents)
+        .map_err(|e| format!("Invalid JSON in kit file '{}': {}", path.display(), e))?;
+    let kit = Kit::from_json(&value)?;
+
+    // Check every parameter before applying any, so a bad file changes nothing
+    let events = kit
+        .parameters
+        .iter()
+        .map(|parameter| {
+            crate::events::ClientEvent::from_parts(
+                "auditioner",
+                &parameter.node,
+                &parameter.event,
+                Some(parameter.value),
+                None,
+            )
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let app_state = state.lock().unwrap();
+    let sender = app_state.command_queue.sender();
+    for event in events {
+        sender.send(ClientCommand::SendClientEvent(event));
+    }
+
+    Ok(kit.to_json())
+}
+
+/// File the drum map is kept in between sessions
+fn drum_map_path(app_handle: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    let dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("No app data directory: {}", e))?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create data directory '{}': {}", dir.display(), e))?;
+    Ok(dir.join("drum_map.json"))
+}
+
+/// The saved drum map, or the General MIDI one if none has been saved
+fn read_drum_map(app_handle: &tauri::AppHandle) -> Result<DrumMap, String> {
+    let path = drum_map_path(app_handle)?;
+    if !path.exists() {
+        return Ok(DrumMap::general_midi());
+    }
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read drum map '{}': {}", path.display(), e))?;
+    let value: serde_json::Value = serde_json::from_str(&contents)
+        .map_err(|e| format!("Invalid JSON in drum map '{}': {}", path.display(), e))?;
+    DrumMap::from_json(&value)
+}
+
+fn write_drum_map(app_handle: &tauri::AppHandle, drum_map: &DrumMap) -> Result<(), String> {
+    let path = drum_map_path(app_handle)?;
+    let contents = serde_json::to_string_pretty(&drum_map.to_json())
+        .map_err(|e| format!("Failed to encode drum map: {}", e))?;
+    std::fs::write(&path, contents)
+        .map_err(|e| format!("Failed to write drum map '{}': {}", path.display(), e))
+}
+
+/// Which instrument each MIDI note triggers, keyed by note number
+#[tauri::command]
+fn get_drum_map(state: State<'_, AppState>) -> serde_json::Value {
+    state.lock().unwrap().drum_map.to_json()
+}
+
+/// Map a MIDI note to an auditioner instrument, or to a sample pad when the
+/// node is the sampler, and save the map; returns the updated map
+#[tauri::command]
+fn set_drum_map_note(
+    note: u8,
+    node: String,
+    pad: Option<usize>,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let target = DrumTarget::new(&node, pad)?;
+    let mut app_state = state.lock().unwrap();
+    app_state.drum_map.set(note, target)?;
+    write_drum_map(&app_handle, &app_state.drum_map)?;
+    Ok(app_state.drum_map.to_json())
+}
+
+/// Stop a MIDI note triggering anything and save the map; returns the updated map
+#[tauri::command]
+fn clear_drum_map_note(
+    note: u8,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let mut app_state = state.lock().unwrap();
+    app_state.drum_map.remove(note);
+    write_drum_map(&app_handle, &app_state.drum_map)?;
+    Ok(app_state.drum_map.to_json())
+}
+
+/// Go back to the General MIDI drum notes and save; returns the map
+#[tauri::command]
+fn reset_drum_map(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let mut app_state = state.lock().unwrap();
+    app_state.drum_map = DrumMap::general_midi();
+    write_drum_map(&app_handle, &app_state.drum_map)?;
+    Ok(app_state.drum_map.to_json())
+}
+
+/// Play a MIDI note-on through the drum map. MIDI input delivers its notes
+/// here, as can the UI; notes the map doesn't cover play the keyboard synth.
+#[tauri::command]
+fn midi_note_on(note: u8, velocity: u8, state: State<'_, AppState>) -> Result<(), String> {
+    play_note(&state.lock().unwrap(), note, velocity)
+}
+
+fn play_note(app_state: &AppAudioState, note: u8, velocity: u8) -> Result<(), String> {
+    let events = match app_state.drum_map.trigger_event(note, velocity) {
+        Some(event) => vec![event?],
+        None => app_state.keyboard.synth_events(note, velocity)?,
+    };
+    let sender = app_state.command_queue.sender();
+    for event in events {
+        sender.send(ClientCommand::SendClientEvent(event));
+    }
+    Ok(())
+}
+
+/// Velocity of notes played from the computer keyboard
+const KEYBOARD_VELOCITY: u8 = 100;
+
+fn keyboard_payload(keyboard: &QwertyKeyboard, note: Option<u8>) -> serde_json::Value {
+    serde_json::json!({
+        "octave": keyboard.octave(),
+        "synth": keyboard.synth(),
+        "note": note,
+    })
+}
+
+/// Play a computer key (a `KeyboardEvent.code`) like a MIDI keyboard: A to '
+/// play notes, Z and X shift the octave. Returns the octave, synth and any
+/// note played.
+#[tauri::command]
+fn keyboard_key_down(
+    code: String,
+    state: State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let mut app_state = state.lock().unwrap();
+    let note = match app_state.keyboard.key_down(&code) {
+        Some(KeyAction::Note(note)) => {
+            play_note(&app_state, note, KEYBOARD_VELOCITY)?;
+            Some(note)
+        }
+        Some(KeyAction::Octave(_)) | None => None,
+    };
+    Ok(keyboard_payload(&app_state.keyboard, note))
+}
+
+/// Choose the synth for keyboard notes the drum map doesn't cover
+#[tauri::command]
+fn set_keyboard_synth(
+    node: String,
+    state: State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let mut app_state = state.lock().unwrap();
+    app_state.keyboard.set_synth(&node)?;
+    Ok(keyboard_payload(&app_state.keyboard, None))
+}
+
+#[tauri::command]
+fn get_keyboard_state(state: State<'_, AppState>) -> serde_json::Value {
+    keyboard_payload(&state.lock().unwrap().keyboard, None)
+}
+
+fn sample_payload(sample: &audio::samples::Sample) -> serde_json::Value {
+    serde_json::json!({
+        "name": sample.name,
+        "duration": sample.duration_seconds()
+    })
+}
+
+/// Decode a WAV or FLAC file into the sample library at the engine rate,
+/// replacing any sample with the same name; returns its name and duration
+#[tauri::command]
+fn import_sample(path: String, state: State<'_, AppState>) -> Result<serde_json::Value, String> {
+    // Decoding and resampling can take a while, so don't hold the state meanwhile
+    let decoded = sample_import::decode_file(std::path::Path::new(&path))?;
+    let sample_rate = state.lock().unwrap().sample_rate;
+    let sample = decoded.resampled(sample_rate);
+
+    let mut app_state = state.lock().unwrap();
+    let sample = app_state.samples.insert(sample);
+    Ok(sample_payload(&sample))
+}
+
+/// Names and durations of the imported samples, sorted by name
+#[tauri::command]
+fn list_samples(state: State<'_, AppState>) -> Vec<serde_json::Value> {
+    let app_state = state.lock().unwrap();
+    app_state
+        .samples
+        .samples()
+        .iter()
+        .map(|sample| sample_payload(sample))
+        .collect()
+}
+
+/// Load an imported sample onto one of the auditioner's sample pads
+#[tauri::command]
+fn assign_sample(pad: usize, name: String, state: State<'_, AppState>) -> Result<(), String> {
+    let app_state = state.lock().unwrap();
+    let sample = app_state
+        .samples
+        .get(&name)
+        .ok_or_else(|| format!("No imported sample named '{}'", name))?;
+    let frames = 0..sample.len();
+    send_sampler_event(
+        &app_state,
+        crate::events::SamplerEvent::AssignSample {
+            pad,
+            sample,
+            frames,
+        },
+    );
+    Ok(())
+}
+
+/// Stack an imported sample onto a pad for hits between two velocities.
+/// Layers covering the same velocities alternate on each hit.
+#[tauri::command]
+fn add_sample_layer(
+    pad: usize,
+    name: String,
+    min_velocity: f32,
+    max_velocity: f32,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&min_velocity) || !(min_velocity..=1.0).contains(&max_velocity) {
+        return Err(format!(
+            "Invalid velocity range {} to {}, expected 0 <= min <= max <= 1",
+            min_velocity, max_velocity
+        ));
+    }
+    let app_state = state.lock().unwrap();
+    let sample = app_state
+        .samples
+        .get(&name)
+        .ok_or_else(|| format!("No imported sample named '{}'", name))?;
+    let frames = 0..sample.len();
+    send_sampler_event(
+        &app_state,
+        crate::events::SamplerEvent::AddLayer {
+            pad,
+            sample,
+            frames,
+            velocity: min_velocity..=max_velocity,
+        },
+    );
+    Ok(())
+}
+
+fn send_sampler_event(app_state: &AppAudioState, event: crate::events::SamplerEvent) {
+    let event =
+        crate::events::ClientEvent::Auditioner(crate::events::AuditionerEvent::Sampler(event));
+    app_state
+        .command_queue
+        .sender()
+        .send(ClientCommand::SendClientEvent(event));
+}
+
+fn slice_markers_payload(app_state: &AppAudioState, name: &str) -> serde_json::Value {
+    let markers = app_state.samples.slice_markers(name);
+    serde_json::json!({
+        "name": name,
+        "markers": markers,
+        "sample_rate": app_state.sample_rate
+    })
+}
+
+/// Cut an imported loop at its strongest transients into up to 16 slices and
+/// return the slice start frames, which replace any markers it had
+#[tauri::command]
+fn slice_sample(
+    name: String,
+    max_slices: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let max_slices = max_slices
+        .unwrap_or(audio::slicing::MAX_SLICES)
+        .min(audio::slicing::MAX_SLICES);
+    let mut app_state = state.lock().unwrap();
+    app_state.samples.detect_slices(&name, max_slices)?;
+    Ok(slice_markers_payload(&app_state, &name))
+}
+
+/// Replace a sample's slice start frames after adjusting them by hand
+#[tauri::command]
+fn set_slice_markers(
+    name: String,
+    markers: Vec<usize>,
+    state: State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let mut app_state = state.lock().unwrap();
+    app_state.samples.set_slice_markers(&name, markers)?;
+    Ok(slice_markers_payload(&app_state, &name))
+}
+
+/// Put each slice of a sliced sample on its own pad, in order from pad 0
+#[tauri::command]
+fn assign_slices(name: String, state: State<'_, AppState>) -> Result<usize, String> {
+    let app_state = state.lock().unwrap();
+    let sample = app_state
+        .samples
+        .get(&name)
+        .ok_or_else(|| format!("No imported sample named '{}'", name))?;
+    let markers = app_state.samples.slice_markers(&name);
+    if markers.is_empty() {
+        return Err(format!("Sample '{}' hasn't been sliced", name));
+    }
+
+    let ranges = audio::slicing::slice_ranges(markers, sample.len());
+    for (pad, frames) in ranges.iter().enumerate() {
+        send_sampler_event(
+            &app_state,
+            crate::events::SamplerEvent::AssignSample {
+                pad,
+                sample: sample.clone(),
+                frames: frames.clone(),
+            },
+        );
+    }
+    Ok(ranges.len())
+}
+
+/// Start logging every command that changes the audio to a file, one JSON
+/// object per line, replacing any log already being recorded
+#[tauri::command]
+fn start_session_log(path: String, state: State<'_, AppState>) -> Result<(), String> {
+    let writer = SessionLogWriter::create(std::path::Path::new(&path))?;
+    let mut app_state = state.lock().unwrap();
+    app_state
+        .command_queue
+        .sender()
+        .send(ClientCommand::SetSessionRecorder(Some(writer.recorder())));
+    // Replacing the old writer closes its file once the engine lets it go
+    app_state.session_log = Some(writer);
+    Ok(())
+}
+
+/// Stop logging and close the file; returns how many commands were logged
+#[tauri::command]
+fn stop_session_log(state: State<'_, AppState>) -> Result<usize, String> {
+    let writer = {
+        let mut app_state = state.lock().unwrap();
+        app_state
+            .command_queue
+            .sender()
+            .send(ClientCommand::SetSessionRecorder(None));
+        app_state.session_log.take()
+    };
+    match writer {
+        Some(writer) => writer.finish(),
+        None => Err("No session log is being recorded".to_string()),
+    }
+}
+
+/// Play a session log back through the running engine at its original timing
+#[tauri::command]
+fn replay_session_log(path: String, state: State<'_, AppState>) -> Result<usize, String> {
+    let log = session::read_session_log(std::path::Path::new(&path))?;
+    let count = log.len();
+    state
+        .lock()
+        .unwrap()
+        .command_queue
+        .sender()
+        .send(ClientCommand::Replay(SessionReplay::new(log)));
+    Ok(count)
+}
+
+/// Seconds rendered after the last logged command when none are given
+const DEFAULT_RENDER_TAIL_SECONDS: f32 = 2.0;
+
+/// Render a session log offline with the built-in systems, sample accurate,
+/// to a 32-bit float WAV file; returns the frames written
+#[tauri::command]
+fn render_session_log(
+    path: String,
+    output_path: String,
+    tail_seconds: Option<f32>,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    let log = session::read_session_log(std::path::Path::new(&path))?;
+    let sample_rate = state.lock().unwrap().sample_rate;
+
+    let command_queue = ClientCommandQueue::new();
+    let event_queue = ServerEventQueue::new();
+    let mut backend = OfflineBackend::new(sample_rate);
+    let engine = AudioEngine::new(
+        &SystemRegistry::with_builtin_systems(),
+        sample_rate,
+        command_queue.receiver(),
+        event_queue.sender(),
+    )?;
+    backend.start(engine).map_err(|e| e.to_string())?;
+
+    let tail_frames =
+        (tail_seconds.unwrap_or(DEFAULT_RENDER_TAIL_SECONDS).max(0.0) * sample_rate) as usize;
+    let samples = backend.render_session(log, tail_frames);
+    session::write_wav(std::path::Path::new(&output_path), sample_rate, &samples)?;
+    Ok(samples.len() / 2)
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() -> ExitCode {
+    run_with_registry(SystemRegistry::with_builtin_systems())
+}
+
+/// Run the app with the built-in systems plus any added by `register`.
+/// A crate providing its own `AudioSystem`s calls this from its `main`.
+#[cfg(feature = "plugins")]
+pub fn run_with_systems<F>(register: F) -> ExitCode
+where
+    F: FnOnce(&mut SystemRegistry),
+{
+    let mut registry = SystemRegistry::with_builtin_systems();
+    register(&mut registry);
+    run_with_registry(registry)
+}
+
+fn run_with_registry(registry: SystemRegistry) -> ExitCode {
     // Initialize audio system in run() scope
     let command_queue = ClientCommandQueue::new();
     let event_queue = ServerEventQueue::new();
@@ -123,8 +780,22 @@ pub fn run() -> ExitCode {
     let event_sender = event_queue.sender();
     let event_receiver = event_queue.receiver();
 
+    let systems = registry.systems();
+
+    // Without a usable device, keep the app and its pipeline running silently
+    let backend: Box<dyn AudioBackend> = match CpalBackend::new() {
+        Ok(backend) => Box::new(backend),
+        Err(e) => {
+            eprintln!("No audio device ({}), running without audio output", e);
+            Box::new(NullBackend::new(NULL_BACKEND_SAMPLE_RATE))
+        }
+    };
+
+    let sample_rate = backend.sample_rate();
+
     // Create AudioOutput - it will live for the duration of run()
-    let _audio_output = match AudioOutput::new(command_receiver, event_sender) {
+    let audio_output = AudioOutput::new(backend, &registry, command_receiver, event_sender);
+    let _audio_output = match audio_output {
         Ok(output) => {
             println!("Audio system initialized successfully - drum machine is paused by default");
             output
@@ -140,7 +811,36 @@ pub fn run() -> ExitCode {
         .plugin(tauri_plugin_opener::init())
         .invoke_handler(tauri::generate_handler![
             send_client_event,
-            switch_audio_system
+            switch_audio_system,
+            load_groove_file,
+            ping,
+            get_command_queue_stats,
+            list_audio_systems,
+            analyze_reverb,
+            analyze_filter,
+            set_output_routing,
+            save_kit,
+            list_kits,
+            load_kit,
+            get_drum_map,
+            set_drum_map_note,
+            clear_drum_map_note,
+            reset_drum_map,
+            midi_note_on,
+            keyboard_key_down,
+            set_keyboard_synth,
+            get_keyboard_state,
+            import_sample,
+            list_samples,
+            assign_sample,
+            add_sample_layer,
+            slice_sample,
+            set_slice_markers,
+            assign_slices,
+            start_session_log,
+            stop_session_log,
+            replay_session_log,
+            render_session_log
         ])
         .setup(move |app| {
             let app_handle = app.handle().clone();
@@ -148,11 +848,26 @@ pub fn run() -> ExitCode {
             // Start event emitter background process
             start_event_emitter(event_receiver, app_handle.clone());
 
-            // Start CPU monitoring
-            start_cpu_monitor(app_handle);
+            // Start CPU and command queue monitoring
+            start_cpu_monitor(app_handle.clone());
+            start_command_queue_monitor(command_queue.sender(), app_handle.clone());
+
+            // A broken drum map file shouldn't stop the app starting
+            let drum_map = read_drum_map(&app_handle).unwrap_or_else(|e| {
+                eprintln!("{}, using the General MIDI drum map", e);
+                DrumMap::general_midi()
+            });
 
             // Manage only the communication channels
-            app.manage(Mutex::new(AppAudioState { command_queue }));
+            app.manage(Mutex::new(AppAudioState {
+                command_queue,
+                systems,
+                samples: SampleLibrary::new(),
+                drum_map,
+                keyboard: QwertyKeyboard::new(),
+                sample_rate,
+                session_log: None,
+            }));
 
             Ok(())
         })
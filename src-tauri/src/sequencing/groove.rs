@@ -0,0 +1,325 @@
+/// Longest template accepted, in steps
+const MAX_GROOVE_STEPS: usize = 64;
+/// Latest a step can be pushed, as a fraction of a step
+const MAX_TIMING_OFFSET: f32 = 0.5;
+
+/// Per-step timing and velocity offsets, repeating every `timing.len()` steps
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrooveTemplate {
+    pub name: String,
+    /// Delay per step as a fraction of a step (0.0 to 0.5, late only)
+    pub timing: Vec<f32>,
+    /// Velocity offset per step (-1.0 to 1.0), added to the note velocity
+    pub velocity: Vec<f32>,
+}
+
+impl GrooveTemplate {
+    pub fn new(name: &str, timing: Vec<f32>, velocity: Vec<f32>) -> Self {
+        Self {
+            name: name.to_string(),
+            timing: timing
+                .into_iter()
+                .map(|t| t.clamp(0.0, MAX_TIMING_OFFSET))
+                .collect(),
+            velocity: velocity.into_iter().map(|v| v.clamp(-1.0, 1.0)).collect(),
+        }
+    }
+
+    /// No timing or velocity change
+    pub fn straight() -> Self {
+        Self::new("straight", vec![0.0], vec![0.0])
+    }
+
+    /// Off-beat 16ths land on the last triplet of each 8th
+    pub fn triplet() -> Self {
+        Self::new("triplet", vec![0.0, 1.0 / 3.0], vec![0.0, -0.2])
+    }
+
+    /// Off-beat 16ths land on the last 16th of a dotted 8th
+    pub fn dotted() -> Self {
+        Self::new("dotted", vec![0.0, 0.5], vec![0.0, -0.3])
+    }
+
+    /// Parse a template from JSON, either as per-step arrays:
+    /// `{ "name": "mpc", "timing": [0.0, 0.2], "velocity": [0.0, -0.1] }`
+    /// (`velocity` is optional and repeats on its own length), or keyed by step:
+    /// `{ "name": "mpc", "length": 16, "steps": { "2": { "timing": 0.2 } } }`
+    /// (steps are 1-based, unlisted steps are straight).
+    pub fn from_json(value: &serde_json::Value) -> Result<Self, String> {
+        let name = value
+            .get("name")
+            .and_then(|n| n.as_str())
+            .ok_or("Groove template needs a name")?;
+
+        if let Some(steps) = value.get("steps") {
+            return Self::from_step_map(name, value.get("length"), steps);
+        }
+
+        let timing = Self::parse_offsets(value.get("timing"), "timing")?
+            .ok_or_else(|| format!("Groove template '{}' needs timing offsets", name))?;
+        let velocity =
+            Self::parse_offsets(value.get("velocity"), "velocity")?.unwrap_or_else(|| vec![0.0]);
+
+        if timing.is_empty() || velocity.is_empty() {
+            return Err(format!("Groove template '{}' has no steps", name));
+        }
+        if timing.len() > MAX_GROOVE_STEPS || velocity.len() > MAX_GROOVE_STEPS {
+            return Err(format!(
+                "Groove template '{}' is longer than {} steps",
+                name, MAX_GROOVE_STEPS
+            ));
+        }
+
+        Ok(Self::new(name, timing, velocity))
+    }
+
+    fn from_step_map(
+        name: &str,
+        length: Option<&serde_json::Value>,
+        steps: &serde_json::Value,
+    ) -> Result<Self, String> {
+        let length = length
+            .and_then(|l| l.as_u64())
+            .ok_or_else(|| format!("Groove template '{}' needs a step length", name))?
+            as usize;
+        if length == 0 || length > MAX_GROOVE_STEPS {
+            return Err(format!(
+                "Groove template '{}' length must be 1 to {} steps",
+                name, MAX_GROOVE_STEPS
+            ));
+        }
+
+        let steps = steps
+            .as_object()
+            .ok_or_else(|| format!("Groove template '{}' steps must be an object", name))?;
+
+        let mut timing = vec![0.0; length];
+        let mut velocity = vec![0.0; length];
+        for (key, offsets) in steps.iter() {
+            let step = key
+                .parse::<usize>()
+                .ok()
+                .filter(|step| (1..=length).contains(step))
+                .ok_or_else(|| {
+                    format!(
+                        "Groove template '{}' has invalid step '{}' (expected 1 to {})",
+                        name, key, length
+                    )
+                })?;
+
+            timing[step - 1] = Self::parse_step_offset(offsets, "timing", 0.0, MAX_TIMING_OFFSET)?;
+            velocity[step - 1] = Self::parse_step_offset(offsets, "velocity", -1.0, 1.0)?;
+        }
+
+        Ok(Self::new(name, timing, velocity))
+    }
+
+    /// Read an optional offset for one step, rejecting values outside the range
+    fn parse_step_offset(
+        offsets: &serde_json::Value,
+        field: &str,
+        min: f32,
+        max: f32,
+    ) -> Result<f32, String> {
+        let Some(value) = offsets.get(field) else {
+            return Ok(0.0);
+        };
+
+        let offset = value
+            .as_f64()
+            .map(|v| v as f32)
+            .filter(|v| v.is_finite())
+            .ok_or_else(|| format!("Groove {} values must be numbers", field))?;
+        if offset < min || offset > max {
+            return Err(format!(
+                "Groove {} offset {} is outside {} to {}",
+                field, offset, min, max
+            ));
+        }
+
+        Ok(offset)
+    }
+
+    fn parse_offsets(
+        value: Option<&serde_json::Value>,
+        field: &str,
+    ) -> Result<Option<Vec<f32>>, String> {
+        let Some(value) = value else {
+            return Ok(None);
+        };
+
+        let items = value
+            .as_array()
+            .ok_or_else(|| format!("Groove {} must be an array", field))?;
+
+        items
+            .iter()
+            .map(|item| {
+                item.as_f64()
+                    .map(|v| v as f32)
+                    .filter(|v| v.is_finite())
+                    .ok_or_else(|| format!("Groove {} values must be numbers", field))
+            })
+            .collect::<Result<Vec<f32>, String>>()
+            .map(Some)
+    }
+
+    pub fn timing_offset(&self, step: u64) -> f32 {
+        self.timing[(step % self.timing.len() as u64) as usize]
+    }
+
+    pub fn velocity_offset(&self, step: u64) -> f32 {
+        self.velocity[(step % self.velocity.len() as u64) as usize]
+    }
+}
+
+/// The selected groove template and how strongly it is applied
+pub struct Groove {
+    templates: Vec<GrooveTemplate>,
+    current: usize,
+    strength: f32,
+}
+
+impl Groove {
+    pub fn new() -> Self {
+        Self {
+            templates: vec![
+                GrooveTemplate::straight(),
+                GrooveTemplate::triplet(),
+                GrooveTemplate::dotted(),
+            ],
+            current: 0,
+            strength: 1.0,
+        }
+    }
+
+    /// Add a template, replacing any existing template with the same name
+    pub fn add_template(&mut self, template: GrooveTemplate) {
+        if let Some(index) = self.templates.iter().position(|t| t.name == template.name) {
+            self.templates[index] = template;
+        } else {
+            self.templates.push(template);
+        }
+    }
+
+    /// Select a template by name
+    pub fn select(&mut self, name: &str) -> Result<(), String> {
+        self.current = self
+            .templates
+            .iter()
+            .position(|t| t.name == name)
+            .ok_or_else(|| format!("Groove template '{}' not found", name))?;
+        Ok(())
+    }
+
+    pub fn current_name(&self) -> &str {
+        &self.templates[self.current].name
+    }
+
+    pub fn template_names(&self) -> Vec<&str> {
+        self.templates.iter().map(|t| t.name.as_str()).collect()
+    }
+
+    /// Blend between straight (0.0) and the full template (1.0)
+    pub fn set_strength(&mut self, strength: f32) {
+        self.strength = strength.clamp(0.0, 1.0);
+    }
+
+    /// Delay for a step as a fraction of a step
+    pub fn timing_offset(&self, step: u64) -> f32 {
+        self.templates[self.current].timing_offset(step) * self.strength
+    }
+
+    /// Velocity offset for a step
+    pub fn velocity_offset(&self, step: u64) -> f32 {
+        self.templates[self.current].velocity_offset(step) * self.strength
+    }
+}
+
+impl Default for Groove {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_templates_and_strength() {
+        let mut groove = Groove::new();
+        assert_eq!(
+            groove.template_names(),
+            vec!["straight", "triplet", "dotted"]
+        );
+        assert_eq!(groove.timing_offset(1), 0.0);
+
+        groove.select("dotted").unwrap();
+        assert_eq!(groove.timing_offset(0), 0.0);
+        assert_eq!(groove.timing_offset(1), 0.5);
+        assert_eq!(groove.timing_offset(3), 0.5);
+
+        groove.set_strength(0.5);
+        assert_eq!(groove.timing_offset(1), 0.25);
+        assert_eq!(groove.velocity_offset(1), -0.15);
+
+        assert!(groove.select("missing").is_err());
+        assert_eq!(groove.current_name(), "dotted");
+    }
+
+    #[test]
+    fn test_template_from_json() {
+        let value = serde_json::json!({
+            "name": "lazy",
+            "timing": [0.0, 0.1, 0.0, 0.9],
+            "velocity": [0.0, -0.5],
+        });
+        let template = GrooveTemplate::from_json(&value).unwrap();
+        assert_eq!(template.name, "lazy");
+        // Timing is clamped to half a step
+        assert_eq!(template.timing, vec![0.0, 0.1, 0.0, 0.5]);
+        assert_eq!(template.velocity_offset(3), -0.5);
+
+        let mut groove = Groove::new();
+        groove.add_template(template);
+        groove.select("lazy").unwrap();
+        assert_eq!(groove.timing_offset(5), 0.1);
+
+        assert!(GrooveTemplate::from_json(&serde_json::json!({ "timing": [0.0] })).is_err());
+        assert!(GrooveTemplate::from_json(&serde_json::json!({ "name": "empty" })).is_err());
+        assert!(GrooveTemplate::from_json(
+            &serde_json::json!({ "name": "bad", "timing": ["late"] })
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_template_from_step_map() {
+        let value = serde_json::json!({
+            "name": "keyed",
+            "length": 4,
+            "steps": {
+                "2": { "timing": 0.25, "velocity": -0.5 },
+                "4": { "timing": 0.1 },
+            },
+        });
+        let template = GrooveTemplate::from_json(&value).unwrap();
+        assert_eq!(template.timing, vec![0.0, 0.25, 0.0, 0.1]);
+        assert_eq!(template.velocity, vec![0.0, -0.5, 0.0, 0.0]);
+
+        // Steps outside the length, bad lengths and out-of-range offsets are rejected
+        let out_of_length = serde_json::json!({
+            "name": "bad", "length": 4, "steps": { "5": { "timing": 0.1 } },
+        });
+        assert!(GrooveTemplate::from_json(&out_of_length).is_err());
+
+        let no_length = serde_json::json!({ "name": "bad", "steps": {} });
+        assert!(GrooveTemplate::from_json(&no_length).is_err());
+
+        let too_late = serde_json::json!({
+            "name": "bad", "length": 2, "steps": { "1": { "timing": 0.9 } },
+        });
+        assert!(GrooveTemplate::from_json(&too_late).is_err());
+    }
+}
@@ -1,6 +1,10 @@
 pub mod clocks;
 pub mod euclidean;
+pub mod groove;
 pub mod markov;
 pub mod tonal;
+pub mod transport;
 
+pub use groove::*;
 pub use tonal::*;
+pub use transport::*;
@@ -1,3 +1,17 @@
+/// Where the clock takes its timing from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClockSource {
+    /// Count audio callback samples at the internal tempo
+    Internal,
+    /// Follow positions supplied by an external transport (MIDI clock, Link)
+    External,
+}
+
+/// Position error (in pulses) above which the clock jumps instead of slewing
+const EXTERNAL_RESYNC_THRESHOLD_PULSES: f64 = 1.0;
+/// Time over which smaller drift errors are corrected
+const EXTERNAL_CORRECTION_SECONDS: f32 = 0.05;
+
 /// Clock that provides timing signals for all sequencers using PPQN (Pulses Per Quarter Note)
 pub struct PPQNClock {
     bpm: f32,
@@ -5,6 +19,13 @@ pub struct PPQNClock {
     sample_rate: f32,
     samples_per_pulse: u32,
     sample_counter: u32,
+
+    // External follow state
+    source: ClockSource,
+    external_position: f64, // Position in pulses
+    last_external_pulse: i64,
+    drift_correction: f64, // Extra pulses per sample while correcting drift
+    correction_samples_remaining: u32,
 }
 
 impl PPQNClock {
@@ -15,6 +36,11 @@ impl PPQNClock {
             sample_rate,
             samples_per_pulse: 0,
             sample_counter: 0,
+            source: ClockSource::Internal,
+            external_position: 0.0,
+            last_external_pulse: -1,
+            drift_correction: 0.0,
+            correction_samples_remaining: 0,
         };
         clock.recalculate_timing();
         clock
@@ -36,15 +62,96 @@ impl PPQNClock {
         self.samples_per_pulse = calculated.max(1);
     }
 
+    pub fn bpm(&self) -> f32 {
+        self.bpm
+    }
+
+    pub fn ppqn(&self) -> u32 {
+        self.ppqn
+    }
+
+    pub fn set_source(&mut self, source: ClockSource) {
+        if source != self.source {
+            self.source = source;
+            self.drift_correction = 0.0;
+            self.correction_samples_remaining = 0;
+        }
+    }
+
+    pub fn source(&self) -> ClockSource {
+        self.source
+    }
+
+    /// Report the external transport position (in pulses of this clock) and tempo.
+    /// Small errors are slewed out over a short window; large jumps relocate.
+    pub fn sync_external(&mut self, position_pulses: f64, bpm: f32) {
+        self.set_bpm(bpm);
+
+        let error = position_pulses - self.external_position;
+        if error.abs() > EXTERNAL_RESYNC_THRESHOLD_PULSES {
+            self.relocate_external(position_pulses);
+            return;
+        }
+
+        let window = (EXTERNAL_CORRECTION_SECONDS * self.sample_rate).max(1.0) as u32;
+        self.drift_correction = error / window as f64;
+        self.correction_samples_remaining = window;
+    }
+
+    fn relocate_external(&mut self, position_pulses: f64) {
+        self.external_position = position_pulses.max(0.0);
+        self.drift_correction = 0.0;
+        self.correction_samples_remaining = 0;
+
+        // Fire immediately when landing exactly on a pulse, otherwise wait for the next one
+        let pulse = self.external_position.floor() as i64;
+        self.last_external_pulse = if self.external_position.fract() == 0.0 {
+            pulse - 1
+        } else {
+            pulse
+        };
+    }
+
+    fn tick_external(&mut self) -> bool {
+        let pulse = self.external_position.floor() as i64;
+        let is_new_pulse = pulse != self.last_external_pulse;
+        self.last_external_pulse = pulse;
+
+        let mut increment = self.bpm as f64 * self.ppqn as f64 / (60.0 * self.sample_rate as f64);
+        if self.correction_samples_remaining > 0 {
+            increment += self.drift_correction;
+            self.correction_samples_remaining -= 1;
+        }
+        // Never run backwards, even while correcting
+        self.external_position += increment.max(0.0);
+
+        is_new_pulse
+    }
+
     /// Call this once per audio sample. Returns true when a new pulse begins.
     pub fn tick(&mut self) -> bool {
+        if self.source == ClockSource::External {
+            return self.tick_external();
+        }
+
         let is_new_pulse = self.sample_counter % self.samples_per_pulse == 0;
         self.sample_counter = self.sample_counter.wrapping_add(1);
         is_new_pulse
     }
 
+    /// How far through the current pulse the clock is, from 0 to 1
+    pub fn pulse_phase(&self) -> f32 {
+        if self.source == ClockSource::External {
+            return self.external_position.fract() as f32;
+        }
+        // The counter has already moved past the sample that started the pulse
+        let elapsed = self.sample_counter.wrapping_sub(1) % self.samples_per_pulse;
+        elapsed as f32 / self.samples_per_pulse as f32
+    }
+
     pub fn reset(&mut self) {
         self.sample_counter = 0;
+        self.relocate_external(0.0);
     }
 }
 
@@ -60,6 +167,8 @@ pub struct TonalSequencer {
     current_frequency: f32,
     /// Current velocity being played
     current_velocity: f32,
+    /// Index of the note most recently started
+    started_index: Option<usize>,
 }
 
 impl TonalSequencer {
@@ -70,6 +179,7 @@ impl TonalSequencer {
             pulses_remaining: 0,
             current_frequency: 0.0,
             current_velocity: 0.0,
+            started_index: None,
         }
     }
 
@@ -80,6 +190,11 @@ impl TonalSequencer {
         self.current_index = self.current_index.min(self.sequence.len());
     }
 
+    /// The notes as (frequency_hz, duration_pulses, velocity)
+    pub fn sequence(&self) -> &[(f32, u32, f32)] {
+        &self.sequence
+    }
+
     /// Push a new note to the end of the sequence
     pub fn push(&mut self, frequency: f32, duration_pulses: u32, velocity: f32) {
         self.sequence.push((frequency, duration_pulses, velocity));
@@ -118,6 +233,12 @@ impl TonalSequencer {
         self.pulses_remaining = 0;
         self.current_frequency = 0.0;
         self.current_velocity = 0.0;
+        self.started_index = None;
+    }
+
+    /// Index of the note most recently started by `on_pulse`, if any
+    pub fn started_index(&self) -> Option<usize> {
+        self.started_index
     }
 
     /// Get the current frequency
@@ -145,6 +266,7 @@ impl TonalSequencer {
                 self.current_frequency = freq;
                 self.current_velocity = velocity;
                 self.pulses_remaining = duration_pulses;
+                self.started_index = Some(self.current_index);
 
                 // Move to next index for next time
                 self.current_index = (self.current_index + 1) % self.sequence.len();
@@ -166,28 +288,47 @@ impl TonalSequencer {
         (self.current_frequency, self.current_velocity)
     }
 
+    /// Total length of the sequence in pulses
+    pub fn total_pulses(&self) -> u32 {
+        self.sequence
+            .iter()
+            .map(|(_, duration_pulses, _)| *duration_pulses)
+            .sum()
+    }
+
     /// Set the playback position (0.0 to 1.0)
     pub fn set_position(&mut self, position: f32) {
-        if self.sequence.is_empty() {
+        let position = position.clamp(0.0, 1.0);
+        let target_pulse = (position * self.total_pulses() as f32) as u32;
+        self.locate_pulse(target_pulse as u64);
+    }
+
+    /// Move to an absolute pulse position, wrapping around the sequence length.
+    /// A note starting exactly on the target pulse plays on the next pulse;
+    /// otherwise the sequencer waits for the following note.
+    pub fn locate_pulse(&mut self, pulse: u64) {
+        let total_pulses = self.total_pulses();
+        if total_pulses == 0 {
+            self.reset();
             return;
         }
 
-        let position = position.clamp(0.0, 1.0);
-
-        // Calculate total duration in pulses
-        let total_pulses: u32 = self
-            .sequence
-            .iter()
-            .map(|(_, duration_pulses, _)| *duration_pulses)
-            .sum();
-        let target_pulse = (position * total_pulses as f32) as u32;
+        let target_pulse = (pulse % total_pulses as u64) as u32;
 
         // Find which note we should be at
         let mut accumulated = 0u32;
         for (index, &(freq, duration_pulses, velocity)) in self.sequence.iter().enumerate() {
             if accumulated + duration_pulses > target_pulse {
-                self.current_index = index;
-                self.pulses_remaining = duration_pulses - (target_pulse - accumulated);
+                let offset = target_pulse - accumulated;
+                if offset == 0 {
+                    self.current_index = index;
+                    self.pulses_remaining = 0;
+                    self.started_index = None;
+                } else {
+                    self.current_index = (index + 1) % self.sequence.len();
+                    self.pulses_remaining = duration_pulses - offset;
+                    self.started_index = Some(index);
+                }
                 self.current_frequency = freq;
                 self.current_velocity = velocity;
                 return;
@@ -196,3 +337,147 @@ impl TonalSequencer {
         }
     }
 }
+
+/// Chord shapes that instruments can voice on a root note
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChordQuality {
+    Major,
+    Minor,
+    Sus2,
+    Sus4,
+    Major7,
+    Minor7,
+    Dominant7,
+}
+
+impl ChordQuality {
+    pub fn from_index(index: u32) -> Option<Self> {
+        match index {
+            0 => Some(ChordQuality::Major),
+            1 => Some(ChordQuality::Minor),
+            2 => Some(ChordQuality::Sus2),
+            3 => Some(ChordQuality::Sus4),
+            4 => Some(ChordQuality::Major7),
+            5 => Some(ChordQuality::Minor7),
+            6 => Some(ChordQuality::Dominant7),
+            _ => None,
+        }
+    }
+
+    /// Semitones above the root for each note of the chord, root first
+    pub fn intervals(self) -> &'static [f32] {
+        match self {
+            ChordQuality::Major => &[0.0, 4.0, 7.0],
+            ChordQuality::Minor => &[0.0, 3.0, 7.0],
+            ChordQuality::Sus2 => &[0.0, 2.0, 7.0],
+            ChordQuality::Sus4 => &[0.0, 5.0, 7.0],
+            ChordQuality::Major7 => &[0.0, 4.0, 7.0, 11.0],
+            ChordQuality::Minor7 => &[0.0, 3.0, 7.0, 10.0],
+            ChordQuality::Dominant7 => &[0.0, 4.0, 7.0, 10.0],
+        }
+    }
+
+    /// Equal-tempered frequency of each chord note above a root
+    pub fn frequencies(self, root: f32) -> impl Iterator<Item = f32> {
+        self.intervals()
+            .iter()
+            .map(move |semitones| root * (semitones / 12.0).exp2())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chords_are_built_on_the_root() {
+        let minor: Vec<f32> = ChordQuality::Minor.frequencies(220.0).collect();
+        assert_eq!(minor.len(), 3);
+        assert_eq!(minor[0], 220.0);
+        // A minor third above A3 is C4
+        assert!((minor[1] - 261.63).abs() < 0.01);
+
+        assert_eq!(ChordQuality::from_index(6), Some(ChordQuality::Dominant7));
+        assert_eq!(ChordQuality::Dominant7.intervals().len(), 4);
+        assert_eq!(ChordQuality::from_index(7), None);
+    }
+
+    #[test]
+    fn test_sequencer_reports_started_note() {
+        let mut sequencer = TonalSequencer::new();
+        sequencer.set_sequence(vec![(440.0, 2, 1.0), (0.0, 1, 0.0)]);
+        assert_eq!(sequencer.started_index(), None);
+
+        let started: Vec<Option<usize>> = (0..5)
+            .map(|_| {
+                sequencer.on_pulse();
+                sequencer.started_index()
+            })
+            .collect();
+        assert_eq!(started, vec![Some(0), Some(0), Some(0), Some(1), Some(1)]);
+
+        sequencer.locate_pulse(1);
+        assert_eq!(sequencer.started_index(), Some(0));
+        sequencer.reset();
+        assert_eq!(sequencer.started_index(), None);
+    }
+
+    #[test]
+    fn test_internal_clock_pulses_at_tempo() {
+        let sample_rate = 48000.0;
+        let mut clock = PPQNClock::new(sample_rate);
+        clock.set_bpm(120.0);
+
+        // 120 BPM at 8 PPQN = 16 pulses per second
+        let pulses = (0..sample_rate as usize).filter(|_| clock.tick()).count();
+        assert_eq!(pulses, 16);
+    }
+
+    #[test]
+    fn test_external_clock_corrects_drift() {
+        let sample_rate = 48000.0;
+        let mut clock = PPQNClock::new(sample_rate);
+        clock.set_source(ClockSource::External);
+        clock.set_bpm(120.0);
+
+        // External transport runs slightly fast and reports every 512 samples
+        let external_bpm = 121.0;
+        let external_pulses_per_sample = external_bpm * 8.0 / (60.0 * sample_rate as f64);
+        let report_interval = 512;
+
+        for block in 0..200 {
+            let external_position = (block * report_interval) as f64 * external_pulses_per_sample;
+            clock.sync_external(external_position, 120.0);
+            for _ in 0..report_interval {
+                clock.tick();
+            }
+        }
+
+        let external_position = (200 * report_interval) as f64 * external_pulses_per_sample;
+        let error = (clock.external_position - external_position).abs();
+        assert!(
+            error < 0.1,
+            "Clock should stay within 0.1 pulse of the external transport, error {}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_external_clock_relocates_on_jump() {
+        let sample_rate = 48000.0;
+        let mut clock = PPQNClock::new(sample_rate);
+        clock.set_source(ClockSource::External);
+
+        // First tick fires pulse 0
+        assert!(clock.tick());
+
+        // Jump to an exact pulse: it fires immediately
+        clock.sync_external(64.0, 120.0);
+        assert!(clock.tick());
+        assert_eq!(clock.last_external_pulse, 64);
+
+        // Jump mid-pulse: wait for the next boundary
+        clock.sync_external(32.5, 120.0);
+        assert!(!clock.tick());
+    }
+}
@@ -0,0 +1,411 @@
+/// Playback state of a transport
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransportState {
+    CountingIn,
+    Playing,
+    Paused,
+    Stopped,
+}
+
+/// Musical position reported to the UI timeline (all fields 1-based)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransportPosition {
+    pub bar: u32,
+    pub beat: u32,
+    pub step: u32,
+}
+
+/// Smooth position for animating playheads: 1-based bar and beat, plus how
+/// far through the beat playback is, from 0 to 1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BeatPhase {
+    pub bar: u32,
+    pub beat: u32,
+    pub phase: f32,
+}
+
+/// Where a transport is and how fast it's moving, for processors that
+/// follow the beat without a transport of their own
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransportClock {
+    pub bpm: f32,
+    pub beats_per_bar: u32,
+    /// Beats since the song start, including how far through the current one
+    pub beats: f64,
+}
+
+impl Default for TransportClock {
+    fn default() -> Self {
+        Self {
+            bpm: 120.0,
+            beats_per_bar: 4,
+            beats: 0.0,
+        }
+    }
+}
+
+impl TransportClock {
+    /// Bar containing the position, counted from 0
+    pub fn bar(&self) -> u64 {
+        (self.beats.max(0.0) / self.beats_per_bar.max(1) as f64) as u64
+    }
+
+    pub fn frames_per_beat(&self, sample_rate: f32) -> usize {
+        ((60.0 / self.bpm) * sample_rate).max(1.0) as usize
+    }
+
+    pub fn frames_per_bar(&self, sample_rate: f32) -> usize {
+        ((60.0 / self.bpm) * self.beats_per_bar as f32 * sample_rate).max(1.0) as usize
+    }
+
+    /// Move on by one frame at the current tempo, for when no transport is
+    /// running to report the position
+    pub fn advance(&mut self, sample_rate: f32) {
+        self.beats += self.bpm as f64 / 60.0 / sample_rate as f64;
+    }
+}
+
+/// Steps (16th notes) per quarter note
+const STEPS_PER_BEAT: u32 = 4;
+
+/// Tracks play state and song position in clock pulses
+pub struct Transport {
+    state: TransportState,
+    ppqn: u32,
+    beats_per_bar: u32,
+    /// Pulse most recently played
+    current_pulse: u64,
+    /// Pulse that will be played on the next clock tick
+    next_pulse: u64,
+
+    // Count-in
+    count_in_bars: u32,
+    count_in_pulse: u64,
+    count_in_pulses_remaining: u64,
+    last_pulse_was_count_in: bool,
+}
+
+impl Transport {
+    pub fn new(ppqn: u32) -> Self {
+        Self {
+            state: TransportState::Playing,
+            ppqn: ppqn.max(1),
+            beats_per_bar: 4,
+            current_pulse: 0,
+            next_pulse: 0,
+            count_in_bars: 0,
+            count_in_pulse: 0,
+            count_in_pulses_remaining: 0,
+            last_pulse_was_count_in: false,
+        }
+    }
+
+    pub fn state(&self) -> TransportState {
+        self.state
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.state == TransportState::Playing
+    }
+
+    pub fn is_counting_in(&self) -> bool {
+        self.state == TransportState::CountingIn
+    }
+
+    /// True while the clock should run (playing or counting in)
+    pub fn is_running(&self) -> bool {
+        self.is_playing() || self.is_counting_in()
+    }
+
+    /// Start playback from the current position.
+    /// Starting from stop runs the count-in first, if one is set.
+    pub fn play(&mut self) {
+        match self.state {
+            TransportState::Stopped if self.count_in_bars > 0 => {
+                self.state = TransportState::CountingIn;
+                self.count_in_pulse = 0;
+                self.count_in_pulses_remaining = self.count_in_length_pulses();
+            }
+            TransportState::CountingIn => {}
+            _ => self.state = TransportState::Playing,
+        }
+    }
+
+    /// Halt playback, keeping the current position
+    pub fn pause(&mut self) {
+        if self.is_running() {
+            self.state = TransportState::Paused;
+        }
+    }
+
+    /// Resume playback after a pause
+    pub fn continue_playback(&mut self) {
+        self.play();
+    }
+
+    /// Halt playback and rewind to the start
+    pub fn stop(&mut self) {
+        self.state = TransportState::Stopped;
+        self.count_in_pulses_remaining = 0;
+        self.locate_pulse(0);
+    }
+
+    /// Bars of count-in played when starting from stop (0 disables)
+    pub fn set_count_in_bars(&mut self, bars: u32) {
+        self.count_in_bars = bars.min(2);
+    }
+
+    pub fn count_in_bars(&self) -> u32 {
+        self.count_in_bars
+    }
+
+    /// Jump to the start of a bar (1-based)
+    pub fn locate_bar(&mut self, bar: u32) {
+        self.locate_pulse(self.bar_start_pulse(bar));
+    }
+
+    /// Jump to an absolute pulse position
+    pub fn locate_pulse(&mut self, pulse: u64) {
+        self.current_pulse = pulse;
+        self.next_pulse = pulse;
+    }
+
+    pub fn set_beats_per_bar(&mut self, beats_per_bar: u32) {
+        self.beats_per_bar = beats_per_bar.clamp(1, 16);
+    }
+
+    pub fn beats_per_bar(&self) -> u32 {
+        self.beats_per_bar
+    }
+
+    /// First pulse of a bar (1-based)
+    pub fn bar_start_pulse(&self, bar: u32) -> u64 {
+        bar.saturating_sub(1) as u64 * self.pulses_per_bar()
+    }
+
+    pub fn pulses_per_bar(&self) -> u64 {
+        (self.ppqn * self.beats_per_bar) as u64
+    }
+
+    fn pulses_per_step(&self) -> u64 {
+        (self.ppqn / STEPS_PER_BEAT).max(1) as u64
+    }
+
+    /// Advance by one clock pulse. Returns true when the pulse starts a new step.
+    /// Count-in pulses do not move the song position and never start a step.
+    pub fn on_pulse(&mut self) -> bool {
+        if self.is_counting_in() {
+            self.last_pulse_was_count_in = true;
+            self.count_in_pulse = self.count_in_length_pulses() - self.count_in_pulses_remaining;
+            self.count_in_pulses_remaining -= 1;
+            if self.count_in_pulses_remaining == 0 {
+                self.state = TransportState::Playing;
+            }
+            return false;
+        }
+
+        self.last_pulse_was_count_in = false;
+        self.current_pulse = self.next_pulse;
+        self.next_pulse += 1;
+        self.current_pulse.is_multiple_of(self.pulses_per_step())
+    }
+
+    fn count_in_length_pulses(&self) -> u64 {
+        self.count_in_bars as u64 * self.pulses_per_bar()
+    }
+
+    /// Whether the last pulse started a beat, and if so whether it was a downbeat
+    pub fn beat_on_last_pulse(&self) -> Option<bool> {
+        let pulse = if self.last_pulse_was_count_in {
+            self.count_in_pulse
+        } else {
+            self.current_pulse
+        };
+
+        if pulse % self.ppqn as u64 == 0 {
+            Some(pulse % self.pulses_per_bar() == 0)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the last pulse belonged to the count-in
+    pub fn last_pulse_was_count_in(&self) -> bool {
+        self.last_pulse_was_count_in
+    }
+
+    /// Pulse most recently played
+    pub fn current_pulse(&self) -> u64 {
+        self.current_pulse
+    }
+
+    /// Step containing the most recent pulse, counted from the song start
+    pub fn current_step(&self) -> u64 {
+        self.current_pulse / self.pulses_per_step()
+    }
+
+    /// Current position as bar:beat:step
+    pub fn position(&self) -> TransportPosition {
+        let ppqn = self.ppqn as u64;
+        let beat_total = self.current_pulse / ppqn;
+        let beats_per_bar = self.beats_per_bar as u64;
+
+        TransportPosition {
+            bar: (beat_total / beats_per_bar) as u32 + 1,
+            beat: (beat_total % beats_per_bar) as u32 + 1,
+            step: ((self.current_pulse % ppqn) / self.pulses_per_step()) as u32 + 1,
+        }
+    }
+
+    /// Current position with the beat's progress, given how far the clock is
+    /// through the current pulse
+    pub fn beat_phase(&self, pulse_phase: f32) -> BeatPhase {
+        let position = self.position();
+        let pulse_in_beat = (self.current_pulse % self.ppqn as u64) as f32;
+        BeatPhase {
+            bar: position.bar,
+            beat: position.beat,
+            phase: ((pulse_in_beat + pulse_phase.clamp(0.0, 1.0)) / self.ppqn as f32).min(1.0),
+        }
+    }
+
+    /// Current position as a clock at the given tempo, given how far the
+    /// clock is through the current pulse
+    pub fn clock(&self, bpm: f32, pulse_phase: f32) -> TransportClock {
+        let pulses = self.current_pulse as f64 + pulse_phase.clamp(0.0, 1.0) as f64;
+        TransportClock {
+            bpm,
+            beats_per_bar: self.beats_per_bar,
+            beats: pulses / self.ppqn as f64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_counts_bars_beats_steps() {
+        let mut transport = Transport::new(8);
+        assert_eq!(
+            transport.position(),
+            TransportPosition {
+                bar: 1,
+                beat: 1,
+                step: 1
+            }
+        );
+
+        // 8 PPQN: 2 pulses per step, 8 per beat, 32 per bar
+        let step_starts = (0..32).filter(|_| transport.on_pulse()).count();
+        assert_eq!(step_starts, 16);
+        assert_eq!(
+            transport.position(),
+            TransportPosition {
+                bar: 1,
+                beat: 4,
+                step: 4
+            }
+        );
+
+        transport.on_pulse();
+        assert_eq!(
+            transport.position(),
+            TransportPosition {
+                bar: 2,
+                beat: 1,
+                step: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_stop_rewinds_and_locate_jumps() {
+        let mut transport = Transport::new(8);
+        for _ in 0..100 {
+            transport.on_pulse();
+        }
+
+        transport.locate_bar(5);
+        assert!(transport.on_pulse());
+        assert_eq!(transport.current_pulse(), 128);
+        assert_eq!(transport.position().bar, 5);
+
+        transport.stop();
+        assert_eq!(transport.state(), TransportState::Stopped);
+        assert!(!transport.is_playing());
+        assert!(transport.on_pulse());
+        assert_eq!(transport.current_pulse(), 0);
+
+        // Pause only applies while playing
+        transport.pause();
+        assert_eq!(transport.state(), TransportState::Stopped);
+        transport.continue_playback();
+        assert!(transport.is_playing());
+    }
+
+    #[test]
+    fn test_count_in_precedes_playback() {
+        let mut transport = Transport::new(8);
+        transport.set_count_in_bars(1);
+        transport.stop();
+        transport.play();
+        assert!(transport.is_counting_in());
+
+        // One bar of count-in: 4 clicks, the first accented, no song steps
+        let mut beats = Vec::new();
+        for _ in 0..32 {
+            assert!(!transport.on_pulse());
+            if let Some(is_downbeat) = transport.beat_on_last_pulse() {
+                beats.push(is_downbeat);
+            }
+        }
+        assert_eq!(beats, vec![true, false, false, false]);
+        assert!(transport.is_playing());
+
+        // Song starts at bar 1
+        assert!(transport.on_pulse());
+        assert_eq!(transport.current_pulse(), 0);
+        assert_eq!(transport.beat_on_last_pulse(), Some(true));
+
+        // Resuming from pause does not count in again
+        transport.pause();
+        transport.play();
+        assert!(transport.is_playing());
+    }
+
+    #[test]
+    fn test_beat_phase_moves_through_the_beat() {
+        let mut transport = Transport::new(8);
+        // Pulse 14 is the seventh pulse of beat 2
+        for _ in 0..15 {
+            transport.on_pulse();
+        }
+        let beat_phase = transport.beat_phase(0.5);
+        assert_eq!((beat_phase.bar, beat_phase.beat), (1, 2));
+        assert!((beat_phase.phase - 6.5 / 8.0).abs() < 1e-6);
+
+        assert_eq!(transport.beat_phase(1.5).phase, 7.0 / 8.0);
+    }
+
+    #[test]
+    fn test_clock_reports_beats_and_bars() {
+        let mut transport = Transport::new(8);
+        transport.set_beats_per_bar(3);
+        // Pulse 28 is the fifth pulse of beat 4, which starts bar 2
+        for _ in 0..29 {
+            transport.on_pulse();
+        }
+        let clock = transport.clock(90.0, 0.5);
+        assert_eq!(clock.beats, 28.5 / 8.0);
+        assert_eq!(clock.bar(), 1);
+        assert_eq!(clock.frames_per_bar(48000.0), 96000);
+
+        let mut free = TransportClock::default();
+        for _ in 0..100 {
+            free.advance(100.0);
+        }
+        assert!((free.beats - 2.0).abs() < 1e-9);
+    }
+}
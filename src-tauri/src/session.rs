@@ -0,0 +1,345 @@
+use crate::commands::ClientCommand;
+use crate::events::ClientEvent;
+use crossbeam::queue::ArrayQueue;
+use std::collections::VecDeque;
+use std::io::{BufRead, BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Most applied commands held for the writer before new ones are dropped
+const SESSION_QUEUE_CAPACITY: usize = 4096;
+
+/// How often the writer thread drains the queue to disk
+const WRITE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Longest a finishing writer waits for the engine to let go of its recorder
+const FINISH_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A command as the engine applied it, on the frame it took effect counted
+/// from the start of the recording
+#[derive(Debug, Clone)]
+pub struct LoggedCommand {
+    pub frame: u64,
+    pub command: ClientCommand,
+}
+
+impl LoggedCommand {
+    /// Only commands that change the audio are logged; pings and output
+    /// routing are left out
+    pub fn is_logged(command: &ClientCommand) -> bool {
+        matches!(
+            command,
+            ClientCommand::SendClientEvent(_) | ClientCommand::SwitchSystem(_)
+        )
+    }
+
+    /// One line of the log: `{ "frame": 512, "event": { "system": ... } }` or
+    /// `{ "frame": 0, "switch_system": "trance_riff" }`
+    pub fn to_json(&self) -> Result<serde_json::Value, String> {
+        match &self.command {
+            ClientCommand::SendClientEvent(event) => Ok(serde_json::json!({
+                "frame": self.frame,
+                "event": event.to_json()?,
+            })),
+            ClientCommand::SwitchSystem(name) => Ok(serde_json::json!({
+                "frame": self.frame,
+                "switch_system": name,
+            })),
+            command => Err(format!("{:?} isn't logged", command)),
+        }
+    }
+
+    pub fn from_json(value: &serde_json::Value) -> Result<Self, String> {
+        let frame = value
+            .get("frame")
+            .and_then(|f| f.as_u64())
+            .ok_or("Logged command needs a frame")?;
+
+        if let Some(name) = value.get("switch_system").and_then(|n| n.as_str()) {
+            return Ok(Self {
+                frame,
+                command: ClientCommand::SwitchSystem(name.to_string()),
+            });
+        }
+
+        let event = value
+            .get("event")
+            .ok_or("Logged command needs an event or switch_system")?;
+        let part = |key: &str| {
+            event
+                .get(key)
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| format!("Logged event needs a {}", key))
+        };
+        let parameter = event
+            .get("parameter")
+            .and_then(|p| p.as_f64())
+            .map(|p| p as f32);
+        let data = event.get("data").filter(|d| !d.is_null()).cloned();
+        let event = ClientEvent::from_parts(
+            part("system")?,
+            part("node")?,
+            part("event")?,
+            parameter,
+            data,
+        )?;
+        Ok(Self {
+            frame,
+            command: ClientCommand::SendClientEvent(event),
+        })
+    }
+}
+
+/// The audio thread's end of a session log: queues each applied command for
+/// the writer thread without blocking
+#[derive(Debug, Clone)]
+pub struct SessionRecorder {
+    queue: Arc<ArrayQueue<LoggedCommand>>,
+}
+
+impl SessionRecorder {
+    /// Queue a command if it's one that gets logged; dropped if the writer
+    /// has fallen too far behind
+    pub fn record(&self, frame: u64, command: &ClientCommand) {
+        if LoggedCommand::is_logged(command) {
+            let _ = self.queue.push(LoggedCommand {
+                frame,
+                command: command.clone(),
+            });
+        }
+    }
+}
+
+/// Writes recorded commands to a session log file, one JSON object per line
+pub struct SessionLogWriter {
+    recorder: SessionRecorder,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<Result<usize, String>>>,
+}
+
+impl SessionLogWriter {
+    /// Create the log file, replacing any file already there
+    pub fn create(path: &Path) -> Result<Self, String> {
+        let file = std::fs::File::create(path)
+            .map_err(|e| format!("Failed to create session log '{}': {}", path.display(), e))?;
+        let recorder = SessionRecorder {
+            queue: Arc::new(ArrayQueue::new(SESSION_QUEUE_CAPACITY)),
+        };
+        let running = Arc::new(AtomicBool::new(true));
+
+        let queue = Arc::clone(&recorder.queue);
+        let thread_running = Arc::clone(&running);
+        let thread = std::thread::spawn(move || {
+            let mut writer = BufWriter::new(file);
+            let mut written = 0;
+            let mut finishing_for = Duration::ZERO;
+            loop {
+                // Once finishing, keep going until the engine has dropped its
+                // recorder (only this thread and the writer's handle are
+                // left), so commands applied just before the stop are kept
+                let released = Arc::strong_count(&queue) <= 2;
+                let running = thread_running.load(Ordering::Acquire)
+                    || (!released && finishing_for < FINISH_TIMEOUT);
+                while let Some(logged) = queue.pop() {
+                    match logged.to_json() {
+                        Ok(line) => {
+                            writeln!(writer, "{}", line)
+                                .map_err(|e| format!("Failed to write session log: {}", e))?;
+                            written += 1;
+                        }
+                        // Sample assignments carry audio, not wire data
+                        Err(e) => eprintln!("Left out of the session log: {}", e),
+                    }
+                }
+                writer
+                    .flush()
+                    .map_err(|e| format!("Failed to write session log: {}", e))?;
+                if !running {
+                    return Ok(written);
+                }
+                if !thread_running.load(Ordering::Acquire) {
+                    finishing_for += WRITE_INTERVAL;
+                }
+                std::thread::sleep(WRITE_INTERVAL);
+            }
+        });
+
+        Ok(Self {
+            recorder,
+            running,
+            thread: Some(thread),
+        })
+    }
+
+    /// Handle for the engine to record into
+    pub fn recorder(&self) -> SessionRecorder {
+        self.recorder.clone()
+    }
+
+    /// Write out everything recorded and close the file, returning how many
+    /// commands were logged. Send the engine `SetSessionRecorder(None)`
+    /// first; this waits briefly for it to be applied.
+    pub fn finish(mut self) -> Result<usize, String> {
+        self.running.store(false, Ordering::Release);
+        match self.thread.take() {
+            Some(thread) => thread
+                .join()
+                .map_err(|_| "Session log writer panicked".to_string())?,
+            None => Ok(0),
+        }
+    }
+}
+
+impl Drop for SessionLogWriter {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Release);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Read a session log back, in frame order
+pub fn read_session_log(path: &Path) -> Result<Vec<LoggedCommand>, String> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| format!("Failed to open session log '{}': {}", path.display(), e))?;
+    let mut commands = Vec::new();
+    for (index, line) in std::io::BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| format!("Failed to read session log: {}", e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let value: serde_json::Value = serde_json::from_str(&line)
+            .map_err(|e| format!("Session log line {} isn't JSON: {}", index + 1, e))?;
+        let logged = LoggedCommand::from_json(&value)
+            .map_err(|e| format!("Session log line {}: {}", index + 1, e))?;
+        commands.push(logged);
+    }
+    commands.sort_by_key(|logged| logged.frame);
+    Ok(commands)
+}
+
+/// Logged commands fed back into an engine at their original spacing
+#[derive(Debug, Clone)]
+pub struct SessionReplay {
+    commands: VecDeque<LoggedCommand>,
+}
+
+impl SessionReplay {
+    pub fn new(mut commands: Vec<LoggedCommand>) -> Self {
+        commands.sort_by_key(|logged| logged.frame);
+        Self {
+            commands: commands.into(),
+        }
+    }
+
+    /// The next command due by the given frame of the replay
+    pub fn next_due(&mut self, frame: u64) -> Option<ClientCommand> {
+        if self.commands.front()?.frame > frame {
+            return None;
+        }
+        self.commands.pop_front().map(|logged| logged.command)
+    }
+
+    /// Frame the next command is due on
+    pub fn next_frame(&self) -> Option<u64> {
+        self.commands.front().map(|logged| logged.frame)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
+
+/// Write interleaved stereo as a 32-bit float WAV file
+pub fn write_wav(path: &Path, sample_rate: f32, samples: &[f32]) -> Result<(), String> {
+    let channels: u16 = 2;
+    let bytes_per_sample: u16 = 4;
+    let data_length = (samples.len() * bytes_per_sample as usize) as u32;
+    let byte_rate = sample_rate as u32 * (channels * bytes_per_sample) as u32;
+
+    let mut bytes = Vec::with_capacity(44 + data_length as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_length).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    // Format 3 is IEEE float
+    bytes.extend_from_slice(&3u16.to_le_bytes());
+    bytes.extend_from_slice(&channels.to_le_bytes());
+    bytes.extend_from_slice(&(sample_rate as u32).to_le_bytes());
+    bytes.extend_from_slice(&byte_rate.to_le_bytes());
+    bytes.extend_from_slice(&(channels * bytes_per_sample).to_le_bytes());
+    bytes.extend_from_slice(&(bytes_per_sample * 8).to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_length.to_le_bytes());
+    for sample in samples {
+        bytes.extend_from_slice(&sample.to_le_bytes());
+    }
+
+    std::fs::write(path, bytes).map_err(|e| format!("Failed to write '{}': {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gain_event(value: f32) -> ClientCommand {
+        let event =
+            ClientEvent::from_parts("auditioner", "kick", "set_gain", Some(value), None).unwrap();
+        ClientCommand::SendClientEvent(event)
+    }
+
+    #[test]
+    fn test_log_round_trips_through_a_file() {
+        let path = std::env::temp_dir().join(format!("session-{}.jsonl", std::process::id()));
+        let writer = SessionLogWriter::create(&path).unwrap();
+        let recorder = writer.recorder();
+        recorder.record(0, &ClientCommand::SwitchSystem("auditioner".to_string()));
+        recorder.record(64, &ClientCommand::Ping(1));
+        recorder.record(128, &gain_event(0.5));
+        drop(recorder);
+        assert_eq!(writer.finish(), Ok(2));
+
+        let commands = read_session_log(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(commands.len(), 2);
+        assert!(matches!(
+            &commands[0].command,
+            ClientCommand::SwitchSystem(name) if name == "auditioner"
+        ));
+        assert_eq!(commands[1].frame, 128);
+        assert_eq!(
+            commands[1].to_json().unwrap(),
+            LoggedCommand {
+                frame: 128,
+                command: gain_event(0.5)
+            }
+            .to_json()
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_replay_hands_out_commands_as_they_fall_due() {
+        let mut replay = SessionReplay::new(vec![
+            LoggedCommand {
+                frame: 100,
+                command: gain_event(0.25),
+            },
+            LoggedCommand {
+                frame: 0,
+                command: gain_event(1.0),
+            },
+        ]);
+        assert!(replay.next_due(0).is_some());
+        assert!(replay.next_due(99).is_none());
+        assert_eq!(replay.next_frame(), Some(100));
+        assert!(replay.next_due(100).is_some());
+        assert!(replay.is_finished());
+
+        assert!(LoggedCommand::from_json(&serde_json::json!({ "frame": 0 })).is_err());
+    }
+}
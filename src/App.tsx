@@ -1,53 +1,229 @@
-import { useState } from "react"
+import { useState, useEffect } from "react"
+import { invoke } from "@tauri-apps/api/core"
+import { listen } from "@tauri-apps/api/event"
 import { AuditionerPage } from "./components/AuditionerPage"
 import { TranceRiffPage } from "./components/TranceRiffPage"
+import { GenericSystemPage } from "./components/GenericSystemPage"
+import { SystemMixer } from "./components/SystemMixer"
+import { EvolveControls } from "./components/EvolveControls"
+import { StutterControls } from "./components/StutterControls"
+import { TapeStopControls } from "./components/TapeStopControls"
+import { MasterOutput } from "./components/MasterOutput"
+import { SessionLogControls } from "./components/SessionLogControls"
+import {
+  Commands,
+  SystemNames,
+  AudioSystemInfo,
+  ServerEvents,
+  CommandErrorPayload,
+  PongPayload,
+  DeviceChangedPayload,
+  CommandQueueStatsEvent,
+  CommandQueueStats,
+} from "./events"
 import "./App.css"
 
-type SystemTab = "auditioner" | "tranceriff"
+interface SystemPage {
+  label: string
+  page: () => JSX.Element
+}
+
+// Systems with a dedicated page; any other registered system gets a generic one
+const SYSTEM_PAGES: Record<string, SystemPage> = {
+  [SystemNames.Auditioner]: { label: "Auditioner", page: AuditionerPage },
+  [SystemNames.TranceRiff]: { label: "Trance Riff", page: TranceRiffPage },
+}
+
+// How often the UI-to-audio latency is measured
+const PING_INTERVAL_MS = 2000
 
 function App() {
-  const [activeTab, setActiveTab] = useState<SystemTab>("auditioner")
+  const [systems, setSystems] = useState<AudioSystemInfo[]>([])
+  const [activeTab, setActiveTab] = useState<string | null>(null)
+  const [commandError, setCommandError] = useState<string | null>(null)
+  const [latency, setLatency] = useState<{
+    roundTripMs: number
+    queueMs: number
+    maxEventQueueMs: number
+  } | null>(null)
+
+  const [outputDevice, setOutputDevice] = useState<string | null>(null)
+  const [queueStats, setQueueStats] = useState<CommandQueueStats | null>(null)
+
+  // Watch for the audio thread falling behind on commands
+  useEffect(() => {
+    invoke<CommandQueueStats>(Commands.GetCommandQueueStats)
+      .then(setQueueStats)
+      .catch((error) => console.error("Error getting queue stats:", error))
+
+    const unlisten = listen<CommandQueueStats>(
+      CommandQueueStatsEvent,
+      (event) => setQueueStats(event.payload)
+    )
+
+    return () => {
+      unlisten.then((stop) => stop())
+    }
+  }, [])
+
+  // Ping the audio thread to measure how long controls take to apply
+  useEffect(() => {
+    const pingSentAt = new Map<number, number>()
+    let nextId = 0
+
+    const unlisten = listen<PongPayload>(ServerEvents.Master.Pong, (event) => {
+      const { id, queue_ms, max_event_queue_ms } = event.payload.data
+      const sentAt = pingSentAt.get(id)
+      if (sentAt === undefined) return
+      pingSentAt.delete(id)
+      setLatency({
+        roundTripMs: performance.now() - sentAt,
+        queueMs: queue_ms,
+        maxEventQueueMs: max_event_queue_ms,
+      })
+    })
+
+    const interval = setInterval(() => {
+      const id = nextId++
+      pingSentAt.set(id, performance.now())
+      invoke(Commands.Ping, { id }).catch((error) =>
+        console.error("Error sending ping:", error)
+      )
+    }, PING_INTERVAL_MS)
+
+    return () => {
+      clearInterval(interval)
+      unlisten.then((stop) => stop())
+    }
+  }, [])
+
+  // Show commands the audio thread rejected instead of dropping them silently
+  useEffect(() => {
+    const unlisten = listen<CommandErrorPayload>(
+      ServerEvents.Master.CommandError,
+      (event) => setCommandError(event.payload.data.message)
+    )
+
+    return () => {
+      unlisten.then((stop) => stop())
+    }
+  }, [])
+
+  // The backend reopens the stream when the output device goes away or changes
+  useEffect(() => {
+    const unlisten = listen<DeviceChangedPayload>(
+      ServerEvents.Master.DeviceChanged,
+      (event) =>
+        setOutputDevice(
+          `${event.payload.data.name} (${event.payload.data.sample_rate} Hz)`
+        )
+    )
 
-  const handleTabChange = (tab: SystemTab) => {
+    return () => {
+      unlisten.then((stop) => stop())
+    }
+  }, [])
+
+  // Build the tabs from whatever systems the backend registered
+  useEffect(() => {
+    invoke<AudioSystemInfo[]>(Commands.ListAudioSystems)
+      .then((registered) => {
+        setSystems(registered)
+        setActiveTab((current) => current ?? registered[0]?.name ?? null)
+      })
+      .catch((error) => console.error("Error listing audio systems:", error))
+  }, [])
+
+  const handleTabChange = (tab: string) => {
     setActiveTab(tab)
   }
 
+  const renderActivePage = () => {
+    const system = systems.find((s) => s.name === activeTab)
+    if (!system) return null
+
+    const Page = SYSTEM_PAGES[system.name]?.page
+    return Page ? <Page /> : <GenericSystemPage system={system} />
+  }
+
   return (
     <main className="min-h-screen bg-gray-900 text-white p-8 font-mono">
       <div className="max-w-6xl mx-auto">
         <div className="mb-6">
-          <h1 className="text-lg text-neutral-300 mb-6">
-            Forbidden Drum Machine
-          </h1>
+          <div className="flex items-baseline justify-between mb-6">
+            <h1 className="text-lg text-neutral-300">Forbidden Drum Machine</h1>
+            <div className="flex space-x-4">
+              {outputDevice && (
+                <span className="text-xs text-gray-500" title="Output device">
+                  Output: {outputDevice}
+                </span>
+              )}
+              {queueStats && (
+                <span
+                  className={`text-xs ${
+                    queueStats.dropped > 0 ? "text-red-400" : "text-gray-500"
+                  }`}
+                  title="Pending commands / most ever pending / dropped"
+                >
+                  Queue: {queueStats.pending} (max{" "}
+                  {queueStats.high_water_mark}, dropped {queueStats.dropped})
+                </span>
+              )}
+              {latency && (
+                <span
+                  className="text-xs text-gray-500"
+                  title="Round trip / time to apply / slowest control since last ping"
+                >
+                  Latency: {latency.roundTripMs.toFixed(1)} ms (
+                  {latency.queueMs.toFixed(1)} ms to audio, max{" "}
+                  {latency.maxEventQueueMs.toFixed(1)} ms)
+                </span>
+              )}
+            </div>
+          </div>
 
           {/* Tab Navigation */}
           <div className="flex space-x-4 mb-6">
+            {systems.map((system) => (
+              <button
+                key={system.name}
+                onClick={() => handleTabChange(system.name)}
+                title={system.description}
+                className={`px-6 py-2 rounded-lg font-medium transition-colors ${
+                  activeTab === system.name
+                    ? "bg-green-600 text-white"
+                    : "bg-gray-700 text-gray-300 hover:bg-gray-600"
+                }`}
+              >
+                {SYSTEM_PAGES[system.name]?.label ?? system.name}
+              </button>
+            ))}
+          </div>
+
+          {systems.length > 1 && (
+            <SystemMixer systems={systems} currentSystem={activeTab} />
+          )}
+          {activeTab && <EvolveControls systemName={activeTab} />}
+          <StutterControls />
+          <TapeStopControls />
+          <MasterOutput />
+          <SessionLogControls />
+        </div>
+
+        {commandError && (
+          <div className="mb-6 flex items-center justify-between rounded-lg bg-red-900 px-4 py-2 text-sm text-red-200">
+            <span>{commandError}</span>
             <button
-              onClick={() => handleTabChange("auditioner")}
-              className={`px-6 py-2 rounded-lg font-medium transition-colors ${
-                activeTab === "auditioner"
-                  ? "bg-green-600 text-white"
-                  : "bg-gray-700 text-gray-300 hover:bg-gray-600"
-              }`}
-            >
-              Auditioner
-            </button>
-            <button
-              onClick={() => handleTabChange("tranceriff")}
-              className={`px-6 py-2 rounded-lg font-medium transition-colors ${
-                activeTab === "tranceriff"
-                  ? "bg-green-600 text-white"
-                  : "bg-gray-700 text-gray-300 hover:bg-gray-600"
-              }`}
+              onClick={() => setCommandError(null)}
+              className="ml-4 text-red-300 hover:text-white"
             >
-              Trance Riff
+              Dismiss
             </button>
           </div>
-        </div>
+        )}
 
         {/* Tab Content */}
-        {activeTab === "auditioner" && <AuditionerPage />}
-        {activeTab === "tranceriff" && <TranceRiffPage />}
+        {renderActivePage()}
       </div>
     </main>
   )
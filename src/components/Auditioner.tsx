@@ -1,6 +1,13 @@
-import { useState } from "react"
+import { useEffect, useState } from "react"
 import { invoke } from "@tauri-apps/api/core"
-import { SystemNames, CommonEvents, Commands } from "../events"
+import { listen } from "@tauri-apps/api/event"
+import {
+  SystemNames,
+  CommonEvents,
+  Commands,
+  parameterChangedEvent,
+  ParameterChangedPayload,
+} from "../events"
 
 export interface ParameterConfig {
   name: string
@@ -12,30 +19,69 @@ export interface ParameterConfig {
   defaultValue: number
   unit?: string
   formatter?: (value: number) => string
+  // Sent alongside the value, e.g. the operator a per-operator event sets
+  data?: number | number[]
 }
 
+// Parameters sharing an event are told apart by their data
+const parameterKey = (param: ParameterConfig): string =>
+  param.data === undefined
+    ? `${param.node}.${param.event}`
+    : `${param.node}.${param.event}.${param.data}`
+
 export interface InstrumentConfig {
   name: string
   color: string
   triggerNode: string | null // e.g., "kick", "clap", or null for no trigger
+  // Adds a button triggering with parameter 1, for instruments with accents
+  accentTrigger?: boolean
+  // Adds a button triggering with data 1, for instruments that slide
+  slideTrigger?: boolean
   parameters: ParameterConfig[]
 }
 
 interface AuditionerProps {
   config: InstrumentConfig
+  // Called with every value keyed by "node.event", e.g. to save a kit
+  onParametersChange?: (parameters: Record<string, number>) => void
 }
 
-export function Auditioner({ config }: AuditionerProps) {
+export function Auditioner({ config, onParametersChange }: AuditionerProps) {
   const [parameters, setParameters] = useState<Record<string, number>>(() => {
     const initial: Record<string, number> = {}
     config.parameters.forEach((param) => {
-      initial[`${param.node}.${param.event}`] = param.defaultValue
+      initial[parameterKey(param)] = param.defaultValue
     })
     return initial
   })
 
+  useEffect(() => {
+    onParametersChange?.(parameters)
+  }, [parameters, onParametersChange])
+
+  // Follow values the engine changes itself so the sliders don't drift
+  useEffect(() => {
+    const unlistens = config.parameters.map((param) =>
+      listen<ParameterChangedPayload>(
+        parameterChangedEvent(SystemNames.Auditioner, param.node, param.event),
+        (event) => {
+          const value = event.payload.parameter
+          if (value === null) return
+          setParameters((prev) => ({
+            ...prev,
+            [parameterKey(param)]: value,
+          }))
+        }
+      )
+    )
+
+    return () => {
+      unlistens.forEach((unlisten) => unlisten.then((stop) => stop()))
+    }
+  }, [config])
+
   const updateParameter = async (param: ParameterConfig, value: number) => {
-    const key = `${param.node}.${param.event}`
+    const key = parameterKey(param)
     setParameters((prev) => ({ ...prev, [key]: value }))
 
     // Use the parameter value directly (all parameters are in the correct units)
@@ -47,21 +93,21 @@ export function Auditioner({ config }: AuditionerProps) {
         nodeName: param.node,
         eventName: param.event,
         parameter: parameterValue,
-        data: null,
+        data: param.data ?? null,
       })
     } catch (error) {
       console.error(`Error setting ${param.name}:`, error)
     }
   }
 
-  const triggerInstrument = async () => {
+  const triggerInstrument = async (accented = false, slide = false) => {
     try {
       await invoke(Commands.SendClientEvent, {
         systemName: SystemNames.Auditioner,
         nodeName: config.triggerNode,
         eventName: CommonEvents.Trigger,
-        parameter: null,
-        data: null,
+        parameter: accented ? 1 : null,
+        data: slide ? 1 : null,
       })
     } catch (error) {
       console.error(`Error triggering ${config.name}:`, error)
@@ -135,18 +181,36 @@ export function Auditioner({ config }: AuditionerProps) {
       <div className="flex justify-between items-center mb-6">
         <h2 className={`text-lg ${colorClasses.title}`}>{config.name}</h2>
         {config.triggerNode && (
-          <button
-            onClick={triggerInstrument}
-            className={`${colorClasses.button} text-white py-2 px-4 rounded-md transition-all transform shadow-lg`}
-          >
-            Trigger
-          </button>
+          <div className="flex gap-2">
+            {config.accentTrigger && (
+              <button
+                onClick={() => triggerInstrument(true)}
+                className={`${colorClasses.button} text-white py-2 px-4 rounded-md transition-all transform shadow-lg`}
+              >
+                Accent
+              </button>
+            )}
+            {config.slideTrigger && (
+              <button
+                onClick={() => triggerInstrument(false, true)}
+                className={`${colorClasses.button} text-white py-2 px-4 rounded-md transition-all transform shadow-lg`}
+              >
+                Slide
+              </button>
+            )}
+            <button
+              onClick={() => triggerInstrument()}
+              className={`${colorClasses.button} text-white py-2 px-4 rounded-md transition-all transform shadow-lg`}
+            >
+              Trigger
+            </button>
+          </div>
         )}
       </div>
 
       <div className="space-y-6">
         {config.parameters.map((param) => {
-          const key = `${param.node}.${param.event}`
+          const key = parameterKey(param)
           return (
             <div key={key}>
               <label className="block text-sm mb-2">
@@ -1,13 +1,53 @@
-import { useEffect } from "react"
+import { useCallback, useEffect, useRef, useState } from "react"
 import { invoke } from "@tauri-apps/api/core"
-import { Auditioner, InstrumentConfig } from "./Auditioner"
+import { Auditioner, InstrumentConfig, ParameterConfig } from "./Auditioner"
+import { DrumMapEditor } from "./DrumMapEditor"
+import { KeyboardPlayer } from "./KeyboardPlayer"
+import { KitBrowser } from "./KitBrowser"
+import { SamplePads } from "./SamplePads"
 import {
   Auditioner as AuditionerEvents,
+  Master as MasterEvents,
   SystemNames,
   NodeNames,
   Commands,
 } from "../events"
 
+// Choke group selector shared by every instrument (0 = no group)
+const chokeGroupParameter = (
+  node: string,
+  event: string,
+  defaultValue = 0
+): ParameterConfig => ({
+  name: "Choke Group",
+  node,
+  event,
+  min: 0,
+  max: 4,
+  step: 1,
+  defaultValue,
+  formatter: (value: number) => (value === 0 ? "Off" : `${value}`),
+})
+
+// Per-instrument lo-fi (sample-rate and bit reduction)
+const lofiAmountParameter = (
+  node: string,
+  event: string
+): ParameterConfig => ({
+  name: "Lo-Fi",
+  node,
+  event,
+  min: 0,
+  max: 1,
+  step: 0.01,
+  defaultValue: 0,
+  unit: "%",
+})
+
+// Names of the tonal chord qualities, by the index their events take
+const chordQualityFormatter = (value: number): string =>
+  ["Maj", "Min", "Sus2", "Sus4", "Maj7", "Min7", "Dom7"][value] ?? ""
+
 // Kick drum configuration
 const kickDrumConfig: InstrumentConfig = {
   name: "Kick Drum",
@@ -84,6 +124,28 @@ const kickDrumConfig: InstrumentConfig = {
       defaultValue: 0.05,
       unit: "s",
     },
+    {
+      name: "Click Level",
+      node: NodeNames.Kick,
+      event: AuditionerEvents.Kick.SetClickLevel,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0,
+      unit: "%",
+    },
+    {
+      name: "Click Decay",
+      node: NodeNames.Kick,
+      event: AuditionerEvents.Kick.SetClickDecay,
+      min: 0.001,
+      max: 0.1,
+      step: 0.001,
+      defaultValue: 0.01,
+      unit: "s",
+    },
+    lofiAmountParameter(NodeNames.Kick, AuditionerEvents.Kick.SetLofiAmount),
+    chokeGroupParameter(NodeNames.Kick, AuditionerEvents.Kick.SetChokeGroup),
   ],
 }
 
@@ -103,6 +165,110 @@ const clapDrumConfig: InstrumentConfig = {
       defaultValue: 0.6,
       unit: "%",
     },
+    lofiAmountParameter(NodeNames.Clap, AuditionerEvents.Clap.SetLofiAmount),
+    chokeGroupParameter(NodeNames.Clap, AuditionerEvents.Clap.SetChokeGroup),
+  ],
+}
+
+// Snare configuration
+const snareConfig: InstrumentConfig = {
+  name: "Snare",
+  color: "blue",
+  triggerNode: NodeNames.Snare,
+  parameters: [
+    {
+      name: "Gain",
+      node: NodeNames.Snare,
+      event: AuditionerEvents.Snare.SetGain,
+      min: 0,
+      max: 2,
+      step: 0.01,
+      defaultValue: 1.0,
+      unit: "%",
+    },
+    {
+      name: "Tune",
+      node: NodeNames.Snare,
+      event: AuditionerEvents.Snare.SetTune,
+      min: 80,
+      max: 500,
+      step: 1,
+      defaultValue: 180,
+      unit: "hz",
+    },
+    {
+      name: "Tone Decay",
+      node: NodeNames.Snare,
+      event: AuditionerEvents.Snare.SetToneDecay,
+      min: 0.02,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0.12,
+      unit: "s",
+    },
+    {
+      name: "Snappy",
+      node: NodeNames.Snare,
+      event: AuditionerEvents.Snare.SetSnappy,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0.5,
+      unit: "%",
+    },
+    {
+      name: "Noise Decay",
+      node: NodeNames.Snare,
+      event: AuditionerEvents.Snare.SetNoiseDecay,
+      min: 0.02,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0.18,
+      unit: "s",
+    },
+    {
+      name: "Noise Frequency",
+      node: NodeNames.Snare,
+      event: AuditionerEvents.Snare.SetNoiseFrequency,
+      min: 500,
+      max: 12000,
+      step: 10,
+      defaultValue: 3500,
+      unit: "hz",
+    },
+    {
+      name: "Noise Color",
+      node: NodeNames.Snare,
+      event: AuditionerEvents.Snare.SetNoiseColor,
+      min: 0,
+      max: 2,
+      step: 1,
+      defaultValue: 0,
+      formatter: (value: number) =>
+        ["White", "Pink", "Brown"][Math.round(value)] ?? `${value}`,
+    },
+    {
+      name: "Pitch Sweep",
+      node: NodeNames.Snare,
+      event: AuditionerEvents.Snare.SetPitchSweep,
+      min: 1,
+      max: 8,
+      step: 0.1,
+      defaultValue: 1,
+      unit: "x",
+    },
+    {
+      name: "Sweep Time",
+      node: NodeNames.Snare,
+      event: AuditionerEvents.Snare.SetSweepTime,
+      min: 0.001,
+      max: 0.2,
+      step: 0.001,
+      defaultValue: 0.04,
+      unit: "s",
+    },
+    lofiAmountParameter(NodeNames.Snare, AuditionerEvents.Snare.SetLofiAmount),
+    chokeGroupParameter(NodeNames.Snare, AuditionerEvents.Snare.SetChokeGroup),
   ],
 }
 
@@ -132,207 +298,1628 @@ const hiHatConfig: InstrumentConfig = {
       defaultValue: 0.05,
       unit: "s",
     },
+    lofiAmountParameter(NodeNames.HiHat, AuditionerEvents.HiHat.SetLofiAmount),
+    // The closed and open hats start out in the same group
+    chokeGroupParameter(
+      NodeNames.HiHat,
+      AuditionerEvents.HiHat.SetChokeGroup,
+      1
+    ),
   ],
 }
 
-// Chord synth configuration
-const chordSynthConfig: InstrumentConfig = {
-  name: "Chord Synth",
-  color: "purple",
-  triggerNode: NodeNames.Chord,
+// Open hi-hat configuration; it takes the same events as the closed hat
+const openHatConfig: InstrumentConfig = {
+  name: "Open Hat",
+  color: "yellow",
+  triggerNode: NodeNames.OpenHat,
   parameters: [
     {
       name: "Gain",
-      node: NodeNames.Chord,
-      event: AuditionerEvents.Chord.SetGain,
-      min: 0,
-      max: 1,
-      step: 0.01,
-      defaultValue: 0.25,
-      unit: "%",
-    },
-    {
-      name: "Base Frequency",
-      node: NodeNames.Chord,
-      event: AuditionerEvents.Chord.SetBaseFrequency,
-      min: 110,
-      max: 440,
-      step: 1,
-      defaultValue: 220,
-      unit: "hz",
-    },
-    {
-      name: "Modulation Index",
-      node: NodeNames.Chord,
-      event: AuditionerEvents.Chord.SetModulationIndex,
+      node: NodeNames.OpenHat,
+      event: AuditionerEvents.HiHat.SetGain,
       min: 0,
       max: 2,
       step: 0.01,
-      defaultValue: 0.5,
-      unit: "x",
-    },
-    {
-      name: "Feedback",
-      node: NodeNames.Chord,
-      event: AuditionerEvents.Chord.SetFeedback,
-      min: 0,
-      max: 0.99,
-      step: 0.01,
-      defaultValue: 0.1,
+      defaultValue: 1.0,
       unit: "%",
     },
     {
-      name: "Attack",
-      node: NodeNames.Chord,
-      event: AuditionerEvents.Chord.SetAttack,
-      min: 0.01,
+      name: "Length",
+      node: NodeNames.OpenHat,
+      event: AuditionerEvents.HiHat.SetLength,
+      min: 0.05,
       max: 2,
       step: 0.01,
-      defaultValue: 0.5,
-      unit: "s",
-    },
-    {
-      name: "Release",
-      node: NodeNames.Chord,
-      event: AuditionerEvents.Chord.SetRelease,
-      min: 0.1,
-      max: 8,
-      step: 0.1,
-      defaultValue: 4,
+      defaultValue: 0.45,
       unit: "s",
     },
+    lofiAmountParameter(
+      NodeNames.OpenHat,
+      AuditionerEvents.HiHat.SetLofiAmount
+    ),
+    chokeGroupParameter(
+      NodeNames.OpenHat,
+      AuditionerEvents.HiHat.SetChokeGroup,
+      1
+    ),
   ],
 }
 
-// Supersaw synth configuration
-const supersawConfig: InstrumentConfig = {
-  name: "Supersaw Synth",
-  color: "green",
-  triggerNode: NodeNames.Supersaw,
+// Tom configuration; around 80Hz for a low tom, 200Hz for a high one
+const tomConfig: InstrumentConfig = {
+  name: "Tom",
+  color: "blue",
+  triggerNode: NodeNames.Tom,
   parameters: [
     {
       name: "Gain",
-      node: NodeNames.Supersaw,
-      event: AuditionerEvents.Supersaw.SetGain,
+      node: NodeNames.Tom,
+      event: AuditionerEvents.Tom.SetGain,
       min: 0,
-      max: 1,
+      max: 2,
       step: 0.01,
-      defaultValue: 0.5,
+      defaultValue: 1.0,
       unit: "%",
     },
     {
       name: "Base Frequency",
-      node: NodeNames.Supersaw,
-      event: AuditionerEvents.Supersaw.SetBaseFrequency,
-      min: 110,
-      max: 880,
+      node: NodeNames.Tom,
+      event: AuditionerEvents.Tom.SetBaseFrequency,
+      min: 50,
+      max: 400,
       step: 1,
-      defaultValue: 440,
+      defaultValue: 120,
       unit: "hz",
     },
     {
-      name: "Detune",
-      node: NodeNames.Supersaw,
-      event: AuditionerEvents.Supersaw.SetDetune,
+      name: "Sweep Depth",
+      node: NodeNames.Tom,
+      event: AuditionerEvents.Tom.SetSweepDepth,
       min: 0,
+      max: 24,
+      step: 0.5,
+      defaultValue: 7,
+      formatter: (value: number) => `${value.toFixed(1)} st`,
+    },
+    {
+      name: "Sweep Time",
+      node: NodeNames.Tom,
+      event: AuditionerEvents.Tom.SetSweepTime,
+      min: 0.01,
+      max: 0.5,
+      step: 0.001,
+      defaultValue: 0.12,
+      unit: "s",
+    },
+    {
+      name: "Decay",
+      node: NodeNames.Tom,
+      event: AuditionerEvents.Tom.SetDecay,
+      min: 0.05,
       max: 2,
       step: 0.01,
-      defaultValue: 1.0,
-      unit: "x",
+      defaultValue: 0.4,
+      unit: "s",
     },
     {
-      name: "Stereo Width",
-      node: NodeNames.Supersaw,
-      event: AuditionerEvents.Supersaw.SetStereoWidth,
+      name: "Noise",
+      node: NodeNames.Tom,
+      event: AuditionerEvents.Tom.SetNoiseAmount,
       min: 0,
       max: 1,
       step: 0.01,
-      defaultValue: 0.8,
+      defaultValue: 0.2,
       unit: "%",
     },
     {
-      name: "Filter Cutoff",
-      node: NodeNames.Supersaw,
-      event: AuditionerEvents.Supersaw.SetFilterCutoff,
-      min: 100,
-      max: 8000,
-      step: 10,
-      defaultValue: 1000,
-      unit: "hz",
+      name: "Noise Decay",
+      node: NodeNames.Tom,
+      event: AuditionerEvents.Tom.SetNoiseDecay,
+      min: 0.005,
+      max: 0.3,
+      step: 0.001,
+      defaultValue: 0.05,
+      unit: "s",
     },
     {
-      name: "Filter Resonance",
-      node: NodeNames.Supersaw,
-      event: AuditionerEvents.Supersaw.SetFilterResonance,
-      min: 0.1,
-      max: 10,
-      step: 0.1,
-      defaultValue: 0.7,
-      unit: "q",
+      name: "FM Amount",
+      node: NodeNames.Tom,
+      event: AuditionerEvents.Tom.SetFmAmount,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0,
+      unit: "%",
     },
+    lofiAmountParameter(NodeNames.Tom, AuditionerEvents.Tom.SetLofiAmount),
+    chokeGroupParameter(NodeNames.Tom, AuditionerEvents.Tom.SetChokeGroup),
+  ],
+}
+
+// Modal drum configuration; strike near the rim for a ringing, metallic head
+const modalDrumConfig: InstrumentConfig = {
+  name: "Modal Drum",
+  color: "red",
+  triggerNode: NodeNames.ModalDrum,
+  parameters: [
     {
-      name: "Filter Env Amount",
-      node: NodeNames.Supersaw,
-      event: AuditionerEvents.Supersaw.SetFilterEnvAmount,
+      name: "Gain",
+      node: NodeNames.ModalDrum,
+      event: AuditionerEvents.ModalDrum.SetGain,
       min: 0,
-      max: 5000,
-      step: 10,
-      defaultValue: 2000,
+      max: 2,
+      step: 0.01,
+      defaultValue: 1.0,
+      unit: "%",
+    },
+    {
+      name: "Tune",
+      node: NodeNames.ModalDrum,
+      event: AuditionerEvents.ModalDrum.SetTune,
+      min: 30,
+      max: 2000,
+      step: 1,
+      defaultValue: 120,
       unit: "hz",
     },
     {
-      name: "Amp Attack",
-      node: NodeNames.Supersaw,
-      event: AuditionerEvents.Supersaw.SetAmpAttack,
-      min: 0.001,
-      max: 2,
-      step: 0.001,
-      defaultValue: 0.01,
+      name: "Decay",
+      node: NodeNames.ModalDrum,
+      event: AuditionerEvents.ModalDrum.SetDecay,
+      min: 0.02,
+      max: 4,
+      step: 0.01,
+      defaultValue: 0.6,
       unit: "s",
     },
     {
-      name: "Amp Release",
-      node: NodeNames.Supersaw,
-      event: AuditionerEvents.Supersaw.SetAmpRelease,
-      min: 0.01,
-      max: 10,
+      name: "Damping",
+      node: NodeNames.ModalDrum,
+      event: AuditionerEvents.ModalDrum.SetDamping,
+      min: 0,
+      max: 1,
       step: 0.01,
       defaultValue: 0.5,
-      unit: "s",
+      unit: "%",
     },
     {
-      name: "Filter Attack",
-      node: NodeNames.Supersaw,
-      event: AuditionerEvents.Supersaw.SetFilterAttack,
-      min: 0.001,
-      max: 2,
-      step: 0.001,
+      name: "Strike Position",
+      node: NodeNames.ModalDrum,
+      event: AuditionerEvents.ModalDrum.SetStrikePosition,
+      min: 0,
+      max: 1,
+      step: 0.01,
       defaultValue: 0.3,
-      unit: "s",
+      unit: "%",
     },
     {
-      name: "Filter Release",
-      node: NodeNames.Supersaw,
-      event: AuditionerEvents.Supersaw.SetFilterRelease,
-      min: 0.01,
-      max: 10,
+      name: "Noise",
+      node: NodeNames.ModalDrum,
+      event: AuditionerEvents.ModalDrum.SetNoise,
+      min: 0,
+      max: 1,
       step: 0.01,
       defaultValue: 0.3,
-      unit: "s",
+      unit: "%",
     },
+    lofiAmountParameter(
+      NodeNames.ModalDrum,
+      AuditionerEvents.ModalDrum.SetLofiAmount
+    ),
+    chokeGroupParameter(
+      NodeNames.ModalDrum,
+      AuditionerEvents.ModalDrum.SetChokeGroup
+    ),
   ],
 }
 
-// Reverb configuration
-const reverbConfig: InstrumentConfig = {
-  name: "Reverb",
-  color: "teal",
-  triggerNode: null, // No trigger for reverb
+// Cowbell configuration
+const cowbellConfig: InstrumentConfig = {
+  name: "Cowbell",
+  color: "yellow",
+  triggerNode: NodeNames.Cowbell,
   parameters: [
     {
-      name: "Send",
-      node: NodeNames.System,
-      event: AuditionerEvents.System.SetReverbSend,
+      name: "Gain",
+      node: NodeNames.Cowbell,
+      event: AuditionerEvents.Cowbell.SetGain,
+      min: 0,
+      max: 2,
+      step: 0.01,
+      defaultValue: 1.0,
+      unit: "%",
+    },
+    {
+      name: "Tuning",
+      node: NodeNames.Cowbell,
+      event: AuditionerEvents.Cowbell.SetTuning,
+      min: 200,
+      max: 2000,
+      step: 1,
+      defaultValue: 540,
+      unit: "hz",
+    },
+    {
+      name: "Decay",
+      node: NodeNames.Cowbell,
+      event: AuditionerEvents.Cowbell.SetDecay,
+      min: 0.02,
+      max: 1.5,
+      step: 0.01,
+      defaultValue: 0.35,
+      unit: "s",
+    },
+    lofiAmountParameter(
+      NodeNames.Cowbell,
+      AuditionerEvents.Cowbell.SetLofiAmount
+    ),
+    chokeGroupParameter(
+      NodeNames.Cowbell,
+      AuditionerEvents.Cowbell.SetChokeGroup
+    ),
+  ],
+}
+
+// Clave configuration; tune it down towards 1kHz for a woodblock
+const claveConfig: InstrumentConfig = {
+  name: "Clave",
+  color: "yellow",
+  triggerNode: NodeNames.Clave,
+  parameters: [
+    {
+      name: "Gain",
+      node: NodeNames.Clave,
+      event: AuditionerEvents.Clave.SetGain,
+      min: 0,
+      max: 2,
+      step: 0.01,
+      defaultValue: 1.0,
+      unit: "%",
+    },
+    {
+      name: "Tuning",
+      node: NodeNames.Clave,
+      event: AuditionerEvents.Clave.SetTuning,
+      min: 200,
+      max: 8000,
+      step: 1,
+      defaultValue: 2500,
+      unit: "hz",
+    },
+    {
+      name: "Decay",
+      node: NodeNames.Clave,
+      event: AuditionerEvents.Clave.SetDecay,
+      min: 0.01,
+      max: 2,
+      step: 0.01,
+      defaultValue: 0.12,
+      unit: "s",
+    },
+    lofiAmountParameter(NodeNames.Clave, AuditionerEvents.Clave.SetLofiAmount),
+    chokeGroupParameter(NodeNames.Clave, AuditionerEvents.Clave.SetChokeGroup),
+  ],
+}
+
+// Glitch percussion configuration
+const glitchConfig: InstrumentConfig = {
+  name: "Glitch Perc",
+  color: "teal",
+  triggerNode: NodeNames.Glitch,
+  parameters: [
+    {
+      name: "Gain",
+      node: NodeNames.Glitch,
+      event: AuditionerEvents.Glitch.SetGain,
+      min: 0,
+      max: 2,
+      step: 0.01,
+      defaultValue: 1.0,
+      unit: "%",
+    },
+    {
+      name: "Texture",
+      node: NodeNames.Glitch,
+      event: AuditionerEvents.Glitch.SetTexture,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0.6,
+      unit: "%",
+    },
+    {
+      name: "Crush",
+      node: NodeNames.Glitch,
+      event: AuditionerEvents.Glitch.SetCrush,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0.5,
+      unit: "%",
+    },
+    {
+      name: "Decay",
+      node: NodeNames.Glitch,
+      event: AuditionerEvents.Glitch.SetDecay,
+      min: 0.005,
+      max: 1,
+      step: 0.001,
+      defaultValue: 0.06,
+      unit: "s",
+    },
+    {
+      name: "Randomness",
+      node: NodeNames.Glitch,
+      event: AuditionerEvents.Glitch.SetRandomness,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0.5,
+      unit: "%",
+    },
+    lofiAmountParameter(
+      NodeNames.Glitch,
+      AuditionerEvents.Glitch.SetLofiAmount
+    ),
+    chokeGroupParameter(
+      NodeNames.Glitch,
+      AuditionerEvents.Glitch.SetChokeGroup
+    ),
+  ],
+}
+
+// Cymbal configuration
+const cymbalConfig: InstrumentConfig = {
+  name: "Cymbal",
+  color: "teal",
+  triggerNode: NodeNames.Cymbal,
+  parameters: [
+    {
+      name: "Gain",
+      node: NodeNames.Cymbal,
+      event: AuditionerEvents.Cymbal.SetGain,
+      min: 0,
+      max: 2,
+      step: 0.01,
+      defaultValue: 1.0,
+      unit: "%",
+    },
+    {
+      name: "Mode",
+      node: NodeNames.Cymbal,
+      event: AuditionerEvents.Cymbal.SetMode,
+      min: 0,
+      max: 1,
+      step: 1,
+      defaultValue: 0,
+      formatter: (value: number) => ["Ride", "Crash"][value] ?? "",
+    },
+    {
+      name: "Tone",
+      node: NodeNames.Cymbal,
+      event: AuditionerEvents.Cymbal.SetTone,
+      min: 100,
+      max: 2000,
+      step: 1,
+      defaultValue: 420,
+      unit: "hz",
+    },
+    {
+      name: "Decay",
+      node: NodeNames.Cymbal,
+      event: AuditionerEvents.Cymbal.SetDecay,
+      min: 0.1,
+      max: 5,
+      step: 0.01,
+      defaultValue: 1.6,
+      unit: "s",
+    },
+    {
+      name: "Sizzle",
+      node: NodeNames.Cymbal,
+      event: AuditionerEvents.Cymbal.SetSizzle,
+      min: 0.01,
+      max: 3,
+      step: 0.01,
+      defaultValue: 0.25,
+      unit: "s",
+    },
+    lofiAmountParameter(
+      NodeNames.Cymbal,
+      AuditionerEvents.Cymbal.SetLofiAmount
+    ),
+    chokeGroupParameter(
+      NodeNames.Cymbal,
+      AuditionerEvents.Cymbal.SetChokeGroup
+    ),
+  ],
+}
+
+// Routings the chord synth starts with: op 2 into op 1, and ops 1 and 3 into
+// the op 0 carrier
+const chordDefaultRoutes = ["2,1", "1,0", "3,0"]
+
+// One slider per entry of the chord synth's 4x4 FM modulation matrix
+const chordModMatrixParameters = (): ParameterConfig[] =>
+  [0, 1, 2, 3].flatMap((src) =>
+    [0, 1, 2, 3].map((dst) => ({
+      name: `Op ${src} → Op ${dst}`,
+      node: NodeNames.Chord,
+      event: AuditionerEvents.Chord.SetModAmount,
+      data: [src, dst],
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: chordDefaultRoutes.includes(`${src},${dst}`) ? 0.5 : 0,
+      unit: "%",
+    }))
+  )
+
+// Chord synth configuration
+const chordSynthConfig: InstrumentConfig = {
+  name: "Chord Synth",
+  color: "purple",
+  triggerNode: NodeNames.Chord,
+  parameters: [
+    {
+      name: "Gain",
+      node: NodeNames.Chord,
+      event: AuditionerEvents.Chord.SetGain,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0.25,
+      unit: "%",
+    },
+    {
+      name: "Base Frequency",
+      node: NodeNames.Chord,
+      event: AuditionerEvents.Chord.SetBaseFrequency,
+      min: 110,
+      max: 440,
+      step: 1,
+      defaultValue: 220,
+      unit: "hz",
+    },
+    {
+      name: "Chord",
+      node: NodeNames.Chord,
+      event: AuditionerEvents.Chord.SetChordQuality,
+      min: 0,
+      max: 6,
+      step: 1,
+      defaultValue: 1,
+      formatter: chordQualityFormatter,
+    },
+    {
+      name: "Inversion",
+      node: NodeNames.Chord,
+      event: AuditionerEvents.Chord.SetInversion,
+      min: 0,
+      max: 3,
+      step: 1,
+      defaultValue: 0,
+      formatter: (value: number) => (value === 0 ? "Root" : `${value}`),
+    },
+    {
+      name: "Spread",
+      node: NodeNames.Chord,
+      event: AuditionerEvents.Chord.SetSpread,
+      min: 0,
+      max: 2,
+      step: 1,
+      defaultValue: 0,
+      unit: "oct",
+    },
+    {
+      name: "Stereo Width",
+      node: NodeNames.Chord,
+      event: AuditionerEvents.Chord.SetStereoWidth,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0.6,
+      unit: "%",
+    },
+    {
+      name: "Unison Voices",
+      node: NodeNames.Chord,
+      event: AuditionerEvents.Chord.SetUnisonVoices,
+      min: 1,
+      max: 8,
+      step: 1,
+      defaultValue: 1,
+      formatter: (value: number) => (value === 1 ? "Off" : `${value}`),
+    },
+    {
+      name: "Unison Detune",
+      node: NodeNames.Chord,
+      event: AuditionerEvents.Chord.SetUnisonDetune,
+      min: 0,
+      max: 50,
+      step: 1,
+      defaultValue: 10,
+      formatter: (value: number) => `${value}¢`,
+    },
+    {
+      name: "Drift",
+      node: NodeNames.Chord,
+      event: AuditionerEvents.Chord.SetDriftAmount,
+      min: 0,
+      max: 50,
+      step: 1,
+      defaultValue: 0,
+      formatter: (value: number) => `${value}¢`,
+    },
+    {
+      name: "Modulation Index",
+      node: NodeNames.Chord,
+      event: AuditionerEvents.Chord.SetModulationIndex,
+      min: 0,
+      max: 2,
+      step: 0.01,
+      defaultValue: 0.5,
+      unit: "x",
+    },
+    {
+      name: "Feedback",
+      node: NodeNames.Chord,
+      event: AuditionerEvents.Chord.SetFeedback,
+      min: 0,
+      max: 0.99,
+      step: 0.01,
+      defaultValue: 0.1,
+      unit: "%",
+    },
+    ...chordModMatrixParameters(),
+    {
+      name: "Attack",
+      node: NodeNames.Chord,
+      event: AuditionerEvents.Chord.SetAttack,
+      min: 0.01,
+      max: 2,
+      step: 0.01,
+      defaultValue: 0.5,
+      unit: "s",
+    },
+    {
+      name: "Release",
+      node: NodeNames.Chord,
+      event: AuditionerEvents.Chord.SetRelease,
+      min: 0.1,
+      max: 8,
+      step: 0.1,
+      defaultValue: 4,
+      unit: "s",
+    },
+    lofiAmountParameter(NodeNames.Chord, AuditionerEvents.Chord.SetLofiAmount),
+    chokeGroupParameter(NodeNames.Chord, AuditionerEvents.Chord.SetChokeGroup),
+  ],
+}
+
+// Supersaw synth configuration
+const supersawConfig: InstrumentConfig = {
+  name: "Supersaw Synth",
+  color: "green",
+  triggerNode: NodeNames.Supersaw,
+  parameters: [
+    {
+      name: "Gain",
+      node: NodeNames.Supersaw,
+      event: AuditionerEvents.Supersaw.SetGain,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0.5,
+      unit: "%",
+    },
+    {
+      name: "Base Frequency",
+      node: NodeNames.Supersaw,
+      event: AuditionerEvents.Supersaw.SetBaseFrequency,
+      min: 110,
+      max: 880,
+      step: 1,
+      defaultValue: 440,
+      unit: "hz",
+    },
+    {
+      name: "Detune",
+      node: NodeNames.Supersaw,
+      event: AuditionerEvents.Supersaw.SetDetune,
+      min: 0,
+      max: 2,
+      step: 0.01,
+      defaultValue: 1.0,
+      unit: "x",
+    },
+    {
+      name: "Stereo Width",
+      node: NodeNames.Supersaw,
+      event: AuditionerEvents.Supersaw.SetStereoWidth,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0.8,
+      unit: "%",
+    },
+    {
+      name: "Drift",
+      node: NodeNames.Supersaw,
+      event: AuditionerEvents.Supersaw.SetDriftAmount,
+      min: 0,
+      max: 50,
+      step: 1,
+      defaultValue: 0,
+      formatter: (value: number) => `${value}¢`,
+    },
+    {
+      name: "Filter Cutoff",
+      node: NodeNames.Supersaw,
+      event: AuditionerEvents.Supersaw.SetFilterCutoff,
+      min: 100,
+      max: 8000,
+      step: 10,
+      defaultValue: 1000,
+      unit: "hz",
+    },
+    {
+      name: "Filter Resonance",
+      node: NodeNames.Supersaw,
+      event: AuditionerEvents.Supersaw.SetFilterResonance,
+      min: 0.1,
+      max: 10,
+      step: 0.1,
+      defaultValue: 0.7,
+      unit: "q",
+    },
+    {
+      name: "Filter Env Amount",
+      node: NodeNames.Supersaw,
+      event: AuditionerEvents.Supersaw.SetFilterEnvAmount,
+      min: 0,
+      max: 5000,
+      step: 10,
+      defaultValue: 2000,
+      unit: "hz",
+    },
+    {
+      name: "Filter Keytrack",
+      node: NodeNames.Supersaw,
+      event: AuditionerEvents.Supersaw.SetFilterKeytrack,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0,
+      unit: "%",
+    },
+    {
+      name: "Filter FM Amount",
+      node: NodeNames.Supersaw,
+      event: AuditionerEvents.Supersaw.SetFilterFmAmount,
+      min: 0,
+      max: 4,
+      step: 0.01,
+      defaultValue: 0,
+      unit: "oct",
+    },
+    {
+      name: "Filter FM Ratio",
+      node: NodeNames.Supersaw,
+      event: AuditionerEvents.Supersaw.SetFilterFmRatio,
+      min: 0.125,
+      max: 16,
+      step: 0.125,
+      defaultValue: 1,
+      unit: "x",
+    },
+    {
+      name: "Amp Attack",
+      node: NodeNames.Supersaw,
+      event: AuditionerEvents.Supersaw.SetAmpAttack,
+      min: 0.001,
+      max: 2,
+      step: 0.001,
+      defaultValue: 0.01,
+      unit: "s",
+    },
+    {
+      name: "Amp Release",
+      node: NodeNames.Supersaw,
+      event: AuditionerEvents.Supersaw.SetAmpRelease,
+      min: 0.01,
+      max: 10,
+      step: 0.01,
+      defaultValue: 0.5,
+      unit: "s",
+    },
+    {
+      name: "Filter Attack",
+      node: NodeNames.Supersaw,
+      event: AuditionerEvents.Supersaw.SetFilterAttack,
+      min: 0.001,
+      max: 2,
+      step: 0.001,
+      defaultValue: 0.3,
+      unit: "s",
+    },
+    {
+      name: "Filter Release",
+      node: NodeNames.Supersaw,
+      event: AuditionerEvents.Supersaw.SetFilterRelease,
+      min: 0.01,
+      max: 10,
+      step: 0.01,
+      defaultValue: 0.3,
+      unit: "s",
+    },
+    lofiAmountParameter(
+      NodeNames.Supersaw,
+      AuditionerEvents.Supersaw.SetLofiAmount
+    ),
+    chokeGroupParameter(
+      NodeNames.Supersaw,
+      AuditionerEvents.Supersaw.SetChokeGroup
+    ),
+  ],
+}
+
+// Pad synth configuration
+const padSynthConfig: InstrumentConfig = {
+  name: "Pad Synth",
+  color: "green",
+  triggerNode: NodeNames.PadSynth,
+  parameters: [
+    {
+      name: "Gain",
+      node: NodeNames.PadSynth,
+      event: AuditionerEvents.PadSynth.SetGain,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0.5,
+      unit: "%",
+    },
+    {
+      name: "Root Frequency",
+      node: NodeNames.PadSynth,
+      event: AuditionerEvents.PadSynth.SetBaseFrequency,
+      min: 55,
+      max: 880,
+      step: 1,
+      defaultValue: 220,
+      unit: "hz",
+    },
+    {
+      name: "Chord",
+      node: NodeNames.PadSynth,
+      event: AuditionerEvents.PadSynth.SetChordQuality,
+      min: 0,
+      max: 6,
+      step: 1,
+      defaultValue: 1,
+      formatter: chordQualityFormatter,
+    },
+    {
+      name: "Detune",
+      node: NodeNames.PadSynth,
+      event: AuditionerEvents.PadSynth.SetDetune,
+      min: 0,
+      max: 2,
+      step: 0.01,
+      defaultValue: 0.6,
+      unit: "x",
+    },
+    {
+      name: "Attack",
+      node: NodeNames.PadSynth,
+      event: AuditionerEvents.PadSynth.SetAttack,
+      min: 0.01,
+      max: 5,
+      step: 0.01,
+      defaultValue: 1.2,
+      unit: "s",
+    },
+    {
+      name: "Release",
+      node: NodeNames.PadSynth,
+      event: AuditionerEvents.PadSynth.SetRelease,
+      min: 0.01,
+      max: 10,
+      step: 0.01,
+      defaultValue: 2.5,
+      unit: "s",
+    },
+    {
+      name: "Filter Cutoff",
+      node: NodeNames.PadSynth,
+      event: AuditionerEvents.PadSynth.SetFilterCutoff,
+      min: 100,
+      max: 8000,
+      step: 10,
+      defaultValue: 3000,
+      unit: "hz",
+    },
+    {
+      name: "Chorus Rate",
+      node: NodeNames.PadSynth,
+      event: AuditionerEvents.PadSynth.SetChorusRate,
+      min: 0.05,
+      max: 5,
+      step: 0.01,
+      defaultValue: 0.5,
+      unit: "hz",
+    },
+    {
+      name: "Chorus Mix",
+      node: NodeNames.PadSynth,
+      event: AuditionerEvents.PadSynth.SetChorusMix,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0.5,
+      unit: "%",
+    },
+    lofiAmountParameter(
+      NodeNames.PadSynth,
+      AuditionerEvents.PadSynth.SetLofiAmount
+    ),
+    chokeGroupParameter(
+      NodeNames.PadSynth,
+      AuditionerEvents.PadSynth.SetChokeGroup
+    ),
+  ],
+}
+
+// 808 bass configuration
+const bass808Config: InstrumentConfig = {
+  name: "808 Bass",
+  color: "teal",
+  triggerNode: NodeNames.Bass808,
+  accentTrigger: true,
+  parameters: [
+    {
+      name: "Gain",
+      node: NodeNames.Bass808,
+      event: AuditionerEvents.Bass808.SetGain,
+      min: 0,
+      max: 2,
+      step: 0.01,
+      defaultValue: 1,
+      unit: "%",
+    },
+    {
+      name: "Base Frequency",
+      node: NodeNames.Bass808,
+      event: AuditionerEvents.Bass808.SetBaseFrequency,
+      min: 30,
+      max: 220,
+      step: 0.5,
+      defaultValue: 55,
+      unit: "hz",
+    },
+    {
+      name: "Glide",
+      node: NodeNames.Bass808,
+      event: AuditionerEvents.Bass808.SetGlide,
+      min: 0,
+      max: 1,
+      step: 0.005,
+      defaultValue: 0.05,
+      unit: "s",
+    },
+    {
+      name: "Shape",
+      node: NodeNames.Bass808,
+      event: AuditionerEvents.Bass808.SetShape,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0,
+      formatter: (value: number) =>
+        value === 0 ? "Sine" : value === 1 ? "Triangle" : value.toFixed(2),
+    },
+    {
+      name: "Phase Distortion",
+      node: NodeNames.Bass808,
+      event: AuditionerEvents.Bass808.SetPhaseDistortion,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0,
+      unit: "%",
+    },
+    {
+      name: "Decay",
+      node: NodeNames.Bass808,
+      event: AuditionerEvents.Bass808.SetDecay,
+      min: 0.1,
+      max: 6,
+      step: 0.01,
+      defaultValue: 1.5,
+      unit: "s",
+    },
+    {
+      name: "Drive",
+      node: NodeNames.Bass808,
+      event: AuditionerEvents.Bass808.SetDrive,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0.2,
+      unit: "%",
+    },
+    {
+      name: "Accent",
+      node: NodeNames.Bass808,
+      event: AuditionerEvents.Bass808.SetAccent,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0.5,
+      unit: "%",
+    },
+    lofiAmountParameter(
+      NodeNames.Bass808,
+      AuditionerEvents.Bass808.SetLofiAmount
+    ),
+    chokeGroupParameter(
+      NodeNames.Bass808,
+      AuditionerEvents.Bass808.SetChokeGroup
+    ),
+    {
+      name: "Kick Duck",
+      node: NodeNames.Bass808,
+      event: AuditionerEvents.Bass808.SetKickDuck,
+      min: 0,
+      max: 1,
+      step: 1,
+      defaultValue: 0,
+      formatter: (value: number) => (value === 0 ? "Off" : "On"),
+    },
+  ],
+}
+
+// Acid bass configuration
+const acidConfig: InstrumentConfig = {
+  name: "Acid Bass",
+  color: "green",
+  triggerNode: NodeNames.Acid,
+  accentTrigger: true,
+  slideTrigger: true,
+  parameters: [
+    {
+      name: "Gain",
+      node: NodeNames.Acid,
+      event: AuditionerEvents.Acid.SetGain,
+      min: 0,
+      max: 2,
+      step: 0.01,
+      defaultValue: 1,
+      unit: "%",
+    },
+    {
+      name: "Base Frequency",
+      node: NodeNames.Acid,
+      event: AuditionerEvents.Acid.SetBaseFrequency,
+      min: 30,
+      max: 440,
+      step: 0.5,
+      defaultValue: 55,
+      unit: "hz",
+    },
+    {
+      name: "Waveform",
+      node: NodeNames.Acid,
+      event: AuditionerEvents.Acid.SetWaveform,
+      min: 0,
+      max: 1,
+      step: 1,
+      defaultValue: 0,
+      formatter: (value: number) => (value === 1 ? "Square" : "Saw"),
+    },
+    {
+      name: "Cutoff",
+      node: NodeNames.Acid,
+      event: AuditionerEvents.Acid.SetCutoff,
+      min: 20,
+      max: 5000,
+      step: 1,
+      defaultValue: 500,
+      unit: "hz",
+    },
+    {
+      name: "Resonance",
+      node: NodeNames.Acid,
+      event: AuditionerEvents.Acid.SetResonance,
+      min: 0.5,
+      max: 20,
+      step: 0.1,
+      defaultValue: 4,
+    },
+    {
+      name: "Env Mod",
+      node: NodeNames.Acid,
+      event: AuditionerEvents.Acid.SetEnvMod,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0.5,
+      unit: "%",
+    },
+    {
+      name: "Decay",
+      node: NodeNames.Acid,
+      event: AuditionerEvents.Acid.SetDecay,
+      min: 0.01,
+      max: 3,
+      step: 0.01,
+      defaultValue: 0.3,
+      unit: "s",
+    },
+    {
+      name: "Accent",
+      node: NodeNames.Acid,
+      event: AuditionerEvents.Acid.SetAccent,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0.5,
+      unit: "%",
+    },
+    {
+      name: "Slide Time",
+      node: NodeNames.Acid,
+      event: AuditionerEvents.Acid.SetSlideTime,
+      min: 0,
+      max: 0.5,
+      step: 0.005,
+      defaultValue: 0.06,
+      unit: "s",
+    },
+    lofiAmountParameter(NodeNames.Acid, AuditionerEvents.Acid.SetLofiAmount),
+    chokeGroupParameter(NodeNames.Acid, AuditionerEvents.Acid.SetChokeGroup),
+  ],
+}
+
+// Wavetable synth configuration; the position scans sine, triangle, saw, square
+const wavetableConfig: InstrumentConfig = {
+  name: "Wavetable",
+  color: "purple",
+  triggerNode: NodeNames.Wavetable,
+  parameters: [
+    {
+      name: "Gain",
+      node: NodeNames.Wavetable,
+      event: AuditionerEvents.Wavetable.SetGain,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0.5,
+      unit: "%",
+    },
+    {
+      name: "Position",
+      node: NodeNames.Wavetable,
+      event: AuditionerEvents.Wavetable.SetPosition,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0,
+      formatter: (value: number) =>
+        ["Sine", "Triangle", "Saw", "Square"][Math.round(value * 3)] ?? "",
+    },
+    {
+      name: "LFO Rate",
+      node: NodeNames.Wavetable,
+      event: AuditionerEvents.Wavetable.SetLfoRate,
+      min: 0.01,
+      max: 20,
+      step: 0.01,
+      defaultValue: 0.5,
+      unit: "hz",
+    },
+    {
+      name: "LFO Depth",
+      node: NodeNames.Wavetable,
+      event: AuditionerEvents.Wavetable.SetLfoDepth,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0,
+      unit: "%",
+    },
+    {
+      name: "Env Amount",
+      node: NodeNames.Wavetable,
+      event: AuditionerEvents.Wavetable.SetPositionEnvAmount,
+      min: -1,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0,
+    },
+    {
+      name: "Env Attack",
+      node: NodeNames.Wavetable,
+      event: AuditionerEvents.Wavetable.SetPositionAttack,
+      min: 0.001,
+      max: 2,
+      step: 0.001,
+      defaultValue: 0.01,
+      unit: "s",
+    },
+    {
+      name: "Env Release",
+      node: NodeNames.Wavetable,
+      event: AuditionerEvents.Wavetable.SetPositionRelease,
+      min: 0.01,
+      max: 5,
+      step: 0.01,
+      defaultValue: 0.5,
+      unit: "s",
+    },
+    {
+      name: "Filter Cutoff",
+      node: NodeNames.Wavetable,
+      event: AuditionerEvents.Wavetable.SetFilterCutoff,
+      min: 20,
+      max: 20000,
+      step: 1,
+      defaultValue: 4000,
+      unit: "hz",
+    },
+    {
+      name: "Filter Resonance",
+      node: NodeNames.Wavetable,
+      event: AuditionerEvents.Wavetable.SetFilterResonance,
+      min: 0.1,
+      max: 10,
+      step: 0.1,
+      defaultValue: 0.7,
+    },
+    {
+      name: "Attack",
+      node: NodeNames.Wavetable,
+      event: AuditionerEvents.Wavetable.SetAttack,
+      min: 0.001,
+      max: 2,
+      step: 0.001,
+      defaultValue: 0.01,
+      unit: "s",
+    },
+    {
+      name: "Release",
+      node: NodeNames.Wavetable,
+      event: AuditionerEvents.Wavetable.SetRelease,
+      min: 0.01,
+      max: 5,
+      step: 0.01,
+      defaultValue: 0.8,
+      unit: "s",
+    },
+    lofiAmountParameter(
+      NodeNames.Wavetable,
+      AuditionerEvents.Wavetable.SetLofiAmount
+    ),
+    chokeGroupParameter(
+      NodeNames.Wavetable,
+      AuditionerEvents.Wavetable.SetChokeGroup
+    ),
+  ],
+}
+
+// Per-operator sliders for the 6-operator FM synth
+const fm6OperatorParameters = (
+  ratios: number[],
+  levels: number[]
+): ParameterConfig[] =>
+  ratios.flatMap((ratio, op) => [
+    {
+      name: `Op ${op + 1} Ratio`,
+      node: NodeNames.Fm6,
+      event: AuditionerEvents.Fm6.SetOpRatio,
+      data: op,
+      min: 0.125,
+      max: 32,
+      step: 0.125,
+      defaultValue: ratio,
+      unit: "x",
+    },
+    {
+      name: `Op ${op + 1} Level`,
+      node: NodeNames.Fm6,
+      event: AuditionerEvents.Fm6.SetOpLevel,
+      data: op,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: levels[op],
+      unit: "%",
+    },
+    {
+      name: `Op ${op + 1} Decay`,
+      node: NodeNames.Fm6,
+      event: AuditionerEvents.Fm6.SetOpDecay,
+      data: op,
+      min: 0.001,
+      max: 5,
+      step: 0.001,
+      defaultValue: 1.5 / (1 + op),
+      unit: "s",
+    },
+    {
+      name: `Op ${op + 1} Sustain`,
+      node: NodeNames.Fm6,
+      event: AuditionerEvents.Fm6.SetOpSustain,
+      data: op,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0.5,
+      unit: "%",
+    },
+  ])
+
+// 6-operator FM synth configuration
+const fm6Config: InstrumentConfig = {
+  name: "6-Op FM Synth",
+  color: "purple",
+  triggerNode: NodeNames.Fm6,
+  parameters: [
+    {
+      name: "Gain",
+      node: NodeNames.Fm6,
+      event: AuditionerEvents.Fm6.SetGain,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0.5,
+      unit: "%",
+    },
+    {
+      name: "Base Frequency",
+      node: NodeNames.Fm6,
+      event: AuditionerEvents.Fm6.SetBaseFrequency,
+      min: 55,
+      max: 880,
+      step: 1,
+      defaultValue: 220,
+      unit: "hz",
+    },
+    {
+      name: "Algorithm",
+      node: NodeNames.Fm6,
+      event: AuditionerEvents.Fm6.SetAlgorithm,
+      min: 0,
+      max: 4,
+      step: 1,
+      defaultValue: 1,
+      formatter: (value: number) =>
+        ["Serial", "Two Stacks", "Three Pairs", "Shared Mod", "Parallel"][
+          value
+        ] ?? "",
+    },
+    {
+      name: "Feedback",
+      node: NodeNames.Fm6,
+      event: AuditionerEvents.Fm6.SetFeedback,
+      min: 0,
+      max: 0.99,
+      step: 0.01,
+      defaultValue: 0,
+      unit: "%",
+    },
+    {
+      name: "Attack",
+      node: NodeNames.Fm6,
+      event: AuditionerEvents.Fm6.SetAttack,
+      min: 0.001,
+      max: 2,
+      step: 0.001,
+      defaultValue: 0.005,
+      unit: "s",
+    },
+    {
+      name: "Release",
+      node: NodeNames.Fm6,
+      event: AuditionerEvents.Fm6.SetRelease,
+      min: 0.01,
+      max: 10,
+      step: 0.01,
+      defaultValue: 1.5,
+      unit: "s",
+    },
+    ...fm6OperatorParameters([1, 2, 1, 1, 3, 7], [1, 0.5, 1, 0.3, 0.4, 0.2]),
+    lofiAmountParameter(NodeNames.Fm6, AuditionerEvents.Fm6.SetLofiAmount),
+    chokeGroupParameter(NodeNames.Fm6, AuditionerEvents.Fm6.SetChokeGroup),
+  ],
+}
+
+// Formant voice configuration
+const formantConfig: InstrumentConfig = {
+  name: "Formant Voice",
+  color: "purple",
+  triggerNode: NodeNames.Formant,
+  parameters: [
+    {
+      name: "Gain",
+      node: NodeNames.Formant,
+      event: AuditionerEvents.Formant.SetGain,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0.5,
+      unit: "%",
+    },
+    {
+      name: "Base Frequency",
+      node: NodeNames.Formant,
+      event: AuditionerEvents.Formant.SetBaseFrequency,
+      min: 55,
+      max: 880,
+      step: 1,
+      defaultValue: 110,
+      unit: "hz",
+    },
+    {
+      name: "Vowel",
+      node: NodeNames.Formant,
+      event: AuditionerEvents.Formant.SetVowel,
+      min: 0,
+      max: 4,
+      step: 0.01,
+      defaultValue: 0,
+      formatter: (value: number) => {
+        const vowels = ["A", "E", "I", "O", "U"]
+        const from = Math.floor(value)
+        return Number.isInteger(value)
+          ? vowels[from]
+          : `${vowels[from]}-${vowels[from + 1]}`
+      },
+    },
+    {
+      name: "Formant Shift",
+      node: NodeNames.Formant,
+      event: AuditionerEvents.Formant.SetFormantShift,
+      min: 0.5,
+      max: 2,
+      step: 0.01,
+      defaultValue: 1,
+      unit: "x",
+    },
+    {
+      name: "Waveform",
+      node: NodeNames.Formant,
+      event: AuditionerEvents.Formant.SetWaveform,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0,
+      formatter: (value: number) =>
+        value === 0 ? "Saw" : value === 1 ? "Pulse" : value.toFixed(2),
+    },
+    {
+      name: "PWM Depth",
+      node: NodeNames.Formant,
+      event: AuditionerEvents.Formant.SetPwmDepth,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0,
+      unit: "%",
+    },
+    {
+      name: "PWM Rate",
+      node: NodeNames.Formant,
+      event: AuditionerEvents.Formant.SetPwmRate,
+      min: 0.01,
+      max: 20,
+      step: 0.01,
+      defaultValue: 0.5,
+      unit: "hz",
+    },
+    {
+      name: "Resonance",
+      node: NodeNames.Formant,
+      event: AuditionerEvents.Formant.SetResonance,
+      min: 1,
+      max: 30,
+      step: 0.1,
+      defaultValue: 8,
+      unit: "q",
+    },
+    {
+      name: "Attack",
+      node: NodeNames.Formant,
+      event: AuditionerEvents.Formant.SetAttack,
+      min: 0.001,
+      max: 2,
+      step: 0.001,
+      defaultValue: 0.01,
+      unit: "s",
+    },
+    {
+      name: "Release",
+      node: NodeNames.Formant,
+      event: AuditionerEvents.Formant.SetRelease,
+      min: 0.01,
+      max: 5,
+      step: 0.01,
+      defaultValue: 0.4,
+      unit: "s",
+    },
+    lofiAmountParameter(
+      NodeNames.Formant,
+      AuditionerEvents.Formant.SetLofiAmount
+    ),
+    chokeGroupParameter(
+      NodeNames.Formant,
+      AuditionerEvents.Formant.SetChokeGroup
+    ),
+  ],
+}
+
+// Sample pad shaping, applied to every pad
+const samplerConfig: InstrumentConfig = {
+  name: "Sampler",
+  color: "yellow",
+  triggerNode: null, // Pads are triggered from the sample pads panel
+  parameters: [
+    {
+      name: "Gain",
+      node: NodeNames.Sampler,
+      event: AuditionerEvents.Sampler.SetGain,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 1,
+      unit: "%",
+    },
+    {
+      name: "Pitch",
+      node: NodeNames.Sampler,
+      event: AuditionerEvents.Sampler.SetPitch,
+      min: -24,
+      max: 24,
+      step: 1,
+      defaultValue: 0,
+      unit: "st",
+    },
+    {
+      name: "Pitch Env Amount",
+      node: NodeNames.Sampler,
+      event: AuditionerEvents.Sampler.SetPitchEnvAmount,
+      min: -24,
+      max: 24,
+      step: 1,
+      defaultValue: 0,
+      unit: "st",
+    },
+    {
+      name: "Pitch Attack",
+      node: NodeNames.Sampler,
+      event: AuditionerEvents.Sampler.SetPitchAttack,
+      min: 0.001,
+      max: 0.5,
+      step: 0.001,
+      defaultValue: 0.001,
+      unit: "s",
+    },
+    {
+      name: "Pitch Release",
+      node: NodeNames.Sampler,
+      event: AuditionerEvents.Sampler.SetPitchRelease,
+      min: 0.01,
+      max: 2,
+      step: 0.01,
+      defaultValue: 0.05,
+      unit: "s",
+    },
+    {
+      name: "Filter Mode",
+      node: NodeNames.Sampler,
+      event: AuditionerEvents.Sampler.SetFilterMode,
+      min: 0,
+      max: 2,
+      step: 1,
+      defaultValue: 0,
+      formatter: (value: number) =>
+        ["Lowpass", "Highpass", "Bandpass"][value] ?? `${value}`,
+    },
+    {
+      name: "Filter Cutoff",
+      node: NodeNames.Sampler,
+      event: AuditionerEvents.Sampler.SetFilterCutoff,
+      min: 20,
+      max: 20000,
+      step: 10,
+      defaultValue: 20000,
+      unit: "hz",
+    },
+    {
+      name: "Filter Resonance",
+      node: NodeNames.Sampler,
+      event: AuditionerEvents.Sampler.SetFilterResonance,
+      min: 0.1,
+      max: 10,
+      step: 0.1,
+      defaultValue: 0.7,
+      unit: "q",
+    },
+    {
+      name: "Filter Env Amount",
+      node: NodeNames.Sampler,
+      event: AuditionerEvents.Sampler.SetFilterEnvAmount,
+      min: -10000,
+      max: 10000,
+      step: 10,
+      defaultValue: 0,
+      unit: "hz",
+    },
+    {
+      name: "Filter Attack",
+      node: NodeNames.Sampler,
+      event: AuditionerEvents.Sampler.SetFilterAttack,
+      min: 0.001,
+      max: 2,
+      step: 0.001,
+      defaultValue: 0.001,
+      unit: "s",
+    },
+    {
+      name: "Filter Release",
+      node: NodeNames.Sampler,
+      event: AuditionerEvents.Sampler.SetFilterRelease,
+      min: 0.01,
+      max: 5,
+      step: 0.01,
+      defaultValue: 0.2,
+      unit: "s",
+    },
+    {
+      name: "Reverse",
+      node: NodeNames.Sampler,
+      event: AuditionerEvents.Sampler.SetReverse,
+      min: 0,
+      max: 1,
+      step: 1,
+      defaultValue: 0,
+      formatter: (value: number) => (value === 1 ? "On" : "Off"),
+    },
+    {
+      name: "Start Offset",
+      node: NodeNames.Sampler,
+      event: AuditionerEvents.Sampler.SetStartOffset,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0,
+      unit: "%",
+    },
+    {
+      name: "Play Mode",
+      node: NodeNames.Sampler,
+      event: AuditionerEvents.Sampler.SetPlayMode,
+      min: 0,
+      max: 1,
+      step: 1,
+      defaultValue: 0,
+      formatter: (value: number) => (value === 1 ? "Loop" : "One-shot"),
+    },
+  ],
+}
+
+// Reverb configuration
+const reverbConfig: InstrumentConfig = {
+  name: "Reverb",
+  color: "teal",
+  triggerNode: null, // No trigger for reverb
+  parameters: [
+    {
+      name: "Send",
+      node: NodeNames.System,
+      event: AuditionerEvents.System.SetReverbSend,
       min: 0,
       max: 1,
       step: 0.01,
@@ -369,6 +1956,36 @@ const reverbConfig: InstrumentConfig = {
       defaultValue: 0.2,
       unit: "%",
     },
+    {
+      name: "Quality",
+      node: NodeNames.Reverb,
+      event: AuditionerEvents.Reverb.SetQuality,
+      min: 0,
+      max: 2,
+      step: 1,
+      defaultValue: 0,
+      formatter: (value: number) => ["Eco", "Normal", "High"][value] ?? "",
+    },
+    {
+      name: "Duck Amount",
+      node: NodeNames.Reverb,
+      event: AuditionerEvents.Reverb.SetDuckAmount,
+      min: 0,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0,
+      unit: "%",
+    },
+    {
+      name: "Duck Release",
+      node: NodeNames.Reverb,
+      event: AuditionerEvents.Reverb.SetDuckRelease,
+      min: 0.05,
+      max: 1,
+      step: 0.01,
+      defaultValue: 0.25,
+      unit: "s",
+    },
   ],
 }
 
@@ -388,14 +2005,113 @@ export function AuditionerPage(): JSX.Element {
     switchToAuditioner()
   }, [])
 
+  const [seed, setSeed] = useState(1)
+
+  // Latest drum values, collected for saving kits
+  const drumParameters = useRef<Record<string, number>>({})
+  const collectDrumParameters = useCallback(
+    (parameters: Record<string, number>) => {
+      drumParameters.current = { ...drumParameters.current, ...parameters }
+    },
+    []
+  )
+
+  // Reseed the random sources so a session can be reproduced
+  const applySeed = async () => {
+    try {
+      await invoke(Commands.SendClientEvent, {
+        systemName: SystemNames.Master,
+        nodeName: NodeNames.System,
+        eventName: MasterEvents.System.SetSeed,
+        parameter: null,
+        data: seed,
+      })
+    } catch (error) {
+      console.error("Error setting seed:", error)
+    }
+  }
+
   return (
     <div className="space-y-8">
-      <Auditioner config={kickDrumConfig} />
-      <Auditioner config={clapDrumConfig} />
-      <Auditioner config={hiHatConfig} />
+      <div className="bg-gray-800 rounded-lg p-6">
+        <div className="flex items-center gap-3">
+          <label className="text-sm font-medium text-gray-300">Seed:</label>
+          <input
+            type="number"
+            min="0"
+            step="1"
+            value={seed}
+            onChange={(e) =>
+              setSeed(Math.max(0, Math.floor(Number(e.target.value) || 0)))
+            }
+            className="bg-gray-700 text-gray-200 rounded px-2 py-1 text-sm w-32"
+          />
+          <button
+            onClick={applySeed}
+            className="px-3 py-1 rounded bg-gray-600 hover:bg-gray-700 text-white text-sm"
+          >
+            Apply
+          </button>
+        </div>
+      </div>
+      <KitBrowser getParameters={() => drumParameters.current} />
+      <KeyboardPlayer />
+      <Auditioner
+        config={kickDrumConfig}
+        onParametersChange={collectDrumParameters}
+      />
+      <Auditioner
+        config={clapDrumConfig}
+        onParametersChange={collectDrumParameters}
+      />
+      <Auditioner
+        config={snareConfig}
+        onParametersChange={collectDrumParameters}
+      />
+      <Auditioner
+        config={hiHatConfig}
+        onParametersChange={collectDrumParameters}
+      />
+      <Auditioner
+        config={openHatConfig}
+        onParametersChange={collectDrumParameters}
+      />
+      <Auditioner
+        config={tomConfig}
+        onParametersChange={collectDrumParameters}
+      />
+      <Auditioner
+        config={modalDrumConfig}
+        onParametersChange={collectDrumParameters}
+      />
+      <Auditioner
+        config={cowbellConfig}
+        onParametersChange={collectDrumParameters}
+      />
+      <Auditioner
+        config={claveConfig}
+        onParametersChange={collectDrumParameters}
+      />
+      <Auditioner
+        config={glitchConfig}
+        onParametersChange={collectDrumParameters}
+      />
+      <Auditioner
+        config={cymbalConfig}
+        onParametersChange={collectDrumParameters}
+      />
       <Auditioner config={chordSynthConfig} />
       <Auditioner config={supersawConfig} />
+      <Auditioner config={padSynthConfig} />
+      <Auditioner config={bass808Config} />
+      <Auditioner config={acidConfig} />
+      <Auditioner config={wavetableConfig} />
+      <Auditioner config={fm6Config} />
+      <Auditioner config={formantConfig} />
+      <SamplePads />
+      <Auditioner config={samplerConfig} />
       <Auditioner config={reverbConfig} />
+      <DrumMapEditor />
     </div>
   )
 }
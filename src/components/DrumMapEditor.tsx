@@ -0,0 +1,124 @@
+import { useEffect, useState } from "react"
+import { invoke } from "@tauri-apps/api/core"
+import {
+  Commands,
+  DrumMap,
+  DrumMapNodes,
+  NodeNames,
+  SAMPLER_PADS,
+} from "../events"
+
+// Edit which instrument each MIDI note triggers; saved between sessions
+export function DrumMapEditor(): JSX.Element {
+  const [drumMap, setDrumMap] = useState<DrumMap>({})
+  const [note, setNote] = useState(36)
+  const [node, setNode] = useState<string>(NodeNames.Kick)
+  const [pad, setPad] = useState(0)
+  const [error, setError] = useState<string | null>(null)
+
+  const update = async (
+    command: string,
+    args: Record<string, unknown> = {}
+  ) => {
+    try {
+      setDrumMap(await invoke<DrumMap>(command, args))
+      setError(null)
+    } catch (error) {
+      setError(String(error))
+    }
+  }
+
+  useEffect(() => {
+    update(Commands.GetDrumMap)
+  }, [])
+
+  const setMapping = () =>
+    update(Commands.SetDrumMapNote, {
+      note,
+      node,
+      pad: node === NodeNames.Sampler ? pad : null,
+    })
+
+  const playNote = (mappedNote: number) =>
+    invoke(Commands.MidiNoteOn, { note: mappedNote, velocity: 100 }).catch(
+      (error) => setError(String(error))
+    )
+
+  return (
+    <div className="bg-gray-800 rounded-lg p-6">
+      <h2 className="mb-4 text-lg text-gray-200">MIDI Drum Map</h2>
+      <div className="flex flex-wrap items-center gap-3 mb-4 text-sm text-gray-300">
+        <label>Note:</label>
+        <input
+          type="number"
+          min={0}
+          max={127}
+          value={note}
+          onChange={(e) => setNote(Number(e.target.value))}
+          className="bg-gray-700 text-gray-200 rounded px-2 py-1 w-20"
+        />
+        <select
+          value={node}
+          onChange={(e) => setNode(e.target.value)}
+          className="bg-gray-700 text-gray-200 rounded px-2 py-1"
+        >
+          {DrumMapNodes.map((name) => (
+            <option key={name} value={name}>
+              {name}
+            </option>
+          ))}
+        </select>
+        {node === NodeNames.Sampler && (
+          <select
+            value={pad}
+            onChange={(e) => setPad(Number(e.target.value))}
+            className="bg-gray-700 text-gray-200 rounded px-2 py-1"
+          >
+            {Array.from({ length: SAMPLER_PADS }, (_, i) => (
+              <option key={i} value={i}>
+                Pad {i + 1}
+              </option>
+            ))}
+          </select>
+        )}
+        <button
+          onClick={setMapping}
+          className="px-3 py-1 rounded bg-gray-600 hover:bg-gray-700 text-white"
+        >
+          Map
+        </button>
+        <button
+          onClick={() => update(Commands.ResetDrumMap)}
+          className="px-3 py-1 rounded bg-gray-600 hover:bg-gray-700 text-white"
+        >
+          Reset to GM
+        </button>
+      </div>
+      <div className="grid grid-cols-4 gap-2 text-sm text-gray-300">
+        {Object.entries(drumMap).map(([mappedNote, target]) => (
+          <div
+            key={mappedNote}
+            className="flex items-center gap-2 bg-gray-700 rounded px-2 py-1"
+          >
+            <button
+              onClick={() => playNote(Number(mappedNote))}
+              className="flex-1 text-left hover:text-white"
+            >
+              {mappedNote}: {target.node}
+              {target.pad !== undefined && ` ${target.pad + 1}`}
+            </button>
+            <button
+              onClick={() =>
+                update(Commands.ClearDrumMapNote, { note: Number(mappedNote) })
+              }
+              className="text-gray-400 hover:text-red-400"
+            >
+              ✕
+            </button>
+          </div>
+        ))}
+      </div>
+      {error && <p className="mt-2 text-sm text-red-400">{error}</p>}
+    </div>
+  )
+}
@@ -0,0 +1,121 @@
+import { useState } from "react"
+import { invoke } from "@tauri-apps/api/core"
+import { Commands, Master, NodeNames, SystemNames } from "../events"
+
+interface EvolveControlsProps {
+  systemName: string
+}
+
+interface EvolveSettings {
+  enabled: boolean
+  everyBars: number
+  intensity: number
+  seed: number
+}
+
+const DEFAULT_SETTINGS: EvolveSettings = {
+  enabled: false,
+  everyBars: 4,
+  intensity: 0.25,
+  seed: 0,
+}
+
+const sendEvolveEvent = (event: string, systemName: string, value: number) =>
+  invoke(Commands.SendClientEvent, {
+    systemName: SystemNames.Master,
+    nodeName: NodeNames.Evolve,
+    eventName: event,
+    parameter: value,
+    data: systemName,
+  }).catch((error) => console.error(`Error sending evolve ${event}:`, error))
+
+// Let the open system drift on its own, a small random change every few bars
+export function EvolveControls({
+  systemName,
+}: EvolveControlsProps): JSX.Element {
+  // Kept per system, as the backend evolves each one separately
+  const [settings, setSettings] = useState<Record<string, EvolveSettings>>({})
+  const current = settings[systemName] ?? DEFAULT_SETTINGS
+
+  const update = (
+    key: keyof EvolveSettings,
+    event: string,
+    value: number | boolean
+  ) => {
+    // Number fields read NaN while they're being cleared
+    if (typeof value === "number" && Number.isNaN(value)) {
+      return
+    }
+    setSettings((previous) => ({
+      ...previous,
+      [systemName]: { ...current, [key]: value },
+    }))
+    sendEvolveEvent(event, systemName, Number(value))
+  }
+
+  return (
+    <div className="mb-6 flex flex-wrap items-center gap-4 rounded-lg bg-gray-800 px-4 py-3 text-sm">
+      <button
+        onClick={() =>
+          update("enabled", Master.Evolve.SetEnabled, !current.enabled)
+        }
+        className={`w-20 rounded px-2 py-1 text-xs ${
+          current.enabled
+            ? "bg-green-600 text-white"
+            : "bg-gray-700 text-gray-300 hover:bg-gray-600"
+        }`}
+      >
+        Evolve
+      </button>
+      <label className="flex items-center gap-2 text-gray-400">
+        Every {current.everyBars} bars
+        <input
+          type="range"
+          min={1}
+          max={64}
+          step={1}
+          value={current.everyBars}
+          onChange={(e) =>
+            update(
+              "everyBars",
+              Master.Evolve.SetEveryBars,
+              parseInt(e.target.value)
+            )
+          }
+          className="w-24"
+        />
+      </label>
+      <label className="flex items-center gap-2 text-gray-400">
+        Intensity {Math.round(current.intensity * 100)}%
+        <input
+          type="range"
+          min={0}
+          max={1}
+          step={0.01}
+          value={current.intensity}
+          onChange={(e) =>
+            update(
+              "intensity",
+              Master.Evolve.SetIntensity,
+              parseFloat(e.target.value)
+            )
+          }
+          className="w-24"
+        />
+      </label>
+      <label className="flex items-center gap-2 text-gray-400">
+        Seed
+        <input
+          type="number"
+          min={0}
+          step={1}
+          value={current.seed}
+          onChange={(e) =>
+            update("seed", Master.Evolve.SetSeed, parseInt(e.target.value))
+          }
+          className="w-20 rounded bg-gray-700 px-2 py-1 text-gray-200"
+        />
+      </label>
+    </div>
+  )
+}
@@ -0,0 +1,25 @@
+import { useEffect } from "react"
+import { invoke } from "@tauri-apps/api/core"
+import { Commands, AudioSystemInfo } from "../events"
+
+interface GenericSystemPageProps {
+  system: AudioSystemInfo
+}
+
+// Fallback page for registered systems that don't have a dedicated page yet
+export function GenericSystemPage({
+  system,
+}: GenericSystemPageProps): JSX.Element {
+  useEffect(() => {
+    invoke(Commands.SwitchAudioSystem, { systemName: system.name }).catch(
+      (error) => console.error(`Error switching to ${system.name}:`, error)
+    )
+  }, [system.name])
+
+  return (
+    <div className="rounded-lg bg-gray-800 p-6">
+      <h2 className="mb-2 text-lg text-neutral-300">{system.name}</h2>
+      <p className="text-sm text-gray-400">{system.description}</p>
+    </div>
+  )
+}
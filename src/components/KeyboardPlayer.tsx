@@ -0,0 +1,88 @@
+import { useEffect, useState } from "react"
+import { invoke } from "@tauri-apps/api/core"
+import { Commands, KeyboardState, KeyboardSynths } from "../events"
+
+// Typing in these shouldn't play notes
+const isEditable = (target: EventTarget | null) =>
+  target instanceof HTMLInputElement ||
+  target instanceof HTMLSelectElement ||
+  target instanceof HTMLTextAreaElement
+
+// Play the auditioner from the computer keyboard: A to ' are notes, Z and X
+// shift the octave. The mapping lives in the backend.
+export function KeyboardPlayer(): JSX.Element {
+  const [enabled, setEnabled] = useState(true)
+  const [keyboard, setKeyboard] = useState<KeyboardState>({
+    octave: 4,
+    synth: KeyboardSynths[0],
+    note: null,
+  })
+  const [error, setError] = useState<string | null>(null)
+
+  useEffect(() => {
+    invoke<KeyboardState>(Commands.GetKeyboardState)
+      .then(setKeyboard)
+      .catch((error) => setError(String(error)))
+  }, [])
+
+  useEffect(() => {
+    if (!enabled) return
+
+    const handleKeyDown = (e: KeyboardEvent) => {
+      if (e.repeat || e.ctrlKey || e.metaKey || e.altKey) return
+      if (isEditable(e.target)) return
+      invoke<KeyboardState>(Commands.KeyboardKeyDown, { code: e.code })
+        .then(setKeyboard)
+        .catch((error) => setError(String(error)))
+    }
+
+    window.addEventListener("keydown", handleKeyDown)
+    return () => window.removeEventListener("keydown", handleKeyDown)
+  }, [enabled])
+
+  const setSynth = async (node: string) => {
+    try {
+      setKeyboard(
+        await invoke<KeyboardState>(Commands.SetKeyboardSynth, { node })
+      )
+      setError(null)
+    } catch (error) {
+      setError(String(error))
+    }
+  }
+
+  return (
+    <div className="bg-gray-800 rounded-lg p-6">
+      <div className="flex flex-wrap items-center gap-3 text-sm text-gray-300">
+        <label className="flex items-center gap-2">
+          <input
+            type="checkbox"
+            checked={enabled}
+            onChange={(e) => setEnabled(e.target.checked)}
+          />
+          Computer keyboard
+        </label>
+        <span>Octave: {keyboard.octave}</span>
+        <span className="font-mono w-16">
+          {keyboard.note !== null ? `Note ${keyboard.note}` : ""}
+        </span>
+        <label>Synth:</label>
+        <select
+          value={keyboard.synth}
+          onChange={(e) => setSynth(e.target.value)}
+          className="bg-gray-700 text-gray-200 rounded px-2 py-1"
+        >
+          {KeyboardSynths.map((name) => (
+            <option key={name} value={name}>
+              {name}
+            </option>
+          ))}
+        </select>
+        <span className="text-gray-500">
+          A–' play notes, Z/X change octave; drum map notes play drums
+        </span>
+      </div>
+      {error && <p className="mt-2 text-sm text-red-400">{error}</p>}
+    </div>
+  )
+}
@@ -0,0 +1,110 @@
+import { useEffect, useState } from "react"
+import { invoke } from "@tauri-apps/api/core"
+import { emit } from "@tauri-apps/api/event"
+import {
+  Commands,
+  Kit,
+  KitNodes,
+  SystemNames,
+  parameterChangedEvent,
+} from "../events"
+
+interface KitBrowserProps {
+  // Current drum parameter values keyed by "node.event"
+  getParameters: () => Record<string, number>
+}
+
+// Save the drum sounds as a named kit and load saved kits back
+export function KitBrowser({ getParameters }: KitBrowserProps): JSX.Element {
+  const [kits, setKits] = useState<string[]>([])
+  const [name, setName] = useState("")
+  const [error, setError] = useState<string | null>(null)
+
+  const refreshKits = () =>
+    invoke<string[]>(Commands.ListKits)
+      .then(setKits)
+      .catch((error) => setError(String(error)))
+
+  useEffect(() => {
+    refreshKits()
+  }, [])
+
+  const saveKit = async () => {
+    const kit: Kit = { name }
+    for (const [key, value] of Object.entries(getParameters())) {
+      const [node, event] = key.split(".")
+      if (!KitNodes.includes(node)) continue
+      const nodeKey = node as keyof Omit<Kit, "name">
+      kit[nodeKey] = { ...kit[nodeKey], [event]: value }
+    }
+
+    try {
+      await invoke(Commands.SaveKit, { kit })
+      setError(null)
+      refreshKits()
+    } catch (error) {
+      setError(String(error))
+    }
+  }
+
+  const loadKit = async (kitName: string) => {
+    try {
+      const kit = await invoke<Kit>(Commands.LoadKit, { name: kitName })
+      setName(kit.name)
+      setError(null)
+
+      // Move the sliders the same way engine-side changes do
+      for (const node of KitNodes) {
+        const events = kit[node as keyof Omit<Kit, "name">] ?? {}
+        for (const [event, value] of Object.entries(events)) {
+          emit(parameterChangedEvent(SystemNames.Auditioner, node, event), {
+            system: SystemNames.Auditioner,
+            node,
+            event,
+            parameter: value,
+            data: null,
+          })
+        }
+      }
+    } catch (error) {
+      setError(String(error))
+    }
+  }
+
+  return (
+    <div className="bg-gray-800 rounded-lg p-6">
+      <div className="flex items-center gap-3">
+        <label className="text-sm font-medium text-gray-300">Kit:</label>
+        <input
+          type="text"
+          value={name}
+          onChange={(e) => setName(e.target.value)}
+          placeholder="Kit name"
+          className="bg-gray-700 text-gray-200 rounded px-2 py-1 text-sm w-48"
+        />
+        <button
+          onClick={saveKit}
+          disabled={name.trim() === ""}
+          className="px-3 py-1 rounded bg-gray-600 hover:bg-gray-700 text-white text-sm disabled:opacity-50"
+        >
+          Save
+        </button>
+        <select
+          value=""
+          onChange={(e) => loadKit(e.target.value)}
+          className="bg-gray-700 text-gray-200 rounded px-2 py-1 text-sm"
+        >
+          <option value="" disabled>
+            Load kit...
+          </option>
+          {kits.map((kit) => (
+            <option key={kit} value={kit}>
+              {kit}
+            </option>
+          ))}
+        </select>
+      </div>
+      {error && <p className="mt-2 text-sm text-red-400">{error}</p>}
+    </div>
+  )
+}
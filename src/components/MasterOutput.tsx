@@ -0,0 +1,95 @@
+import { useState, useEffect } from "react"
+import { invoke } from "@tauri-apps/api/core"
+import { listen } from "@tauri-apps/api/event"
+import {
+  Commands,
+  Master,
+  NodeNames,
+  ServerEvents,
+  SystemNames,
+  OutputMeterPayload,
+  ParameterChangedPayload,
+} from "../events"
+
+// The limiter's ceiling; levels over it are clipped
+const CEILING = 0.95
+
+// How long the clip light stays on after the last report
+const CLIP_LIGHT_MS = 2000
+
+const toPercent = (level: number) => `${Math.min(level / CEILING, 1) * 100}%`
+
+// Overall output level with a peak-hold meter and clip warning
+export function MasterOutput(): JSX.Element {
+  const [gain, setGain] = useState(1)
+  const [meter, setMeter] = useState({ peak: 0, held_peak: 0 })
+  const [clipped, setClipped] = useState<number | null>(null)
+
+  useEffect(() => {
+    const unlistenMeter = listen<OutputMeterPayload>(
+      ServerEvents.Master.OutputMeter,
+      (event) => setMeter(event.payload.data)
+    )
+
+    let clipTimeout: ReturnType<typeof setTimeout> | undefined
+    const unlistenClipping = listen<ParameterChangedPayload>(
+      ServerEvents.Master.ClippingDetected,
+      (event) => {
+        setClipped(event.payload.parameter)
+        clearTimeout(clipTimeout)
+        clipTimeout = setTimeout(() => setClipped(null), CLIP_LIGHT_MS)
+      }
+    )
+
+    return () => {
+      clearTimeout(clipTimeout)
+      unlistenMeter.then((stop) => stop())
+      unlistenClipping.then((stop) => stop())
+    }
+  }, [])
+
+  return (
+    <div className="mb-6 flex flex-wrap items-center gap-4 rounded-lg bg-gray-800 px-4 py-3 text-sm">
+      <label className="flex items-center gap-2 text-gray-400">
+        Master {gain.toFixed(2)}
+        <input
+          type="range"
+          min={0}
+          max={2}
+          step={0.01}
+          value={gain}
+          onChange={(e) => {
+            const value = parseFloat(e.target.value)
+            setGain(value)
+            invoke(Commands.SendClientEvent, {
+              systemName: SystemNames.Master,
+              nodeName: NodeNames.System,
+              eventName: Master.System.SetMasterGain,
+              parameter: value,
+            }).catch((error) =>
+              console.error("Error setting master gain:", error)
+            )
+          }}
+          className="w-32"
+        />
+      </label>
+      <div className="relative h-2 w-48 overflow-hidden rounded bg-gray-700">
+        <div
+          className="absolute inset-y-0 left-0 bg-green-500"
+          style={{ width: toPercent(meter.peak) }}
+        />
+        <div
+          className="absolute inset-y-0 w-0.5 bg-yellow-300"
+          style={{ left: toPercent(meter.held_peak) }}
+        />
+      </div>
+      <span
+        className={`rounded px-2 py-1 text-xs ${
+          clipped !== null ? "bg-red-700 text-white" : "bg-gray-700 text-gray-500"
+        }`}
+      >
+        {clipped !== null ? `Clip ${clipped}/s` : "Clip"}
+      </span>
+    </div>
+  )
+}
@@ -0,0 +1,364 @@
+import { useEffect, useState } from "react"
+import { invoke } from "@tauri-apps/api/core"
+import {
+  Auditioner as AuditionerEvents,
+  Commands,
+  NodeNames,
+  SAMPLER_PADS,
+  SampleInfo,
+  SliceMarkers,
+  SystemNames,
+} from "../events"
+
+const sendSamplerEvent = (
+  event: string,
+  value: number,
+  data: number | null = null
+) =>
+  invoke(Commands.SendClientEvent, {
+    systemName: SystemNames.Auditioner,
+    nodeName: NodeNames.Sampler,
+    eventName: event,
+    parameter: value,
+    data,
+  }).catch((error) => console.error(`Error sending sampler ${event}:`, error))
+
+const triggerPad = (pad: number, velocity: number) =>
+  sendSamplerEvent(AuditionerEvents.Sampler.Trigger, pad, velocity)
+
+const stopPad = (pad: number) =>
+  sendSamplerEvent(AuditionerEvents.Sampler.Stop, pad)
+
+// Import WAV/FLAC files and play them from the auditioner's sample pads
+export function SamplePads(): JSX.Element {
+  const [samples, setSamples] = useState<SampleInfo[]>([])
+  const [assigned, setAssigned] = useState<(string | null)[]>(
+    Array(SAMPLER_PADS).fill(null)
+  )
+  const [path, setPath] = useState("")
+  const [error, setError] = useState<string | null>(null)
+  const [slices, setSlices] = useState<SliceMarkers | null>(null)
+  const [bpm, setBpm] = useState(120)
+  const [loopBpm, setLoopBpm] = useState(0)
+  const [stretchQuality, setStretchQuality] = useState(1)
+  const [grainSize, setGrainSize] = useState(0.05)
+  const [velocity, setVelocity] = useState(1)
+  // Layers stacked on each pad after its assigned sample
+  const [layers, setLayers] = useState<number[]>(Array(SAMPLER_PADS).fill(0))
+  const [layerVelocity, setLayerVelocity] = useState({ min: 0, max: 1 })
+
+  useEffect(() => {
+    invoke<SampleInfo[]>(Commands.ListSamples)
+      .then(setSamples)
+      .catch((error) => setError(String(error)))
+  }, [])
+
+  const importSample = async () => {
+    try {
+      const sample = await invoke<SampleInfo>(Commands.ImportSample, { path })
+      setSamples((prev) =>
+        [...prev.filter((s) => s.name !== sample.name), sample].sort((a, b) =>
+          a.name.localeCompare(b.name)
+        )
+      )
+      setError(null)
+    } catch (error) {
+      setError(String(error))
+    }
+  }
+
+  const assignSample = async (pad: number, name: string) => {
+    try {
+      await invoke(Commands.AssignSample, { pad, name })
+      setAssigned((prev) => prev.map((n, i) => (i === pad ? name : n)))
+      setLayers((prev) => prev.map((n, i) => (i === pad ? 0 : n)))
+      setError(null)
+    } catch (error) {
+      setError(String(error))
+    }
+  }
+
+  const addLayer = async (pad: number, name: string) => {
+    try {
+      await invoke(Commands.AddSampleLayer, {
+        pad,
+        name,
+        minVelocity: layerVelocity.min,
+        maxVelocity: layerVelocity.max,
+      })
+      setLayers((prev) => prev.map((n, i) => (i === pad ? n + 1 : n)))
+      setError(null)
+    } catch (error) {
+      setError(String(error))
+    }
+  }
+
+  const sliceSample = async (name: string) => {
+    try {
+      setSlices(await invoke<SliceMarkers>(Commands.SliceSample, { name }))
+      setError(null)
+    } catch (error) {
+      setError(String(error))
+    }
+  }
+
+  // Markers are shown in milliseconds and stored as frames
+  const moveMarker = (index: number, milliseconds: number) => {
+    if (!slices) return
+    const frame = Math.round((milliseconds / 1000) * slices.sample_rate)
+    setSlices({
+      ...slices,
+      markers: slices.markers.map((m, i) => (i === index ? frame : m)),
+    })
+  }
+
+  const applyMarkers = async () => {
+    if (!slices) return
+    try {
+      setSlices(
+        await invoke<SliceMarkers>(Commands.SetSliceMarkers, {
+          name: slices.name,
+          markers: slices.markers,
+        })
+      )
+      setError(null)
+    } catch (error) {
+      setError(String(error))
+    }
+  }
+
+  const slicesToPads = async () => {
+    if (!slices) return
+    try {
+      await invoke(Commands.SetSliceMarkers, {
+        name: slices.name,
+        markers: slices.markers,
+      })
+      const count = await invoke<number>(Commands.AssignSlices, {
+        name: slices.name,
+      })
+      setAssigned((prev) =>
+        prev.map((n, i) => (i < count ? `${slices.name} ${i + 1}` : n))
+      )
+      setLayers((prev) => prev.map((n, i) => (i < count ? 0 : n)))
+      setError(null)
+    } catch (error) {
+      setError(String(error))
+    }
+  }
+
+  return (
+    <div className="bg-gray-800 rounded-lg p-6">
+      <h2 className="mb-4 text-lg text-gray-200">Sample Pads</h2>
+      <div className="flex items-center gap-3 mb-4">
+        <input
+          type="text"
+          value={path}
+          onChange={(e) => setPath(e.target.value)}
+          placeholder="Path to a WAV or FLAC file"
+          className="bg-gray-700 text-gray-200 rounded px-2 py-1 text-sm flex-1"
+        />
+        <button
+          onClick={importSample}
+          disabled={path.trim() === ""}
+          className="px-3 py-1 rounded bg-gray-600 hover:bg-gray-700 text-white text-sm disabled:opacity-50"
+        >
+          Import
+        </button>
+      </div>
+      <div className="flex items-center gap-3 mb-4">
+        <select
+          value={slices?.name ?? ""}
+          onChange={(e) => sliceSample(e.target.value)}
+          className="bg-gray-700 text-gray-200 rounded px-2 py-1 text-sm"
+        >
+          <option value="" disabled>
+            Slice loop...
+          </option>
+          {samples.map((sample) => (
+            <option key={sample.name} value={sample.name}>
+              {sample.name}
+            </option>
+          ))}
+        </select>
+        {slices && (
+          <>
+            <button
+              onClick={applyMarkers}
+              className="px-3 py-1 rounded bg-gray-600 hover:bg-gray-700 text-white text-sm"
+            >
+              Apply markers
+            </button>
+            <button
+              onClick={slicesToPads}
+              className="px-3 py-1 rounded bg-gray-600 hover:bg-gray-700 text-white text-sm"
+            >
+              Slices to pads
+            </button>
+          </>
+        )}
+      </div>
+      {slices && (
+        <div className="flex flex-wrap gap-2 mb-4">
+          {slices.markers.map((marker, i) => (
+            <input
+              key={i}
+              type="number"
+              min={0}
+              value={Math.round((marker / slices.sample_rate) * 1000)}
+              onChange={(e) => moveMarker(i, Number(e.target.value))}
+              title={`Slice ${i + 1} start (ms)`}
+              className="bg-gray-700 text-gray-200 rounded px-2 py-1 text-xs w-20"
+            />
+          ))}
+        </div>
+      )}
+      <div className="flex flex-wrap items-center gap-3 mb-4 text-sm text-gray-300">
+        <label>BPM:</label>
+        <input
+          type="number"
+          min={1}
+          value={bpm}
+          onChange={(e) => {
+            setBpm(Number(e.target.value))
+            sendSamplerEvent(
+              AuditionerEvents.Sampler.SetBpm,
+              Number(e.target.value)
+            )
+          }}
+          className="bg-gray-700 text-gray-200 rounded px-2 py-1 w-20"
+        />
+        <label>Loop BPM:</label>
+        <input
+          type="number"
+          min={0}
+          title="Tempo the loops were recorded at, 0 to play them unstretched"
+          value={loopBpm}
+          onChange={(e) => {
+            setLoopBpm(Number(e.target.value))
+            sendSamplerEvent(
+              AuditionerEvents.Sampler.SetLoopBpm,
+              Number(e.target.value)
+            )
+          }}
+          className="bg-gray-700 text-gray-200 rounded px-2 py-1 w-20"
+        />
+        <select
+          value={stretchQuality}
+          onChange={(e) => {
+            setStretchQuality(Number(e.target.value))
+            sendSamplerEvent(
+              AuditionerEvents.Sampler.SetStretchQuality,
+              Number(e.target.value)
+            )
+          }}
+          className="bg-gray-700 text-gray-200 rounded px-2 py-1"
+        >
+          <option value={0}>Granular</option>
+          <option value={1}>WSOLA</option>
+        </select>
+        <label>Grain: {Math.round(grainSize * 1000)} ms</label>
+        <input
+          type="range"
+          min={0.01}
+          max={0.2}
+          step={0.005}
+          value={grainSize}
+          onChange={(e) => {
+            setGrainSize(Number(e.target.value))
+            sendSamplerEvent(
+              AuditionerEvents.Sampler.SetGrainSize,
+              Number(e.target.value)
+            )
+          }}
+        />
+      </div>
+      <div className="flex flex-wrap items-center gap-3 mb-4 text-sm text-gray-300">
+        <label>Velocity: {Math.round(velocity * 100)}%</label>
+        <input
+          type="range"
+          min={0}
+          max={1}
+          step={0.01}
+          value={velocity}
+          onChange={(e) => setVelocity(Number(e.target.value))}
+        />
+        <label>New layers for velocities</label>
+        <input
+          type="number"
+          min={0}
+          max={1}
+          step={0.05}
+          value={layerVelocity.min}
+          onChange={(e) =>
+            setLayerVelocity({ ...layerVelocity, min: Number(e.target.value) })
+          }
+          className="bg-gray-700 text-gray-200 rounded px-2 py-1 w-20"
+        />
+        <label>to</label>
+        <input
+          type="number"
+          min={0}
+          max={1}
+          step={0.05}
+          value={layerVelocity.max}
+          onChange={(e) =>
+            setLayerVelocity({ ...layerVelocity, max: Number(e.target.value) })
+          }
+          className="bg-gray-700 text-gray-200 rounded px-2 py-1 w-20"
+        />
+      </div>
+      <div className="grid grid-cols-4 gap-3">
+        {assigned.map((name, pad) => (
+          <div key={pad} className="flex flex-col gap-2">
+            <button
+              onClick={() => triggerPad(pad, velocity)}
+              disabled={name === null}
+              className="h-16 rounded bg-blue-600 hover:bg-blue-700 text-white text-sm disabled:opacity-50"
+            >
+              {name ?? `Pad ${pad + 1}`}
+              {layers[pad] > 0 && ` +${layers[pad]}`}
+            </button>
+            <button
+              onClick={() => stopPad(pad)}
+              disabled={name === null}
+              className="px-2 py-1 rounded bg-gray-600 hover:bg-gray-700 text-white text-xs disabled:opacity-50"
+            >
+              Stop
+            </button>
+            <select
+              value={name ?? ""}
+              onChange={(e) => assignSample(pad, e.target.value)}
+              className="bg-gray-700 text-gray-200 rounded px-2 py-1 text-xs"
+            >
+              <option value="" disabled>
+                Assign...
+              </option>
+              {samples.map((sample) => (
+                <option key={sample.name} value={sample.name}>
+                  {sample.name} ({sample.duration.toFixed(2)}s)
+                </option>
+              ))}
+            </select>
+            <select
+              value=""
+              onChange={(e) => addLayer(pad, e.target.value)}
+              disabled={name === null}
+              className="bg-gray-700 text-gray-200 rounded px-2 py-1 text-xs disabled:opacity-50"
+            >
+              <option value="" disabled>
+                Add layer...
+              </option>
+              {samples.map((sample) => (
+                <option key={sample.name} value={sample.name}>
+                  {sample.name}
+                </option>
+              ))}
+            </select>
+          </div>
+        ))}
+      </div>
+      {error && <p className="mt-2 text-sm text-red-400">{error}</p>}
+    </div>
+  )
+}
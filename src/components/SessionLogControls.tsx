@@ -0,0 +1,91 @@
+import { useState } from "react"
+import { invoke } from "@tauri-apps/api/core"
+import { Commands } from "../events"
+
+// Record what's played to a session log, then replay it live or bounce it
+export function SessionLogControls(): JSX.Element {
+  const [path, setPath] = useState("")
+  const [recording, setRecording] = useState(false)
+  const [status, setStatus] = useState<string | null>(null)
+  const [error, setError] = useState<string | null>(null)
+
+  const run = async (action: () => Promise<string>) => {
+    try {
+      setStatus(await action())
+      setError(null)
+    } catch (error) {
+      setError(String(error))
+    }
+  }
+
+  const toggleRecording = () =>
+    run(async () => {
+      if (recording) {
+        const count = await invoke<number>(Commands.StopSessionLog)
+        setRecording(false)
+        return `Logged ${count} commands`
+      }
+      await invoke(Commands.StartSessionLog, { path })
+      setRecording(true)
+      return "Recording"
+    })
+
+  const replay = () =>
+    run(async () => {
+      const count = await invoke<number>(Commands.ReplaySessionLog, { path })
+      return `Replaying ${count} commands`
+    })
+
+  const render = () =>
+    run(async () => {
+      const outputPath = path.replace(/\.[^./]*$/, "") + ".wav"
+      const frames = await invoke<number>(Commands.RenderSessionLog, {
+        path,
+        outputPath,
+      })
+      return `Rendered ${frames} frames to ${outputPath}`
+    })
+
+  const noPath = path.trim() === ""
+
+  return (
+    <div className="mb-6 rounded-lg bg-gray-800 px-4 py-3 text-sm">
+      <div className="flex flex-wrap items-center gap-3">
+        <input
+          type="text"
+          value={path}
+          onChange={(e) => setPath(e.target.value)}
+          placeholder="Path to a session log"
+          className="flex-1 rounded bg-gray-700 px-2 py-1 text-gray-200"
+        />
+        <button
+          onClick={toggleRecording}
+          disabled={noPath && !recording}
+          className={`w-20 rounded px-2 py-1 text-xs disabled:opacity-50 ${
+            recording
+              ? "bg-red-600 text-white"
+              : "bg-gray-700 text-gray-300 hover:bg-gray-600"
+          }`}
+        >
+          {recording ? "Stop" : "Record"}
+        </button>
+        <button
+          onClick={replay}
+          disabled={noPath || recording}
+          className="rounded bg-gray-700 px-2 py-1 text-xs text-gray-300 hover:bg-gray-600 disabled:opacity-50"
+        >
+          Replay
+        </button>
+        <button
+          onClick={render}
+          disabled={noPath || recording}
+          className="rounded bg-gray-700 px-2 py-1 text-xs text-gray-300 hover:bg-gray-600 disabled:opacity-50"
+        >
+          Render
+        </button>
+      </div>
+      {status && <p className="mt-2 text-gray-400">{status}</p>}
+      {error && <p className="mt-2 text-red-400">{error}</p>}
+    </div>
+  )
+}
@@ -0,0 +1,89 @@
+import { useState } from "react"
+import { invoke } from "@tauri-apps/api/core"
+import {
+  Commands,
+  Master,
+  NodeNames,
+  STUTTER_DIVISIONS,
+  SystemNames,
+} from "../events"
+
+const sendStutterEvent = (event: string, value?: number) =>
+  invoke(Commands.SendClientEvent, {
+    systemName: SystemNames.Master,
+    nodeName: NodeNames.Stutter,
+    eventName: event,
+    parameter: value ?? null,
+  }).catch((error) => console.error(`Error sending stutter ${event}:`, error))
+
+// Hold a division to repeat the last slice of the mix, release to drop back in
+export function StutterControls(): JSX.Element {
+  const [held, setHeld] = useState<number | null>(null)
+  const [pitch, setPitch] = useState(0)
+  const [decay, setDecay] = useState(1)
+
+  const press = (division: number) => {
+    setHeld(division)
+    sendStutterEvent(Master.Stutter.Press, division)
+  }
+
+  const release = () => {
+    if (held === null) return
+    setHeld(null)
+    sendStutterEvent(Master.Stutter.Release)
+  }
+
+  return (
+    <div className="mb-6 flex flex-wrap items-center gap-4 rounded-lg bg-gray-800 px-4 py-3 text-sm">
+      <span className="text-gray-300">Stutter</span>
+      <div className="flex gap-1">
+        {STUTTER_DIVISIONS.map((division) => (
+          <button
+            key={division}
+            onPointerDown={() => press(division)}
+            onPointerUp={release}
+            onPointerLeave={release}
+            className={`w-12 rounded px-2 py-1 text-xs select-none ${
+              held === division
+                ? "bg-orange-600 text-white"
+                : "bg-gray-700 text-gray-300 hover:bg-gray-600"
+            }`}
+          >
+            1/{division}
+          </button>
+        ))}
+      </div>
+      <label className="flex items-center gap-2 text-gray-400">
+        Pitch {pitch > 0 ? `+${pitch}` : pitch}
+        <input
+          type="range"
+          min={-12}
+          max={12}
+          value={pitch}
+          onChange={(e) => {
+            const value = parseInt(e.target.value)
+            setPitch(value)
+            sendStutterEvent(Master.Stutter.SetPitch, value)
+          }}
+          className="w-24"
+        />
+      </label>
+      <label className="flex items-center gap-2 text-gray-400">
+        Decay {decay.toFixed(2)}
+        <input
+          type="range"
+          min={0}
+          max={1}
+          step={0.01}
+          value={decay}
+          onChange={(e) => {
+            const value = parseFloat(e.target.value)
+            setDecay(value)
+            sendStutterEvent(Master.Stutter.SetDecay, value)
+          }}
+          className="w-24"
+        />
+      </label>
+    </div>
+  )
+}
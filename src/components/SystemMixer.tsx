@@ -0,0 +1,216 @@
+import { useState, useEffect } from "react"
+import { invoke } from "@tauri-apps/api/core"
+import { listen } from "@tauri-apps/api/event"
+import {
+  Commands,
+  Master,
+  NodeNames,
+  ServerEvents,
+  SystemNames,
+  AudioSystemInfo,
+  SceneStatePayload,
+} from "../events"
+
+interface SystemMixerProps {
+  systems: AudioSystemInfo[]
+  // The open tab's system; switching to it solos it on the backend
+  currentSystem: string | null
+}
+
+interface Channel {
+  active: boolean
+  gain: number
+  muted: boolean
+}
+
+const DEFAULT_CHANNEL: Channel = { active: false, gain: 1, muted: false }
+
+const sendMixerEvent = (event: string, systemName: string, value: number) =>
+  invoke(Commands.SendClientEvent, {
+    systemName: SystemNames.Master,
+    nodeName: NodeNames.Mixer,
+    eventName: event,
+    parameter: value,
+    data: systemName,
+  }).catch((error) => console.error(`Error sending mixer ${event}:`, error))
+
+const sendSceneEvent = (event: string, sceneName?: string, fade?: number) =>
+  invoke(Commands.SendClientEvent, {
+    systemName: SystemNames.Master,
+    nodeName: NodeNames.Scene,
+    eventName: event,
+    parameter: fade ?? null,
+    data: sceneName ?? null,
+  }).catch((error) => console.error(`Error sending scene ${event}:`, error))
+
+// Layer other systems under the open one, each with its own gain and mute
+export function SystemMixer({
+  systems,
+  currentSystem,
+}: SystemMixerProps): JSX.Element {
+  const [channels, setChannels] = useState<Record<string, Channel>>({})
+  const [scenes, setScenes] = useState<string[]>([])
+  const [currentScene, setCurrentScene] = useState<string | null>(null)
+  const [sceneName, setSceneName] = useState("")
+  const [fadeSeconds, setFadeSeconds] = useState(0)
+
+  // Recalling a scene moves the backend's mixer, so follow it here
+  useEffect(() => {
+    const unlisten = listen<SceneStatePayload>(
+      ServerEvents.Master.SceneState,
+      (event) => {
+        const state = event.payload.data
+        setScenes(state.scenes)
+        setCurrentScene(state.current)
+        setChannels((previous) => {
+          const next = { ...previous }
+          for (const [name, channel] of Object.entries(state.channels)) {
+            next[name] = {
+              ...(previous[name] ?? DEFAULT_CHANNEL),
+              ...channel,
+            }
+          }
+          return next
+        })
+      }
+    )
+    sendSceneEvent(Master.Scene.GetScenes)
+    return () => {
+      unlisten.then((stop) => stop())
+    }
+  }, [])
+
+  // Opening a tab switches to its system, which deactivates every other one
+  useEffect(() => {
+    setChannels((previous) => {
+      const next: Record<string, Channel> = {}
+      for (const system of systems) {
+        next[system.name] = {
+          ...(previous[system.name] ?? DEFAULT_CHANNEL),
+          active: system.name === currentSystem,
+        }
+      }
+      return next
+    })
+  }, [systems, currentSystem])
+
+  const updateChannel = (name: string, update: Partial<Channel>) =>
+    setChannels((previous) => ({
+      ...previous,
+      [name]: { ...(previous[name] ?? DEFAULT_CHANNEL), ...update },
+    }))
+
+  return (
+    <div className="mb-6 rounded-lg bg-gray-800 px-4 py-3">
+      <div className="flex flex-wrap gap-6">
+        {systems.map((system) => {
+          const channel = channels[system.name] ?? DEFAULT_CHANNEL
+          return (
+            <div key={system.name} className="flex items-center gap-3 text-sm">
+              <label className="flex items-center gap-2 text-gray-300">
+                <input
+                  type="checkbox"
+                  checked={channel.active}
+                  disabled={system.name === currentSystem}
+                  onChange={(e) => {
+                    updateChannel(system.name, { active: e.target.checked })
+                    sendMixerEvent(
+                      Master.Mixer.SetActive,
+                      system.name,
+                      e.target.checked ? 1 : 0
+                    )
+                  }}
+                />
+                {system.name}
+              </label>
+              <input
+                type="range"
+                min={0}
+                max={2}
+                step={0.01}
+                value={channel.gain}
+                onChange={(e) => {
+                  const value = parseFloat(e.target.value)
+                  updateChannel(system.name, { gain: value })
+                  sendMixerEvent(Master.Mixer.SetGain, system.name, value)
+                }}
+                className="w-24"
+              />
+              <button
+                onClick={() => {
+                  updateChannel(system.name, { muted: !channel.muted })
+                  sendMixerEvent(
+                    Master.Mixer.SetMute,
+                    system.name,
+                    channel.muted ? 0 : 1
+                  )
+                }}
+                className={`rounded px-2 py-1 text-xs ${
+                  channel.muted
+                    ? "bg-red-700 text-white"
+                    : "bg-gray-700 text-gray-300 hover:bg-gray-600"
+                }`}
+              >
+                M
+              </button>
+            </div>
+          )
+        })}
+      </div>
+      <div className="mt-3 flex flex-wrap items-center gap-2 border-t border-gray-700 pt-3 text-sm">
+        <input
+          type="text"
+          value={sceneName}
+          placeholder="Scene name"
+          onChange={(e) => setSceneName(e.target.value)}
+          className="w-32 rounded bg-gray-700 px-2 py-1 text-gray-200"
+        />
+        <button
+          disabled={!sceneName.trim()}
+          onClick={() => {
+            sendSceneEvent(Master.Scene.Save, sceneName.trim())
+            setSceneName("")
+          }}
+          className="rounded bg-gray-700 px-2 py-1 text-xs text-gray-300 hover:bg-gray-600 disabled:opacity-50"
+        >
+          Save
+        </button>
+        <label className="flex items-center gap-2 text-gray-400">
+          Fade {fadeSeconds.toFixed(1)}s
+          <input
+            type="range"
+            min={0}
+            max={30}
+            step={0.5}
+            value={fadeSeconds}
+            onChange={(e) => setFadeSeconds(parseFloat(e.target.value))}
+            className="w-24"
+          />
+        </label>
+        {scenes.map((scene) => (
+          <span key={scene} className="flex items-center">
+            <button
+              onClick={() =>
+                sendSceneEvent(Master.Scene.Recall, scene, fadeSeconds)
+              }
+              className={`rounded-l px-2 py-1 text-xs ${
+                scene === currentScene
+                  ? "bg-blue-700 text-white"
+                  : "bg-gray-700 text-gray-300 hover:bg-gray-600"
+              }`}
+            >
+              {scene}
+            </button>
+            <button
+              onClick={() => sendSceneEvent(Master.Scene.Delete, scene)}
+              className="rounded-r bg-gray-700 px-1 py-1 text-xs text-gray-500 hover:bg-gray-600"
+              title={`Delete ${scene}`}
+            >
+              ×
+            </button>
+          </span>
+        ))}
+      </div>
+    </div>
+  )
+}
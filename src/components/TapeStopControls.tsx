@@ -0,0 +1,64 @@
+import { useState } from "react"
+import { invoke } from "@tauri-apps/api/core"
+import { Commands, Master, NodeNames, SystemNames } from "../events"
+
+const sendTapeStopEvent = (event: string, seconds: number) =>
+  invoke(Commands.SendClientEvent, {
+    systemName: SystemNames.Master,
+    nodeName: NodeNames.TapeStop,
+    eventName: event,
+    parameter: seconds,
+  }).catch((error) => console.error(`Error sending tape ${event}:`, error))
+
+// Slow the whole mix to a halt, then spin it back up
+export function TapeStopControls(): JSX.Element {
+  const [stopped, setStopped] = useState(false)
+  const [stopSeconds, setStopSeconds] = useState(1)
+  const [startSeconds, setStartSeconds] = useState(0.5)
+
+  return (
+    <div className="mb-6 flex flex-wrap items-center gap-4 rounded-lg bg-gray-800 px-4 py-3 text-sm">
+      <button
+        onClick={() => {
+          if (stopped) {
+            sendTapeStopEvent(Master.TapeStop.Start, startSeconds)
+          } else {
+            sendTapeStopEvent(Master.TapeStop.Stop, stopSeconds)
+          }
+          setStopped(!stopped)
+        }}
+        className={`w-24 rounded px-2 py-1 text-xs ${
+          stopped
+            ? "bg-orange-600 text-white"
+            : "bg-gray-700 text-gray-300 hover:bg-gray-600"
+        }`}
+      >
+        {stopped ? "Spin up" : "Tape stop"}
+      </button>
+      <label className="flex items-center gap-2 text-gray-400">
+        Stop {stopSeconds.toFixed(1)}s
+        <input
+          type="range"
+          min={0}
+          max={4}
+          step={0.1}
+          value={stopSeconds}
+          onChange={(e) => setStopSeconds(parseFloat(e.target.value))}
+          className="w-24"
+        />
+      </label>
+      <label className="flex items-center gap-2 text-gray-400">
+        Spin up {startSeconds.toFixed(1)}s
+        <input
+          type="range"
+          min={0}
+          max={4}
+          step={0.1}
+          value={startSeconds}
+          onChange={(e) => setStartSeconds(parseFloat(e.target.value))}
+          className="w-24"
+        />
+      </label>
+    </div>
+  )
+}
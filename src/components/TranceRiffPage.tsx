@@ -1,11 +1,38 @@
 import { useState, useEffect } from "react"
 import { invoke } from "@tauri-apps/api/core"
-import { TranceRiff, SystemNames, NodeNames, Commands } from "../events"
+import { listen } from "@tauri-apps/api/event"
+import {
+  TranceRiff,
+  SystemNames,
+  NodeNames,
+  Commands,
+  ServerEvents,
+  TransportPositionPayload,
+  BeatPhasePayload,
+  GrooveTemplatesPayload,
+} from "../events"
 import { ChordArpControls } from "./ChordArpControls"
 
 export function TranceRiffPage(): JSX.Element {
   const [bpm, setBpm] = useState(138)
   const [isPaused, setIsPaused] = useState(false)
+  const [position, setPosition] = useState({ bar: 1, beat: 1, step: 1 })
+  const [beatPhase, setBeatPhase] = useState({ bar: 1, beat: 1, phase: 0 })
+
+  // Metronome
+  const [metronomeEnabled, setMetronomeEnabled] = useState(false)
+  const [metronomeVolume, setMetronomeVolume] = useState(0.5)
+  const [accentDownbeat, setAccentDownbeat] = useState(true)
+  const [countInBars, setCountInBars] = useState(0)
+
+  // Groove
+  const [grooveTemplates, setGrooveTemplates] = useState<string[]>([
+    "straight",
+  ])
+  const [groove, setGroove] = useState("straight")
+  const [grooveStrength, setGrooveStrength] = useState(1.0)
+  const [grooveFilePath, setGrooveFilePath] = useState("")
+  const [grooveFileError, setGrooveFileError] = useState<string | null>(null)
 
   // Synth parameters
   const [synthGain, setSynthGain] = useState(0.5)
@@ -34,6 +61,79 @@ export function TranceRiffPage(): JSX.Element {
     switchToTranceRiff()
   }, [])
 
+  // Follow the transport position for the timeline display
+  useEffect(() => {
+    const unlisten = listen<TransportPositionPayload>(
+      ServerEvents.TranceRiff.TransportPosition,
+      (event) => setPosition(event.payload.data)
+    )
+
+    return () => {
+      unlisten.then((stop) => stop())
+    }
+  }, [])
+
+  // Pulse the beat light smoothly between steps
+  useEffect(() => {
+    const unlisten = listen<BeatPhasePayload>(
+      ServerEvents.TranceRiff.BeatPhase,
+      (event) => setBeatPhase(event.payload.data)
+    )
+
+    return () => {
+      unlisten.then((stop) => stop())
+    }
+  }, [])
+
+  // Fetch the available groove templates
+  useEffect(() => {
+    const unlisten = listen<GrooveTemplatesPayload>(
+      ServerEvents.TranceRiff.GrooveTemplates,
+      (event) => {
+        setGrooveTemplates(event.payload.data.templates)
+        setGroove(event.payload.data.current)
+      }
+    )
+
+    invoke(Commands.SendClientEvent, {
+      systemName: SystemNames.TranceRiff,
+      nodeName: NodeNames.System,
+      eventName: TranceRiff.System.GetGrooveTemplates,
+    }).catch((error) =>
+      console.error("Error requesting groove templates:", error)
+    )
+
+    return () => {
+      unlisten.then((stop) => stop())
+    }
+  }, [])
+
+  const handleLoadGrooveFile = async () => {
+    try {
+      await invoke<string>(Commands.LoadGrooveFile, {
+        path: grooveFilePath,
+        systemName: SystemNames.TranceRiff,
+      })
+      setGrooveFileError(null)
+    } catch (error) {
+      setGrooveFileError(String(error))
+    }
+  }
+
+  const handleGrooveChange = async (name: string) => {
+    setGroove(name)
+    try {
+      await invoke(Commands.SendClientEvent, {
+        systemName: SystemNames.TranceRiff,
+        nodeName: NodeNames.System,
+        eventName: TranceRiff.System.SetGroove,
+        data: name,
+      })
+    } catch (error) {
+      console.error("Error setting groove:", error)
+    }
+  }
+
   const sendAudioEvent = async (
     nodeName: string,
     eventName: string,
@@ -66,6 +166,29 @@ export function TranceRiffPage(): JSX.Element {
     )
   }
 
+  const handleStop = () => {
+    setIsPaused(true)
+    sendAudioEvent(NodeNames.System, TranceRiff.System.Stop, 0)
+  }
+
+  const handleMetronomeParameter = (
+    eventName: string,
+    value: number,
+    setter: (val: number) => void
+  ) => {
+    setter(value)
+    sendAudioEvent(NodeNames.Metronome, eventName, value)
+  }
+
+  const handleMetronomeToggle = (
+    eventName: string,
+    value: boolean,
+    setter: (val: boolean) => void
+  ) => {
+    setter(value)
+    sendAudioEvent(NodeNames.Metronome, eventName, value ? 1 : 0)
+  }
+
   const handleSequenceGenerated = (
     _sequence: Array<[number, number, number]>
   ) => {
@@ -102,6 +225,24 @@ export function TranceRiffPage(): JSX.Element {
             {isPaused ? "▶ Play" : "⏸ Pause"}
           </button>
 
+          <button
+            onClick={handleStop}
+            className="px-6 py-2 rounded-lg font-medium bg-gray-600 hover:bg-gray-700 text-white"
+          >
+            ■ Stop
+          </button>
+
+          <span className="text-sm font-mono text-gray-300 w-16">
+            {position.bar}:{position.beat}:{position.step}
+          </span>
+
+          <span
+            className={`w-3 h-3 rounded-full ${
+              beatPhase.beat === 1 ? "bg-yellow-400" : "bg-green-400"
+            }`}
+            style={{ opacity: 1 - beatPhase.phase }}
+          />
+
           <div className="flex items-center gap-3">
             <label className="text-sm font-medium text-gray-300">BPM:</label>
             <input
@@ -115,6 +256,145 @@ export function TranceRiffPage(): JSX.Element {
             <span className="text-sm text-gray-400 w-12">{bpm}</span>
           </div>
         </div>
+
+        {/* Groove */}
+        <div className="flex items-center gap-6 mb-6">
+          <div className="flex items-center gap-3">
+            <label className="text-sm font-medium text-gray-300">Groove:</label>
+            <select
+              value={groove}
+              onChange={(e) => handleGrooveChange(e.target.value)}
+              className="bg-gray-700 text-gray-200 rounded px-2 py-1 text-sm"
+            >
+              {grooveTemplates.map((name) => (
+                <option key={name} value={name}>
+                  {name}
+                </option>
+              ))}
+            </select>
+          </div>
+
+          <div className="flex items-center gap-3">
+            <label className="text-sm font-medium text-gray-300">
+              Strength:
+            </label>
+            <input
+              type="range"
+              min={0}
+              max={1}
+              step={0.01}
+              value={grooveStrength}
+              onChange={(e) => {
+                const value = parseFloat(e.target.value)
+                setGrooveStrength(value)
+                sendAudioEvent(
+                  NodeNames.System,
+                  TranceRiff.System.SetGrooveStrength,
+                  value
+                )
+              }}
+              className="w-24"
+            />
+            <span className="text-sm text-gray-400 w-12">
+              {Math.round(grooveStrength * 100)}%
+            </span>
+          </div>
+
+          <div className="flex items-center gap-3">
+            <input
+              type="text"
+              placeholder="Groove file path (.json)"
+              value={grooveFilePath}
+              onChange={(e) => setGrooveFilePath(e.target.value)}
+              className="bg-gray-700 text-gray-200 rounded px-2 py-1 text-sm w-64"
+            />
+            <button
+              onClick={handleLoadGrooveFile}
+              disabled={grooveFilePath.length === 0}
+              className="px-3 py-1 rounded bg-gray-600 hover:bg-gray-700 text-white text-sm"
+            >
+              Load
+            </button>
+            {grooveFileError && (
+              <span className="text-sm text-red-400">{grooveFileError}</span>
+            )}
+          </div>
+        </div>
+
+        {/* Metronome */}
+        <div className="flex items-center gap-6">
+          <label className="flex items-center gap-2 text-sm text-gray-300">
+            <input
+              type="checkbox"
+              checked={metronomeEnabled}
+              onChange={(e) =>
+                handleMetronomeToggle(
+                  TranceRiff.Metronome.SetEnabled,
+                  e.target.checked,
+                  setMetronomeEnabled
+                )
+              }
+            />
+            Click
+          </label>
+
+          <label className="flex items-center gap-2 text-sm text-gray-300">
+            <input
+              type="checkbox"
+              checked={accentDownbeat}
+              onChange={(e) =>
+                handleMetronomeToggle(
+                  TranceRiff.Metronome.SetAccentDownbeat,
+                  e.target.checked,
+                  setAccentDownbeat
+                )
+              }
+            />
+            Accent Downbeat
+          </label>
+
+          <div className="flex items-center gap-3">
+            <label className="text-sm font-medium text-gray-300">
+              Click Volume:
+            </label>
+            <input
+              type="range"
+              min={0}
+              max={1}
+              step={0.01}
+              value={metronomeVolume}
+              onChange={(e) =>
+                handleMetronomeParameter(
+                  TranceRiff.Metronome.SetVolume,
+                  parseFloat(e.target.value),
+                  setMetronomeVolume
+                )
+              }
+              className="w-24"
+            />
+          </div>
+
+          <div className="flex items-center gap-3">
+            <label className="text-sm font-medium text-gray-300">
+              Count-in:
+            </label>
+            <select
+              value={countInBars}
+              onChange={(e) =>
+                handleMetronomeParameter(
+                  TranceRiff.Metronome.SetCountInBars,
+                  parseInt(e.target.value),
+                  setCountInBars
+                )
+              }
+              className="bg-gray-700 text-gray-200 rounded px-2 py-1 text-sm"
+            >
+              <option value={0}>Off</option>
+              <option value={1}>1 bar</option>
+              <option value={2}>2 bars</option>
+            </select>
+          </div>
+        </div>
       </div>
 
       {/* Chord Arp Controls */}
@@ -15,33 +15,153 @@ export const Auditioner = {
   Kick: {
     Trigger: "trigger",
     SetGain: "set_gain",
+    SetChokeGroup: "set_choke_group",
+    SetLofiAmount: "set_lofi_amount",
     SetBaseFrequency: "set_base_frequency",
     SetFrequencyRatio: "set_frequency_ratio",
     SetAmpAttack: "set_amp_attack",
     SetAmpRelease: "set_amp_release",
     SetFreqAttack: "set_freq_attack",
     SetFreqRelease: "set_freq_release",
+    SetClickLevel: "set_click_level",
+    SetClickDecay: "set_click_decay",
   },
 
   // Clap node events
   Clap: {
     Trigger: "trigger",
     SetGain: "set_gain",
+    SetChokeGroup: "set_choke_group",
+    SetLofiAmount: "set_lofi_amount",
+  },
+
+  // Snare node events
+  Snare: {
+    Trigger: "trigger",
+    SetGain: "set_gain",
+    SetChokeGroup: "set_choke_group",
+    SetLofiAmount: "set_lofi_amount",
+    SetTune: "set_tune",
+    SetToneDecay: "set_tone_decay",
+    SetSnappy: "set_snappy",
+    SetNoiseDecay: "set_noise_decay",
+    SetNoiseFrequency: "set_noise_frequency",
+    // 0 = white, 1 = pink, 2 = brown
+    SetNoiseColor: "set_noise_color",
+    // Ratio the body starts at above its tune; 1 is no sweep
+    SetPitchSweep: "set_pitch_sweep",
+    SetSweepTime: "set_sweep_time",
   },
 
   // HiHat node events
   HiHat: {
     Trigger: "trigger",
     SetGain: "set_gain",
+    SetChokeGroup: "set_choke_group",
+    SetLofiAmount: "set_lofi_amount",
     SetLength: "set_length",
   },
 
+  // Tom node events; tune the one voice for low, mid or high toms
+  Tom: {
+    Trigger: "trigger",
+    SetGain: "set_gain",
+    SetChokeGroup: "set_choke_group",
+    SetLofiAmount: "set_lofi_amount",
+    SetBaseFrequency: "set_base_frequency",
+    // Semitones above the base frequency each hit starts from, 0-24
+    SetSweepDepth: "set_sweep_depth",
+    SetSweepTime: "set_sweep_time",
+    SetDecay: "set_decay",
+    SetNoiseAmount: "set_noise_amount",
+    SetNoiseDecay: "set_noise_decay",
+    // Depth of through-zero FM on the tone, 0 to 1
+    SetFmAmount: "set_fm_amount",
+  },
+
+  // Modal drum node events; strike position runs from centre (0) to rim (1)
+  ModalDrum: {
+    Trigger: "trigger",
+    SetGain: "set_gain",
+    SetChokeGroup: "set_choke_group",
+    SetLofiAmount: "set_lofi_amount",
+    SetTune: "set_tune",
+    SetDecay: "set_decay",
+    SetDamping: "set_damping",
+    SetStrikePosition: "set_strike_position",
+    SetNoise: "set_noise",
+  },
+
+  // Cowbell node events
+  Cowbell: {
+    Trigger: "trigger",
+    SetGain: "set_gain",
+    SetChokeGroup: "set_choke_group",
+    SetLofiAmount: "set_lofi_amount",
+    // Frequency of the lower oscillator, 540Hz on the 808
+    SetTuning: "set_tuning",
+    SetDecay: "set_decay",
+  },
+
+  // Clave node events
+  Clave: {
+    Trigger: "trigger",
+    SetGain: "set_gain",
+    SetChokeGroup: "set_choke_group",
+    SetLofiAmount: "set_lofi_amount",
+    // Pitch of the ping, around 2.5kHz for a clave
+    SetTuning: "set_tuning",
+    SetDecay: "set_decay",
+  },
+
+  // Glitch percussion node events; every hit varies by the randomness
+  Glitch: {
+    Trigger: "trigger",
+    SetGain: "set_gain",
+    SetChokeGroup: "set_choke_group",
+    SetLofiAmount: "set_lofi_amount",
+    SetTexture: "set_texture",
+    SetCrush: "set_crush",
+    SetDecay: "set_decay",
+    SetRandomness: "set_randomness",
+  },
+
+  // Cymbal node events
+  Cymbal: {
+    Trigger: "trigger",
+    SetGain: "set_gain",
+    SetChokeGroup: "set_choke_group",
+    SetLofiAmount: "set_lofi_amount",
+    // 0 = ride, 1 = crash; also resets decay, sizzle and high-pass
+    SetMode: "set_mode",
+    SetTone: "set_tone",
+    SetDecay: "set_decay",
+    SetSizzle: "set_sizzle",
+  },
+
   // Chord node events
   Chord: {
     Trigger: "trigger",
     SetGain: "set_gain",
+    SetChokeGroup: "set_choke_group",
+    SetLofiAmount: "set_lofi_amount",
     SetBaseFrequency: "set_base_frequency",
+    // 0 = major, 1 = minor, 2 = sus2, 3 = sus4, 4 = maj7, 5 = min7, 6 = dom7
+    SetChordQuality: "set_chord_quality",
+    // data: a custom chord as ratios of each note to the root
+    SetChordRatios: "set_chord_ratios",
+    SetInversion: "set_inversion",
+    SetSpread: "set_spread",
+    SetStereoWidth: "set_stereo_width",
     SetModulationIndex: "set_modulation_index",
+    // Detuned copies of each voice, 1 (off) to 8
+    SetUnisonVoices: "set_unison_voices",
+    // Cents the outermost unison copies sit from the note
+    SetUnisonDetune: "set_unison_detune",
+    // Cents each operator's pitch wanders, like an analog oscillator
+    SetDriftAmount: "set_drift_amount",
+    // data: [source, destination] operators (0-3), 0 being the carrier
+    SetModAmount: "set_mod_amount",
     SetFeedback: "set_feedback",
     SetAttack: "set_attack",
     SetRelease: "set_release",
@@ -51,26 +171,200 @@ export const Auditioner = {
   Supersaw: {
     Trigger: "trigger",
     SetGain: "set_gain",
+    SetChokeGroup: "set_choke_group",
+    SetLofiAmount: "set_lofi_amount",
     SetBaseFrequency: "set_base_frequency",
     SetDetune: "set_detune",
     SetStereoWidth: "set_stereo_width",
+    // Cents each saw's pitch wanders, like an analog oscillator
+    SetDriftAmount: "set_drift_amount",
     SetFilterCutoff: "set_filter_cutoff",
     SetFilterResonance: "set_filter_resonance",
     SetFilterEnvAmount: "set_filter_env_amount",
+    SetFilterKeytrack: "set_filter_keytrack",
+    SetFilterFmAmount: "set_filter_fm_amount",
+    SetFilterFmRatio: "set_filter_fm_ratio",
     SetAmpAttack: "set_amp_attack",
     SetAmpRelease: "set_amp_release",
     SetFilterAttack: "set_filter_attack",
     SetFilterRelease: "set_filter_release",
   },
 
+  // PadSynth node events
+  PadSynth: {
+    Trigger: "trigger",
+    SetGain: "set_gain",
+    SetChokeGroup: "set_choke_group",
+    SetLofiAmount: "set_lofi_amount",
+    SetBaseFrequency: "set_base_frequency",
+    // 0 = major, 1 = minor, 2 = sus2, 3 = sus4, 4 = maj7, 5 = min7, 6 = dom7
+    SetChordQuality: "set_chord_quality",
+    SetDetune: "set_detune",
+    SetAttack: "set_attack",
+    SetRelease: "set_release",
+    SetFilterCutoff: "set_filter_cutoff",
+    SetChorusRate: "set_chorus_rate",
+    SetChorusMix: "set_chorus_mix",
+  },
+
+  // 808 bass node events; trigger parameter 1 plays an accented note
+  Bass808: {
+    Trigger: "trigger",
+    SetGain: "set_gain",
+    SetChokeGroup: "set_choke_group",
+    SetLofiAmount: "set_lofi_amount",
+    SetBaseFrequency: "set_base_frequency",
+    // Seconds to slide to a new note while one rings
+    SetGlide: "set_glide",
+    // 0 = sine, 1 = triangle
+    SetShape: "set_shape",
+    // Bends the sine towards a saw, 0 to 1
+    SetPhaseDistortion: "set_phase_distortion",
+    SetDecay: "set_decay",
+    SetDrive: "set_drive",
+    // How much louder and more driven accented notes are
+    SetAccent: "set_accent",
+    // 1 = the kick chokes the 808
+    SetKickDuck: "set_kick_duck",
+  },
+
+  // Acid bass node events; trigger parameter 1 accents, data 1 slides
+  Acid: {
+    Trigger: "trigger",
+    SetGain: "set_gain",
+    SetChokeGroup: "set_choke_group",
+    SetLofiAmount: "set_lofi_amount",
+    SetBaseFrequency: "set_base_frequency",
+    // Seconds a slide takes
+    SetSlideTime: "set_slide_time",
+    // 0 = saw, 1 = square
+    SetWaveform: "set_waveform",
+    SetCutoff: "set_cutoff",
+    SetResonance: "set_resonance",
+    // Depth of the filter envelope, 0-1
+    SetEnvMod: "set_env_mod",
+    SetDecay: "set_decay",
+    SetAccent: "set_accent",
+  },
+
+  // Wavetable node events
+  Wavetable: {
+    Trigger: "trigger",
+    SetGain: "set_gain",
+    SetChokeGroup: "set_choke_group",
+    SetLofiAmount: "set_lofi_amount",
+    SetBaseFrequency: "set_base_frequency",
+    // 0 = sine, 1 = square, through triangle and saw
+    SetPosition: "set_position",
+    SetLfoRate: "set_lfo_rate",
+    SetLfoDepth: "set_lfo_depth",
+    // Position added at the envelope's peak, -1 to 1
+    SetPositionEnvAmount: "set_position_env_amount",
+    SetPositionAttack: "set_position_attack",
+    SetPositionRelease: "set_position_release",
+    SetFilterCutoff: "set_filter_cutoff",
+    SetFilterResonance: "set_filter_resonance",
+    SetAttack: "set_attack",
+    SetRelease: "set_release",
+  },
+
+  // 6-operator FM node events; per-operator events take the operator (0-5)
+  // as their data
+  Fm6: {
+    Trigger: "trigger",
+    SetGain: "set_gain",
+    SetChokeGroup: "set_choke_group",
+    SetLofiAmount: "set_lofi_amount",
+    SetBaseFrequency: "set_base_frequency",
+    SetAlgorithm: "set_algorithm",
+    SetFeedback: "set_feedback",
+    SetOpRatio: "set_op_ratio",
+    SetOpLevel: "set_op_level",
+    SetOpAttack: "set_op_attack",
+    SetOpDecay: "set_op_decay",
+    SetOpSustain: "set_op_sustain",
+    SetAttack: "set_attack",
+    SetRelease: "set_release",
+  },
+
+  // Formant voice node events; the vowel morphs from A (0) through to U (4)
+  Formant: {
+    Trigger: "trigger",
+    SetGain: "set_gain",
+    SetChokeGroup: "set_choke_group",
+    SetLofiAmount: "set_lofi_amount",
+    SetBaseFrequency: "set_base_frequency",
+    SetVowel: "set_vowel",
+    SetFormantShift: "set_formant_shift",
+    SetWaveform: "set_waveform",
+    // How far an LFO sweeps the pulse's width, 0 to 1
+    SetPwmDepth: "set_pwm_depth",
+    SetPwmRate: "set_pwm_rate",
+    SetResonance: "set_resonance",
+    SetAttack: "set_attack",
+    SetRelease: "set_release",
+  },
+
   // Reverb node events
   Reverb: {
     SetSize: "set_size",
     SetModulationDepth: "set_modulation_depth",
     SetFeedback: "set_feedback",
+    // 0 = eco (4 channels), 1 = normal (8), 2 = high (16)
+    SetQuality: "set_quality",
+    SetDuckAmount: "set_duck_amount",
+    SetDuckRelease: "set_duck_release",
+  },
+
+  // Master EQ node events
+  Eq: {
+    SetLowFrequency: "set_low_frequency",
+    SetLowGain: "set_low_gain",
+    SetMidFrequency: "set_mid_frequency",
+    SetMidGain: "set_mid_gain",
+    SetMidQ: "set_mid_q",
+    SetHighFrequency: "set_high_frequency",
+    SetHighGain: "set_high_gain",
+  },
+
+  // Sample pad events; trigger takes the pad index as its parameter
+  Sampler: {
+    // parameter: pad, data: velocity (0-1)
+    Trigger: "trigger",
+    // parameter: pad
+    Stop: "stop",
+    SetGain: "set_gain",
+    SetBpm: "set_bpm",
+    // 0 plays loops unstretched
+    SetLoopBpm: "set_loop_bpm",
+    // 0 = granular, 1 = WSOLA
+    SetStretchQuality: "set_stretch_quality",
+    // Seconds
+    SetGrainSize: "set_grain_size",
+    // Semitones
+    SetPitch: "set_pitch",
+    SetPitchEnvAmount: "set_pitch_env_amount",
+    SetPitchAttack: "set_pitch_attack",
+    SetPitchRelease: "set_pitch_release",
+    // 0 = lowpass, 1 = highpass, 2 = bandpass
+    SetFilterMode: "set_filter_mode",
+    SetFilterCutoff: "set_filter_cutoff",
+    SetFilterResonance: "set_filter_resonance",
+    SetFilterEnvAmount: "set_filter_env_amount",
+    SetFilterAttack: "set_filter_attack",
+    SetFilterRelease: "set_filter_release",
+    // 1 = reversed
+    SetReverse: "set_reverse",
+    // 0-1 of the way into the slice
+    SetStartOffset: "set_start_offset",
+    // 0 = one-shot, 1 = loop
+    SetPlayMode: "set_play_mode",
   },
 } as const
 
+// Sample pads on the auditioner, also the most slices a loop is cut into
+export const SAMPLER_PADS = 16
+
 // ============================================================================
 // TRANCE RIFF SYSTEM
 // ============================================================================
@@ -79,8 +373,27 @@ export const TranceRiff = {
   System: {
     SetBpm: "set_bpm",
     SetPaused: "set_paused",
+    Play: "play",
+    Stop: "stop",
+    Continue: "continue",
+    Locate: "locate",
+    GetPosition: "get_position",
+    SetGroove: "set_groove",
+    SetGrooveStrength: "set_groove_strength",
+    LoadGroove: "load_groove",
+    GetGrooveTemplates: "get_groove_templates",
     SetSequence: "set_sequence",
     ResetSequence: "reset_sequence",
+    SetClockSource: "set_clock_source",
+    SyncClock: "sync_clock",
+  },
+
+  // Metronome node events
+  Metronome: {
+    SetEnabled: "set_enabled",
+    SetVolume: "set_volume",
+    SetAccentDownbeat: "set_accent_downbeat",
+    SetCountInBars: "set_count_in_bars",
   },
 
   // Supersaw node events
@@ -93,6 +406,9 @@ export const TranceRiff = {
     SetFilterCutoff: "set_filter_cutoff",
     SetFilterResonance: "set_filter_resonance",
     SetFilterEnvAmount: "set_filter_env_amount",
+    SetFilterKeytrack: "set_filter_keytrack",
+    SetFilterFmAmount: "set_filter_fm_amount",
+    SetFilterFmRatio: "set_filter_fm_ratio",
     SetAmpAttack: "set_amp_attack",
     SetAmpRelease: "set_amp_release",
     SetFilterAttack: "set_filter_attack",
@@ -100,30 +416,476 @@ export const TranceRiff = {
   },
 } as const
 
+// ============================================================================
+// MASTER SECTION (handled by the audio server, applies to every system)
+// ============================================================================
+export const Master = {
+  // DJ filter node events
+  Filter: {
+    SetPosition: "set_position",
+    SetResonance: "set_resonance",
+  },
+  // Server-wide events
+  System: {
+    // data: integer seed, reseeds every random source in every system
+    SetSeed: "set_seed",
+    // parameter: 0-2, overall output level ahead of the limiter
+    SetMasterGain: "set_master_gain",
+  },
+  // Per-system mixer channels; data: system name
+  Mixer: {
+    // parameter: 1 to layer the system in, 0 to stop processing it
+    SetActive: "set_active",
+    // parameter: 0-2
+    SetGain: "set_gain",
+    // parameter: 1 to mute, the system keeps running silently
+    SetMute: "set_mute",
+  },
+  // Live looper; data: loop index unless noted
+  Looper: {
+    // Starts recording at the next bar line
+    Record: "record",
+    // Toggles recording a layer over a playing loop
+    Overdub: "overdub",
+    Undo: "undo",
+    Clear: "clear",
+    // parameter: 0-1
+    SetLevel: "set_level",
+    // parameter: 1-8 bars of the playing system's transport, length of the
+    // next recording
+    SetBars: "set_bars",
+    // data: system name to record, or "master" for the whole mix
+    SetSource: "set_source",
+  },
+  // Scene snapshots of the mixer, master filter and loop levels; data: scene name
+  Scene: {
+    Save: "save",
+    // parameter: crossfade seconds (0-30), instant when omitted
+    Recall: "recall",
+    Delete: "delete",
+    GetScenes: "get_scenes",
+  },
+  // Momentary beat repeat over the whole mix
+  Stutter: {
+    // parameter: note division to repeat (4, 8, 16 or 32) at the playing
+    // system's tempo, held until release
+    Press: "press",
+    Release: "release",
+    // parameter: -12 to 12 semitones shift per repeat
+    SetPitch: "set_pitch",
+    // parameter: 0-1, gain of each repeat relative to the last
+    SetDecay: "set_decay",
+  },
+  // One-shot tape gestures; parameter: ramp seconds (0-4)
+  TapeStop: {
+    // Slows the mix to a halt and holds it silent
+    Stop: "stop",
+    // Spins back up and rejoins the live mix
+    Start: "start",
+  },
+  // data: system name for every evolve event
+  Evolve: {
+    // parameter: 1 mutates the system every few bars, 0 stops
+    SetEnabled: "set_enabled",
+    // parameter: 1-64 bars of the playing system's transport between mutations
+    SetEveryBars: "set_every_bars",
+    // parameter: 0-1, how big each mutation is
+    SetIntensity: "set_intensity",
+    // parameter: whole number, the same seed evolves the same way
+    SetSeed: "set_seed",
+  },
+} as const
+
+export const STUTTER_DIVISIONS = [4, 8, 16, 32] as const
+
 // ============================================================================
 // SYSTEM AND NODE NAMES
 // ============================================================================
 export const SystemNames = {
   Auditioner: "auditioner",
   TranceRiff: "trance_riff",
+  Master: "master",
 } as const
 
 export const NodeNames = {
   System: "system",
   Kick: "kick",
   Clap: "clap",
+  Snare: "snare",
   HiHat: "hihat",
+  OpenHat: "open_hat",
+  Tom: "tom",
+  ModalDrum: "modal_drum",
+  Cowbell: "cowbell",
+  Clave: "clave",
+  Glitch: "glitch",
+  Cymbal: "cymbal",
   Chord: "chord",
   Supersaw: "supersaw",
+  PadSynth: "pad_synth",
+  Bass808: "bass808",
+  Acid: "acid",
+  Wavetable: "wavetable",
+  Fm6: "fm6",
+  Formant: "formant",
   Reverb: "reverb",
+  Eq: "eq",
+  Filter: "filter",
+  Metronome: "metronome",
+  Mixer: "mixer",
+  Sampler: "sampler",
+  Looper: "looper",
+  Scene: "scene",
+  Stutter: "stutter",
+  TapeStop: "tape_stop",
+  Evolve: "evolve",
+} as const
+
+// ============================================================================
+// SERVER EVENTS (emitted by the backend as "{system}_{node}_{event}")
+// ============================================================================
+export const ServerEvents = {
+  TranceRiff: {
+    // data: { bar, beat, step } (1-based), parameter: 1 while playing
+    TransportPosition: "trance_riff_system_transport_position",
+    // data: { bar, beat (1-based), phase (0-1) }, ~30 Hz while playing
+    BeatPhase: "trance_riff_system_beat_phase",
+    // data: { templates: string[], current: string }
+    GrooveTemplates: "trance_riff_system_groove_templates",
+  },
+  Master: {
+    // data: { event, message }, sent when the audio thread rejects a command
+    CommandError: "master_system_command_error",
+    // data: { id, queue_ms, max_event_queue_ms }, answers a ping command
+    Pong: "master_system_pong",
+    // data: { name, sample_rate, channels }, output moved to another device
+    DeviceChanged: "master_system_device_changed",
+    // data: { source, loops: LoopInfo[] }, sent when any loop changes
+    LooperState: "master_looper_state",
+    // data: SceneState, sent when scenes are saved, recalled or deleted
+    SceneState: "master_scene_state",
+    // data: { peak, held_peak }, linear output levels ~30 times a second
+    OutputMeter: "master_system_output_meter",
+    // parameter: samples clipped by the limiter over the last second
+    ClippingDetected: "master_system_clipping_detected",
+  },
 } as const
 
+// Parameters the engine changes itself are emitted under the event that sets
+// them, with the new value as the parameter
+export const parameterChangedEvent = (
+  system: string,
+  node: string,
+  event: string
+) => `${system}_${node}_${event}`
+
+export interface ParameterChangedPayload {
+  system: string
+  node: string
+  event: string
+  parameter: number | null
+  data: null
+}
+
+// Any sequenced system reports playheads per track: the track list when the
+// system is activated, then a step change whenever a track moves
+export const stepChangedEvent = (system: string) =>
+  `${system}_system_step_changed`
+export const tracksEvent = (system: string) => `${system}_system_tracks`
+export const patternUpdatedEvent = (system: string) =>
+  `${system}_system_pattern_updated`
+
+export interface StepChangedPayload {
+  system: string
+  node: string
+  event: string
+  parameter: number | null
+  data: { track_id: string; step: number }
+}
+
+// frequency is null for drum steps and rests; length is in the system's pulses
+export interface PatternStep {
+  velocity: number
+  frequency: number | null
+  length: number
+}
+
+export interface PatternUpdatedPayload {
+  system: string
+  node: string
+  event: string
+  parameter: number | null
+  data: { track_id: string; steps: PatternStep[] }
+}
+
+export interface TracksPayload {
+  system: string
+  node: string
+  event: string
+  parameter: number | null
+  data: { tracks: string[] }
+}
+
+export interface TransportPositionPayload {
+  system: string
+  node: string
+  event: string
+  parameter: number | null
+  data: { bar: number; beat: number; step: number }
+}
+
+export interface BeatPhasePayload {
+  system: string
+  node: string
+  event: string
+  parameter: number | null
+  data: { bar: number; beat: number; phase: number }
+}
+
+export const MAX_LOOPS = 4
+
+export interface LoopInfo {
+  state: "empty" | "armed" | "recording" | "playing" | "overdubbing"
+  layers: number
+  level: number
+  bars: number
+}
+
+export interface LooperStatePayload {
+  system: string
+  node: string
+  event: string
+  parameter: number | null
+  data: { source: string; loops: LoopInfo[] }
+}
+
+export interface SceneState {
+  scenes: string[]
+  current: string | null
+  // Where the recalled scene leaves each mixer channel
+  channels: Record<string, { gain: number; muted: boolean }>
+  filter_position: number
+}
+
+export interface SceneStatePayload {
+  system: string
+  node: string
+  event: string
+  parameter: number | null
+  data: SceneState
+}
+
+export interface GrooveTemplatesPayload {
+  system: string
+  node: string
+  event: string
+  parameter: number | null
+  data: { templates: string[]; current: string }
+}
+
+export interface CommandErrorPayload {
+  system: string
+  node: string
+  event: string
+  parameter: number | null
+  data: { event: string; message: string }
+}
+
+export interface DeviceChangedPayload {
+  system: string
+  node: string
+  event: string
+  parameter: number | null
+  data: { name: string; sample_rate: number; channels: number }
+}
+
+export interface PongPayload {
+  system: string
+  node: string
+  event: string
+  parameter: number | null
+  data: { id: number; queue_ms: number; max_event_queue_ms: number }
+}
+
+export interface OutputMeterPayload {
+  system: string
+  node: string
+  event: string
+  parameter: number | null
+  data: { peak: number; held_peak: number }
+}
+
+// Emitted directly by the app every second, outside the system/node scheme
+export const CommandQueueStatsEvent = "command_queue_stats"
+
+export interface CommandQueueStats {
+  pending: number
+  high_water_mark: number
+  dropped: number
+}
+
+// Drum instrument parameters keyed by node, then by the event that sets them
+export interface Kit {
+  name: string
+  kick?: Record<string, number>
+  clap?: Record<string, number>
+  snare?: Record<string, number>
+  hihat?: Record<string, number>
+  open_hat?: Record<string, number>
+  tom?: Record<string, number>
+  modal_drum?: Record<string, number>
+  cowbell?: Record<string, number>
+  clave?: Record<string, number>
+  glitch?: Record<string, number>
+  cymbal?: Record<string, number>
+}
+
+export const KitNodes: string[] = [
+  NodeNames.Kick,
+  NodeNames.Clap,
+  NodeNames.Snare,
+  NodeNames.HiHat,
+  NodeNames.OpenHat,
+  NodeNames.Tom,
+  NodeNames.ModalDrum,
+  NodeNames.Cowbell,
+  NodeNames.Clave,
+  NodeNames.Glitch,
+  NodeNames.Cymbal,
+]
+
+// MIDI note number -> the auditioner instrument or sample pad it triggers
+export type DrumMap = Record<string, { node: string; pad?: number }>
+
+export const DrumMapNodes: string[] = [
+  NodeNames.Kick,
+  NodeNames.Clap,
+  NodeNames.Snare,
+  NodeNames.HiHat,
+  NodeNames.OpenHat,
+  NodeNames.Tom,
+  NodeNames.ModalDrum,
+  NodeNames.Cowbell,
+  NodeNames.Clave,
+  NodeNames.Glitch,
+  NodeNames.Cymbal,
+  NodeNames.Chord,
+  NodeNames.Supersaw,
+  NodeNames.PadSynth,
+  NodeNames.Bass808,
+  NodeNames.Acid,
+  NodeNames.Wavetable,
+  NodeNames.Fm6,
+  NodeNames.Formant,
+  NodeNames.Sampler,
+]
+
+// Computer keyboard octave, the synth playing notes the drum map doesn't
+// cover, and the note the last key played if any
+export interface KeyboardState {
+  octave: number
+  synth: string
+  note: number | null
+}
+
+export const KeyboardSynths: string[] = [
+  NodeNames.Supersaw,
+  NodeNames.PadSynth,
+  NodeNames.Chord,
+  NodeNames.Bass808,
+  NodeNames.Acid,
+  NodeNames.Wavetable,
+  NodeNames.Fm6,
+  NodeNames.Formant,
+]
+
+// An imported sample, named after its file; duration in seconds
+export interface SampleInfo {
+  name: string
+  duration: number
+}
+
+// Start frame of each slice of an imported sample
+export interface SliceMarkers {
+  name: string
+  markers: number[]
+  sample_rate: number
+}
+
+// Returned by the list_audio_systems command, in registration order
+export interface AudioSystemInfo {
+  name: string
+  description: string
+}
+
+// Result of Commands.AnalyzeReverb; rt60 is null if the tail is too short
+export interface ReverbAnalysis {
+  rt60: number | null
+  envelope: number[]
+  sample_rate: number
+}
+
+// Result of Commands.AnalyzeFilter, magnitudes_db[i] is at frequencies[i]
+export interface FilterResponse {
+  frequencies: number[]
+  magnitudes_db: number[]
+}
+
 // ============================================================================
 // TAURI COMMANDS
 // ============================================================================
 export const Commands = {
   SendClientEvent: "send_client_event",
   SwitchAudioSystem: "switch_audio_system",
+  LoadGrooveFile: "load_groove_file",
+  Ping: "ping",
+  GetCommandQueueStats: "get_command_queue_stats",
+  ListAudioSystems: "list_audio_systems",
+  AnalyzeReverb: "analyze_reverb",
+  AnalyzeFilter: "analyze_filter",
+  // args: { leftChannel, rightChannel, mono }, channels count from 0
+  SetOutputRouting: "set_output_routing",
+  // args: { kit: Kit }, returns the saved name
+  SaveKit: "save_kit",
+  ListKits: "list_kits",
+  // args: { name }, applies the kit and returns it
+  LoadKit: "load_kit",
+  // Drum map commands return the whole DrumMap after any change
+  GetDrumMap: "get_drum_map",
+  // args: { note, node, pad? }, pad only for the sampler
+  SetDrumMapNote: "set_drum_map_note",
+  // args: { note }
+  ClearDrumMapNote: "clear_drum_map_note",
+  // Back to the General MIDI drum notes
+  ResetDrumMap: "reset_drum_map",
+  // args: { note, velocity } (0-127), triggers the mapped instrument
+  MidiNoteOn: "midi_note_on",
+  // args: { code } (KeyboardEvent.code), returns KeyboardState
+  KeyboardKeyDown: "keyboard_key_down",
+  // args: { node }, one of KeyboardSynths, returns KeyboardState
+  SetKeyboardSynth: "set_keyboard_synth",
+  GetKeyboardState: "get_keyboard_state",
+  // args: { path } to a WAV or FLAC file, returns its SampleInfo
+  ImportSample: "import_sample",
+  ListSamples: "list_samples",
+  // args: { pad, name }, loads an imported sample onto an auditioner pad
+  AssignSample: "assign_sample",
+  AddSampleLayer: "add_sample_layer",
+  // args: { name, maxSlices? }, cuts at transients and returns SliceMarkers
+  SliceSample: "slice_sample",
+  // args: { name, markers }, replaces the markers and returns SliceMarkers
+  SetSliceMarkers: "set_slice_markers",
+  // args: { name }, puts slice i on pad i and returns the slice count
+  AssignSlices: "assign_slices",
+  // args: { path }, logs every command that changes the audio to the file
+  StartSessionLog: "start_session_log",
+  // Closes the log and returns how many commands were logged
+  StopSessionLog: "stop_session_log",
+  // args: { path }, plays a log back live and returns its command count
+  ReplaySessionLog: "replay_session_log",
+  // args: { path, outputPath, tailSeconds? }, bounces a log to a WAV file
+  // and returns the frames written
+  RenderSessionLog: "render_session_log",
 } as const
 
 // ============================================================================